@@ -0,0 +1,121 @@
+// the VM's memory. instructions go through read8/write8/read16, which is where the MMIO range
+// is mapped in and where addresses past the end are dealt with. the RAM behind it can also be
+// got at directly, e.g. for loading ROMs and save states, without any of that
+
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)] // like the rest of the core, see chip8.rs
+
+use crate::mmio::{self, Mmio};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub const SIZE: usize = 4096;
+pub const XO_SIZE: usize = 65536; // with the xo-memory quirk
+
+// what happens to an access past the end of memory
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Bounds {
+    Fault, // it's an error
+    Wrap,  // the address wraps around to the start
+}
+
+pub struct MemoryBus {
+    ram: Vec<u8>,
+    bounds: Bounds,
+    mmio: Option<Box<dyn Mmio + Send>>, // what's behind the MMIO range, None to leave it RAM
+    pub(crate) ticks: u64,              // the VM's 60Hz ticks, for MMIO reads
+}
+
+impl MemoryBus {
+    pub fn new(size: usize) -> Self {
+        Self {
+            ram: vec![0; size],
+            bounds: Bounds::Fault,
+            mmio: None,
+            ticks: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ram.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ram.is_empty()
+    }
+
+    pub fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    pub fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    // grow or shrink the RAM, keeping what fits
+    pub fn resize(&mut self, size: usize) {
+        self.ram.resize(size, 0);
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn set_bounds(&mut self, bounds: Bounds) {
+        self.bounds = bounds;
+    }
+
+    pub fn set_mmio(&mut self, mmio: Option<Box<dyn Mmio + Send>>) {
+        self.mmio = mmio;
+    }
+
+    pub(crate) fn take_mmio(&mut self) -> Option<Box<dyn Mmio + Send>> {
+        self.mmio.take()
+    }
+
+    // where addr is in RAM under the bounds policy, Err(addr) if it's nowhere
+    fn resolve(&self, addr: usize) -> Result<usize, usize> {
+        match self.bounds {
+            _ if addr < self.ram.len() => Ok(addr),
+            Bounds::Wrap => Ok(addr % self.ram.len()),
+            Bounds::Fault => Err(addr),
+        }
+    }
+
+    // mmio and the offset into its range if addr is in it
+    fn mapped(&mut self, addr: usize) -> Option<(&mut (dyn Mmio + Send), usize)> {
+        let offset = addr.checked_sub(mmio::BASE).filter(|&o| o < mmio::LEN)?;
+        Some((self.mmio.as_deref_mut()?, offset))
+    }
+
+    pub fn read8(&mut self, addr: usize) -> Result<u8, usize> {
+        let addr = self.resolve(addr)?;
+        let ticks = self.ticks;
+        match self.mapped(addr) {
+            Some((mmio, offset)) => Ok(mmio.read(offset, ticks)),
+            None => Ok(self.ram[addr]),
+        }
+    }
+
+    // big-endian, like opcodes
+    pub fn read16(&mut self, addr: usize) -> Result<u16, usize> {
+        Ok((self.read8(addr)? as u16) << 8 | self.read8(addr + 1)? as u16)
+    }
+
+    pub fn write8(&mut self, addr: usize, value: u8) -> Result<(), usize> {
+        let addr = self.resolve(addr)?;
+        match self.mapped(addr) {
+            Some((mmio, offset)) => mmio.write(offset, value),
+            None => self.ram[addr] = value,
+        }
+        Ok(())
+    }
+}
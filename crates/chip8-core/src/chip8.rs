@@ -0,0 +1,1323 @@
+// the core runs whatever a ROM has in it, untrusted ones included, so nothing in it may panic:
+// what a ROM gets wrong comes back as an Error. tests/panic_free.rs checks for the panics these
+// lints can't see, like indexing
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+
+use crate::bus::{self, Bounds, MemoryBus};
+use crate::clock::{Clock, CycleClock};
+use crate::disasm;
+use crate::fonts::{self, Font};
+use crate::mmio::Mmio;
+use crate::observer::{Event, Observer};
+use crate::quirks::Quirks;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    UnknownOpcode { pc: u16, opcode: u16 },
+    StackOverflow { pc: u16 },
+    StackUnderflow { pc: u16 },
+    MemoryOutOfBounds { pc: u16, addr: usize },
+    PcOutOfBounds { pc: u16 },
+    KeyWaitTimeout { pc: u16 }, // FX0A waited longer than set_key_wait_timeout allows
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::UnknownOpcode { pc, opcode } => {
+                write!(f, "unhandled opcode {:04X} at {:03X}", opcode, pc)
+            }
+            Self::StackOverflow { pc } => write!(f, "stack overflow calling from {:03X}", pc),
+            Self::StackUnderflow { pc } => write!(f, "return with empty stack at {:03X}", pc),
+            Self::MemoryOutOfBounds { pc, addr } => {
+                write!(f, "access past end of memory ({:X}) at {:03X}", addr, pc)
+            }
+            Self::PcOutOfBounds { pc } => write!(f, "program counter out of memory at {:X}", pc),
+            Self::KeyWaitTimeout { pc } => write!(f, "gave up waiting for a key at {:03X}", pc),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StateError {
+    NotAState,
+    UnsupportedVersion(u8),
+    Truncated,
+    Corrupt,
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::NotAState => write!(f, "not a CHIP-8 save state"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported save state version {}", v),
+            Self::Truncated => write!(f, "save state is truncated"),
+            Self::Corrupt => write!(f, "save state is corrupt"),
+        }
+    }
+}
+
+impl core::error::Error for StateError {}
+
+pub const PROGRAM_START: u16 = 0x200; // where ROMs are loaded and run from unless told otherwise
+
+const STATE_MAGIC: &[u8; 4] = b"C8ST";
+// 2 had no memory size and was always 4K, 3 no instruction or frame counts, 4 a byte of quirks
+const STATE_VERSION: u8 = 5;
+
+// size of a save state, anything after that is extra data saved alongside it, like a replay.
+// None if it isn't one
+pub fn state_len(state: &[u8]) -> Option<usize> {
+    if state.get(..4)? != STATE_MAGIC {
+        return None;
+    }
+    let version = *state.get(4)?;
+    let (memory, header) = match version {
+        2 => (bus::SIZE, 4 + 1 + 2),
+        3..=5 => {
+            let len = state.get(7..11)?;
+            let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+            (len, 4 + 1 + 2 + 4)
+        }
+        _ => return None,
+    };
+    let counts = if version >= 4 { 8 + 8 } else { 0 };
+    let quirks = if version >= 5 { 2 } else { 1 };
+    let fields = 16 + 2 + 2 + 64 * 32 + 2 + 16 * 2 + 2 + 16 + quirks + 2 + 8 + counts + 8;
+    Some(header + memory + fields + memory / 8)
+}
+
+// reads the fields of a save state back in the order they were written. public for the formats
+// chip8 builds on save states, like movies, but not part of the API
+#[doc(hidden)]
+pub struct StateReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], StateError> {
+        if self.data.len() < len {
+            return Err(StateError::Truncated);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, StateError> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, StateError> {
+        let mut b = [0; 4];
+        b.copy_from_slice(self.bytes(4)?);
+        Ok(u32::from_be_bytes(b))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, StateError> {
+        let mut b = [0; 8];
+        b.copy_from_slice(self.bytes(8)?);
+        Ok(u64::from_be_bytes(b))
+    }
+}
+
+type OpcodeFn = fn(&mut Chip8) -> Result<(), Error>;
+
+pub const CYCLES_PER_TICK: u8 = 5; // instructions run for every 60Hz timer tick
+const MIN_KEY_HOLD: u64 = 2 * CYCLES_PER_TICK as u64; // shortest a key press lasts, in cycles
+
+// opcodes by their first hex digit, for set_throttle
+pub const OPCODE_CLASSES: [&str; 16] = [
+    "sys", "jp", "call", "se", "sne", "sereg", "ld", "add", "alu", "snereg", "ldi", "jpv", "rnd",
+    "drw", "key", "misc",
+];
+
+// an opcode class by name or first hex digit, e.g. drw or D
+pub fn opcode_class(s: &str) -> Option<usize> {
+    (OPCODE_CLASSES
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(s)))
+    .or_else(|| {
+        u8::from_str_radix(s, 16)
+            .ok()
+            .filter(|_| s.len() == 1)
+            .map(|n| n as usize)
+    })
+}
+
+// each bit of a sprite byte spread out to a byte of its own, leftmost pixel in the lowest byte, to
+// match 8 pixels of gfx read as a little-endian u64
+const SPREAD: [u64; 256] = {
+    let mut table = [0; 256];
+    let mut b = 0;
+    while b < 256 {
+        let mut p = 0;
+        while p < 8 {
+            table[b] |= (b as u64 >> (7 - p) & 1) << (8 * p);
+            p += 1;
+        }
+        b += 1;
+    }
+    table
+};
+
+pub struct Chip8 {
+    // CHIP-8 VM
+    opcode: u16,           // current opcode
+    bus: MemoryBus,        // system memory
+    v: [u8; 16],           // registers V0-VE (VF is flag for some instructions)
+    i: u16,                // address register
+    pc: u16,               // program counter
+    gfx: [u8; 64 * 32],    // pixels state
+    screen: [u8; 64 * 32], // gfx as of the last tick, for the vblank draw quirk
+    delay_timer: u8,
+    sound_timer: u8, // timers count down at 60Hz
+    stack: [u16; 16],
+    sp: u16,                // stack pointer
+    key: [u8; 16],          // hex keypad state
+    key_down_at: [u64; 16], // cycle each key was last pressed on
+    key_release: u16, // keys let go of too quickly, released once they've been held long enough
+
+    // emulator resources
+    quirks: Quirks,
+    permissive: bool, // carry on past ROM bugs that would otherwise be errors, see set_permissive
+    throttle: [u8; 16], // extra cycles each class of opcode takes, see set_throttle
+    key_wait_timeout: Option<u64>, // ticks FX0A waits before it's an error, see set_key_wait_timeout
+    key_wait: Option<(u16, u64)>,  // the FX0A waiting and the tick it started on
+    vblank: bool, // a 60Hz tick has happened since the last draw, for the display wait quirk
+    draw_flag: bool,
+    rng: StdRng,
+    cycles: u64,       // instructions run, and any extra cycles they were throttled by
+    instructions: u64, // just the instructions
+    frames: u64,       // cycles that ended with the draw flag up
+    clock: Box<dyn Clock + Send>, // source of 60Hz ticks for the timers
+    clock_ticks: u64,  // ticks the clock had given when last asked
+    ticks: u64,        // ticks counted down on the timers
+    opcode_fns: [OpcodeFn; 16],
+    executed: Vec<bool>, // addresses that have been fetched as part of an instruction
+    events: Vec<Event>, // events raised by the current instruction, handed to the observer after it runs
+}
+
+// seeded from the host's entropy. without std there's none to be had, so every VM starts from
+// the same seed until seed_rng is given one from the board
+#[cfg(feature = "std")]
+fn new_rng() -> StdRng {
+    StdRng::from_entropy()
+}
+
+#[cfg(not(feature = "std"))]
+fn new_rng() -> StdRng {
+    StdRng::seed_from_u64(0)
+}
+
+impl Chip8 {
+    pub fn new() -> Self {
+        let mut bus = MemoryBus::new(bus::SIZE);
+
+        // CHIP-8 systems had the interpreter in the first 512 bytes of memory
+        // since we're emulating that we can just store the fontset there
+        bus.ram_mut()[fonts::FONT_START..][..fonts::FONT_LEN].copy_from_slice(&fonts::SCHIP);
+
+        Self {
+            opcode: 0,
+            bus,
+            v: [0; 16],
+            i: 0,
+            pc: PROGRAM_START,
+            gfx: [0; 64 * 32],
+            screen: [0; 64 * 32],
+            delay_timer: 0,
+            sound_timer: 0,
+            stack: [0; 16],
+            sp: 0,
+            key: [0; 16],
+            key_down_at: [0; 16],
+            key_release: 0,
+
+            quirks: Quirks::default(),
+            permissive: false,
+            throttle: [0; 16],
+            key_wait_timeout: None,
+            key_wait: None,
+            vblank: false,
+            draw_flag: false,
+            rng: new_rng(),
+            cycles: 0,
+            instructions: 0,
+            frames: 0,
+            clock: Box::new(CycleClock),
+            clock_ticks: 0,
+            ticks: 0,
+            opcode_fns: [
+                Self::cls_ret, // 00**
+                Self::jmp,     // 1NNN
+                Self::call,    // 2NNN
+                Self::eb,      // 3XNN
+                Self::neb,     // 4XNN
+                Self::er,      // 5XY0
+                Self::ld,      // 6XNN
+                Self::addb,    // 7XNN
+                Self::alu,     // 8XY*
+                Self::ner,     // 9XY0
+                Self::si,      // ANNN
+                Self::jmpo,    // BNNN
+                Self::rng,     // CXNN
+                Self::draw,    // DXYN
+                Self::key,     // EX**
+                Self::ex,      // FX**
+            ],
+            executed: vec![false; bus::SIZE],
+            events: Vec::new(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load_game<P: AsRef<std::path::Path>>(&mut self, filename: P) -> std::io::Result<()> {
+        use std::io::Read;
+        let mut file = std::fs::File::open(filename)?;
+        let _ = file.read(&mut self.bus.ram_mut()[PROGRAM_START as usize..])?;
+        Ok(())
+    }
+
+    // replace the hex digits FX29 uses, see fonts
+    pub fn set_font(&mut self, font: &Font) {
+        self.bus.ram_mut()[fonts::FONT_START..][..fonts::FONT_LEN].copy_from_slice(font);
+    }
+
+    pub fn load_rom(&mut self, rom: &[u8]) {
+        self.load_rom_at(rom, PROGRAM_START);
+    }
+
+    // load a ROM assembled for somewhere other than PROGRAM_START, or a fragment of one. as much
+    // as fits before the end of memory is loaded, and the PC is left alone, see set_pc
+    pub fn load_rom_at(&mut self, rom: &[u8], org: u16) {
+        let memory = self.bus.ram_mut();
+        let org = (org as usize).min(memory.len());
+        let len = rom.len().min(memory.len() - org);
+        memory[org..org + len].copy_from_slice(&rom[..len]);
+    }
+
+    // where the next instruction is fetched from, to start a ROM somewhere other than where it
+    // was loaded
+    pub fn set_pc(&mut self, pc: u16) {
+        self.pc = pc;
+    }
+
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    // fill memory, the registers, timers and stack with garbage the way real hardware comes up,
+    // to catch ROMs counting on them being zero. the font is left alone and the VM's RNG seeded
+    // from the same seed, so a run can be repeated. call it before loading the ROM
+    pub fn randomize(&mut self, seed: u64) {
+        self.seed_rng(seed);
+        let font = fonts::FONT_START..fonts::FONT_START + fonts::FONT_LEN;
+        for (addr, b) in self.bus.ram_mut().iter_mut().enumerate() {
+            if !font.contains(&addr) {
+                *b = self.rng.gen();
+            }
+        }
+        self.rng.fill(&mut self.v);
+        self.i = self.rng.gen();
+        self.delay_timer = self.rng.gen();
+        self.sound_timer = self.rng.gen();
+        self.rng.fill(&mut self.stack);
+    }
+
+    // returns the clock that was being used
+    pub fn set_clock(&mut self, clock: Box<dyn Clock + Send>) -> Box<dyn Clock + Send> {
+        let old = core::mem::replace(&mut self.clock, clock);
+        self.resync_clock();
+        old
+    }
+
+    // map host features into the top of memory, see mmio
+    pub fn set_mmio(&mut self, mmio: Option<Box<dyn Mmio + Send>>) {
+        self.bus.set_mmio(mmio);
+    }
+
+    // make every opcode in a class of OPCODE_CLASSES take extra cycles more, as far as the clock,
+    // timers and key presses can tell, e.g. to slow down only draws and find a ROM that
+    // depends on how fast they are. not part of save states
+    pub fn set_throttle(&mut self, class: usize, extra: u8) {
+        self.throttle[class] = extra;
+    }
+
+    // make FX0A an error once it's waited this many 60Hz ticks for a key, for running headless
+    // where no key is ever coming and a ROM waiting for one would otherwise never stop. None, the
+    // default, waits forever. not part of save states
+    pub fn set_key_wait_timeout(&mut self, ticks: Option<u64>) {
+        self.key_wait_timeout = ticks;
+        self.key_wait = None;
+    }
+
+    pub fn key_wait_timeout(&self) -> Option<u64> {
+        self.key_wait_timeout
+    }
+
+    pub fn throttle(&self) -> &[u8; 16] {
+        &self.throttle
+    }
+
+    // forget any ticks the clock has given since the VM last ran, e.g. while it was paused
+    pub fn resync_clock(&mut self) {
+        self.clock_ticks = self.clock.ticks(self.cycles);
+    }
+
+    // the ROM has finished, with a jump to itself as is common or by exiting with 00FD
+    pub fn halted(&self) -> bool {
+        matches!(self.next_opcode(), Some(op) if op == 0x1000 | self.pc) || self.exited()
+    }
+
+    // the ROM ran SUPER-CHIP's 00FD to quit the interpreter
+    pub fn exited(&self) -> bool {
+        self.next_opcode() == Some(0x00FD)
+    }
+
+    fn next_opcode(&self) -> Option<u16> {
+        let pc = self.pc as usize;
+        let memory = self.bus.ram();
+        (pc + 1 < memory.len()).then(|| (memory[pc] as u16) << 8 | memory[pc + 1] as u16)
+    }
+
+    // cycles the clock has been given since the VM was created: instructions run, plus whatever
+    // extra set_throttle makes them take
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    // instructions run since the VM was created, whatever they were throttled by. the count
+    // before an instruction runs is its number in a trace and what the debugger's until cycle
+    // stops on
+    pub fn cycles_executed(&self) -> u64 {
+        self.instructions
+    }
+
+    // cycles that have ended with the draw flag up since the VM was created, the frames a
+    // frontend presenting on the draw flag has shown
+    pub fn frames_rendered(&self) -> u64 {
+        self.frames
+    }
+
+    // 60Hz ticks the timers have counted since the VM was created or loaded, unlike the clock's
+    // own count this skips any time spent paused
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    // also resizes memory for the xo-memory quirk, anything past 4K is lost going back to 4K
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+        let size = if quirks.xo_memory {
+            bus::XO_SIZE
+        } else {
+            bus::SIZE
+        };
+        self.bus.resize(size);
+        self.executed.resize(size, false);
+    }
+
+    // for playing ROMs rather than debugging them: addresses past the end of memory wrap around
+    // instead of being errors, and a PC left on an odd address by a bad jump is moved back to the
+    // even one before it
+    pub fn set_permissive(&mut self, permissive: bool) {
+        self.permissive = permissive;
+        self.bus.set_bounds(if permissive {
+            Bounds::Wrap
+        } else {
+            Bounds::Fault
+        });
+    }
+
+    pub fn draw_flag(&self) -> bool {
+        self.draw_flag
+    }
+
+    pub fn gfx(&self) -> &[u8] {
+        &self.gfx
+    }
+
+    // what a frontend should show, which is gfx unless the vblank draw quirk holds draws back
+    // until the tick
+    pub fn screen(&self) -> &[u8] {
+        if self.quirks.vblank_draw {
+            &self.screen
+        } else {
+            &self.gfx
+        }
+    }
+
+    pub fn pc(&self) -> u16 {
+        self.pc
+    }
+
+    pub fn i(&self) -> u16 {
+        self.i
+    }
+
+    pub fn v(&self) -> &[u8] {
+        &self.v
+    }
+
+    // return addresses, innermost last
+    pub fn stack(&self) -> &[u16] {
+        &self.stack[..self.sp as usize]
+    }
+
+    pub fn delay_timer(&self) -> u8 {
+        self.delay_timer
+    }
+
+    pub fn sound_timer(&self) -> u8 {
+        self.sound_timer
+    }
+
+    // the RAM, without going through the bus
+    pub fn memory(&self) -> &[u8] {
+        self.bus.ram()
+    }
+
+    pub fn memory_mut(&mut self) -> &mut [u8] {
+        self.bus.ram_mut()
+    }
+
+    pub fn bus_mut(&mut self) -> &mut MemoryBus {
+        &mut self.bus
+    }
+
+    pub fn sound_flag(&self) -> bool {
+        self.sound_timer > 0
+    }
+
+    pub fn is_key_pressed(&self, key: usize) -> bool {
+        self.key[key] == 1
+    }
+
+    // the keys that are down, a bit per key
+    pub fn keys(&self) -> u16 {
+        (0..16).fold(0, |keys, k| keys | (self.key[k] as u16) << k)
+    }
+
+    pub fn clear_keys(&mut self) {
+        self.key = [0; 16];
+        self.key_release = 0;
+    }
+
+    pub fn press_key(&mut self, key: usize) {
+        self.key[key] = 1;
+        self.key_down_at[key] = self.cycles;
+        self.key_release &= !(1 << key);
+    }
+
+    // a key tapped faster than a ROM polls input would be missed, so it stays down until it's
+    // been held for at least MIN_KEY_HOLD cycles
+    pub fn release_key(&mut self, key: usize) {
+        if self.cycles - self.key_down_at[key] >= MIN_KEY_HOLD {
+            self.key[key] = 0;
+            self.key_release &= !(1 << key);
+        } else {
+            self.key_release |= 1 << key;
+        }
+    }
+
+    // sets a key straight away, for callers that decide the keys for every frame themselves
+    pub fn set_key(&mut self, key: usize, pressed: bool) {
+        self.key[key] = pressed as u8;
+        self.key_release &= !(1 << key);
+    }
+
+    // set_key for all the keys at once, a bit per key
+    pub fn set_keys(&mut self, keys: u16) {
+        for key in 0..16 {
+            self.set_key(key, keys & (1 << key) != 0);
+        }
+    }
+
+    // everything needed to resume the VM later, as a versioned big-endian blob. the RNG can't be
+    // serialized so it's reseeded from a value stored in the state, which keeps this VM and any
+    // loaded from the state producing the same numbers
+    pub fn save_state(&mut self) -> Vec<u8> {
+        let seed = self.rng.gen();
+        self.seed_rng(seed);
+
+        let mut out = Vec::with_capacity(self.bus.len() * 2);
+        out.extend_from_slice(STATE_MAGIC);
+        out.push(STATE_VERSION);
+        out.extend_from_slice(&self.opcode.to_be_bytes());
+        out.extend_from_slice(&(self.bus.len() as u32).to_be_bytes());
+        out.extend_from_slice(self.bus.ram());
+        out.extend_from_slice(&self.v);
+        out.extend_from_slice(&self.i.to_be_bytes());
+        out.extend_from_slice(&self.pc.to_be_bytes());
+        out.extend_from_slice(&self.gfx);
+        out.push(self.delay_timer);
+        out.push(self.sound_timer);
+        for s in &self.stack {
+            out.extend_from_slice(&s.to_be_bytes());
+        }
+        out.extend_from_slice(&self.sp.to_be_bytes());
+        out.extend_from_slice(&self.key);
+        out.extend_from_slice(&self.quirks.bits().to_be_bytes());
+        out.push(self.vblank as u8);
+        out.push(self.draw_flag as u8);
+        out.extend_from_slice(&self.cycles.to_be_bytes());
+        out.extend_from_slice(&self.instructions.to_be_bytes());
+        out.extend_from_slice(&self.frames.to_be_bytes());
+        out.extend_from_slice(&seed.to_be_bytes());
+        for chunk in self.executed.chunks(8) {
+            out.push(chunk.iter().rev().fold(0, |b, &e| b << 1 | e as u8));
+        }
+        out
+    }
+
+    // replace the whole VM with a state from save_state, leaving it untouched if that fails
+    pub fn load_state(&mut self, state: &[u8]) -> Result<(), StateError> {
+        let mut r = StateReader { data: state };
+        if r.bytes(4).map_err(|_| StateError::NotAState)? != STATE_MAGIC {
+            return Err(StateError::NotAState);
+        }
+        let version = match r.u8()? {
+            v @ 2..=STATE_VERSION => v,
+            v => return Err(StateError::UnsupportedVersion(v)),
+        };
+
+        let mut vm = Self::new();
+        vm.opcode = r.u16()?;
+        let size = if version == 2 {
+            bus::SIZE
+        } else {
+            r.u32()? as usize
+        };
+        if size != bus::SIZE && size != bus::XO_SIZE {
+            return Err(StateError::Corrupt);
+        }
+        vm.bus.resize(size);
+        vm.executed.resize(size, false);
+        vm.bus.ram_mut().copy_from_slice(r.bytes(size)?);
+        vm.v.copy_from_slice(r.bytes(16)?);
+        vm.i = r.u16()?;
+        vm.pc = r.u16()?;
+        vm.gfx.copy_from_slice(r.bytes(64 * 32)?);
+        vm.screen = vm.gfx;
+        vm.delay_timer = r.u8()?;
+        vm.sound_timer = r.u8()?;
+        for s in vm.stack.iter_mut() {
+            *s = r.u16()?;
+        }
+        vm.sp = r.u16()?;
+        vm.key.copy_from_slice(r.bytes(16)?);
+        vm.quirks = Quirks::from_bits(if version >= 5 {
+            r.u16()?
+        } else {
+            r.u8()? as u16
+        });
+        // memory is sized by the quirk, a state where they disagree has been tampered with
+        if vm.quirks.xo_memory != (size == bus::XO_SIZE) {
+            return Err(StateError::Corrupt);
+        }
+        vm.vblank = r.u8()? != 0;
+        vm.draw_flag = r.u8()? != 0;
+        vm.cycles = r.u64()?;
+        // older states didn't count them, so take every cycle to have been an instruction
+        (vm.instructions, vm.frames) = if version >= 4 {
+            (r.u64()?, r.u64()?)
+        } else {
+            (vm.cycles, 0)
+        };
+        vm.seed_rng(r.u64()?);
+        for (chunk, bits) in vm.executed.chunks_mut(8).zip(r.bytes(size / 8)?) {
+            for (bit, e) in chunk.iter_mut().enumerate() {
+                *e = bits & (1 << bit) != 0;
+            }
+        }
+        if vm.sp as usize > vm.stack.len() || vm.gfx.iter().any(|&p| p > 1) {
+            return Err(StateError::Corrupt);
+        }
+
+        vm.clock = core::mem::replace(&mut self.clock, Box::new(CycleClock));
+        vm.bus.set_mmio(self.bus.take_mmio());
+        vm.bus.set_bounds(self.bus.bounds());
+        vm.permissive = self.permissive;
+        vm.throttle = self.throttle;
+        vm.key_wait_timeout = self.key_wait_timeout;
+        *self = vm;
+        self.resync_clock();
+        Ok(())
+    }
+
+    pub fn emulate_cycle(&mut self) -> Result<(), Error> {
+        self.emulate_cycle_observed(&mut ())
+    }
+
+    pub fn emulate_cycle_observed(&mut self, observer: &mut dyn Observer) -> Result<(), Error> {
+        if self.permissive {
+            self.pc = ((self.pc as usize & !1) % self.bus.len()) as u16;
+        }
+        let pc = self.pc as usize;
+        // two-byte opcodes, which can start on an odd address as they could on the original
+        // interpreter. one starting on the last byte is past the end unless addresses wrap
+        self.opcode = self
+            .bus
+            .read16(pc)
+            .map_err(|_| Error::PcOutOfBounds { pc: self.pc })?;
+        let len = self.bus.len();
+        self.executed[pc % len] = true;
+        self.executed[(pc + 1) % len] = true;
+        observer.on_event(
+            self,
+            &Event::Exec {
+                pc: self.pc,
+                opcode: self.opcode,
+            },
+        );
+
+        self.draw_flag = false;
+
+        let f = self.opcode_fns[((self.opcode & 0xF000) >> 12) as usize];
+        let result = f(self);
+
+        let events = core::mem::take(&mut self.events);
+        for e in &events {
+            observer.on_event(self, e);
+        }
+        self.events = events;
+        self.events.clear();
+        result?;
+
+        self.cycles += 1 + self.throttle[(self.opcode >> 12) as usize] as u64;
+        self.instructions += 1;
+        let ticks = self.clock.ticks(self.cycles);
+        let new_ticks = ticks.saturating_sub(self.clock_ticks).min(255) as u8;
+        self.clock_ticks = ticks;
+        self.ticks += new_ticks as u64;
+        self.bus.ticks = self.ticks;
+        if new_ticks > 0 {
+            self.vblank = true;
+            self.delay_timer = self.delay_timer.saturating_sub(new_ticks);
+            self.sound_timer = self.sound_timer.saturating_sub(new_ticks);
+        }
+        if self.quirks.vblank_draw {
+            // the draw flag is up when the screen changes rather than when the ROM draws
+            self.draw_flag = new_ticks > 0 && self.screen != self.gfx;
+            if self.draw_flag {
+                self.screen = self.gfx;
+            }
+        }
+        if self.draw_flag {
+            self.frames += 1;
+        }
+        if self.key_release != 0 {
+            for key in 0..16 {
+                if self.key_release & (1 << key) != 0 {
+                    self.release_key(key);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn unknown_opcode(&self) -> Error {
+        Error::UnknownOpcode {
+            pc: self.pc,
+            opcode: self.opcode,
+        }
+    }
+
+    // make sure an instruction touching len bytes of memory at start stays inside memory
+    fn check_range(&self, start: usize, len: usize) -> Result<usize, Error> {
+        if self.bus.bounds() == Bounds::Fault && start + len > self.bus.len() {
+            return Err(Error::MemoryOutOfBounds {
+                pc: self.pc,
+                addr: start + len - 1,
+            });
+        }
+        Ok(start)
+    }
+
+    // the PC wraps past the top of memory rather than overflowing, a 64K XO-CHIP PC can get there
+    fn advance(&mut self, by: u16) {
+        self.pc = self.pc.wrapping_add(by);
+    }
+
+    // how far a skip instruction moves the PC when it skips, F000 NNNN is a 4 byte instruction
+    // that has to be skipped whole
+    fn skip_len(&self) -> u16 {
+        let next = self.pc as usize + 2;
+        if self.quirks.xo_memory && self.bus.ram().get(next..next + 2) == Some(&[0xF0, 0]) {
+            6
+        } else {
+            4
+        }
+    }
+
+    fn vf_reset(&mut self) {
+        if self.quirks.vf_reset {
+            self.v[0xF] = 0;
+        }
+    }
+
+    fn shift_source(&self, x: usize, y: usize) -> u8 {
+        if self.quirks.shifting {
+            self.v[x]
+        } else {
+            self.v[y]
+        }
+    }
+
+    // with the memory quirk FX55/FX65 leave I just past the last register they touched
+    fn memory_quirk(&mut self, x: usize) {
+        if self.quirks.memory {
+            self.i = self.i.wrapping_add(x as u16 + 1);
+        }
+    }
+
+    fn load(&mut self, addr: usize) -> Result<u8, Error> {
+        let pc = self.pc;
+        self.bus
+            .read8(addr)
+            .map_err(|addr| Error::MemoryOutOfBounds { pc, addr })
+    }
+
+    // every store to memory by an instruction goes through here so observers can see it
+    fn store(&mut self, addr: usize, value: u8) -> Result<(), Error> {
+        let pc = self.pc;
+        let old = self.bus.ram()[addr % self.bus.len()];
+        self.bus
+            .write8(addr, value)
+            .map_err(|addr| Error::MemoryOutOfBounds { pc, addr })?;
+        let addr = (addr % self.bus.len()) as u16;
+        self.events.push(Event::Write { pc, addr, value });
+        if self.executed[addr as usize] {
+            let new = value;
+            self.events.push(Event::SelfModify { pc, addr, old, new });
+        }
+        Ok(())
+    }
+
+    fn cls_ret(&mut self) -> Result<(), Error> {
+        match self.opcode & 0xFF {
+            0xE0 => {
+                // 00E0
+                // clear screen
+                self.gfx = [0; 64 * 32];
+                self.advance(2);
+            }
+            0xEE => {
+                // 00EE
+                // return from subroutine
+                if self.sp < 1 {
+                    return Err(Error::StackUnderflow { pc: self.pc });
+                }
+                self.sp -= 1;
+                let sp = self.sp as usize;
+                self.pc = self.stack[sp].wrapping_add(2);
+                self.stack[sp] = 0;
+            }
+            0xFD => {
+                // 00FD
+                // exit, which here means staying put so the VM is halted
+            }
+            0xC0..=0xCF => {
+                // 00CN
+                // scroll the screen down N rows
+                let n = (self.opcode & 0xF) as usize * 64;
+                self.gfx.copy_within(..64 * 32 - n, n);
+                self.gfx[..n].fill(0);
+                self.scrolled();
+            }
+            0xD0..=0xDF => {
+                // 00DN
+                // scroll the screen up N rows, as on XO-CHIP
+                let n = (self.opcode & 0xF) as usize * 64;
+                self.gfx.copy_within(n.., 0);
+                self.gfx[64 * 32 - n..].fill(0);
+                self.scrolled();
+            }
+            0xFB => {
+                // 00FB
+                // scroll the screen right 4 pixels
+                for row in self.gfx.chunks_exact_mut(64) {
+                    row.copy_within(..60, 4);
+                    row[..4].fill(0);
+                }
+                self.scrolled();
+            }
+            0xFC => {
+                // 00FC
+                // scroll the screen left 4 pixels
+                for row in self.gfx.chunks_exact_mut(64) {
+                    row.copy_within(4.., 0);
+                    row[60..].fill(0);
+                }
+                self.scrolled();
+            }
+            _ => return Err(self.unknown_opcode()),
+        }
+        Ok(())
+    }
+
+    fn jmp(&mut self) -> Result<(), Error> {
+        // 1NNN
+        // jump to NNN
+        self.pc = self.opcode & 0x0FFF;
+        Ok(())
+    }
+
+    fn call(&mut self) -> Result<(), Error> {
+        // 2NNN
+        // call subroutine at NNN
+        if self.sp as usize == self.stack.len() {
+            return Err(Error::StackOverflow { pc: self.pc });
+        }
+        self.stack[self.sp as usize] = self.pc;
+        self.sp += 1;
+        self.pc = self.opcode & 0x0FFF;
+        Ok(())
+    }
+
+    fn eb(&mut self) -> Result<(), Error> {
+        // 3XNN
+        // skip if VX == NN
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let n = (self.opcode & 0xFF) as u8;
+        self.advance(if self.v[x] == n { self.skip_len() } else { 2 });
+        Ok(())
+    }
+
+    fn neb(&mut self) -> Result<(), Error> {
+        // 4XNN
+        // skip if VX != NN
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let n = (self.opcode & 0xFF) as u8;
+        self.advance(if self.v[x] != n { self.skip_len() } else { 2 });
+        Ok(())
+    }
+
+    fn er(&mut self) -> Result<(), Error> {
+        // 5XY0
+        // skip if VX == VY
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let y = ((self.opcode & 0xF0) >> 4) as usize;
+        self.advance(if self.v[x] == self.v[y] {
+            self.skip_len()
+        } else {
+            2
+        });
+        Ok(())
+    }
+
+    fn ld(&mut self) -> Result<(), Error> {
+        // 6XNN
+        // set VX to NN
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let n = (self.opcode & 0xFF) as u8;
+        self.v[x] = n;
+        self.advance(2);
+        Ok(())
+    }
+
+    fn addb(&mut self) -> Result<(), Error> {
+        // 7XNN
+        // add NN to VX (no carry)
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let n = (self.opcode & 0xFF) as u8;
+        self.v[x] = self.v[x].wrapping_add(n);
+        self.advance(2);
+        Ok(())
+    }
+
+    fn alu(&mut self) -> Result<(), Error> {
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let y = ((self.opcode & 0xF0) >> 4) as usize;
+        match self.opcode & 0xF {
+            0x0 => {
+                // 8XY0
+                // set VX to VY
+                self.v[x] = self.v[y];
+            }
+            0x1 => {
+                // 8XY1
+                // set VX to VX OR VY
+                self.v[x] |= self.v[y];
+                self.vf_reset();
+            }
+            0x2 => {
+                // 8XY2
+                // set VX to VX AND VY
+                self.v[x] &= self.v[y];
+                self.vf_reset();
+            }
+            0x3 => {
+                // 8XY3
+                // set VX to VX XOR VY
+                self.v[x] ^= self.v[y];
+                self.vf_reset();
+            }
+            0x4 => {
+                // 8XY4
+                // add VY to VX (set VF = 1 if there's a carry)
+                // VF is always written last so it wins when it's also the destination
+                let (sum, carry) = self.v[x].overflowing_add(self.v[y]);
+                self.v[x] = sum;
+                self.v[0xF] = carry as u8;
+            }
+            0x5 => {
+                // 8XY5
+                // sub VY from VX (set VF = 0 if there's a borrow and 1 if not)
+                let (diff, borrow) = self.v[x].overflowing_sub(self.v[y]);
+                self.v[x] = diff;
+                self.v[0xF] = !borrow as u8;
+            }
+            0x6 => {
+                // 8XY6
+                // store the LSB of VY in VF and set VX to VY shifted one to the right
+                // (VX is shifted in place with the shifting quirk)
+                let src = self.shift_source(x, y);
+                self.v[x] = src >> 1;
+                self.v[0xF] = src & 0x1;
+            }
+            0x7 => {
+                // 8XY7
+                // set VX to VY - VX (set VF = 0 if there's a borrow and 1 if not)
+                let (diff, borrow) = self.v[y].overflowing_sub(self.v[x]);
+                self.v[x] = diff;
+                self.v[0xF] = !borrow as u8;
+            }
+            0xE => {
+                // 8XYE
+                // store the MSB of VY in VF and set VX to VY shifted one to the left
+                // (VX is shifted in place with the shifting quirk)
+                let src = self.shift_source(x, y);
+                self.v[x] = src << 1;
+                self.v[0xF] = src >> 7;
+            }
+            _ => return Err(self.unknown_opcode()),
+        }
+        self.advance(2);
+        Ok(())
+    }
+
+    fn ner(&mut self) -> Result<(), Error> {
+        // 9XY0
+        // skip if VX != VY
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let y = ((self.opcode & 0xF0) >> 4) as usize;
+        self.advance(if self.v[x] != self.v[y] {
+            self.skip_len()
+        } else {
+            2
+        });
+        Ok(())
+    }
+
+    fn si(&mut self) -> Result<(), Error> {
+        // ANNN
+        // set I to NNN
+        self.i = self.opcode & 0xFFF;
+        self.advance(2);
+        Ok(())
+    }
+
+    fn jmpo(&mut self) -> Result<(), Error> {
+        // BNNN
+        // jump to NNN + V0 (or NNN + VX with the jumping quirk)
+        let n = self.opcode & 0xFFF;
+        let x = if self.quirks.jumping {
+            ((self.opcode & 0xF00) >> 8) as usize
+        } else {
+            0
+        };
+        self.pc = n + self.v[x] as u16;
+        Ok(())
+    }
+
+    fn rng(&mut self) -> Result<(), Error> {
+        // CXNN
+        // Set VX = RNG[0, 256) & NN
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let n = (self.opcode & 0xFF) as u8;
+        self.v[x] = n & (self.rng.gen_range(0, 256) as u8);
+        self.advance(2);
+        Ok(())
+    }
+
+    fn draw(&mut self) -> Result<(), Error> {
+        // DXYN
+        // draw a sprite at VX,VY with a width of 8 pixels and a height of N pixels
+        // each row of 8 pixels is bit-coded in memory starting at I
+        // currently drawn pixels are XORd with pixels in memory
+        // VF is set to 1 if any currently drawn pixels are unset during this
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let y = ((self.opcode & 0xF0) >> 4) as usize;
+        let height = (self.opcode & 0xF) as usize;
+
+        if self.quirks.display_wait {
+            if !self.vblank {
+                return Ok(()); // try again next cycle
+            }
+            self.vblank = false;
+        }
+
+        // the starting position always wraps, the clipping quirk decides what happens past the edge
+        let vx = self.v[x] as usize % 64;
+        let vy = self.v[y] as usize % 32;
+        let i = self.check_range(self.i as usize, height)?;
+
+        // a screen row is 8 words of 8 pixels, and a sprite row spread out to match straddles two
+        // of them unless vx is a multiple of 8. past the right edge it wraps to the first word or
+        // is clipped
+        let (word, shift) = (vx / 8, vx % 8 * 8);
+        let next = match word + 1 {
+            8 if self.quirks.clipping => None,
+            w => Some(w % 8),
+        };
+        self.v[0xF] = 0; // gets set to 1 if any screen pixels are unset during draw
+        for row in 0..height {
+            if self.quirks.clipping && vy + row >= 32 {
+                break;
+            }
+            let pixels = SPREAD[self.load(i + row)? as usize]; // load sprite starting at I
+            let line = 64 * ((vy + row) % 32);
+            self.xor_pixels(line + word * 8, pixels << shift);
+            if let Some(next) = next.filter(|_| shift != 0) {
+                self.xor_pixels(line + next * 8, pixels >> (64 - shift));
+            }
+        }
+
+        self.draw_flag = true;
+        self.advance(2);
+        Ok(())
+    }
+
+    fn scrolled(&mut self) {
+        self.draw_flag = true;
+        self.advance(2);
+    }
+
+    // flip the 8 pixels of gfx at `at` where bits has a 1, setting VF if any were on
+    fn xor_pixels(&mut self, at: usize, bits: u64) {
+        let pixels: &mut [u8; 8] = match self.gfx.get_mut(at..at + 8).map(TryInto::try_into) {
+            Some(Ok(pixels)) => pixels,
+            _ => return,
+        };
+        let screen = u64::from_le_bytes(*pixels);
+        if screen & bits != 0 {
+            self.v[0xF] = 1;
+        }
+        *pixels = (screen ^ bits).to_le_bytes();
+    }
+
+    fn key(&mut self) -> Result<(), Error> {
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        let pressed = self.key[(self.v[x] & 0xF) as usize] == 1;
+        match self.opcode & 0xFF {
+            0x9E => {
+                // 0xEX9E
+                // skip if key stored in VX is pressed
+                self.advance(if pressed { self.skip_len() } else { 2 });
+            }
+            0xA1 => {
+                // 0xEXA1
+                // skip if key stored in VX isn't pressed
+                self.advance(if !pressed { self.skip_len() } else { 2 });
+            }
+
+            _ => return Err(self.unknown_opcode()),
+        }
+        Ok(())
+    }
+
+    fn ex(&mut self) -> Result<(), Error> {
+        let x = ((self.opcode & 0xF00) >> 8) as usize;
+        match self.opcode & 0xFF {
+            0x00 if x == 0 && self.quirks.xo_memory => {
+                // 0xF000 NNNN
+                // set I to the 16 bit address in the next two bytes
+                let pc = self.pc;
+                self.i = self
+                    .bus
+                    .read16(pc as usize + 2)
+                    .map_err(|_| Error::PcOutOfBounds { pc })?;
+                self.advance(2);
+            }
+            0x7 => {
+                // 0xFX07
+                // set VX to delay timer. with the timer-phase quirk, a read on the instruction a
+                // tick lands on gets the count it's going to, as if the interrupt got there first
+                let cycles = self.cycles + 1 + self.throttle[0xF] as u64;
+                let landing =
+                    self.quirks.timer_phase && self.clock.ticks(cycles) > self.clock_ticks;
+                self.v[x] = self.delay_timer.saturating_sub(landing as u8);
+            }
+            0xA => {
+                // 0xFX0A
+                // store next key press in VX, blocking instruction
+                let mut pressed = false;
+                // check all keys recording the first pressed one
+                for i in 0..=0xF {
+                    if self.key[i as usize] == 1 {
+                        pressed = true;
+                        self.v[x] = i;
+                        break;
+                    }
+                }
+                if pressed {
+                    self.key_wait = None;
+                } else {
+                    let pc = self.pc;
+                    let since = match self.key_wait {
+                        Some((at, since)) if at == pc => since,
+                        _ => self.ticks,
+                    };
+                    if self
+                        .key_wait_timeout
+                        .is_some_and(|t| self.ticks.saturating_sub(since) >= t)
+                    {
+                        self.key_wait = None;
+                        return Err(Error::KeyWaitTimeout { pc });
+                    }
+                    self.key_wait = Some((pc, since));
+                    self.pc = pc.wrapping_sub(2); // repeat this instruction if no pressed key
+                }
+            }
+            0x15 => {
+                // 0xFX15
+                // set delay timer to vx
+                self.delay_timer = self.v[x];
+            }
+            0x18 => {
+                // 0xFX18
+                // set sound timer to vx
+                self.sound_timer = self.v[x];
+            }
+            0x1E => {
+                // 0xFX1E
+                // add VX to I
+                self.i = self.i.wrapping_add(self.v[x] as u16);
+            }
+            0x29 => {
+                // 0xFX29
+                // set I to location in memory of sprite for character in VX
+                self.i = (fonts::FONT_START + fonts::GLYPH_LEN * self.v[x] as usize) as u16;
+            }
+            0x33 => {
+                // 0xFX33
+                // store the BCD representation of VX at I
+                // so 193 becomes [1, 9, 3] in memory at I
+                let vx = self.v[x];
+                let i = self.check_range(self.i as usize, 3)?;
+                self.store(i, vx / 100)?;
+                self.store(i + 1, (vx / 10) % 10)?;
+                self.store(i + 2, (vx % 100) % 10)?;
+            }
+            0x55 => {
+                // 0xFX55
+                // store V0 to VX (inclusive) in memory at I
+                let i = self.check_range(self.i as usize, x + 1)?;
+                for r in 0..=x {
+                    self.store(i + r, self.v[r])?;
+                }
+                self.memory_quirk(x);
+            }
+            0x65 => {
+                // 0xFX65
+                // fill V0 to VX (inclusive) from memory at I
+                let i = self.check_range(self.i as usize, x + 1)?;
+                for r in 0..=x {
+                    self.v[r] = self.load(i + r)?;
+                }
+                self.memory_quirk(x);
+            }
+            _ => return Err(self.unknown_opcode()),
+        }
+        self.advance(2);
+        Ok(())
+    }
+}
+
+impl Default for Chip8 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the machine state as a report: registers in rows of eight, I, the instruction at the PC,
+// the timers and the stack
+impl fmt::Display for Chip8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, row) in self.v.chunks(8).enumerate() {
+            let regs: Vec<String> = (row.iter().enumerate())
+                .map(|(i, v)| format!("V{:X} {:02X}", n * 8 + i, v))
+                .collect();
+            writeln!(f, "{}", regs.join("  "))?;
+        }
+        let memory = self.bus.ram();
+        let pc = self.pc as usize;
+        let opcode =
+            u16::from_be_bytes([memory[pc % memory.len()], memory[(pc + 1) % memory.len()]]);
+        writeln!(
+            f,
+            "I  {:03X}  PC {:03X}: {:04X}  {}",
+            self.i,
+            self.pc,
+            opcode,
+            disasm::Instruction::decode(opcode)
+        )?;
+        writeln!(
+            f,
+            "DT {:02X}  ST {:02X}",
+            self.delay_timer, self.sound_timer
+        )?;
+        let stack: Vec<String> = self.stack().iter().map(|a| format!("{:03X}", a)).collect();
+        write!(f, "stack {}/{}", self.sp, self.stack.len())?;
+        if !stack.is_empty() {
+            write!(f, ": {}", stack.join(" "))?;
+        }
+        writeln!(f)
+    }
+}
+
+impl fmt::Debug for Chip8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
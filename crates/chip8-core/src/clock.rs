@@ -0,0 +1,76 @@
+use crate::chip8::CYCLES_PER_TICK;
+#[cfg(target_has_atomic = "64")]
+use alloc::sync::Arc;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+const TICK: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+// where the VM's 60Hz ticks (timers, display wait) come from. ticks returns how many ticks have
+// happened in total, the VM counts down its timers once for every new one it sees
+pub trait Clock {
+    // cycles is how many instructions the VM has run, for clocks that follow the VM
+    fn ticks(&mut self, cycles: u64) -> u64;
+}
+
+// virtual time that ticks every CYCLES_PER_TICK instructions, so runs are repeatable no matter
+// how fast the host is. the default
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CycleClock;
+
+impl Clock for CycleClock {
+    fn ticks(&mut self, cycles: u64) -> u64 {
+        // the first tick lands on the first instruction
+        cycles.div_ceil(CYCLES_PER_TICK as u64)
+    }
+}
+
+// real 60Hz ticks from the host's monotonic clock. without std a board's timer does this with a
+// Clock of its own
+#[cfg(feature = "std")]
+pub struct RealClock {
+    start: Instant,
+}
+
+#[cfg(feature = "std")]
+impl Default for RealClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for RealClock {
+    fn ticks(&mut self, _: u64) -> u64 {
+        (self.start.elapsed().as_nanos() / TICK.as_nanos()) as u64
+    }
+}
+
+// ticks only when told to, so tests can put timers exactly where they want them. clones share
+// the same count so one can be handed to the VM and the other kept to advance it
+#[cfg(target_has_atomic = "64")]
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    ticks: Arc<AtomicU64>,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl ManualClock {
+    pub fn advance(&self, ticks: u64) {
+        self.ticks.fetch_add(ticks, Ordering::SeqCst);
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl Clock for ManualClock {
+    fn ticks(&mut self, _: u64) -> u64 {
+        self.ticks.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,351 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Instruction {
+    Cls,             // 00E0
+    Ret,             // 00EE
+    Exit,            // 00FD
+    ScrollDown(u8),  // 00CN
+    ScrollUp(u8),    // 00DN
+    ScrollRight,     // 00FB
+    ScrollLeft,      // 00FC
+    Sys(u16),        // 0NNN
+    Jp(u16),         // 1NNN
+    Call(u16),       // 2NNN
+    SeByte(u8, u8),  // 3XNN
+    SneByte(u8, u8), // 4XNN
+    SeReg(u8, u8),   // 5XY0
+    LdByte(u8, u8),  // 6XNN
+    AddByte(u8, u8), // 7XNN
+    LdReg(u8, u8),   // 8XY0
+    Or(u8, u8),      // 8XY1
+    And(u8, u8),     // 8XY2
+    Xor(u8, u8),     // 8XY3
+    AddReg(u8, u8),  // 8XY4
+    Sub(u8, u8),     // 8XY5
+    Shr(u8, u8),     // 8XY6
+    Subn(u8, u8),    // 8XY7
+    Shl(u8, u8),     // 8XYE
+    SneReg(u8, u8),  // 9XY0
+    LdI(u16),        // ANNN
+    JpV0(u16),       // BNNN
+    Rnd(u8, u8),     // CXNN
+    Drw(u8, u8, u8), // DXYN
+    Skp(u8),         // EX9E
+    Sknp(u8),        // EXA1
+    LdVxDt(u8),      // FX07
+    LdVxK(u8),       // FX0A
+    LdDtVx(u8),      // FX15
+    LdStVx(u8),      // FX18
+    AddI(u8),        // FX1E
+    LdF(u8),         // FX29
+    LdB(u8),         // FX33
+    LdIVx(u8),       // FX55
+    LdVxI(u8),       // FX65
+    Unknown(u16),
+}
+
+// what an instruction does, for looking up in the debugger
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OpcodeDoc {
+    pub pattern: &'static str, // as Instruction::pattern gives it
+    pub syntax: &'static str,  // as the disassembly shows it, with the operands named
+    pub summary: &'static str,
+    pub quirks: &'static [&'static str], // from quirks::NAMES, the ones that change what it does
+}
+
+const fn doc(
+    pattern: &'static str,
+    syntax: &'static str,
+    summary: &'static str,
+    quirks: &'static [&'static str],
+) -> OpcodeDoc {
+    OpcodeDoc {
+        pattern,
+        syntax,
+        summary,
+        quirks,
+    }
+}
+
+// every instruction the decoder knows, in opcode order
+pub const OPCODES: [OpcodeDoc; 40] = [
+    doc("00CN", "SCD N", "scroll the screen down N rows (SCHIP)", &[]),
+    doc("00DN", "SCU N", "scroll the screen up N rows (XO-CHIP)", &[]),
+    doc("00E0", "CLS", "clear the screen", &[]),
+    doc("00EE", "RET", "return from a subroutine, to the address on top of the stack", &[]),
+    doc("00FB", "SCR", "scroll the screen right 4 pixels (SCHIP)", &[]),
+    doc("00FC", "SCL", "scroll the screen left 4 pixels (SCHIP)", &[]),
+    doc("00FD", "EXIT", "stop the interpreter (SCHIP), here the VM halts", &[]),
+    doc(
+        "0NNN",
+        "SYS NNN",
+        "run the machine code routine at NNN on the original hardware, which can't be done here",
+        &[],
+    ),
+    doc("1NNN", "JP NNN", "jump to NNN", &[]),
+    doc("2NNN", "CALL NNN", "call the subroutine at NNN, pushing the return address on the stack", &[]),
+    doc("3XNN", "SE VX, NN", "skip the next instruction if VX is NN", &["xo-memory"]),
+    doc("4XNN", "SNE VX, NN", "skip the next instruction if VX isn't NN", &["xo-memory"]),
+    doc("5XY0", "SE VX, VY", "skip the next instruction if VX is VY", &["xo-memory"]),
+    doc("6XNN", "LD VX, NN", "set VX to NN", &[]),
+    doc("7XNN", "ADD VX, NN", "add NN to VX, wrapping around and leaving VF alone", &[]),
+    doc("8XY0", "LD VX, VY", "set VX to VY", &[]),
+    doc("8XY1", "OR VX, VY", "set VX to VX OR VY", &["vf-reset"]),
+    doc("8XY2", "AND VX, VY", "set VX to VX AND VY", &["vf-reset"]),
+    doc("8XY3", "XOR VX, VY", "set VX to VX XOR VY", &["vf-reset"]),
+    doc("8XY4", "ADD VX, VY", "add VY to VX, VF is 1 if it carried and 0 if not", &[]),
+    doc("8XY5", "SUB VX, VY", "subtract VY from VX, VF is 0 if it borrowed and 1 if not", &[]),
+    doc(
+        "8XY6",
+        "SHR VX, VY",
+        "shift VY right one bit into VX, VF is the bit shifted out",
+        &["shifting"],
+    ),
+    doc("8XY7", "SUBN VX, VY", "set VX to VY minus VX, VF is 0 if it borrowed and 1 if not", &[]),
+    doc(
+        "8XYE",
+        "SHL VX, VY",
+        "shift VY left one bit into VX, VF is the bit shifted out",
+        &["shifting"],
+    ),
+    doc("9XY0", "SNE VX, VY", "skip the next instruction if VX isn't VY", &["xo-memory"]),
+    doc("ANNN", "LD I, NNN", "set I to NNN", &[]),
+    doc("BNNN", "JP V0, NNN", "jump to NNN plus V0", &["jumping"]),
+    doc("CXNN", "RND VX, NN", "set VX to a random byte AND NN", &[]),
+    doc(
+        "DXYN",
+        "DRW VX, VY, N",
+        "draw the N rows of the sprite at I at VX,VY by XORing it onto the screen, VF is 1 if that \
+         turned any pixel off and 0 if not",
+        &["display-wait", "clipping", "vblank-draw"],
+    ),
+    doc("EX9E", "SKP VX", "skip the next instruction if the key in VX is down", &["xo-memory"]),
+    doc("EXA1", "SKNP VX", "skip the next instruction if the key in VX is up", &["xo-memory"]),
+    doc("FX07", "LD VX, DT", "set VX to the delay timer", &["timer-phase"]),
+    doc("FX0A", "LD VX, K", "wait for a key to be pressed and let go, then set VX to it", &[]),
+    doc("FX15", "LD DT, VX", "set the delay timer to VX, it counts down at 60Hz", &[]),
+    doc("FX18", "LD ST, VX", "set the sound timer to VX, the buzzer sounds until it's counted down", &[]),
+    doc("FX1E", "ADD I, VX", "add VX to I", &[]),
+    doc("FX29", "LD F, VX", "point I at the font's digit for the low nibble of VX", &[]),
+    doc("FX33", "LD B, VX", "store VX in decimal at I, hundreds then tens then ones", &[]),
+    doc("FX55", "LD [I], VX", "store V0 to VX in memory from I", &["memory"]),
+    doc("FX65", "LD VX, [I]", "load V0 to VX from memory at I", &["memory"]),
+];
+
+// what query names: an opcode like D125, a pattern like DXYN or a mnemonic like ADD
+pub fn lookup(query: &str) -> Vec<&'static OpcodeDoc> {
+    let query = query.to_ascii_uppercase();
+    let by_opcode = (query.len() == 4)
+        .then(|| u16::from_str_radix(&query, 16).ok())
+        .flatten()
+        .and_then(|opcode| Instruction::decode(opcode).doc());
+    if let Some(doc) = by_opcode {
+        return vec![doc];
+    }
+    let matches = |doc: &&OpcodeDoc| {
+        doc.pattern == query || doc.syntax.split(' ').next() == Some(query.as_str())
+    };
+    OPCODES.iter().filter(matches).collect()
+}
+
+impl fmt::Display for OpcodeDoc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}  {}\n  {}", self.pattern, self.syntax, self.summary)?;
+        for name in self.quirks {
+            let desc = (crate::quirks::NAMES.iter())
+                .find(|(n, _)| n == name)
+                .map_or("", |(_, desc)| desc);
+            write!(f, "\n  quirk {}: {}", name, desc)?;
+        }
+        Ok(())
+    }
+}
+
+impl Instruction {
+    pub fn decode(opcode: u16) -> Self {
+        let x = ((opcode & 0xF00) >> 8) as u8;
+        let y = ((opcode & 0xF0) >> 4) as u8;
+        let n = (opcode & 0xF) as u8;
+        let nn = (opcode & 0xFF) as u8;
+        let nnn = opcode & 0xFFF;
+        match opcode >> 12 {
+            0x0 => match opcode {
+                0x00E0 => Self::Cls,
+                0x00EE => Self::Ret,
+                0x00FD => Self::Exit,
+                0x00FB => Self::ScrollRight,
+                0x00FC => Self::ScrollLeft,
+                _ if opcode & 0xFFF0 == 0x00C0 => Self::ScrollDown(n),
+                _ if opcode & 0xFFF0 == 0x00D0 => Self::ScrollUp(n),
+                _ => Self::Sys(nnn),
+            },
+            0x1 => Self::Jp(nnn),
+            0x2 => Self::Call(nnn),
+            0x3 => Self::SeByte(x, nn),
+            0x4 => Self::SneByte(x, nn),
+            0x5 if n == 0 => Self::SeReg(x, y),
+            0x6 => Self::LdByte(x, nn),
+            0x7 => Self::AddByte(x, nn),
+            0x8 => match n {
+                0x0 => Self::LdReg(x, y),
+                0x1 => Self::Or(x, y),
+                0x2 => Self::And(x, y),
+                0x3 => Self::Xor(x, y),
+                0x4 => Self::AddReg(x, y),
+                0x5 => Self::Sub(x, y),
+                0x6 => Self::Shr(x, y),
+                0x7 => Self::Subn(x, y),
+                0xE => Self::Shl(x, y),
+                _ => Self::Unknown(opcode),
+            },
+            0x9 if n == 0 => Self::SneReg(x, y),
+            0xA => Self::LdI(nnn),
+            0xB => Self::JpV0(nnn),
+            0xC => Self::Rnd(x, nn),
+            0xD => Self::Drw(x, y, n),
+            0xE => match nn {
+                0x9E => Self::Skp(x),
+                0xA1 => Self::Sknp(x),
+                _ => Self::Unknown(opcode),
+            },
+            0xF => match nn {
+                0x07 => Self::LdVxDt(x),
+                0x0A => Self::LdVxK(x),
+                0x15 => Self::LdDtVx(x),
+                0x18 => Self::LdStVx(x),
+                0x1E => Self::AddI(x),
+                0x29 => Self::LdF(x),
+                0x33 => Self::LdB(x),
+                0x55 => Self::LdIVx(x),
+                0x65 => Self::LdVxI(x),
+                _ => Self::Unknown(opcode),
+            },
+            _ => Self::Unknown(opcode),
+        }
+    }
+
+    // the opcode pattern, e.g. 8XY4, for grouping instructions of one kind together
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            Self::Cls => "00E0",
+            Self::Ret => "00EE",
+            Self::Exit => "00FD",
+            Self::ScrollDown(..) => "00CN",
+            Self::ScrollUp(..) => "00DN",
+            Self::ScrollRight => "00FB",
+            Self::ScrollLeft => "00FC",
+            Self::Sys(..) => "0NNN",
+            Self::Jp(..) => "1NNN",
+            Self::Call(..) => "2NNN",
+            Self::SeByte(..) => "3XNN",
+            Self::SneByte(..) => "4XNN",
+            Self::SeReg(..) => "5XY0",
+            Self::LdByte(..) => "6XNN",
+            Self::AddByte(..) => "7XNN",
+            Self::LdReg(..) => "8XY0",
+            Self::Or(..) => "8XY1",
+            Self::And(..) => "8XY2",
+            Self::Xor(..) => "8XY3",
+            Self::AddReg(..) => "8XY4",
+            Self::Sub(..) => "8XY5",
+            Self::Shr(..) => "8XY6",
+            Self::Subn(..) => "8XY7",
+            Self::Shl(..) => "8XYE",
+            Self::SneReg(..) => "9XY0",
+            Self::LdI(..) => "ANNN",
+            Self::JpV0(..) => "BNNN",
+            Self::Rnd(..) => "CXNN",
+            Self::Drw(..) => "DXYN",
+            Self::Skp(..) => "EX9E",
+            Self::Sknp(..) => "EXA1",
+            Self::LdVxDt(..) => "FX07",
+            Self::LdVxK(..) => "FX0A",
+            Self::LdDtVx(..) => "FX15",
+            Self::LdStVx(..) => "FX18",
+            Self::AddI(..) => "FX1E",
+            Self::LdF(..) => "FX29",
+            Self::LdB(..) => "FX33",
+            Self::LdIVx(..) => "FX55",
+            Self::LdVxI(..) => "FX65",
+            Self::Unknown(_) => "????",
+        }
+    }
+
+    // just the operation, e.g. ADD for both 7XNN and 8XY4
+    pub fn mnemonic(&self) -> &'static str {
+        self.doc()
+            .and_then(|doc| doc.syntax.split(' ').next())
+            .unwrap_or("DW")
+    }
+
+    // what the instruction does, None for an unknown one
+    pub fn doc(&self) -> Option<&'static OpcodeDoc> {
+        OPCODES.iter().find(|doc| doc.pattern == self.pattern())
+    }
+
+    // true for the instructions that conditionally skip the next one
+    pub fn is_skip(&self) -> bool {
+        matches!(
+            self,
+            Self::SeByte(..)
+                | Self::SneByte(..)
+                | Self::SeReg(..)
+                | Self::SneReg(..)
+                | Self::Skp(_)
+                | Self::Sknp(_)
+        )
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Cls => write!(f, "CLS"),
+            Self::Ret => write!(f, "RET"),
+            Self::Exit => write!(f, "EXIT"),
+            Self::ScrollDown(n) => write!(f, "SCD {}", n),
+            Self::ScrollUp(n) => write!(f, "SCU {}", n),
+            Self::ScrollRight => write!(f, "SCR"),
+            Self::ScrollLeft => write!(f, "SCL"),
+            Self::Sys(a) => write!(f, "SYS 0x{:03X}", a),
+            Self::Jp(a) => write!(f, "JP 0x{:03X}", a),
+            Self::Call(a) => write!(f, "CALL 0x{:03X}", a),
+            Self::SeByte(x, n) => write!(f, "SE V{:X}, 0x{:02X}", x, n),
+            Self::SneByte(x, n) => write!(f, "SNE V{:X}, 0x{:02X}", x, n),
+            Self::SeReg(x, y) => write!(f, "SE V{:X}, V{:X}", x, y),
+            Self::LdByte(x, n) => write!(f, "LD V{:X}, 0x{:02X}", x, n),
+            Self::AddByte(x, n) => write!(f, "ADD V{:X}, 0x{:02X}", x, n),
+            Self::LdReg(x, y) => write!(f, "LD V{:X}, V{:X}", x, y),
+            Self::Or(x, y) => write!(f, "OR V{:X}, V{:X}", x, y),
+            Self::And(x, y) => write!(f, "AND V{:X}, V{:X}", x, y),
+            Self::Xor(x, y) => write!(f, "XOR V{:X}, V{:X}", x, y),
+            Self::AddReg(x, y) => write!(f, "ADD V{:X}, V{:X}", x, y),
+            Self::Sub(x, y) => write!(f, "SUB V{:X}, V{:X}", x, y),
+            Self::Shr(x, y) => write!(f, "SHR V{:X}, V{:X}", x, y),
+            Self::Subn(x, y) => write!(f, "SUBN V{:X}, V{:X}", x, y),
+            Self::Shl(x, y) => write!(f, "SHL V{:X}, V{:X}", x, y),
+            Self::SneReg(x, y) => write!(f, "SNE V{:X}, V{:X}", x, y),
+            Self::LdI(a) => write!(f, "LD I, 0x{:03X}", a),
+            Self::JpV0(a) => write!(f, "JP V0, 0x{:03X}", a),
+            Self::Rnd(x, n) => write!(f, "RND V{:X}, 0x{:02X}", x, n),
+            Self::Drw(x, y, n) => write!(f, "DRW V{:X}, V{:X}, {}", x, y, n),
+            Self::Skp(x) => write!(f, "SKP V{:X}", x),
+            Self::Sknp(x) => write!(f, "SKNP V{:X}", x),
+            Self::LdVxDt(x) => write!(f, "LD V{:X}, DT", x),
+            Self::LdVxK(x) => write!(f, "LD V{:X}, K", x),
+            Self::LdDtVx(x) => write!(f, "LD DT, V{:X}", x),
+            Self::LdStVx(x) => write!(f, "LD ST, V{:X}", x),
+            Self::AddI(x) => write!(f, "ADD I, V{:X}", x),
+            Self::LdF(x) => write!(f, "LD F, V{:X}", x),
+            Self::LdB(x) => write!(f, "LD B, V{:X}", x),
+            Self::LdIVx(x) => write!(f, "LD [I], V{:X}", x),
+            Self::LdVxI(x) => write!(f, "LD V{:X}, [I]", x),
+            Self::Unknown(op) => write!(f, "DW 0x{:04X}", op),
+        }
+    }
+}
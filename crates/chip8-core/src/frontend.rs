@@ -0,0 +1,296 @@
+// what a frontend is: something that shows the screen and sounds the buzzer (Display) and hands
+// input to the machine (Keypad). the frontends themselves live with whatever uses them
+
+use crate::overlay::Corner;
+use crate::palette::Palette;
+use crate::synth::Model;
+use crate::Chip8;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+// the part of the VM a frontend gets to touch
+pub trait Machine {
+    fn press_key(&mut self, key: usize);
+    fn release_key(&mut self, key: usize);
+    fn clear_keys(&mut self);
+}
+
+impl Machine for Chip8 {
+    fn press_key(&mut self, key: usize) {
+        Chip8::press_key(self, key);
+    }
+
+    fn release_key(&mut self, key: usize) {
+        Chip8::release_key(self, key);
+    }
+
+    fn clear_keys(&mut self) {
+        Chip8::clear_keys(self);
+    }
+}
+
+// a machine that notes when the frontend last pressed a key on it, to tell when nobody's playing
+#[cfg(feature = "std")]
+pub struct Stamped<'a, M: Machine> {
+    pub machine: &'a mut M,
+    pub pressed: &'a mut Option<Instant>,
+}
+
+#[cfg(feature = "std")]
+impl<M: Machine> Machine for Stamped<'_, M> {
+    fn press_key(&mut self, key: usize) {
+        *self.pressed = Some(Instant::now());
+        self.machine.press_key(key);
+    }
+
+    fn release_key(&mut self, key: usize) {
+        self.machine.release_key(key);
+    }
+
+    fn clear_keys(&mut self) {
+        self.machine.clear_keys();
+    }
+}
+
+// several VMs shown side by side, keys 16 and up go to the second and so on
+pub struct Machines<'a>(pub &'a mut [Chip8]);
+
+impl Machine for Machines<'_> {
+    fn press_key(&mut self, key: usize) {
+        if let Some(chip8) = self.0.get_mut(key / 16) {
+            chip8.press_key(key % 16);
+        }
+    }
+
+    fn release_key(&mut self, key: usize) {
+        if let Some(chip8) = self.0.get_mut(key / 16) {
+            chip8.release_key(key % 16);
+        }
+    }
+
+    fn clear_keys(&mut self) {
+        for chip8 in self.0.iter_mut() {
+            chip8.clear_keys();
+        }
+    }
+}
+
+// screens put next to each other into one, for frontends showing several VMs
+pub fn side_by_side(screens: &[&[u8]]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(screens.len() * 64 * 32);
+    for row in 0..32 {
+        for screen in screens {
+            out.extend_from_slice(&screen[row * 64..(row + 1) * 64]);
+        }
+    }
+    out
+}
+
+// turning the screen clockwise, for games made to be held on their side or a display mounted
+// that way
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    None,
+    Quarter,      // 90 degrees
+    Half,         // 180
+    ThreeQuarter, // 270
+}
+
+impl Rotation {
+    pub fn degrees(self) -> u32 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Quarter => 90,
+            Rotation::Half => 180,
+            Rotation::ThreeQuarter => 270,
+        }
+    }
+
+    // the size of something width by height once it's turned
+    pub fn size(self, (width, height): (u32, u32)) -> (u32, u32) {
+        match self {
+            Rotation::Quarter | Rotation::ThreeQuarter => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    // where an x, y, width, height rectangle on an unturned area of size ends up once it's turned
+    pub fn rect(
+        self,
+        (x, y, w, h): (i32, i32, u32, u32),
+        size: (u32, u32),
+    ) -> (i32, i32, u32, u32) {
+        let (width, height) = (size.0 as i32, size.1 as i32);
+        match self {
+            Rotation::None => (x, y, w, h),
+            Rotation::Quarter => (height - y - h as i32, x, h, w),
+            Rotation::Half => (width - x - w as i32, height - y - h as i32, w, h),
+            Rotation::ThreeQuarter => (y, width - x - w as i32, h, w),
+        }
+    }
+
+    // the keys that move up, down, left and right on the turned screen for a game that moves
+    // with 2, 8, 4 and 6 the way most do
+    pub fn directions(self) -> [usize; 4] {
+        match self {
+            Rotation::None => [2, 8, 4, 6],
+            Rotation::Quarter => [4, 6, 8, 2],
+            Rotation::Half => [8, 2, 6, 4],
+            Rotation::ThreeQuarter => [6, 4, 2, 8],
+        }
+    }
+}
+
+impl FromStr for Rotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "0" => Ok(Rotation::None),
+            "90" => Ok(Rotation::Quarter),
+            "180" => Ok(Rotation::Half),
+            "270" => Ok(Rotation::ThreeQuarter),
+            _ => Err(format!("{} isn't 0, 90, 180 or 270", s)),
+        }
+    }
+}
+
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.degrees())
+    }
+}
+
+// how the VM's pixels fill the window or screen they're drawn on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScreenMode {
+    #[default]
+    Fit, // as big as fits with square pixels, bars at the sides or top and bottom
+    Stretch,   // filling it all, squashed or stretched
+    DotMatrix, // as Fit, with each pixel a dot with a gap round it like an LED matrix's
+}
+
+pub const SCREEN_MODES: [(&str, ScreenMode); 3] = [
+    ("fit", ScreenMode::Fit),
+    ("stretch", ScreenMode::Stretch),
+    ("dot-matrix", ScreenMode::DotMatrix),
+];
+
+impl ScreenMode {
+    // what's left dark between pixels scale wide, none if they're too small to spare any
+    pub fn gap(self, scale: usize) -> usize {
+        match self {
+            ScreenMode::DotMatrix if scale > 1 => (scale / 4).max(1),
+            _ => 0,
+        }
+    }
+}
+
+impl FromStr for ScreenMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match SCREEN_MODES.iter().find(|(name, _)| *name == s) {
+            Some(&(_, mode)) => Ok(mode),
+            None => Err(format!("{} isn't fit, stretch or dot-matrix", s)),
+        }
+    }
+}
+
+impl fmt::Display for ScreenMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = SCREEN_MODES.iter().find(|&&(_, m)| m == *self).unwrap().0;
+        write!(f, "{}", name)
+    }
+}
+
+// settings every frontend is made with
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub toast_time: Duration, // how long notifications show for, zero for never
+    pub screens: usize,       // VMs shown side by side, see Machines
+    pub announce: bool, // the tui writes what happens as lines of text for screen readers, not the screen
+    pub buzzer: Model,
+    pub palette: Palette,
+    pub rotation: Rotation, // of the window's picture, the overlay stays upright
+    pub keypad_corner: Corner, // where the keypad shows, once it's turned on
+    pub screen_mode: ScreenMode,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            toast_time: Duration::from_secs(1),
+            screens: 1,
+            announce: false,
+            buzzer: Model::Square,
+            palette: Palette::default(),
+            rotation: Rotation::None,
+            keypad_corner: Corner::default(),
+            screen_mode: ScreenMode::default(),
+        }
+    }
+}
+
+// keys that control the emulator rather than the game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Hotkey {
+    Quit,
+    Mute,
+    SaveState,
+    LoadState,
+    NextSlot,
+    PrevSlot,
+    RecordMacro,  // start recording a macro, or give up on one being recorded
+    Macro(usize), // one of MACRO_KEYS
+    Cheats,       // turn the frozen values of cheats on or off
+    Pause,        // stop and show the high scores, or carry on
+    Keypad,       // show the keypad over the game or stop showing it
+    Focus(bool),  // not a key, the window got or lost focus
+}
+
+// keys macros can be bound to
+pub const MACRO_KEYS: [&str; 4] = ["F1", "F2", "F3", "F4"];
+
+pub trait Display {
+    // show the 64x32 screen, one byte per pixel
+    fn present(&mut self, gfx: &[u8]);
+    fn buzzer(&mut self, _on: bool) {}
+    // shake any game controllers while on, alongside or instead of the buzzer
+    fn rumble(&mut self, _on: bool) {}
+    // show that the buzzer is sounding for anyone who can't hear it, while on
+    fn flash(&mut self, _on: bool) {}
+    // briefly show a message over the game, e.g. after a hotkey
+    fn notify(&mut self, _text: &str) {}
+    // lines to keep showing over the game until they're replaced, like a HUD or the debugger's
+    // watches
+    fn watches(&mut self, _lines: &[String]) {}
+    // the keypad over the game with the keys held lit (a bit each), for streams and tutorials,
+    // or nothing there when None
+    fn keys(&mut self, _held: Option<u16>) {}
+    // draw from now on the way mode says, after the config file changes it
+    fn set_screen_mode(&mut self, _mode: ScreenMode) {}
+    // the emulator is stopping, gfx is the last screen
+    fn finish(&mut self, _gfx: &[u8]) {}
+    // stop once the ROM halts, for frontends nobody is watching
+    fn exit_on_halt(&self) -> bool {
+        false
+    }
+}
+
+pub trait Keypad {
+    // hand any input that's arrived to the machine, returning the hotkeys that were pressed
+    fn poll(&mut self, machine: &mut dyn Machine) -> Vec<Hotkey>;
+}
+
+pub trait Frontend: Display + Keypad {}
+
+impl<T: Display + Keypad> Frontend for T {}
@@ -0,0 +1,74 @@
+use crate::chip8::{Chip8, Error};
+use crate::quirks::Quirks;
+use alloc::vec::Vec;
+
+pub const SEED: u64 = 0; // fixed RNG seed so separate runs can be compared
+
+// FNV-1a, cheap enough to run on every frame
+pub fn frame_hash(gfx: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325;
+    for &b in gfx {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Outcome {
+    pub cycles: usize, // cycles actually run, fewer than asked for if the VM hit an error or was stopped
+    pub frames: Vec<u64>, // hash of the screen after every draw
+    pub error: Option<Error>, // what stopped the VM early, if anything
+}
+
+impl Outcome {
+    // errors are only compared by kind since moving code around changes their addresses
+    pub fn same_as(&self, other: &Self) -> bool {
+        self.cycles == other.cycles
+            && self.frames == other.frames
+            && self.error.map(|e| core::mem::discriminant(&e))
+                == other.error.map(|e| core::mem::discriminant(&e))
+    }
+}
+
+// a VM with the ROM loaded that will behave the same every time it's run
+pub fn machine(rom: &[u8], quirks: Quirks) -> Chip8 {
+    let mut chip8 = Chip8::new();
+    chip8.seed_rng(SEED);
+    chip8.set_quirks(quirks);
+    chip8.load_rom(rom);
+    chip8
+}
+
+// run a ROM without a window, sound, input or any delays between cycles
+pub fn run(rom: &[u8], cycles: usize, quirks: Quirks) -> Outcome {
+    run_machine(&mut machine(rom, quirks), cycles, |_, _| true)
+}
+
+// like run, but before_cycle gets a chance to poke at the VM (e.g. press keys) before every cycle
+// and can stop the run early by returning false
+pub fn run_machine<F>(chip8: &mut Chip8, cycles: usize, mut before_cycle: F) -> Outcome
+where
+    F: FnMut(&mut Chip8, usize) -> bool,
+{
+    let mut outcome = Outcome {
+        cycles: 0,
+        frames: Vec::new(),
+        error: None,
+    };
+    while outcome.cycles < cycles {
+        if !before_cycle(chip8, outcome.cycles) {
+            break;
+        }
+        if let Err(e) = chip8.emulate_cycle() {
+            outcome.error = Some(e);
+            break;
+        }
+        outcome.cycles += 1;
+        if chip8.draw_flag() {
+            outcome.frames.push(frame_hash(chip8.gfx()));
+        }
+    }
+    outcome
+}
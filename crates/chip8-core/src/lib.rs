@@ -0,0 +1,32 @@
+// the VM and nothing else a consumer wouldn't want: no SDL, no files, no CLI. the chip8 crate
+// builds the emulator on top of it
+//
+// everything public here is the API and changes with semver, except what's #[doc(hidden)], which
+// is only there for the other chip8 crates. enums and structs that are meant to grow, like the
+// errors, events and quirks, are #[non_exhaustive] so adding to them isn't a breaking change
+//
+// without the default std feature it's no_std, needing only an allocator, for boards driving LED
+// matrices and the like. left out then is whatever reads files or the host's clocks: the VM's
+// RNG starts from a fixed seed until seed_rng, ticks come from CycleClock unless set_clock brings
+// the board's timer, and a board wanting --ext-mmio writes its own Mmio in place of Host
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod bus;
+pub mod chip8;
+pub mod clock;
+pub mod disasm;
+pub mod fonts;
+pub mod frontend;
+pub mod headless;
+pub mod mmio;
+pub mod observer;
+pub mod overlay;
+pub mod palette;
+pub mod prelude;
+pub mod quirks;
+pub mod synth;
+
+pub use crate::chip8::Chip8;
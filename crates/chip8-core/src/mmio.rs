@@ -0,0 +1,58 @@
+// an optional extension for homebrew: the last 16 bytes of memory stand for host features
+// instead of RAM, so a ROM can read the time, count frames and get random bytes without
+// CXNN. off unless asked for with --ext-mmio, since no real interpreter has it
+//
+//   FF0  seconds of the UTC time of day
+//   FF1  minutes
+//   FF2  hours
+//   FF3  frame counter, the low byte of the 60Hz ticks
+//   FF4  a new random byte on every read
+//
+// the rest read as zero, and writes anywhere in the range go nowhere. Host needs the host's
+// clock and entropy, so without std a board brings its own Mmio
+
+#[cfg(feature = "std")]
+use rand::{prelude::*, rngs::StdRng};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const BASE: usize = 0xFF0;
+pub const LEN: usize = 16;
+
+// something behind the mapped range, offsets are from BASE
+pub trait Mmio {
+    // ticks is how many 60Hz ticks the VM has counted
+    fn read(&mut self, offset: usize, ticks: u64) -> u8;
+    fn write(&mut self, _offset: usize, _value: u8) {}
+}
+
+#[cfg(feature = "std")]
+pub struct Host {
+    rng: StdRng,
+}
+
+#[cfg(feature = "std")]
+impl Default for Host {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Mmio for Host {
+    fn read(&mut self, offset: usize, ticks: u64) -> u8 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        match offset {
+            0 => (now % 60) as u8,
+            1 => (now / 60 % 60) as u8,
+            2 => (now / 3600 % 24) as u8,
+            3 => ticks as u8,
+            4 => self.rng.gen(),
+            _ => 0,
+        }
+    }
+}
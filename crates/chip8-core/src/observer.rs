@@ -0,0 +1,88 @@
+use crate::Chip8;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    // sent before the instruction at pc runs
+    Exec {
+        pc: u16,
+        opcode: u16,
+    },
+    // instruction at pc stored value at addr
+    Write {
+        pc: u16,
+        addr: u16,
+        value: u8,
+    },
+    // addr was written after having been executed, changing it from old to new
+    SelfModify {
+        pc: u16,
+        addr: u16,
+        old: u8,
+        new: u8,
+    },
+}
+
+// anything that wants to watch the VM run, e.g. tracers and statistics
+pub trait Observer {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event);
+}
+
+impl Observer for () {
+    fn on_event(&mut self, _: &Chip8, _: &Event) {}
+}
+
+impl<T: Observer + ?Sized> Observer for &mut T {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        (**self).on_event(chip8, event);
+    }
+}
+
+impl<T: Observer> Observer for Option<T> {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        if let Some(o) = self {
+            o.on_event(chip8, event);
+        }
+    }
+}
+
+impl<A: Observer, B: Observer> Observer for (A, B) {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        self.0.on_event(chip8, event);
+        self.1.on_event(chip8, event);
+    }
+}
+
+// remembers every address that was overwritten after being executed, the first instruction to do
+// it, what was there before and what's there now
+#[derive(Default)]
+pub struct SelfModifyLog {
+    pub writes: BTreeMap<u16, (u16, u8, u8)>, // modified addr -> pc, old, new
+}
+
+impl Observer for SelfModifyLog {
+    fn on_event(&mut self, _: &Chip8, event: &Event) {
+        if let Event::SelfModify { pc, addr, old, new } = *event {
+            self.writes.entry(addr).or_insert((pc, old, new)).2 = new;
+        }
+    }
+}
+
+impl SelfModifyLog {
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "{} byte(s) of executed code were overwritten:\n",
+            self.writes.len()
+        );
+        for (addr, (pc, old, new)) in &self.writes {
+            out.push_str(&format!(
+                "  0x{:03X} written by 0x{:03X}, {:02X} -> {:02X}\n",
+                addr, pc, old, new
+            ));
+        }
+        out
+    }
+}
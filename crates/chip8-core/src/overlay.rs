@@ -0,0 +1,246 @@
+// things frontends draw over the game: a small bitmap font, short-lived notifications and the
+// keypad with the keys held lit
+
+#[cfg(feature = "std")]
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::string::ToString;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+pub const GLYPH_WIDTH: usize = 3;
+pub const GLYPH_HEIGHT: usize = 5;
+pub const FADE_TIME: Duration = Duration::from_millis(500); // after a toast's time is up
+#[cfg(feature = "std")]
+const MAX_TOASTS: usize = 3; // older ones are dropped to make room
+
+// 3x5 glyphs, a row to a byte with the leftmost pixel in bit 2. lowercase is drawn as uppercase
+// and anything without a glyph as a box. letters with umlauts drop them
+pub fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    let c = match c {
+        'Ä' | 'ä' => 'A',
+        'Ö' | 'ö' => 'O',
+        'Ü' | 'ü' => 'U',
+        'ß' => 'S',
+        c => c,
+    };
+    match c.to_ascii_uppercase() {
+        ' ' => [0, 0, 0, 0, 0],
+        '0' => [7, 5, 5, 5, 7],
+        '1' => [2, 6, 2, 2, 7],
+        '2' => [7, 1, 7, 4, 7],
+        '3' => [7, 1, 3, 1, 7],
+        '4' => [5, 5, 7, 1, 1],
+        '5' => [7, 4, 7, 1, 7],
+        '6' => [7, 4, 7, 5, 7],
+        '7' => [7, 1, 2, 2, 2],
+        '8' => [7, 5, 7, 5, 7],
+        '9' => [7, 5, 7, 1, 7],
+        'A' => [2, 5, 7, 5, 5],
+        'B' => [6, 5, 6, 5, 6],
+        'C' => [3, 4, 4, 4, 3],
+        'D' => [6, 5, 5, 5, 6],
+        'E' => [7, 4, 6, 4, 7],
+        'F' => [7, 4, 6, 4, 4],
+        'G' => [3, 4, 5, 5, 3],
+        'H' => [5, 5, 7, 5, 5],
+        'I' => [7, 2, 2, 2, 7],
+        'J' => [1, 1, 1, 5, 2],
+        'K' => [5, 5, 6, 5, 5],
+        'L' => [4, 4, 4, 4, 7],
+        'M' => [5, 7, 7, 5, 5],
+        'N' => [6, 5, 5, 5, 5],
+        'O' => [2, 5, 5, 5, 2],
+        'P' => [6, 5, 6, 4, 4],
+        'Q' => [2, 5, 5, 6, 3],
+        'R' => [6, 5, 6, 5, 5],
+        'S' => [3, 4, 2, 1, 6],
+        'T' => [7, 2, 2, 2, 2],
+        'U' => [5, 5, 5, 5, 7],
+        'V' => [5, 5, 5, 5, 2],
+        'W' => [5, 5, 7, 7, 5],
+        'X' => [5, 5, 2, 5, 5],
+        'Y' => [5, 5, 2, 2, 2],
+        'Z' => [7, 1, 2, 4, 7],
+        '.' => [0, 0, 0, 0, 2],
+        ',' => [0, 0, 0, 2, 4],
+        ':' => [0, 2, 0, 2, 0],
+        '!' => [2, 2, 2, 0, 2],
+        '?' => [7, 1, 2, 0, 2],
+        '%' => [5, 1, 2, 4, 5],
+        '-' => [0, 0, 7, 0, 0],
+        '+' => [0, 2, 7, 2, 0],
+        '=' => [0, 7, 0, 7, 0],
+        '/' => [1, 1, 2, 4, 4],
+        '(' => [1, 2, 2, 2, 1],
+        ')' => [4, 2, 2, 2, 4],
+        '\'' => [2, 2, 0, 0, 0],
+        _ => [7, 5, 5, 5, 7],
+    }
+}
+
+// calls plot with the position of every lit pixel of text, glyphs are a pixel apart
+pub fn draw_text(text: &str, mut plot: impl FnMut(usize, usize)) {
+    for (n, c) in text.chars().enumerate() {
+        for (y, row) in glyph(c).iter().enumerate() {
+            for x in 0..GLYPH_WIDTH {
+                if row & (4 >> x) != 0 {
+                    plot(n * (GLYPH_WIDTH + 1) + x, y);
+                }
+            }
+        }
+    }
+}
+
+// width in pixels of text drawn by draw_text
+pub fn text_width(text: &str) -> usize {
+    (text.chars().count() * (GLYPH_WIDTH + 1)).saturating_sub(1)
+}
+
+// the COSMAC VIP's keypad, as it's laid out
+pub const KEYPAD_ROWS: [[usize; 4]; 4] = [
+    [1, 2, 3, 0xC],
+    [4, 5, 6, 0xD],
+    [7, 8, 9, 0xE],
+    [0xA, 0, 0xB, 0xF],
+];
+
+// of the keypad drawn by draw_keypad: 4 keys a glyph and a pixel round it, a pixel apart
+pub const KEYPAD_WIDTH: usize = 4 * (GLYPH_WIDTH + 3) - 1;
+pub const KEYPAD_HEIGHT: usize = 4 * (GLYPH_HEIGHT + 3) - 1;
+
+// calls plot with the position of every lit pixel of the keypad, each key's digit, except that a
+// key in held (a bit each) is drawn lit all over with its digit left dark
+pub fn draw_keypad(held: u16, mut plot: impl FnMut(usize, usize)) {
+    let digits = b"0123456789ABCDEF";
+    for (row, keys) in KEYPAD_ROWS.iter().enumerate() {
+        for (col, &key) in keys.iter().enumerate() {
+            let (left, top) = (col * (GLYPH_WIDTH + 3), row * (GLYPH_HEIGHT + 3));
+            let glyph = glyph(digits[key] as char);
+            let down = held & 1 << key != 0;
+            for y in 0..GLYPH_HEIGHT + 2 {
+                for x in 0..GLYPH_WIDTH + 2 {
+                    let inside = (1..=GLYPH_WIDTH).contains(&x) && (1..=GLYPH_HEIGHT).contains(&y);
+                    let digit = inside && glyph[y - 1] & (4 >> (x - 1)) != 0;
+                    if digit != down {
+                        plot(left + x, top + y);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// where over the game the keypad goes, out of the way of the watches top left and the
+// notifications bottom left by default
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+pub const CORNERS: [(&str, Corner); 4] = [
+    ("top-left", Corner::TopLeft),
+    ("top-right", Corner::TopRight),
+    ("bottom-left", Corner::BottomLeft),
+    ("bottom-right", Corner::BottomRight),
+];
+
+impl Corner {
+    // the top left of something width by height in this corner of an area of size, margin in
+    // from its edges
+    pub fn place(
+        self,
+        (width, height): (usize, usize),
+        size: (usize, usize),
+        margin: usize,
+    ) -> (usize, usize) {
+        let right = size.0.saturating_sub(width + margin);
+        let bottom = size.1.saturating_sub(height + margin);
+        match self {
+            Corner::TopLeft => (margin, margin),
+            Corner::TopRight => (right, margin),
+            Corner::BottomLeft => (margin, bottom),
+            Corner::BottomRight => (right, bottom),
+        }
+    }
+}
+
+impl FromStr for Corner {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match CORNERS.iter().find(|(name, _)| *name == s) {
+            Some(&(_, corner)) => Ok(corner),
+            None => Err(format!(
+                "{} isn't top-left, top-right, bottom-left or bottom-right",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Corner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = CORNERS.iter().find(|&&(_, c)| c == *self).unwrap().0;
+        write!(f, "{}", name)
+    }
+}
+
+// notifications like "state 1 saved" that show for a while and then fade out. a zero duration
+// turns them off. they need the host's clock, so they're only there with std
+#[cfg(feature = "std")]
+pub struct Toasts {
+    duration: Duration,
+    toasts: VecDeque<(String, Instant)>, // text, when it was shown
+}
+
+#[cfg(feature = "std")]
+impl Toasts {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            toasts: VecDeque::new(),
+        }
+    }
+
+    pub fn notify(&mut self, text: &str, now: Instant) {
+        if self.duration.is_zero() {
+            return;
+        }
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back((text.to_string(), now));
+    }
+
+    // toasts still showing, oldest first, with how opaque they are from 0 to 1
+    pub fn visible(&mut self, now: Instant) -> Vec<(&str, f32)> {
+        let gone = self.duration + FADE_TIME;
+        self.toasts
+            .retain(|(_, shown)| now.saturating_duration_since(*shown) < gone);
+        self.toasts
+            .iter()
+            .map(|(text, shown)| {
+                let age = now.saturating_duration_since(*shown);
+                let fade =
+                    age.saturating_sub(self.duration).as_secs_f32() / FADE_TIME.as_secs_f32();
+                (text.as_str(), 1.0 - fade)
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
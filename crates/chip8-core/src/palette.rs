@@ -0,0 +1,87 @@
+// the colors frontends draw the screen in. each has four, one for every pixel value XO-CHIP's two
+// planes can make: off, lit on the first plane, on the second and on both. a screen that only
+// uses one plane, like every CHIP-8 and SCHIP ROM, just shows the first two
+//
+// apart from classic they're picked to stay apart under the common color blindnesses
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+pub type Rgb = [u8; 3];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub name: &'static str,
+    pub colors: [Rgb; 4], // off, first plane, second plane, both
+    pub about: &'static str,
+}
+
+pub const PALETTES: [Palette; 5] = [
+    Palette {
+        name: "classic",
+        colors: [[0, 0, 0], [255, 255, 255], [170, 170, 170], [85, 85, 85]],
+        about: "white on black, as it's always been",
+    },
+    Palette {
+        name: "okabe-ito",
+        colors: [[0, 0, 0], [230, 159, 0], [86, 180, 233], [240, 228, 66]],
+        about: "orange, sky blue and yellow from Okabe and Ito's set, safe for all common color blindness",
+    },
+    Palette {
+        name: "ibm",
+        colors: [[0, 0, 0], [255, 176, 0], [100, 143, 255], [220, 38, 127]],
+        about: "gold, blue and magenta from IBM's accessible set, safe for red-green color blindness",
+    },
+    Palette {
+        name: "high-contrast",
+        colors: [[0, 0, 0], [255, 255, 0], [0, 255, 255], [255, 255, 255]],
+        about: "yellow, cyan and white at full brightness, for low vision",
+    },
+    Palette {
+        name: "paper",
+        colors: [
+            [245, 242, 232],
+            [26, 26, 26],
+            [0, 114, 178],
+            [213, 94, 0],
+        ],
+        about: "dark on light, with Okabe and Ito's blue and vermilion for the planes",
+    },
+];
+
+impl Palette {
+    // the color of a pixel as the screen holds it, a bit for each plane
+    pub fn color(&self, pixel: u8) -> Rgb {
+        self.colors[(pixel & 3) as usize]
+    }
+
+    // "classic, okabe-ito, ..." for help text
+    pub fn names() -> String {
+        PALETTES.map(|p| p.name).join(", ")
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        PALETTES[0]
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        (PALETTES.iter())
+            .find(|p| p.name == s)
+            .copied()
+            .ok_or_else(|| format!("unknown palette {}, one of {}", s, Self::names()))
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
@@ -0,0 +1,12 @@
+// what a frontend or tool needs to run ROMs, for `use chip8_core::prelude::*`
+
+pub use crate::chip8::{Error, StateError, CYCLES_PER_TICK, PROGRAM_START};
+pub use crate::clock::Clock;
+pub use crate::disasm::Instruction;
+pub use crate::frontend::{Display, Frontend, Hotkey, Keypad, Machine, Options};
+pub use crate::mmio::Mmio;
+pub use crate::observer::{Event, Observer};
+pub use crate::palette::Palette;
+pub use crate::quirks::Quirks;
+pub use crate::synth::Model;
+pub use crate::Chip8;
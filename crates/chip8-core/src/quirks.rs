@@ -0,0 +1,236 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+// behaviors that differ between CHIP-8 interpreters, ROMs are often written against one of them
+// new quirks can be added without breaking anything, so make them from a profile, from_bits or a
+// string and set fields on that, rather than with a struct expression
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Quirks {
+    pub vf_reset: bool,     // 8XY1/8XY2/8XY3 clear VF
+    pub memory: bool,       // FX55/FX65 leave I pointing past the last register
+    pub display_wait: bool, // DXYN waits for the next 60Hz tick before drawing
+    pub clipping: bool,     // sprites are cut off at the screen edges instead of wrapping
+    pub shifting: bool,     // 8XY6/8XYE shift VX in place instead of shifting VY into VX
+    pub jumping: bool,      // BNNN jumps to NNN + VX (BXNN) instead of NNN + V0
+    pub vblank_draw: bool,  // the screen only changes at the 60Hz tick, with all the draws since
+    pub xo_memory: bool,    // 64K of memory as on XO-CHIP, with F000 NNNN to point I anywhere in it
+    pub timer_phase: bool, // FX07 reads the delay timer one lower on an instruction a tick lands on
+}
+
+// name used on the command line, description
+pub const NAMES: [(&str, &str); 9] = [
+    ("vf-reset", "8XY1/8XY2/8XY3 clear VF"),
+    ("memory", "FX55/FX65 increment I"),
+    ("display-wait", "DXYN waits for the 60Hz tick"),
+    ("clipping", "sprites clip at the screen edges"),
+    ("shifting", "8XY6/8XYE shift VX instead of VY"),
+    ("jumping", "BNNN jumps to NNN + VX"),
+    ("vblank-draw", "the screen is shown at the 60Hz tick only"),
+    ("xo-memory", "64K of memory and F000 NNNN to load I from it"),
+    (
+        "timer-phase",
+        "FX07 reads DT one lower as the 60Hz tick lands",
+    ),
+];
+
+// the quirks before this in NAMES are the ones detect-quirks tries. the rest only change how the
+// screen looks, are platform features a ROM is written for, or are too fine to tell apart from a
+// few seconds of play rather than differences to guess at
+pub const VM_QUIRKS: usize = 6;
+
+impl Quirks {
+    // what this emulator has always done
+    pub const DEFAULT: Self = Self {
+        vf_reset: false,
+        memory: false,
+        display_wait: false,
+        clipping: false,
+        shifting: true,
+        jumping: false,
+        vblank_draw: false,
+        xo_memory: false,
+        timer_phase: false,
+    };
+
+    // the original COSMAC VIP interpreter
+    pub const CHIP8: Self = Self {
+        vf_reset: true,
+        memory: true,
+        display_wait: true,
+        clipping: true,
+        shifting: false,
+        jumping: false,
+        vblank_draw: false,
+        xo_memory: false,
+        timer_phase: false,
+    };
+
+    // SUPER-CHIP 1.1 on the HP48
+    pub const SCHIP: Self = Self {
+        vf_reset: false,
+        memory: false,
+        display_wait: false,
+        clipping: true,
+        shifting: true,
+        jumping: true,
+        vblank_draw: false,
+        xo_memory: false,
+        timer_phase: false,
+    };
+
+    // Octo's XO-CHIP
+    pub const XOCHIP: Self = Self {
+        vf_reset: false,
+        memory: true,
+        display_wait: false,
+        clipping: false,
+        shifting: false,
+        jumping: false,
+        vblank_draw: false,
+        xo_memory: true,
+        timer_phase: false,
+    };
+
+    pub const PROFILES: [(&'static str, Self); 4] = [
+        ("default", Self::DEFAULT),
+        ("chip8", Self::CHIP8),
+        ("schip", Self::SCHIP),
+        ("xochip", Self::XOCHIP),
+    ];
+
+    pub fn flags(&self) -> [bool; 9] {
+        [
+            self.vf_reset,
+            self.memory,
+            self.display_wait,
+            self.clipping,
+            self.shifting,
+            self.jumping,
+            self.vblank_draw,
+            self.xo_memory,
+            self.timer_phase,
+        ]
+    }
+
+    fn flag_mut(&mut self, index: usize) -> &mut bool {
+        match index {
+            0 => &mut self.vf_reset,
+            1 => &mut self.memory,
+            2 => &mut self.display_wait,
+            3 => &mut self.clipping,
+            4 => &mut self.shifting,
+            5 => &mut self.jumping,
+            6 => &mut self.vblank_draw,
+            7 => &mut self.xo_memory,
+            _ => &mut self.timer_phase,
+        }
+    }
+
+    // one bit per quirk in NAMES order, for storing quirks compactly
+    pub fn bits(&self) -> u16 {
+        self.flags()
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, &on)| bits | (on as u16) << i)
+    }
+
+    pub fn from_bits(bits: u16) -> Self {
+        let mut q = Self::default();
+        for i in 0..NAMES.len() {
+            *q.flag_mut(i) = bits & (1 << i) != 0;
+        }
+        q
+    }
+
+    // every combination of the quirks that change how ROMs run, the others left off
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..1 << VM_QUIRKS).map(Self::from_bits)
+    }
+
+    // name of the profile with this exact set of quirks, if there is one
+    pub fn profile_name(&self) -> Option<&'static str> {
+        Self::PROFILES
+            .iter()
+            .find(|(_, q)| q == self)
+            .map(|(name, _)| *name)
+    }
+
+    // number of quirks that differ between the two sets
+    pub fn distance(&self, other: &Self) -> usize {
+        self.flags()
+            .iter()
+            .zip(other.flags().iter())
+            .filter(|(a, b)| a != b)
+            .count()
+    }
+
+    // the profile that needs the fewest quirks flipped to get to this set
+    pub fn nearest_profile(&self) -> (&'static str, Self) {
+        *Self::PROFILES
+            .iter()
+            .min_by_key(|(_, q)| q.distance(self))
+            .unwrap()
+    }
+
+    pub fn help() -> String {
+        let mut out = String::from("profiles: ");
+        let names: Vec<&str> = Self::PROFILES.iter().map(|(n, _)| *n).collect();
+        out.push_str(&names.join(", "));
+        out.push_str("\nquirks (prefix with no- to turn off):\n");
+        for (name, desc) in &NAMES {
+            out.push_str(&format!("  {:<13} {}\n", name, desc));
+        }
+        out
+    }
+}
+
+// a profile name followed by any number of quirks to turn on or off, e.g. "chip8,no-clipping"
+impl FromStr for Quirks {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut quirks = Self::DEFAULT;
+        for (n, item) in s.split(',').map(str::trim).enumerate() {
+            if let Some((_, q)) = Self::PROFILES.iter().find(|(name, _)| *name == item) {
+                if n != 0 {
+                    return Err(format!("profile {} must come first", item));
+                }
+                quirks = *q;
+                continue;
+            }
+            let (name, on) = match item.strip_prefix("no-") {
+                Some(name) => (name, false),
+                None => (item, true),
+            };
+            match NAMES.iter().position(|(n, _)| *n == name) {
+                Some(i) => *quirks.flag_mut(i) = on,
+                None => return Err(format!("unknown quirk {}\n{}", item, Self::help())),
+            }
+        }
+        Ok(quirks)
+    }
+}
+
+// the shortest description that parses back to the same quirks
+impl fmt::Display for Quirks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (profile, base) = self.nearest_profile();
+        write!(f, "{}", profile)?;
+        for (i, (on, base)) in self.flags().iter().zip(base.flags().iter()).enumerate() {
+            if on != base {
+                write!(f, ",{}{}", if *on { "" } else { "no-" }, NAMES[i].0)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
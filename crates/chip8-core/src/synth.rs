@@ -0,0 +1,135 @@
+// the buzzer's sound, made here so it can go to the speakers and to --record-audio alike. there's
+// more than one model of it, see Model
+
+pub const SAMPLE_RATE: u32 = 44_100;
+pub const PITCH: f32 = 440.0;
+pub const VOLUME: f32 = 0.25;
+
+pub const MODELS: [&str; 2] = ["square", "vip"];
+
+const VIP_PITCH: f32 = 1400.0; // roughly the VIP's tone
+const VIP_SPEAKER_LOW: f32 = 150.0; // Hz the speaker's coupling starts cutting below
+const VIP_SPEAKER_HIGH: f32 = 4000.0; // and the speaker itself above
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Model {
+    // a clean square wave, silent between beeps
+    #[default]
+    Square,
+    // the COSMAC VIP's: its Q line switched a speaker fully on and off, so the tone is a square
+    // of 0 and 1 rounded off by a small speaker, and the jump in its level as the tone starts and
+    // stops is heard as a click. from a short sound timer that's most of what's heard
+    Vip,
+}
+
+impl Model {
+    // one of MODELS
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "square" => Some(Self::Square),
+            "vip" => Some(Self::Vip),
+            _ => None,
+        }
+    }
+}
+
+pub struct SquareWave {
+    phase_inc: f32,
+    phase: f32,
+    volume: f32,
+}
+
+impl SquareWave {
+    // pitch Hz for rate samples a second
+    pub fn new(pitch: f32, rate: u32) -> Self {
+        Self {
+            phase_inc: pitch / rate as f32,
+            phase: 0.0,
+            volume: VOLUME,
+        }
+    }
+
+    pub fn fill(&mut self, out: &mut [f32]) {
+        for x in out.iter_mut() {
+            *x = if self.phase <= 0.5 {
+                self.volume
+            } else {
+                -self.volume
+            };
+            self.phase = (self.phase + self.phase_inc) % 1.0;
+        }
+    }
+}
+
+// how much of the way a one pole filter at cutoff Hz moves toward its input each sample
+fn pole(cutoff: f32, rate: u32) -> f32 {
+    1.0 - exp(-2.0 * core::f32::consts::PI * cutoff / rate as f32)
+}
+
+#[cfg(feature = "std")]
+fn exp(x: f32) -> f32 {
+    x.exp()
+}
+
+// without std there's no exp, so its series. x is -π at worst, for the speaker's top end at 8kHz,
+// where twenty terms is as close as an f32 gets
+#[cfg(not(feature = "std"))]
+fn exp(x: f32) -> f32 {
+    let (mut sum, mut term) = (1.0, 1.0);
+    for n in 1..20 {
+        term *= x / n as f32;
+        sum += term;
+    }
+    sum
+}
+
+// the buzzer as one of the models, on or off as the sound timer says
+pub struct Buzzer {
+    model: Model,
+    wave: SquareWave,
+    low: f32,  // the low pass's output
+    high: f32, // and what the high pass has taken out
+    low_pole: f32,
+    high_pole: f32,
+}
+
+impl Buzzer {
+    pub fn new(model: Model, rate: u32) -> Self {
+        let pitch = match model {
+            Model::Square => PITCH,
+            Model::Vip => VIP_PITCH,
+        };
+        Self {
+            model,
+            wave: SquareWave::new(pitch, rate),
+            low: 0.0,
+            high: 0.0,
+            low_pole: pole(VIP_SPEAKER_HIGH, rate),
+            high_pole: pole(VIP_SPEAKER_LOW, rate),
+        }
+    }
+
+    pub fn fill(&mut self, on: bool, out: &mut [f32]) {
+        match self.model {
+            Model::Square if on => self.wave.fill(out),
+            Model::Square => out.iter_mut().for_each(|x| *x = 0.0),
+            Model::Vip => {
+                for x in out.iter_mut() {
+                    let level = if on && self.wave.phase <= 0.5 {
+                        1.0
+                    } else {
+                        0.0
+                    };
+                    if on {
+                        self.wave.phase = (self.wave.phase + self.wave.phase_inc) % 1.0;
+                    }
+                    self.low += self.low_pole * (level - self.low);
+                    self.high += self.high_pole * (self.low - self.high);
+                    // a steady tone swings about half each way, so this brings it to VOLUME
+                    *x = (self.low - self.high) * 2.0 * VOLUME;
+                }
+            }
+        }
+    }
+}
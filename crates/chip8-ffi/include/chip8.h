@@ -0,0 +1,105 @@
+/* C API for the chip8 core, build with: cargo build --release -p chip8-ffi
+ * and link against target/release/libchip8_ffi.so (or .dylib/.dll).
+ *
+ *     chip8_t *vm = chip8_new();
+ *     chip8_load_rom(vm, rom, rom_len);
+ *     while (chip8_step(vm) == CHIP8_OK) {
+ *         if (chip8_draw_flag(vm))
+ *             draw(chip8_framebuffer(vm));
+ *     }
+ *     chip8_free(vm);
+ */
+
+#ifndef CHIP8_H
+#define CHIP8_H
+
+/* made by cbindgen from src/lib.rs, don't edit. CHIP8_BLESS=1 cargo test --test ffi writes it again */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct Chip8 chip8_t;
+
+// the framebuffer is CHIP8_WIDTH * CHIP8_HEIGHT bytes, row major, 0 or 1 per pixel
+#define CHIP8_WIDTH 64
+
+#define CHIP8_HEIGHT 32
+
+// chip8_step results, anything but CHIP8_OK means the VM can't continue
+#define CHIP8_OK 0
+
+#define CHIP8_ERR_NULL -1
+
+#define CHIP8_ERR_UNKNOWN_OPCODE 1
+
+#define CHIP8_ERR_STACK_OVERFLOW 2
+
+#define CHIP8_ERR_STACK_UNDERFLOW 3
+
+#define CHIP8_ERR_MEMORY_OUT_OF_BOUNDS 4
+
+#define CHIP8_ERR_PC_OUT_OF_BOUNDS 5
+
+#define CHIP8_ERR_KEY_WAIT_TIMEOUT 6
+
+// an error added to the core after this version of the API
+#define CHIP8_ERR_OTHER 99
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+chip8_t *chip8_new(void);
+
+// # Safety
+// chip8 must be NULL or a pointer from chip8_new that hasn't been freed yet
+void chip8_free(chip8_t *chip8);
+
+// copies the ROM to 0x200, anything past the end of memory is dropped without being read
+//
+// # Safety
+// chip8 as for chip8_free, rom must point to len readable bytes
+int chip8_load_rom(chip8_t *chip8, const uint8_t *rom, size_t len);
+
+// makes CXNN produce the same numbers every run
+//
+// # Safety
+// chip8 as for chip8_free
+void chip8_seed(chip8_t *chip8, uint64_t seed);
+
+// runs one instruction, about 3ms of CHIP-8 time
+//
+// # Safety
+// chip8 as for chip8_free
+int chip8_step(chip8_t *chip8);
+
+// valid until the next chip8_step
+//
+// # Safety
+// chip8 as for chip8_free
+const uint8_t *chip8_framebuffer(const chip8_t *chip8);
+
+// whether the last chip8_step changed the screen
+//
+// # Safety
+// chip8 as for chip8_free
+bool chip8_draw_flag(const chip8_t *chip8);
+
+// whether the buzzer should be sounding
+//
+// # Safety
+// chip8 as for chip8_free
+bool chip8_sound(const chip8_t *chip8);
+
+// key is 0x0-0xF
+//
+// # Safety
+// chip8 as for chip8_free
+void chip8_set_key(chip8_t *chip8, uint8_t key, bool pressed);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CHIP8_H */
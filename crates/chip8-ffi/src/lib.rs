@@ -0,0 +1,138 @@
+// C API for the core, built as libchip8_ffi.so (or .dylib/.dll). its header, include/chip8.h, is
+// made from this file by cbindgen and checked in, tests/ffi.rs fails when the two disagree. every
+// function takes the pointer returned by chip8_new and treats NULL as a no-op (or an error where
+// there's something to return)
+
+use chip8_core::chip8::Error;
+use chip8_core::Chip8;
+use std::os::raw::c_int;
+
+/// the framebuffer is CHIP8_WIDTH * CHIP8_HEIGHT bytes, row major, 0 or 1 per pixel
+pub const CHIP8_WIDTH: usize = 64;
+pub const CHIP8_HEIGHT: usize = 32;
+
+/// chip8_step results, anything but CHIP8_OK means the VM can't continue
+pub const CHIP8_OK: c_int = 0;
+pub const CHIP8_ERR_NULL: c_int = -1;
+pub const CHIP8_ERR_UNKNOWN_OPCODE: c_int = 1;
+pub const CHIP8_ERR_STACK_OVERFLOW: c_int = 2;
+pub const CHIP8_ERR_STACK_UNDERFLOW: c_int = 3;
+pub const CHIP8_ERR_MEMORY_OUT_OF_BOUNDS: c_int = 4;
+pub const CHIP8_ERR_PC_OUT_OF_BOUNDS: c_int = 5;
+pub const CHIP8_ERR_KEY_WAIT_TIMEOUT: c_int = 6;
+/// an error added to the core after this version of the API
+pub const CHIP8_ERR_OTHER: c_int = 99;
+
+fn error_code(e: Error) -> c_int {
+    match e {
+        Error::UnknownOpcode { .. } => CHIP8_ERR_UNKNOWN_OPCODE,
+        Error::StackOverflow { .. } => CHIP8_ERR_STACK_OVERFLOW,
+        Error::StackUnderflow { .. } => CHIP8_ERR_STACK_UNDERFLOW,
+        Error::MemoryOutOfBounds { .. } => CHIP8_ERR_MEMORY_OUT_OF_BOUNDS,
+        Error::PcOutOfBounds { .. } => CHIP8_ERR_PC_OUT_OF_BOUNDS,
+        Error::KeyWaitTimeout { .. } => CHIP8_ERR_KEY_WAIT_TIMEOUT,
+        _ => CHIP8_ERR_OTHER,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn chip8_new() -> *mut Chip8 {
+    Box::into_raw(Box::new(Chip8::new()))
+}
+
+/// # Safety
+/// chip8 must be NULL or a pointer from chip8_new that hasn't been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn chip8_free(chip8: *mut Chip8) {
+    if !chip8.is_null() {
+        drop(Box::from_raw(chip8));
+    }
+}
+
+/// copies the ROM to 0x200, anything past the end of memory is dropped without being read
+///
+/// # Safety
+/// chip8 as for chip8_free, rom must point to len readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_rom(chip8: *mut Chip8, rom: *const u8, len: usize) -> c_int {
+    match chip8.as_mut() {
+        Some(chip8) if !rom.is_null() => {
+            let len = len.min(chip8.memory().len());
+            chip8.load_rom(std::slice::from_raw_parts(rom, len));
+            CHIP8_OK
+        }
+        _ => CHIP8_ERR_NULL,
+    }
+}
+
+/// makes CXNN produce the same numbers every run
+///
+/// # Safety
+/// chip8 as for chip8_free
+#[no_mangle]
+pub unsafe extern "C" fn chip8_seed(chip8: *mut Chip8, seed: u64) {
+    if let Some(chip8) = chip8.as_mut() {
+        chip8.seed_rng(seed);
+    }
+}
+
+/// runs one instruction, about 3ms of CHIP-8 time
+///
+/// # Safety
+/// chip8 as for chip8_free
+#[no_mangle]
+pub unsafe extern "C" fn chip8_step(chip8: *mut Chip8) -> c_int {
+    match chip8.as_mut() {
+        Some(chip8) => match chip8.emulate_cycle() {
+            Ok(()) => CHIP8_OK,
+            Err(e) => error_code(e),
+        },
+        None => CHIP8_ERR_NULL,
+    }
+}
+
+/// valid until the next chip8_step
+///
+/// # Safety
+/// chip8 as for chip8_free
+#[no_mangle]
+pub unsafe extern "C" fn chip8_framebuffer(chip8: *const Chip8) -> *const u8 {
+    match chip8.as_ref() {
+        Some(chip8) => chip8.gfx().as_ptr(),
+        None => std::ptr::null(),
+    }
+}
+
+/// whether the last chip8_step changed the screen
+///
+/// # Safety
+/// chip8 as for chip8_free
+#[no_mangle]
+pub unsafe extern "C" fn chip8_draw_flag(chip8: *const Chip8) -> bool {
+    chip8.as_ref().is_some_and(|c| c.draw_flag())
+}
+
+/// whether the buzzer should be sounding
+///
+/// # Safety
+/// chip8 as for chip8_free
+#[no_mangle]
+pub unsafe extern "C" fn chip8_sound(chip8: *const Chip8) -> bool {
+    chip8.as_ref().is_some_and(|c| c.sound_flag())
+}
+
+/// key is 0x0-0xF
+///
+/// # Safety
+/// chip8 as for chip8_free
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_key(chip8: *mut Chip8, key: u8, pressed: bool) {
+    if let Some(chip8) = chip8.as_mut() {
+        let key = (key & 0xF) as usize;
+        if pressed {
+            chip8.press_key(key);
+        } else {
+            chip8.release_key(key);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use chip8_core::synth::Buzzer;
+use sdl2::audio::AudioCallback;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// plays all the time, so models that go on sounding after the buzzer's turned off can
+pub struct Playback {
+    pub buzzer: Buzzer,
+    pub on: Arc<AtomicBool>,
+}
+
+impl AudioCallback for Playback {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        self.buzzer.fill(self.on.load(Ordering::Relaxed), out);
+    }
+}
@@ -0,0 +1,458 @@
+// the SDL frontend: a window, sound, the keyboard and game controllers
+
+mod audio;
+
+pub use sdl2; // for the key types keypad and hotkey take
+
+use chip8_core::frontend::{Display, Hotkey, Keypad, Machine, Options, Rotation, ScreenMode};
+use chip8_core::overlay::{self, Corner, Toasts};
+use chip8_core::palette::{Palette, Rgb};
+use chip8_core::synth::{self, Buzzer};
+use sdl2::audio::{AudioDevice, AudioSpecDesired};
+use sdl2::controller::GameController;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Scancode};
+use sdl2::pixels;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::video::Window;
+use sdl2::EventPump;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SCALE: u32 = 4;
+// rumble for longer than the sound timer can run, it's stopped when the timer runs out
+const RUMBLE_MS: u32 = 5000;
+const TOAST_SCALE: u32 = 2; // size of a font pixel in window pixels
+const KEYPAD_SCALE: u32 = 3; // and of the keypad's, bigger so it reads on a stream
+const REDRAW_TIME: Duration = Duration::from_millis(16); // while toasts fade, with no new frames
+const FLASH_WIDTH: u32 = 6; // of the border shown while the buzzer sounds, in window pixels
+
+const HOTKEYS: [(Keycode, Hotkey); 13] = [
+    (Keycode::M, Hotkey::Mute),
+    (Keycode::F1, Hotkey::Macro(0)),
+    (Keycode::F2, Hotkey::Macro(1)),
+    (Keycode::F3, Hotkey::Macro(2)),
+    (Keycode::F4, Hotkey::Macro(3)),
+    (Keycode::F5, Hotkey::SaveState),
+    (Keycode::F6, Hotkey::PrevSlot),
+    (Keycode::F7, Hotkey::NextSlot),
+    (Keycode::F8, Hotkey::RecordMacro),
+    (Keycode::F9, Hotkey::LoadState),
+    (Keycode::F10, Hotkey::Cheats),
+    (Keycode::F11, Hotkey::Pause),
+    (Keycode::F12, Hotkey::Keypad),
+];
+
+const KEYPAD: [Scancode; 16] = [
+    Scancode::X,    // 0
+    Scancode::Num1, // 1
+    Scancode::Num2, // 2
+    Scancode::Num3, // 3
+    Scancode::Q,    // 4
+    Scancode::W,    // 5
+    Scancode::E,    // 6
+    Scancode::A,    // 7
+    Scancode::S,    // 8
+    Scancode::D,    // 9
+    Scancode::Z,    // A
+    Scancode::C,    // B
+    Scancode::Num4, // C
+    Scancode::R,    // D
+    Scancode::F,    // E
+    Scancode::V,    // F
+];
+
+// for the second screen, laid out like frontend::QWERTY_RIGHT
+const KEYPAD_RIGHT: [Scancode; 16] = [
+    Scancode::Comma,     // 0
+    Scancode::Num7,      // 1
+    Scancode::Num8,      // 2
+    Scancode::Num9,      // 3
+    Scancode::U,         // 4
+    Scancode::I,         // 5
+    Scancode::O,         // 6
+    Scancode::J,         // 7
+    Scancode::K,         // 8
+    Scancode::L,         // 9
+    Scancode::N,         // A
+    Scancode::Period,    // B
+    Scancode::Num0,      // C
+    Scancode::P,         // D
+    Scancode::Semicolon, // E
+    Scancode::Slash,     // F
+];
+
+// the window pixels the screens take up side by side, before any rotation
+fn screen_size(screens: usize) -> (u32, u32) {
+    (64 * SCALE * screens as u32, 32 * SCALE)
+}
+
+// the key a scancode is, keys on the right of the keyboard are 16 and up for the second screen
+// when there is one
+pub fn keypad(key: Scancode, screens: usize) -> Option<usize> {
+    match KEYPAD.iter().position(|&k| k == key) {
+        Some(i) => Some(i),
+        None if screens > 1 => KEYPAD_RIGHT.iter().position(|&k| k == key).map(|i| 16 + i),
+        None => None,
+    }
+}
+
+pub fn hotkey(key: Keycode) -> Option<Hotkey> {
+    HOTKEYS.iter().find(|&&(k, _)| k == key).map(|&(_, h)| h)
+}
+
+pub struct Sdl {
+    canvas: Canvas<Window>,
+    _audio_device: AudioDevice<audio::Playback>, // plays for as long as it's kept
+    buzzing: Arc<AtomicBool>,
+    controller_subsystem: Option<sdl2::GameControllerSubsystem>, // None if there's no support
+    controllers: Vec<GameController>,
+    rumbling: bool,
+    flashing: bool,
+    event_pump: EventPump,
+    toasts: Toasts,
+    screens: usize,
+    palette: Palette,
+    rotation: Rotation,
+    screen_mode: ScreenMode,
+    watches: Vec<String>,
+    keys: Option<u16>, // held, while the keypad's shown
+    keypad_corner: Corner,
+    gfx: Vec<u8>, // the last screen, to draw toasts over again as they fade
+    drawn_at: Instant,
+}
+
+impl Sdl {
+    pub fn new(options: &Options) -> Result<Self, String> {
+        let sdl_ctx = sdl2::init()?;
+        let video = sdl_ctx.video()?;
+
+        let (width, height) = options.rotation.size(screen_size(options.screens));
+        let window = video
+            .window("CHIP-8", width, height)
+            .position_centered()
+            .resizable()
+            .build()
+            .map_err(|e| e.to_string())?;
+        let mut canvas = window.into_canvas().build().map_err(|e| e.to_string())?;
+        canvas.set_blend_mode(BlendMode::Blend);
+
+        let audio_subsystem = sdl_ctx.audio()?;
+        let audio_spec = AudioSpecDesired {
+            freq: Some(synth::SAMPLE_RATE as i32),
+            channels: Some(1), // mono
+            samples: None,     // default
+        };
+        let buzzing = Arc::new(AtomicBool::new(false));
+        let audio_device =
+            audio_subsystem.open_playback(None, &audio_spec, |spec| audio::Playback {
+                buzzer: Buzzer::new(options.buzzer, spec.freq as u32),
+                on: Arc::clone(&buzzing),
+            })?;
+        audio_device.resume();
+
+        Ok(Self {
+            canvas,
+            _audio_device: audio_device,
+            buzzing,
+            // controllers already plugged in arrive as added events
+            controller_subsystem: sdl_ctx.game_controller().ok(),
+            controllers: Vec::new(),
+            rumbling: false,
+            flashing: false,
+            event_pump: sdl_ctx.event_pump()?,
+            toasts: Toasts::new(options.toast_time),
+            screens: options.screens,
+            palette: options.palette,
+            rotation: options.rotation,
+            screen_mode: options.screen_mode,
+            watches: Vec::new(),
+            keys: None,
+            keypad_corner: options.keypad_corner,
+            gfx: Vec::new(),
+            drawn_at: Instant::now(),
+        })
+    }
+
+    // everything's drawn at its size in screen_size, scaled up to however big the window's been
+    // made: keeping its shape with bars round it, or stretched to fill it
+    fn fit(&mut self) {
+        let (width, height) = self.rotation.size(screen_size(self.screens));
+        if self.screen_mode != ScreenMode::Stretch {
+            let _ = self.canvas.set_logical_size(width, height);
+            return;
+        }
+        // no logical size puts the scale back to 1, for it to be set again from the window's
+        let _ = self.canvas.set_logical_size(0, 0);
+        if let Ok((w, h)) = self.canvas.output_size() {
+            let _ = (self.canvas).set_scale(w as f32 / width as f32, h as f32 / height as f32);
+        }
+    }
+
+    // a rectangle of the screen as it's laid out unturned, where it goes in the window
+    fn turned(&self, x: i32, y: i32, w: u32, h: u32) -> Rect {
+        let (x, y, w, h) = (self.rotation).rect((x, y, w, h), screen_size(self.screens));
+        Rect::new(x, y, w, h)
+    }
+
+    fn set_rumble(controller: &mut GameController, on: bool) {
+        let (low, high, ms) = if on {
+            (0x4000, 0xC000, RUMBLE_MS)
+        } else {
+            (0, 0, 0)
+        };
+        // not every controller can rumble
+        let _ = controller.set_rumble(low, high, ms);
+    }
+
+    // a line of overlay text at y on a dark box so it shows over anything
+    fn draw_line(&mut self, text: &str, y: i32, color: pixels::Color) {
+        let line_height = (overlay::GLYPH_HEIGHT as u32 + 2) * TOAST_SCALE;
+        let width = (overlay::text_width(text) as u32 + 2) * TOAST_SCALE;
+        self.canvas
+            .set_draw_color(pixels::Color::RGBA(0, 0, 0, color.a / 4 * 3));
+        let _ = self.canvas.fill_rect(Rect::new(0, y, width, line_height));
+        self.canvas.set_draw_color(color);
+        let mut rects = Vec::new();
+        overlay::draw_text(text, |px, py| {
+            rects.push(Rect::new(
+                (px as u32 + 1) as i32 * TOAST_SCALE as i32,
+                y + (py as u32 + 1) as i32 * TOAST_SCALE as i32,
+                TOAST_SCALE,
+                TOAST_SCALE,
+            ))
+        });
+        let _ = self.canvas.fill_rects(&rects);
+    }
+
+    // the keypad in its corner of the window on a dark box, with a font pixel's border
+    fn draw_keypad(&mut self, held: u16) {
+        let scale = KEYPAD_SCALE as usize;
+        let (w, h) = (
+            (overlay::KEYPAD_WIDTH + 2) * scale,
+            (overlay::KEYPAD_HEIGHT + 2) * scale,
+        );
+        let (width, height) = self.rotation.size(screen_size(self.screens));
+        let (x, y) = (self.keypad_corner).place((w, h), (width as usize, height as usize), 0);
+        let (x, y, w, h) = (x as i32, y as i32, w as u32, h as u32);
+        self.canvas
+            .set_draw_color(pixels::Color::RGBA(0, 0, 0, 192));
+        let _ = self.canvas.fill_rect(Rect::new(x, y, w, h));
+        self.canvas
+            .set_draw_color(pixels::Color::RGB(255, 255, 255));
+        let mut rects = Vec::new();
+        overlay::draw_keypad(held, |px, py| {
+            rects.push(Rect::new(
+                x + (px as u32 + 1) as i32 * KEYPAD_SCALE as i32,
+                y + (py as u32 + 1) as i32 * KEYPAD_SCALE as i32,
+                KEYPAD_SCALE,
+                KEYPAD_SCALE,
+            ))
+        });
+        let _ = self.canvas.fill_rects(&rects);
+    }
+
+    // toasts go bottom left, newest at the bottom, watches top left, and the keypad wherever
+    // it's been put
+    fn draw_overlay(&mut self) {
+        if let Some(held) = self.keys {
+            self.draw_keypad(held);
+        }
+        let line_height = (overlay::GLYPH_HEIGHT as i32 + 2) * TOAST_SCALE as i32;
+        let watches = std::mem::take(&mut self.watches);
+        for (n, text) in watches.iter().enumerate() {
+            self.draw_line(
+                text,
+                n as i32 * line_height,
+                pixels::Color::RGBA(0, 255, 255, 255),
+            );
+        }
+        self.watches = watches;
+
+        let visible: Vec<(String, f32)> = self
+            .toasts
+            .visible(Instant::now())
+            .iter()
+            .map(|(text, alpha)| (text.to_string(), *alpha))
+            .collect();
+        let bottom = self.rotation.size(screen_size(self.screens)).1 as i32;
+        for (n, (text, alpha)) in visible.iter().rev().enumerate() {
+            let y = bottom - (n as i32 + 1) * line_height;
+            let alpha = (alpha * 255.0) as u8;
+            self.draw_line(text, y, pixels::Color::RGBA(255, 255, 0, alpha));
+        }
+    }
+}
+
+impl Display for Sdl {
+    fn present(&mut self, gfx: &[u8]) {
+        let color = |[r, g, b]: Rgb| pixels::Color::RGB(r, g, b);
+        self.fit();
+        self.canvas.set_draw_color(color(self.palette.color(0)));
+        self.canvas.clear();
+        // the lit pixels of each plane, or both, in their own color
+        let mut rects = [Vec::new(), Vec::new(), Vec::new()];
+        let width = 64 * self.screens as i32;
+        let gap = self.screen_mode.gap(SCALE as usize) as u32;
+        let dot = SCALE - gap;
+        for (i, p) in gfx.iter().enumerate() {
+            if *p & 3 == 0 {
+                continue;
+            }
+            let i = i as i32;
+            let x = (i % width) * SCALE as i32 + (gap / 2) as i32;
+            let y = (i / width) * SCALE as i32 + (gap / 2) as i32;
+            rects[(*p & 3) as usize - 1].push(self.turned(x, y, dot, dot));
+        }
+        for (n, rects) in rects.iter().enumerate().filter(|(_, r)| !r.is_empty()) {
+            self.canvas
+                .set_draw_color(color(self.palette.color(n as u8 + 1)));
+            self.canvas.fill_rects(rects).unwrap();
+        }
+        // a line between screens
+        self.canvas.set_draw_color(pixels::Color::RGB(96, 96, 96));
+        for n in 1..self.screens as i32 {
+            let x = n * 64 * SCALE as i32;
+            let _ = self.canvas.fill_rect(self.turned(x - 1, 0, 2, 32 * SCALE));
+        }
+        if self.flashing {
+            let (width, height) = self.rotation.size(screen_size(self.screens));
+            self.canvas.set_draw_color(pixels::Color::RGB(255, 160, 0));
+            let _ = self.canvas.fill_rects(&[
+                Rect::new(0, 0, width, FLASH_WIDTH),
+                Rect::new(0, (height - FLASH_WIDTH) as i32, width, FLASH_WIDTH),
+                Rect::new(0, 0, FLASH_WIDTH, height),
+                Rect::new((width - FLASH_WIDTH) as i32, 0, FLASH_WIDTH, height),
+            ]);
+        }
+        self.draw_overlay();
+        self.canvas.present();
+        self.gfx.clear();
+        self.gfx.extend_from_slice(gfx);
+        self.drawn_at = Instant::now();
+    }
+
+    fn buzzer(&mut self, on: bool) {
+        self.buzzing.store(on, Ordering::Relaxed);
+    }
+
+    fn rumble(&mut self, on: bool) {
+        if self.rumbling != on {
+            self.rumbling = on;
+            for controller in &mut self.controllers {
+                Self::set_rumble(controller, on);
+            }
+        }
+    }
+
+    // a border round the window
+    fn flash(&mut self, on: bool) {
+        if self.flashing != on {
+            self.flashing = on;
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+    }
+
+    fn notify(&mut self, text: &str) {
+        self.toasts.notify(text, Instant::now());
+        let gfx = std::mem::take(&mut self.gfx);
+        self.present(&gfx);
+    }
+
+    fn watches(&mut self, lines: &[String]) {
+        if self.watches != lines {
+            self.watches = lines.to_vec();
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+    }
+
+    fn keys(&mut self, held: Option<u16>) {
+        if self.keys != held {
+            self.keys = held;
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+    }
+
+    fn set_screen_mode(&mut self, mode: ScreenMode) {
+        if self.screen_mode != mode {
+            self.screen_mode = mode;
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+    }
+}
+
+impl Keypad for Sdl {
+    // keys go to the VM as soon as they happen rather than being sampled every so often,
+    // the VM makes sure even the quickest tap lasts long enough for the ROM to see
+    fn poll(&mut self, machine: &mut dyn Machine) -> Vec<Hotkey> {
+        let mut hotkeys = Vec::new();
+        let mut resized = false;
+        for e in self.event_pump.poll_iter() {
+            match e {
+                Event::Quit { .. }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                } => hotkeys.push(Hotkey::Quit),
+                Event::KeyDown {
+                    keycode,
+                    scancode: Some(key),
+                    repeat: false,
+                    ..
+                } => {
+                    if let Some(i) = keypad(key, self.screens) {
+                        machine.press_key(i);
+                    } else if let Some(hotkey) = keycode.and_then(hotkey) {
+                        hotkeys.push(hotkey);
+                    }
+                }
+                Event::KeyUp {
+                    scancode: Some(key),
+                    ..
+                } => {
+                    if let Some(i) = keypad(key, self.screens) {
+                        machine.release_key(i);
+                    }
+                }
+                // no key up events come while another window has focus
+                Event::Window {
+                    win_event: WindowEvent::FocusLost,
+                    ..
+                } => {
+                    machine.clear_keys();
+                    hotkeys.push(Hotkey::Focus(false));
+                }
+                Event::Window {
+                    win_event: WindowEvent::FocusGained,
+                    ..
+                } => hotkeys.push(Hotkey::Focus(true)),
+                Event::Window {
+                    win_event: WindowEvent::SizeChanged(..),
+                    ..
+                } => resized = true,
+                Event::ControllerDeviceAdded { which, .. } => {
+                    let opened = self.controller_subsystem.as_ref().map(|c| c.open(which));
+                    if let Some(Ok(mut controller)) = opened {
+                        Self::set_rumble(&mut controller, self.rumbling);
+                        self.controllers.push(controller);
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    self.controllers.retain(|c| c.instance_id() != which)
+                }
+                _ => {}
+            }
+        }
+        if resized || !self.toasts.is_empty() && self.drawn_at.elapsed() >= REDRAW_TIME {
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+        hotkeys
+    }
+}
@@ -0,0 +1,416 @@
+use chip8_core::disasm::Instruction;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+pub const ENTRY: u16 = 0x200; // where programs are loaded and start executing
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteKind {
+    Data,
+    Code,    // first byte of an instruction reachable from the entry point
+    Operand, // second byte of an instruction
+}
+
+#[derive(Debug)]
+pub struct Block {
+    pub start: u16,
+    pub end: u16,          // address just past the last instruction
+    pub succs: Vec<u16>,   // successor blocks within the ROM
+    pub call: Option<u16>, // subroutine called by the last instruction
+}
+
+pub struct Analysis {
+    rom: Vec<u8>,
+    kinds: Vec<ByteKind>,
+    pub blocks: BTreeMap<u16, Block>,
+    pub functions: BTreeMap<u16, BTreeSet<u16>>, // subroutine entry -> subroutines it calls
+    pub data_refs: BTreeSet<u16>,                // addresses loaded into I
+    pub indirect_jumps: BTreeSet<u16>,           // BNNN sites whose targets we can't follow
+    pub self_modifying: BTreeMap<u16, u16>, // FX33/FX55 sites that store into code -> first code addr hit
+    pub keys: u16,                          // keys EX9E/EXA1 are seen testing, a bit each
+    pub any_key: bool, // FX0A waits for a key, or a key is tested that can't be worked out
+}
+
+fn fetch(rom: &[u8], addr: u16) -> Option<Instruction> {
+    let off = addr.checked_sub(ENTRY)? as usize;
+    if off + 1 >= rom.len() {
+        return None;
+    }
+    Some(Instruction::decode(
+        (rom[off] as u16) << 8 | rom[off + 1] as u16,
+    ))
+}
+
+// addresses control may flow to after executing the instruction at addr
+fn flow(addr: u16, ins: Instruction) -> Vec<u16> {
+    match ins {
+        Instruction::Jp(a) => vec![a],
+        Instruction::Call(_) => vec![addr + 2],
+        Instruction::Ret | Instruction::Exit | Instruction::JpV0(_) | Instruction::Sys(_) => vec![],
+        Instruction::Unknown(_) => vec![],
+        i if i.is_skip() => vec![addr + 2, addr + 4],
+        _ => vec![addr + 2],
+    }
+}
+
+fn ends_block(ins: Instruction) -> bool {
+    matches!(
+        ins,
+        Instruction::Jp(_)
+            | Instruction::Call(_)
+            | Instruction::Ret
+            | Instruction::Exit
+            | Instruction::JpV0(_)
+            | Instruction::Sys(_)
+    ) || ins.is_skip()
+}
+
+// the value of I after ins runs, if it can be known
+fn track_i(ins: Instruction, i: Option<u16>) -> Option<u16> {
+    match ins {
+        Instruction::LdI(a) => Some(a),
+        Instruction::AddI(_) | Instruction::LdF(_) => None,
+        _ => i,
+    }
+}
+
+// the values of V0-VF after ins runs, where they can be known
+fn track_v(ins: Instruction, v: &mut [Option<u8>; 16]) {
+    match ins {
+        Instruction::LdByte(x, n) => v[x as usize] = Some(n),
+        Instruction::AddByte(x, n) => v[x as usize] = v[x as usize].map(|a| a.wrapping_add(n)),
+        Instruction::LdReg(x, y) => v[x as usize] = v[y as usize],
+        Instruction::Or(x, _)
+        | Instruction::And(x, _)
+        | Instruction::Xor(x, _)
+        | Instruction::AddReg(x, _)
+        | Instruction::Sub(x, _)
+        | Instruction::Shr(x, _)
+        | Instruction::Subn(x, _)
+        | Instruction::Shl(x, _) => {
+            v[x as usize] = None;
+            v[0xF] = None;
+        }
+        Instruction::Rnd(x, _) | Instruction::LdVxDt(x) | Instruction::LdVxK(x) => {
+            v[x as usize] = None
+        }
+        Instruction::LdVxI(x) => v[..=x as usize].fill(None),
+        Instruction::Drw(..) => v[0xF] = None,
+        _ => {}
+    }
+}
+
+impl Analysis {
+    // follow every jump and call from the entry point, marking what we reach as code
+    pub fn new(rom: &[u8]) -> Self {
+        let rom = &rom[..rom.len().min(0x1000 - ENTRY as usize)];
+        let mut kinds = vec![ByteKind::Data; rom.len()];
+        let mut instructions = BTreeMap::new();
+        let mut leaders = BTreeSet::new();
+        let mut calls = BTreeSet::new();
+        let mut data_refs = BTreeSet::new();
+        let mut indirect_jumps = BTreeSet::new();
+
+        leaders.insert(ENTRY);
+        let mut work = vec![ENTRY];
+        while let Some(addr) = work.pop() {
+            if instructions.contains_key(&addr) {
+                continue;
+            }
+            let ins = match fetch(rom, addr) {
+                Some(Instruction::Unknown(_)) | None => continue,
+                Some(i) => i,
+            };
+            instructions.insert(addr, ins);
+            let off = (addr - ENTRY) as usize;
+            kinds[off] = ByteKind::Code;
+            kinds[off + 1] = ByteKind::Operand;
+
+            match ins {
+                Instruction::Jp(a) => {
+                    leaders.insert(a);
+                }
+                Instruction::Call(a) => {
+                    leaders.insert(a);
+                    calls.insert(a);
+                    work.push(a);
+                }
+                Instruction::LdI(a) => {
+                    data_refs.insert(a);
+                }
+                Instruction::JpV0(_) => {
+                    indirect_jumps.insert(addr);
+                }
+                _ => {}
+            }
+            let next = flow(addr, ins);
+            if ends_block(ins) {
+                leaders.extend(next.iter().copied());
+            }
+            work.extend(next);
+        }
+
+        // split the reached instructions into basic blocks
+        let mut blocks = BTreeMap::new();
+        let mut current: Option<Block> = None;
+        for (&addr, &ins) in &instructions {
+            if let Some(b) = current.take() {
+                if b.end == addr && !leaders.contains(&addr) {
+                    current = Some(b);
+                } else {
+                    let mut b = b;
+                    if b.succs.is_empty() && b.end == addr {
+                        b.succs.push(addr); // fell through into a leader
+                    }
+                    blocks.insert(b.start, b);
+                }
+            }
+            let b = current.get_or_insert(Block {
+                start: addr,
+                end: addr,
+                succs: vec![],
+                call: None,
+            });
+            b.end = addr + 2;
+            if ends_block(ins) {
+                b.succs = flow(addr, ins)
+                    .into_iter()
+                    .filter(|a| instructions.contains_key(a))
+                    .collect();
+                if let Instruction::Call(a) = ins {
+                    b.call = Some(a);
+                }
+                blocks.insert(b.start, current.take().unwrap());
+            }
+        }
+        if let Some(b) = current {
+            blocks.insert(b.start, b);
+        }
+
+        // the entry point and every call target are treated as subroutines
+        let mut functions = BTreeMap::new();
+        for &f in std::iter::once(&ENTRY).chain(calls.iter()) {
+            let mut callees = BTreeSet::new();
+            let mut seen = BTreeSet::new();
+            let mut work = vec![f];
+            while let Some(a) = work.pop() {
+                if !seen.insert(a) {
+                    continue;
+                }
+                if let Some(b) = blocks.get(&a) {
+                    callees.extend(b.call);
+                    work.extend(b.succs.iter().copied());
+                }
+            }
+            functions.insert(f, callees);
+        }
+
+        // work out what I holds on entry to each block: Some(Some(a)) if every path agrees on a,
+        // Some(None) if it varies or can't be known and None if the block hasn't been reached yet
+        let mut i_in: BTreeMap<u16, Option<Option<u16>>> = BTreeMap::new();
+        i_in.insert(ENTRY, Some(Some(0)));
+        let mut work = vec![ENTRY];
+        while let Some(a) = work.pop() {
+            let b = match blocks.get(&a) {
+                Some(b) => b,
+                None => continue,
+            };
+            let mut i = i_in[&a].unwrap_or(None);
+            for addr in (b.start..b.end).step_by(2) {
+                i = track_i(instructions[&addr], i);
+            }
+            let mut flows: Vec<(u16, Option<u16>)> = b.succs.iter().map(|&s| (s, i)).collect();
+            if let Some(c) = b.call {
+                // the callee sees our I but we can't know what it leaves behind
+                flows = flows.into_iter().map(|(s, _)| (s, None)).collect();
+                flows.push((c, i));
+            }
+            for (s, i) in flows {
+                let entry = i_in.entry(s).or_insert(None);
+                let merged = match *entry {
+                    None => Some(i),
+                    Some(prev) if prev == i => continue,
+                    Some(_) => Some(None),
+                };
+                if *entry != merged {
+                    *entry = merged;
+                    work.push(s);
+                }
+            }
+        }
+
+        // look for stores through a known I that land on code
+        let mut self_modifying = BTreeMap::new();
+        for b in blocks.values() {
+            let mut i = i_in.get(&b.start).copied().flatten().flatten();
+            for addr in (b.start..b.end).step_by(2) {
+                let ins = instructions[&addr];
+                let len = match ins {
+                    Instruction::LdB(_) => 3,
+                    Instruction::LdIVx(x) => x as u16 + 1,
+                    _ => 0,
+                };
+                if let Some(target) = i.and_then(|i| {
+                    (i..i + len).find(|&a| {
+                        a.checked_sub(ENTRY)
+                            .and_then(|off| kinds.get(off as usize))
+                            .is_some_and(|&k| k != ByteKind::Data)
+                    })
+                }) {
+                    self_modifying.insert(addr, target);
+                }
+                i = track_i(ins, i);
+            }
+        }
+
+        // the keys tested, going by registers loaded earlier in the same block. skips end blocks,
+        // which keeps this simple and still catches the usual LD VX, key; SKNP VX
+        let (mut keys, mut any_key) = (0u16, false);
+        for b in blocks.values() {
+            let mut v = [None; 16];
+            for addr in (b.start..b.end).step_by(2) {
+                let ins = instructions[&addr];
+                match ins {
+                    Instruction::Skp(x) | Instruction::Sknp(x) => match v[x as usize] {
+                        Some(key) => keys |= 1u16 << (key & 0xF),
+                        None => any_key = true,
+                    },
+                    Instruction::LdVxK(_) => any_key = true,
+                    _ => {}
+                }
+                track_v(ins, &mut v);
+            }
+        }
+
+        Self {
+            rom: rom.to_vec(),
+            kinds,
+            blocks,
+            functions,
+            data_refs,
+            indirect_jumps,
+            self_modifying,
+            keys,
+            any_key,
+        }
+    }
+
+    pub fn kind(&self, addr: u16) -> Option<ByteKind> {
+        let off = addr.checked_sub(ENTRY)? as usize;
+        self.kinds.get(off).copied()
+    }
+
+    pub fn is_code(&self, addr: u16) -> bool {
+        self.kind(addr) == Some(ByteKind::Code)
+    }
+
+    pub fn code_bytes(&self) -> usize {
+        self.kinds.iter().filter(|&&k| k != ByteKind::Data).count()
+    }
+
+    // listing of the ROM where only reachable code is decoded and everything else is shown as data
+    pub fn disassemble(&self) -> String {
+        let mut out = String::new();
+        let mut off = 0;
+        while off < self.rom.len() {
+            let addr = ENTRY + off as u16;
+            if self.functions.contains_key(&addr) {
+                let _ = writeln!(out, "\nsub_{:03X}:", addr);
+            } else if self.blocks.contains_key(&addr) {
+                let _ = writeln!(out, "L_{:03X}:", addr);
+            }
+            if self.kinds[off] == ByteKind::Code {
+                let opcode = (self.rom[off] as u16) << 8 | self.rom[off + 1] as u16;
+                let _ = write!(
+                    out,
+                    "  {:03X}: {:04X}  {}",
+                    addr,
+                    opcode,
+                    Instruction::decode(opcode)
+                );
+                match self.self_modifying.get(&addr) {
+                    Some(target) => {
+                        let _ = writeln!(out, "  ; writes into code at {:03X}", target);
+                    }
+                    None => out.push('\n'),
+                }
+                off += 2;
+                continue;
+            }
+
+            // group runs of data into lines of up to 8 bytes
+            let start = off;
+            while off < self.rom.len()
+                && off - start < 8
+                && self.kinds[off] != ByteKind::Code
+                && (off == start || !self.data_refs.contains(&(ENTRY + off as u16)))
+            {
+                off += 1;
+            }
+            let bytes: Vec<String> = self.rom[start..off]
+                .iter()
+                .map(|b| format!("0x{:02X}", b))
+                .collect();
+            let _ = writeln!(out, "  {:03X}: DB {}", addr, bytes.join(", "));
+        }
+        out
+    }
+
+    fn block_label(&self, b: &Block) -> String {
+        let mut label = String::new();
+        let mut addr = b.start;
+        while addr < b.end {
+            if let Some(ins) = fetch(&self.rom, addr) {
+                let _ = write!(label, "{:03X}: {}\\l", addr, ins);
+            }
+            addr += 2;
+        }
+        label
+    }
+
+    // Graphviz control-flow graph, calls are dashed edges
+    pub fn cfg_dot(&self) -> String {
+        let mut out = String::from("digraph cfg {\n    node [shape=box fontname=\"monospace\"];\n");
+        for b in self.blocks.values() {
+            // blocks that modify code are highlighted
+            let color = if self.self_modifying.range(b.start..b.end).next().is_some() {
+                " color=red"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "    \"{:03X}\" [label=\"{}\"{}];",
+                b.start,
+                self.block_label(b),
+                color
+            );
+            for s in &b.succs {
+                let _ = writeln!(out, "    \"{:03X}\" -> \"{:03X}\";", b.start, s);
+            }
+            if let Some(c) = b.call {
+                let _ = writeln!(
+                    out,
+                    "    \"{:03X}\" -> \"{:03X}\" [style=dashed];",
+                    b.start, c
+                );
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    // Graphviz graph of which subroutines call which
+    pub fn call_graph_dot(&self) -> String {
+        let mut out =
+            String::from("digraph calls {\n    node [shape=box fontname=\"monospace\"];\n");
+        for (f, callees) in &self.functions {
+            let _ = writeln!(out, "    \"sub_{:03X}\";", f);
+            for c in callees {
+                let _ = writeln!(out, "    \"sub_{:03X}\" -> \"sub_{:03X}\";", f, c);
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
@@ -0,0 +1,162 @@
+// chip8 analyze: every ROM in a directory run headless for a while, several at once, and a CSV row
+// of figures for each, to sort through a collection by what the ROMs need and whether they work
+
+use crate::analysis::Analysis;
+use crate::detect;
+use chip8_core::chip8::CYCLES_PER_TICK;
+use chip8_core::disasm::Instruction;
+use chip8_core::headless;
+use chip8_core::observer::{Event, Observer};
+use chip8_core::quirks::Quirks;
+use chip8_core::Chip8;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub const HEADER: &str =
+    "rom,bytes,code_bytes,platform,quirks,opcodes,opcodes_used,draws_per_sec,frames,crash";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    pub rom: String,
+    pub bytes: usize,
+    pub code_bytes: usize, // reachable code, as far as the analyzer can tell
+    pub quirks: Option<Quirks>, // what it ran cleanly under, nearest a profile
+    pub opcodes: BTreeSet<&'static str>, // the patterns it ran, e.g. DXYN
+    pub draws_per_sec: f64, // of CHIP-8 time
+    pub frames: usize,     // distinct screens it drew
+    pub crash: Option<String>, // how it failed, under quirks or the default if nothing worked
+}
+
+// the patterns run
+#[derive(Default)]
+struct Opcodes(BTreeSet<&'static str>);
+
+impl Observer for Opcodes {
+    fn on_event(&mut self, _: &Chip8, event: &Event) {
+        if let Event::Exec { opcode, .. } = *event {
+            self.0.insert(Instruction::decode(opcode).pattern());
+        }
+    }
+}
+
+// run rom for frames 60Hz frames, first under every combination of quirks to find what it needs,
+// then under that to count what it does. keys are pressed now and then to get past title screens
+pub fn analyze(name: &str, rom: &[u8], frames: u64) -> Row {
+    let cycles = (frames * CYCLES_PER_TICK as u64) as usize;
+    let report = detect::detect(rom, cycles);
+    let quirks = report.suggestion();
+
+    let mut chip8 = headless::machine(rom, quirks.unwrap_or_default());
+    let mut opcodes = Opcodes::default();
+    let mut draws = 0;
+    let mut screens = BTreeSet::new();
+    let mut crash = None;
+    for cycle in 0..cycles {
+        if chip8.halted() {
+            break;
+        }
+        detect::mash(&mut chip8, cycle);
+        if let Err(e) = chip8.emulate_cycle_observed(&mut opcodes) {
+            crash = Some(format!("{} after {} cycles", e, cycle));
+            break;
+        }
+        if chip8.draw_flag() {
+            draws += 1;
+            screens.insert(headless::frame_hash(chip8.gfx()));
+        }
+    }
+    if crash.is_none() && quirks.is_none() {
+        // it didn't error out under the default, so it hung
+        crash = (report.profile(Quirks::default())).and_then(|t| t.failure());
+    }
+    let seconds = chip8.ticks().max(1) as f64 / 60.0;
+    Row {
+        rom: name.to_string(),
+        bytes: rom.len(),
+        code_bytes: Analysis::new(rom).code_bytes(),
+        quirks,
+        opcodes: opcodes.0,
+        draws_per_sec: draws as f64 / seconds,
+        frames: screens.len(),
+        crash,
+    }
+}
+
+// analyze every ROM on threads threads, the rows in the same order as roms. one that can't be read
+// gets a row saying so
+pub fn analyze_all(roms: &[PathBuf], frames: u64, threads: usize) -> Vec<Row> {
+    let next = AtomicUsize::new(0);
+    let rows = Mutex::new(vec![None; roms.len()]);
+    std::thread::scope(|s| {
+        for _ in 0..threads.clamp(1, roms.len().max(1)) {
+            s.spawn(|| loop {
+                let n = next.fetch_add(1, Ordering::Relaxed);
+                let path = match roms.get(n) {
+                    Some(path) => path,
+                    None => break,
+                };
+                let row = row(path, frames);
+                rows.lock().unwrap()[n] = Some(row);
+            });
+        }
+    });
+    rows.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+fn row(path: &Path, frames: u64) -> Row {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    match std::fs::read(path) {
+        Ok(rom) => analyze(&name, &rom, frames),
+        Err(e) => Row {
+            rom: name.to_string(),
+            bytes: 0,
+            code_bytes: 0,
+            quirks: None,
+            opcodes: BTreeSet::new(),
+            draws_per_sec: 0.0,
+            frames: 0,
+            crash: Some(e.to_string()),
+        },
+    }
+}
+
+// quoted if it has to be
+fn field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+impl Row {
+    pub fn csv(&self) -> String {
+        let opcodes: Vec<&str> = self.opcodes.iter().copied().collect();
+        [
+            field(&self.rom),
+            self.bytes.to_string(),
+            self.code_bytes.to_string(),
+            self.quirks
+                .map_or("", |q| q.nearest_profile().0)
+                .to_string(),
+            field(&self.quirks.map(|q| q.to_string()).unwrap_or_default()),
+            self.opcodes.len().to_string(),
+            opcodes.join(" "),
+            format!("{:.1}", self.draws_per_sec),
+            self.frames.to_string(),
+            field(self.crash.as_deref().unwrap_or("")),
+        ]
+        .join(",")
+    }
+}
+
+pub fn csv(rows: &[Row]) -> String {
+    let mut out = format!("{}\n", HEADER);
+    for row in rows {
+        out.push_str(&row.csv());
+        out.push('\n');
+    }
+    out
+}
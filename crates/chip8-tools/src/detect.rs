@@ -0,0 +1,145 @@
+use chip8_core::chip8::Error;
+use chip8_core::headless;
+use chip8_core::quirks::{self, Quirks};
+use chip8_core::Chip8;
+
+const STUCK_CYCLES: usize = 3_000; // longer than any delay-timer wait, which tops out around 1300
+const STUCK_SPAN: u16 = 6; // a loop of at most this many bytes counts as one place to be stuck in
+const KEY_PERIOD: usize = 300; // cycles between simulated key presses
+const KEY_HOLD: usize = 100; // cycles each simulated key is held for
+
+#[derive(Debug)]
+pub struct Trial {
+    pub quirks: Quirks,
+    pub cycles: usize,        // cycles run before stopping
+    pub error: Option<Error>, // the VM stopped itself
+    pub stuck: Option<u16>,   // PC of a loop the ROM never left
+    pub halted: bool,         // the ROM finished with a jump to itself, which is fine
+    pub frames: usize,        // distinct screens drawn
+}
+
+impl Trial {
+    pub fn clean(&self) -> bool {
+        self.error.is_none() && self.stuck.is_none()
+    }
+
+    pub fn failure(&self) -> Option<String> {
+        if let Some(e) = self.error {
+            Some(format!("{} after {} cycles", e, self.cycles))
+        } else {
+            self.stuck.map(|pc| {
+                format!(
+                    "stuck in a loop at 0x{:03X} after {} cycles",
+                    pc, self.cycles
+                )
+            })
+        }
+    }
+}
+
+pub struct Report {
+    pub trials: Vec<Trial>, // best first
+}
+
+impl Report {
+    pub fn clean(&self) -> impl Iterator<Item = &Trial> {
+        self.trials.iter().filter(|t| t.clean())
+    }
+
+    // quirks set the same way in every clean run while some other setting failed,
+    // the ROM presumably depends on them
+    pub fn required(&self) -> Vec<(&'static str, bool)> {
+        let clean: Vec<&Trial> = self.clean().collect();
+        if clean.is_empty() || clean.len() == self.trials.len() {
+            return Vec::new();
+        }
+        let first = clean[0].quirks.flags();
+        quirks::NAMES[..quirks::VM_QUIRKS]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| clean.iter().all(|t| t.quirks.flags()[*i] == first[*i]))
+            .map(|(i, (name, _))| (*name, first[i]))
+            .collect()
+    }
+
+    // the clean run closest to one of the named profiles
+    pub fn suggestion(&self) -> Option<Quirks> {
+        self.clean().next().map(|t| t.quirks)
+    }
+
+    pub fn profile(&self, quirks: Quirks) -> Option<&Trial> {
+        self.trials.iter().find(|t| t.quirks == quirks)
+    }
+}
+
+// press a key now and then so ROMs get past title screens and key waits
+pub fn mash(chip8: &mut Chip8, cycle: usize) {
+    if cycle.is_multiple_of(KEY_PERIOD) {
+        chip8.press_key((cycle / KEY_PERIOD).wrapping_mul(7) % 16);
+    } else if cycle % KEY_PERIOD == KEY_HOLD {
+        chip8.clear_keys();
+    }
+}
+
+pub fn trial(rom: &[u8], cycles: usize, quirks: Quirks) -> Trial {
+    let mut chip8 = headless::machine(rom, quirks);
+    let (mut lo, mut hi) = (chip8.pc(), chip8.pc());
+    let mut since = 0; // cycles spent between lo and hi
+    let mut stuck = None;
+    let mut halted = false;
+
+    let outcome = headless::run_machine(&mut chip8, cycles, |chip8, cycle| {
+        let pc = chip8.pc();
+        if chip8.halted() {
+            halted = true;
+            return false;
+        }
+        let (l, h) = (lo.min(pc), hi.max(pc));
+        if h - l <= STUCK_SPAN {
+            lo = l;
+            hi = h;
+            since += 1;
+        } else {
+            lo = pc;
+            hi = pc;
+            since = 0;
+        }
+        if since == STUCK_CYCLES {
+            stuck = Some(lo);
+            return false;
+        }
+        mash(chip8, cycle);
+        true
+    });
+
+    let mut frames = outcome.frames;
+    frames.sort_unstable();
+    frames.dedup();
+    Trial {
+        quirks,
+        cycles: outcome.cycles,
+        error: outcome.error,
+        stuck,
+        halted,
+        frames: frames.len(),
+    }
+}
+
+// runs the ROM for a while under every combination of quirks and ranks the combinations:
+// first the ones that neither errored out nor hung, nearest a named profile first, then
+// the failures, those that lasted longest first. ties go to the profile listed first
+pub fn detect(rom: &[u8], cycles: usize) -> Report {
+    let mut trials: Vec<Trial> = Quirks::all().map(|q| trial(rom, cycles, q)).collect();
+    trials.sort_by_key(|t| {
+        let lasted = if t.clean() { 0 } else { t.cycles };
+        let (name, nearest) = t.quirks.nearest_profile();
+        let profile = Quirks::PROFILES.iter().position(|(n, _)| *n == name);
+        (
+            !t.clean(),
+            std::cmp::Reverse(lasted),
+            t.quirks.distance(&nearest),
+            profile,
+        )
+    });
+    Report { trials }
+}
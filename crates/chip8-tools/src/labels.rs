@@ -0,0 +1,86 @@
+// names for regions of memory, like "score" at 2A4 or "sprite_table" for the 8 bytes from 300,
+// shared by everything that shows memory: the debugger's hex dumps, watch expressions and cheats,
+// and --trace. they're kept per ROM with the cheats, as "name.sprite_table = 300 8" lines
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+// len bytes from addr
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub addr: u16,
+    pub len: u16,
+}
+
+impl Region {
+    // "300" for one byte, or "300 8"
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut words = s.split_whitespace();
+        let addr = u16::from_str_radix(words.next()?, 16).ok()?;
+        let len = match words.next() {
+            Some(len) => len.parse().ok().filter(|&len| len > 0)?,
+            None => 1,
+        };
+        words.next().is_none().then_some(Self { addr, len })
+    }
+
+    pub fn contains(&self, addr: u16) -> bool {
+        (self.addr..self.addr.saturating_add(self.len)).contains(&addr)
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:03X}", self.addr)?;
+        if self.len > 1 {
+            write!(f, " {}", self.len)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Labels {
+    regions: BTreeMap<String, Region>,
+}
+
+impl Labels {
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Region> {
+        self.regions.get(name).copied()
+    }
+
+    pub fn insert(&mut self, name: &str, region: Region) {
+        self.regions.insert(name.to_string(), region);
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.regions.remove(name).is_some()
+    }
+
+    // by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Region)> {
+        self.regions.iter().map(|(name, &r)| (name.as_str(), r))
+    }
+
+    // the name of a region starting at addr, if there is one
+    pub fn name_at(&self, addr: u16) -> Option<&str> {
+        self.iter()
+            .find(|(_, r)| r.addr == addr)
+            .map(|(name, _)| name)
+    }
+
+    // addr by the region it's in, e.g. "score" or "sprite_table+3". where regions overlap the one
+    // starting nearest to addr
+    pub fn describe(&self, addr: u16) -> Option<String> {
+        let (name, region) =
+            (self.iter().filter(|(_, r)| r.contains(addr))).max_by_key(|(_, r)| r.addr)?;
+        Some(match addr - region.addr {
+            0 => name.to_string(),
+            offset => format!("{}+{}", name, offset),
+        })
+    }
+}
@@ -0,0 +1,11 @@
+// working on ROMs rather than playing them: what's code and what's data, which quirks a ROM
+// needs, and diffing, trimming and patching them. the disassembler is chip8_core::disasm, the VM
+// needs it for its errors
+
+pub mod analysis;
+pub mod batch;
+pub mod detect;
+pub mod labels;
+pub mod patch;
+pub mod romdiff;
+pub mod trim;
@@ -0,0 +1,63 @@
+// IPS patches, the usual way fixes and hacks for ROMs are passed around: "PATCH", then records of
+// a 3 byte offset, a 2 byte length and that many bytes to write there, up to "EOF". a record with
+// a length of 0 is a run instead, a 2 byte count and the byte to repeat. an offset past the end of
+// the ROM grows it, and a 3 byte length after "EOF" truncates it
+
+const HEADER: &[u8] = b"PATCH";
+const FOOTER: &[u8] = b"EOF";
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = (self.data.get(self.pos..self.pos + len))
+            .ok_or_else(|| format!("patch ends in the middle of a record at {:X}", self.pos))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn number(&mut self, len: usize) -> Result<usize, String> {
+        Ok(self.bytes(len)?.iter().fold(0, |n, &b| n << 8 | b as usize))
+    }
+}
+
+pub fn apply_ips(rom: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    if !patch.starts_with(HEADER) {
+        return Err("not an IPS patch, it doesn't start with PATCH".to_string());
+    }
+    let mut out = rom.to_vec();
+    let mut r = Reader {
+        data: patch,
+        pos: HEADER.len(),
+    };
+    loop {
+        let rest = &patch[r.pos..];
+        if rest.is_empty() {
+            return Err("patch has no EOF at the end".to_string());
+        }
+        if rest == FOOTER {
+            return Ok(out);
+        }
+        if rest.len() == FOOTER.len() + 3 && rest.starts_with(FOOTER) {
+            r.pos += FOOTER.len();
+            out.truncate(r.number(3)?);
+            return Ok(out);
+        }
+        let offset = r.number(3)?;
+        let data = match r.number(2)? {
+            0 => {
+                let count = r.number(2)?;
+                vec![r.bytes(1)?[0]; count]
+            }
+            len => r.bytes(len)?.to_vec(),
+        };
+        let end = offset + data.len();
+        if out.len() < end {
+            out.resize(end, 0);
+        }
+        out[offset..end].copy_from_slice(&data);
+    }
+}
@@ -0,0 +1,104 @@
+// byte differences between two ROMs, shown as disassembly where they fall in code so a patch or
+// a corrupted byte can be read as what it does to the program
+
+use crate::analysis::{Analysis, ByteKind, ENTRY};
+use chip8_core::disasm::Instruction;
+use std::fmt::Write;
+use std::ops::Range;
+
+const MERGE_GAP: usize = 2; // differences closer than this are shown as one, e.g. both bytes of an opcode
+
+// offsets into the ROMs of a run of bytes that differ
+pub fn diff(a: &[u8], b: &[u8]) -> Vec<Range<usize>> {
+    let mut hunks: Vec<Range<usize>> = Vec::new();
+    for off in 0..a.len().max(b.len()) {
+        if a.get(off) == b.get(off) {
+            continue;
+        }
+        match hunks.last_mut() {
+            Some(last) if off - last.end < MERGE_GAP => last.end = off + 1,
+            _ => hunks.push(off..off + 1),
+        }
+    }
+    hunks
+}
+
+// a ROM split into instructions where it's code and single bytes where it's data
+struct Listing {
+    units: Vec<(usize, String)>, // offset and what's there
+    len: usize,
+}
+
+impl Listing {
+    fn new(rom: &[u8]) -> Self {
+        let analysis = Analysis::new(rom);
+        let mut units = Vec::new();
+        let mut off = 0;
+        while off < rom.len() {
+            let addr = ENTRY + off as u16;
+            if analysis.kind(addr) == Some(ByteKind::Code) {
+                let opcode = (rom[off] as u16) << 8 | rom[off + 1] as u16;
+                let text = format!(
+                    "{:03X}: {:04X}  {}",
+                    addr,
+                    opcode,
+                    Instruction::decode(opcode)
+                );
+                units.push((off, text));
+                off += 2;
+            } else {
+                units.push((off, format!("{:03X}: DB 0x{:02X}", addr, rom[off])));
+                off += 1;
+            }
+        }
+        Self {
+            units,
+            len: rom.len(),
+        }
+    }
+
+    // the units with any byte in range
+    fn covering(&self, range: &Range<usize>) -> Range<usize> {
+        // units cover the ROM end to end, so the one holding a byte is the last starting at or
+        // before it
+        let start = if range.start < self.len {
+            (self.units.partition_point(|(off, _)| *off <= range.start)).saturating_sub(1)
+        } else {
+            self.units.len()
+        };
+        let end = self.units.partition_point(|(off, _)| *off < range.end);
+        start..end.max(start)
+    }
+}
+
+// a unified diff of a against b, with context units of a either side of every change
+pub fn report(a: &[u8], b: &[u8], a_name: &str, b_name: &str, context: usize) -> String {
+    let hunks = diff(a, b);
+    if hunks.is_empty() {
+        return String::new();
+    }
+    let (la, lb) = (Listing::new(a), Listing::new(b));
+    let mut out = format!("--- {}\n+++ {}\n", a_name, b_name);
+    for hunk in &hunks {
+        let (ua, ub) = (la.covering(hunk), lb.covering(hunk));
+        let _ = writeln!(
+            out,
+            "@@ {:03X}..{:03X} @@",
+            ENTRY as usize + hunk.start,
+            ENTRY as usize + hunk.end
+        );
+        for (_, text) in &la.units[ua.start.saturating_sub(context)..ua.start] {
+            let _ = writeln!(out, "  {}", text);
+        }
+        for (_, text) in &la.units[ua.clone()] {
+            let _ = writeln!(out, "- {}", text);
+        }
+        for (_, text) in &lb.units[ub] {
+            let _ = writeln!(out, "+ {}", text);
+        }
+        for (_, text) in la.units[ua.end..].iter().take(context) {
+            let _ = writeln!(out, "  {}", text);
+        }
+    }
+    out
+}
@@ -0,0 +1,119 @@
+use crate::analysis::{Analysis, ByteKind, ENTRY};
+use chip8_core::disasm::Instruction;
+use chip8_core::headless;
+use chip8_core::quirks::Quirks;
+use std::fmt;
+
+const MIN_GAP: usize = 16; // shorter runs of zeroes aren't worth relocating around
+
+pub struct Trimmed {
+    pub rom: Vec<u8>,
+    pub frames: usize,   // frames compared while verifying
+    pub relocated: bool, // false if relocation was asked for but changed behavior and was dropped
+}
+
+#[derive(Debug)]
+pub struct Diverged;
+
+impl fmt::Display for Diverged {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "trimmed ROM doesn't behave the same as the original")
+    }
+}
+
+impl std::error::Error for Diverged {}
+
+pub fn trim(
+    rom: &[u8],
+    relocate: bool,
+    cycles: usize,
+    quirks: Quirks,
+) -> Result<Trimmed, Diverged> {
+    let reference = headless::run(rom, cycles, quirks);
+
+    let len = rom.iter().rposition(|&b| b != 0).map_or(0, |p| p + 1);
+    let stripped = &rom[..len];
+
+    if relocate {
+        let candidate = close_gaps(stripped);
+        if headless::run(&candidate, cycles, quirks).same_as(&reference) {
+            return Ok(Trimmed {
+                rom: candidate,
+                frames: reference.frames.len(),
+                relocated: true,
+            });
+        }
+    }
+
+    if !headless::run(stripped, cycles, quirks).same_as(&reference) {
+        return Err(Diverged);
+    }
+    Ok(Trimmed {
+        rom: stripped.to_vec(),
+        frames: reference.frames.len(),
+        relocated: false,
+    })
+}
+
+// remove long runs of zeroes that nothing appears to point at, moving everything after them down
+fn close_gaps(rom: &[u8]) -> Vec<u8> {
+    let analysis = Analysis::new(rom);
+    let addr = |off: usize| ENTRY + off as u16;
+
+    let mut gaps = Vec::new(); // (start offset, length)
+    let mut off = 0;
+    while off < rom.len() {
+        let start = off;
+        while off < rom.len() && rom[off] == 0 && analysis.kind(addr(off)) == Some(ByteKind::Data) {
+            off += 1;
+        }
+        let len = (off - start) & !1; // keep instructions on the same alignment
+        if len >= MIN_GAP
+            && !analysis
+                .data_refs
+                .range(addr(start)..addr(off))
+                .any(|_| true)
+        {
+            gaps.push((start, len));
+        }
+        off = off.max(start + 1);
+    }
+
+    let relocate = |a: u16| -> u16 {
+        if a < ENTRY || a as usize >= ENTRY as usize + rom.len() {
+            return a;
+        }
+        let removed: usize = gaps
+            .iter()
+            .filter(|&&(start, len)| (a - ENTRY) as usize >= start + len)
+            .map(|&(_, len)| len)
+            .sum();
+        a - removed as u16
+    };
+
+    let mut out = Vec::with_capacity(rom.len());
+    let mut off = 0;
+    while off < rom.len() {
+        if let Some(&(_, len)) = gaps.iter().find(|&&(start, _)| start == off) {
+            off += len;
+            continue;
+        }
+        if analysis.is_code(addr(off)) {
+            let opcode = (rom[off] as u16) << 8 | rom[off + 1] as u16;
+            let patched = match Instruction::decode(opcode) {
+                Instruction::Jp(a)
+                | Instruction::Call(a)
+                | Instruction::LdI(a)
+                | Instruction::JpV0(a) => opcode & 0xF000 | relocate(a),
+                _ => opcode,
+            };
+            out.push((patched >> 8) as u8);
+            out.push(patched as u8);
+            off += 2;
+            continue;
+        }
+        out.push(rom[off]);
+        off += 1;
+    }
+    out
+}
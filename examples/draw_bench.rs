@@ -0,0 +1,45 @@
+// times a loop that does little but draw sprites all over the screen, wrapping at the edges,
+// under the default quirks and with clipping. prints the best of a few runs
+// cargo run --release --example draw_bench -- [CYCLES]
+
+use chip8::headless;
+use chip8::quirks::Quirks;
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 5;
+
+// LD I, 0; then over and over DRW V0, V1, 15; ADD V0, 3; DRW V0, V1, 15; ADD V1, 5
+fn rom() -> Vec<u8> {
+    let mut rom = vec![0xA0, 0x00];
+    for _ in 0..100 {
+        rom.extend([0xD0, 0x1F, 0x70, 0x03, 0xD0, 0x1F, 0x71, 0x05]);
+    }
+    rom.extend([0x12, 0x02]); // JP 0x202
+    rom
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cycles: usize = std::env::args()
+        .nth(1)
+        .map_or(Ok(4_000_000), |n| n.parse())?;
+    for (name, quirks) in [("wrapping", Quirks::DEFAULT), ("clipping", Quirks::SCHIP)] {
+        // the best of a few runs, the others being slowed by whatever else the machine was doing
+        let mut elapsed = Duration::MAX;
+        for _ in 0..RUNS {
+            let mut chip8 = headless::machine(&rom(), quirks);
+            let start = Instant::now();
+            for _ in 0..cycles {
+                chip8.emulate_cycle()?;
+            }
+            elapsed = elapsed.min(start.elapsed());
+        }
+        println!(
+            "{}: {} cycles in {:.2?}, {:.1} ns a cycle",
+            name,
+            cycles,
+            elapsed,
+            elapsed.as_nanos() as f64 / cycles as f64
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,199 @@
+// the core driving a small display the way it would on a microcontroller: a Display that draws
+// into an embedded-graphics DrawTarget, a Keypad that scans a 4x4 key matrix and a buzzer on a
+// pin. it runs on the host against a stand-in for an SSD1306's 128x64 buffer and prints that
+// at the end
+// cargo run --example embedded -- ROM [CYCLES]
+//
+// on a board, build chip8-core with default-features = false, drop the mirrored traits below for
+// embedded_graphics' own, and hand Screen the ssd1306 crate's buffered display (flushing after
+// present) or a HUB75 matrix driver for a 64x32 panel, which gets the picture 1:1. the ticks come
+// from a Clock over the board's timer, and seed_rng from whatever noise it has
+
+use chip8_core::prelude::*;
+
+// just what's used of embedded_graphics' Pixel, Point, Size, BinaryColor and DrawTarget, with
+// the same shapes, so this builds without the crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryColor {
+    Off,
+    On,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+pub struct Pixel<C>(pub Point, pub C);
+
+pub trait DrawTarget {
+    type Color;
+    type Error;
+
+    fn size(&self) -> Size; // OriginDimensions in embedded_graphics
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>;
+}
+
+// the VM's screen on any monochrome target, blown up as far as it fits and centered
+pub struct Screen<T: DrawTarget<Color = BinaryColor>, B: FnMut(bool)> {
+    target: T,
+    buzzer: B,               // sets the piezo's pin
+    error: Option<T::Error>, // the latest the target gave, Display can't hand it back
+}
+
+impl<T: DrawTarget<Color = BinaryColor>, B: FnMut(bool)> Display for Screen<T, B> {
+    fn present(&mut self, gfx: &[u8]) {
+        let Size { width, height } = self.target.size();
+        let scale = (width / 64).min(height / 32).max(1) as i32;
+        let left = (width as i32 - 64 * scale) / 2;
+        let top = (height as i32 - 32 * scale) / 2;
+        let pixels = gfx.iter().enumerate().flat_map(|(i, &p)| {
+            let (x, y) = (
+                (i % 64) as i32 * scale + left,
+                (i / 64) as i32 * scale + top,
+            );
+            let color = if p == 0 {
+                BinaryColor::Off
+            } else {
+                BinaryColor::On
+            };
+            (0..scale * scale).map(move |n| {
+                Pixel(
+                    Point {
+                        x: x + n % scale,
+                        y: y + n / scale,
+                    },
+                    color,
+                )
+            })
+        });
+        if let Err(e) = self.target.draw_iter(pixels) {
+            self.error = Some(e);
+        }
+    }
+
+    fn buzzer(&mut self, on: bool) {
+        (self.buzzer)(on);
+    }
+}
+
+// a matrix of 16 buttons wired like the COSMAC VIP's keypad, scanned every poll. scan returns a
+// bit for each CHIP-8 key held down
+pub struct KeyMatrix<S: FnMut() -> u16> {
+    scan: S,
+    held: u16,
+}
+
+impl<S: FnMut() -> u16> Keypad for KeyMatrix<S> {
+    fn poll(&mut self, machine: &mut dyn Machine) -> Vec<Hotkey> {
+        let held = (self.scan)();
+        for key in 0..16 {
+            match (self.held & 1 << key != 0, held & 1 << key != 0) {
+                (false, true) => machine.press_key(key),
+                (true, false) => machine.release_key(key),
+                _ => {}
+            }
+        }
+        self.held = held;
+        Vec::new()
+    }
+}
+
+// an SSD1306's memory: eight pages of 128 columns, a byte of eight pixels down in each
+pub struct Oled {
+    buffer: [u8; 128 * 64 / 8],
+}
+
+impl DrawTarget for Oled {
+    type Color = BinaryColor;
+    type Error = Point; // a pixel off the edge
+
+    fn size(&self) -> Size {
+        Size {
+            width: 128,
+            height: 64,
+        }
+    }
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Point>
+    where
+        I: IntoIterator<Item = Pixel<BinaryColor>>,
+    {
+        for Pixel(point, color) in pixels {
+            if !(0..128).contains(&point.x) || !(0..64).contains(&point.y) {
+                return Err(point);
+            }
+            let (byte, bit) = ((point.y / 8 * 128 + point.x) as usize, point.y % 8);
+            match color {
+                BinaryColor::On => self.buffer[byte] |= 1 << bit,
+                BinaryColor::Off => self.buffer[byte] &= !(1 << bit),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Oled {
+    // two rows of pixels to a line of half blocks
+    fn text(&self) -> String {
+        let lit = |x: usize, y: usize| self.buffer[y / 8 * 128 + x] & 1 << (y % 8) != 0;
+        let mut out = String::new();
+        for y in (0..64).step_by(2) {
+            out.extend((0..128).map(|x| match (lit(x, y), lit(x, y + 1)) {
+                (false, false) => ' ',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (true, true) => '█',
+            }));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let rom = std::fs::read(args.next().ok_or("usage: embedded ROM [CYCLES]")?)?;
+    let cycles: u64 = args
+        .next()
+        .map_or(Ok(10 * 60 * CYCLES_PER_TICK as u64), |n| n.parse())?;
+
+    let mut chip8 = Chip8::new();
+    chip8.seed_rng(0x8BADF00D);
+    chip8.load_rom(&rom);
+    let mut screen = Screen {
+        target: Oled {
+            buffer: [0; 128 * 64 / 8],
+        },
+        buzzer: |_| {}, // the pin's set_state on a board
+        error: None,
+    };
+    // nobody's pressing anything
+    let mut keys = KeyMatrix {
+        scan: || 0,
+        held: 0,
+    };
+    for _ in 0..cycles {
+        chip8.emulate_cycle()?;
+        if chip8.draw_flag() {
+            screen.present(chip8.gfx());
+        }
+        screen.buzzer(chip8.sound_flag());
+        keys.poll(&mut chip8);
+    }
+    if let Some(point) = screen.error {
+        return Err(format!("drew off the display at {}, {}", point.x, point.y).into());
+    }
+    print!("{}", screen.target.text());
+    Ok(())
+}
@@ -0,0 +1,46 @@
+// plays a ROM with random key presses, a baseline to compare trained agents against
+// cargo run --release --example random_agent -- ROM [EPISODES]
+
+use chip8::gym::Env;
+use rand::prelude::*;
+
+const EPISODE_FRAMES: usize = 60 * 60; // a minute of play
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let rom = std::fs::read(args.next().ok_or("usage: random_agent ROM [EPISODES]")?)?;
+    let episodes: u64 = args.next().map_or(Ok(5), |n| n.parse())?;
+
+    let mut env = Env::new(&rom);
+    env.set_max_frames(Some(EPISODE_FRAMES));
+    let mut rng = StdRng::seed_from_u64(0);
+    for episode in 0..episodes {
+        env.set_seed(episode);
+        env.reset();
+        let mut screens = std::collections::HashSet::new();
+        loop {
+            // one key at a time, or none, is how most games are played
+            let action = match rng.gen_range(0, 17) {
+                16 => 0,
+                key => 1 << key,
+            };
+            let (screen, done) = env.step(action);
+            screens.insert(screen.to_vec());
+            if done {
+                break;
+            }
+        }
+        let end = match env.error() {
+            Some(e) => e.to_string(),
+            None => "time up".to_string(),
+        };
+        println!(
+            "episode {}: {} frames, {} distinct screens, {}",
+            episode,
+            env.frames(),
+            screens.len(),
+            end
+        );
+    }
+    Ok(())
+}
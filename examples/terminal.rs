@@ -0,0 +1,36 @@
+// the smallest possible frontend: draws the screen in the terminal for a few seconds
+// cargo run --example terminal -- ROM
+
+use chip8::runner::{run_with, Frontend};
+use chip8::Chip8;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args().nth(1).ok_or("usage: terminal ROM")?;
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&std::fs::read(path)?);
+
+    let mut ticks = 0;
+    run_with(
+        &mut chip8,
+        Frontend {
+            render: |gfx: &[u8]| {
+                let mut screen = String::from("\x1b[H"); // back to the top left corner
+                for row in gfx.chunks(64) {
+                    screen.extend(row.iter().map(|&p| if p == 0 { ' ' } else { '#' }));
+                    screen.push('\n');
+                }
+                print!("{}", screen);
+            },
+            input: |_: &mut [bool; 16]| {
+                ticks += 1;
+                ticks < 5 * 60
+            },
+            audio: |on: bool| {
+                if on {
+                    print!("\x07");
+                }
+            },
+        },
+    )?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+#!/bin/sh
+# Downloads the ROMs used by tests/timendus.rs into tests/roms/.
+#
+# Timendus' CHIP-8 test suite (https://github.com/Timendus/chip8-test-suite) is GPL licensed so we
+# don't keep a copy in this repo. The goldens were made against the single-file build of the suite
+# that's redistributed in the c8 crate, which we fetch from crates.io so the bytes never change.
+set -e
+
+cd "$(dirname "$0")/.."
+mkdir -p tests/roms
+curl -sSfL https://static.crates.io/crates/c8/c8-1.0.1.crate |
+    tar -xzO c8-1.0.1/roms/test_suite.ch8 >tests/roms/chip8-test-suite.ch8
+echo "wrote tests/roms/chip8-test-suite.ch8"
@@ -0,0 +1,195 @@
+// named goals for a ROM, unlocked once and announced when the game first meets them. they're
+// written by hand per ROM in <config dir>/achievements/<ROM's SHA-1>.cfg as config lines of a
+// name and a debugger expression, which can use the names in the ROM's cheats, optionally held
+// for a number of seconds: "Level 5 = level >= 5" or "Survivor = lives > 0 for 300". what's
+// been unlocked is kept in <data dir>/achievements/<ROM's SHA-1> with a line for each, when it
+// was unlocked in seconds since 1970 then its name
+
+use crate::config::Config;
+use crate::dirs;
+use crate::expr::{Expr, Value};
+use crate::labels::Labels;
+use crate::sha1;
+use crate::Chip8;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Achievement {
+    pub name: String,
+    condition: Expr,
+    frames: u64, // the condition has to hold for, 0 for just once
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Achievements {
+    defined: Vec<Achievement>,
+    held: Vec<u64>, // frames each condition has held for so far
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unlock {
+    pub when: u64, // seconds since 1970, UTC
+    pub name: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Unlocked {
+    entries: Vec<Unlock>, // in the order they were unlocked
+}
+
+// <config dir>/achievements/<ROM's SHA-1>.cfg
+pub fn path(rom_sha1: &[u8; 20]) -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("achievements")
+            .join(format!("{}.cfg", sha1::hex(rom_sha1))),
+    )
+}
+
+// <data dir>/achievements/<ROM's SHA-1>
+pub fn unlocked_path(rom_sha1: &[u8; 20]) -> Option<PathBuf> {
+    Some(
+        dirs::data_dir()?
+            .join("achievements")
+            .join(sha1::hex(rom_sha1)),
+    )
+}
+
+// "lives > 0 for 300" to the expression and 300 seconds
+fn split_time(value: &str) -> Result<(&str, u64), String> {
+    match value.rsplit_once(" for ") {
+        Some((condition, secs)) => match secs.trim().parse::<u64>() {
+            Ok(secs) => Ok((condition, secs)),
+            Err(_) => Err(format!("{} isn't a number of seconds", secs.trim())),
+        },
+        None => Ok((value, 0)),
+    }
+}
+
+impl Achievements {
+    // with labels as the names the conditions can use, the cheats' usually
+    pub fn from_config(config: &Config, labels: &Labels) -> Result<Self, String> {
+        let mut achievements = Self::default();
+        for (name, value) in config.entries() {
+            if achievements.defined.iter().any(|a| a.name == name) {
+                return Err(format!("{} is there twice", name));
+            }
+            let (condition, secs) = split_time(value).map_err(|e| format!("{}: {}", name, e))?;
+            let condition =
+                Expr::parse_with(condition, labels).map_err(|e| format!("{}: {}", name, e))?;
+            if let Expr::MemRange(..) = condition {
+                return Err(format!("{}: a memory range isn't a condition", name));
+            }
+            achievements.defined.push(Achievement {
+                name: name.to_string(),
+                condition,
+                frames: secs * 60,
+            });
+        }
+        achievements.held = vec![0; achievements.defined.len()];
+        Ok(achievements)
+    }
+
+    // none if the file isn't there
+    pub fn load(path: &Path, labels: &Labels) -> Result<Self, Box<dyn std::error::Error>> {
+        let config = Config::load(path)?;
+        Self::from_config(&config, labels).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    pub fn defined(&self) -> &[Achievement] {
+        &self.defined
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defined.is_empty()
+    }
+
+    // after frames more 60Hz frames, the names of those met that aren't in unlocked yet. a
+    // condition that can't be worked out, like one dividing by zero, doesn't hold
+    pub fn frame(&mut self, chip8: &Chip8, frames: u64, unlocked: &Unlocked) -> Vec<String> {
+        let mut met = Vec::new();
+        for (a, held) in self.defined.iter().zip(&mut self.held) {
+            if unlocked.has(&a.name) {
+                continue;
+            }
+            match a.condition.eval(chip8) {
+                Ok(Value::Number(n)) if n != 0 => *held += frames,
+                _ => *held = 0,
+            }
+            if *held > 0 && *held >= a.frames {
+                met.push(a.name.clone());
+            }
+        }
+        met
+    }
+
+    // start the held times again, as after loading a state
+    pub fn reset(&mut self) {
+        self.held.iter_mut().for_each(|h| *h = 0);
+    }
+}
+
+impl Unlocked {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut unlocked = Self::default();
+        for (n, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.splitn(2, ' ');
+            match (fields.next().map(str::parse), fields.next()) {
+                (Some(Ok(when)), Some(name)) if !name.is_empty() => {
+                    unlocked.unlock(name, when);
+                }
+                _ => return Err(format!("line {} is bad", n + 1)),
+            }
+        }
+        Ok(unlocked)
+    }
+
+    // none yet if the file isn't there
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn entries(&self) -> &[Unlock] {
+        &self.entries
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.entries.iter().any(|u| u.name == name)
+    }
+
+    // false if it already was
+    pub fn unlock(&mut self, name: &str, when: u64) -> bool {
+        if self.has(name) {
+            return false;
+        }
+        self.entries.push(Unlock {
+            when,
+            name: name.to_string(),
+        });
+        true
+    }
+}
+
+impl fmt::Display for Unlocked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for u in &self.entries {
+            writeln!(f, "{} {}", u.when, u.name)?;
+        }
+        Ok(())
+    }
+}
@@ -1,23 +0,0 @@
-use sdl2::audio::AudioCallback;
-
-pub struct SquareWave {
-    pub phase_inc: f32,
-    pub phase: f32,
-    pub volume: f32,
-}
-
-impl AudioCallback for SquareWave {
-    type Channel = f32;
-
-    fn callback(&mut self, out: &mut [f32]) {
-        // Generate a square wave
-        for x in out.iter_mut() {
-            *x = if self.phase <= 0.5 {
-                self.volume
-            } else {
-                -self.volume
-            };
-            self.phase = (self.phase + self.phase_inc) % 1.0;
-        }
-    }
-}
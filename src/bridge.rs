@@ -0,0 +1,203 @@
+// --bridge: what's happening in the game published to MQTT or OSC, for installations that react
+// to it being played, lights that come on with the sound or a visual for each key. it watches the
+// VM as an observer, and is built with the bridge feature
+
+use crate::observer::{Event, Observer};
+use crate::Chip8;
+use std::io::{self, Write};
+use std::net::{TcpStream, UdpSocket};
+use std::time::Duration;
+
+const PREFIX: &str = "chip8"; // of the addresses or topics, when the URL doesn't give one
+const TIMEOUT: Duration = Duration::from_millis(100); // the VM doesn't wait longer on a broker
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Signal {
+    Sound(bool),
+    Draws(u32), // sprites drawn in the frame just gone, for frames with any
+    Key(u8, bool),
+}
+
+// turns the VM's events into signals, as they change
+#[derive(Default)]
+pub struct Signals {
+    sound: bool,
+    keys: u16,
+    draws: u32,
+    tick: u64,
+}
+
+impl Signals {
+    // what's changed by the time the instruction in event is about to run
+    pub fn on(&mut self, chip8: &Chip8, event: &Event, mut emit: impl FnMut(Signal)) {
+        let opcode = match *event {
+            Event::Exec { opcode, .. } => opcode,
+            _ => return,
+        };
+        if chip8.ticks() != self.tick {
+            if self.draws > 0 {
+                emit(Signal::Draws(self.draws));
+            }
+            self.draws = 0;
+            self.tick = chip8.ticks();
+        }
+        if chip8.sound_flag() != self.sound {
+            self.sound = chip8.sound_flag();
+            emit(Signal::Sound(self.sound));
+        }
+        let keys = chip8.keys();
+        for key in (0..16).filter(|k| (keys ^ self.keys) & 1 << k != 0) {
+            emit(Signal::Key(key, keys & 1 << key != 0));
+        }
+        self.keys = keys;
+        if opcode & 0xF000 == 0xD000 {
+            self.draws += 1;
+        }
+    }
+}
+
+// strings in OSC are NUL terminated and padded to 4 bytes
+fn osc_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(s.as_bytes());
+    out.resize((out.len() / 4 + 1) * 4, 0);
+}
+
+// an OSC message of int32 arguments
+pub fn osc(address: &str, args: &[i32]) -> Vec<u8> {
+    let mut out = Vec::new();
+    osc_string(&mut out, address);
+    osc_string(&mut out, &format!(",{}", "i".repeat(args.len())));
+    for arg in args {
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+    out
+}
+
+// an MQTT packet: its type and flags, then the length of the rest 7 bits a byte
+fn mqtt_packet(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![kind];
+    let mut len = body.len();
+    loop {
+        let byte = (len % 128) as u8;
+        len /= 128;
+        out.push(if len > 0 { byte | 0x80 } else { byte });
+        if len == 0 {
+            break;
+        }
+    }
+    out.extend_from_slice(body);
+    out
+}
+
+fn mqtt_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+// MQTT 3.1.1's CONNECT, with a clean session and no keep alive so an idle game isn't dropped
+pub fn mqtt_connect(client: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    mqtt_string(&mut body, "MQTT");
+    body.extend_from_slice(&[4, 0x02, 0, 0]);
+    mqtt_string(&mut body, client);
+    mqtt_packet(0x10, &body)
+}
+
+// a PUBLISH at QoS 0, sent and forgotten
+pub fn mqtt_publish(topic: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    mqtt_string(&mut body, topic);
+    body.extend_from_slice(payload);
+    mqtt_packet(0x30, &body)
+}
+
+enum Sink {
+    Osc(UdpSocket),
+    Mqtt(TcpStream),
+}
+
+pub struct Bridge {
+    signals: Signals,
+    sink: Sink,
+    prefix: String,
+    pub error: Option<io::Error>, // why it stopped publishing, if it has
+}
+
+impl Bridge {
+    // osc://HOST:PORT or mqtt://HOST:PORT, then maybe /PREFIX for the addresses or topics
+    pub fn connect(url: &str) -> Result<Self, String> {
+        let (scheme, rest) = url.split_once("://").ok_or("not a URL")?;
+        let (host, prefix) = rest.split_once('/').unwrap_or((rest, PREFIX));
+        let prefix = prefix.trim_matches('/');
+        let prefix = if prefix.is_empty() { PREFIX } else { prefix };
+        let sink = match scheme {
+            "osc" => {
+                let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+                socket.connect(host).map_err(|e| e.to_string())?;
+                Sink::Osc(socket)
+            }
+            "mqtt" => {
+                let mut stream = TcpStream::connect(host).map_err(|e| e.to_string())?;
+                stream
+                    .set_write_timeout(Some(TIMEOUT))
+                    .map_err(|e| e.to_string())?;
+                stream
+                    .write_all(&mqtt_connect(&format!("chip8-{}", std::process::id())))
+                    .map_err(|e| e.to_string())?;
+                Sink::Mqtt(stream)
+            }
+            _ => return Err(format!("{} isn't osc or mqtt", scheme)),
+        };
+        Ok(Self {
+            signals: Signals::default(),
+            sink,
+            prefix: prefix.to_string(),
+            error: None,
+        })
+    }
+
+    fn send(&mut self, signal: Signal) -> io::Result<()> {
+        let prefix = &self.prefix;
+        match &mut self.sink {
+            Sink::Osc(socket) => {
+                let message = match signal {
+                    Signal::Sound(on) => osc(&format!("/{}/sound", prefix), &[on as i32]),
+                    Signal::Draws(n) => osc(&format!("/{}/draws", prefix), &[n as i32]),
+                    Signal::Key(key, down) => {
+                        osc(&format!("/{}/key", prefix), &[key as i32, down as i32])
+                    }
+                };
+                // nothing listening yet is fine, installations come and go
+                let _ = socket.send(&message);
+                Ok(())
+            }
+            Sink::Mqtt(stream) => {
+                let (topic, payload) = match signal {
+                    Signal::Sound(on) => (format!("{}/sound", prefix), (on as u8).to_string()),
+                    Signal::Draws(n) => (format!("{}/draws", prefix), n.to_string()),
+                    Signal::Key(key, down) => (
+                        format!("{}/key/{:X}", prefix, key),
+                        (down as u8).to_string(),
+                    ),
+                };
+                stream.write_all(&mqtt_publish(&topic, payload.as_bytes()))
+            }
+        }
+    }
+}
+
+impl Observer for Bridge {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        if self.error.is_some() {
+            return;
+        }
+        let mut signals = Vec::new();
+        self.signals.on(chip8, event, |s| signals.push(s));
+        for signal in signals {
+            if let Err(e) = self.send(signal) {
+                self.error = Some(e);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// developer mode for ROM authors. memory the ROM doesn't load into is filled with a canary byte
+// so reading it before writing it stands out, and at exit there's a report of how deep the stack
+// got and how much of the free memory was used, to show how close the ROM is to the limits
+
+use crate::fonts::{FONT_LEN, FONT_START};
+use crate::observer::{Event, Observer};
+use crate::Chip8;
+use std::fmt::Write;
+
+pub const CANARY: u8 = 0xA5;
+const FONT_END: usize = FONT_START + FONT_LEN; // the font is the only thing below 0x200
+const ROM_START: usize = 0x200;
+const STACK_LEVELS: usize = 16;
+
+pub struct Canaries {
+    rom_end: usize,
+    written: Vec<bool>,    // free bytes the ROM has stored to
+    deepest: usize,        // most return addresses on the stack at once
+    ran_free: Option<u16>, // first time the PC went into free memory that wasn't written
+}
+
+impl Canaries {
+    // fill the free memory around a ROM of rom_len bytes that's already been loaded
+    pub fn new(chip8: &mut Chip8, rom_len: usize) -> Self {
+        let memory = chip8.memory_mut();
+        let rom_end = (ROM_START + rom_len).min(memory.len());
+        memory[FONT_END..ROM_START].fill(CANARY);
+        memory[rom_end..].fill(CANARY);
+        Self {
+            rom_end,
+            written: vec![false; memory.len()],
+            deepest: 0,
+            ran_free: None,
+        }
+    }
+
+    fn is_free(&self, addr: usize) -> bool {
+        (FONT_END..ROM_START).contains(&addr) || addr >= self.rom_end
+    }
+
+    pub fn report(&self, chip8: &Chip8) -> String {
+        let deepest = self.deepest.max(chip8.stack().len());
+        let free = (FONT_END..self.written.len())
+            .filter(|&a| self.is_free(a))
+            .count();
+        let written: Vec<usize> = (0..self.written.len())
+            .filter(|&a| self.written[a])
+            .collect();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "stack: {} of {} levels used", deepest, STACK_LEVELS);
+        let _ = write!(
+            out,
+            "memory: ROM {} bytes at 0x200-0x{:03X}, {} of {} free bytes written",
+            self.rom_end - ROM_START,
+            self.rom_end.max(ROM_START + 1) - 1,
+            written.len(),
+            free
+        );
+        match (written.first(), written.last()) {
+            (Some(lowest), Some(highest)) => {
+                let _ = writeln!(out, " (0x{:03X}-0x{:03X})", lowest, highest);
+            }
+            _ => out.push('\n'),
+        }
+        if let Some(pc) = self.ran_free {
+            let _ = writeln!(
+                out,
+                "warning: ran into unused memory at 0x{:03X}, it holds {:02X} canaries",
+                pc, CANARY
+            );
+        }
+        out
+    }
+}
+
+impl Observer for Canaries {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        match *event {
+            Event::Exec { pc, .. } => {
+                self.deepest = self.deepest.max(chip8.stack().len());
+                let pc = pc as usize;
+                if self.ran_free.is_none()
+                    && pc < self.written.len()
+                    && self.is_free(pc)
+                    && !self.written[pc]
+                {
+                    self.ran_free = Some(pc as u16);
+                }
+            }
+            Event::Write { addr, .. } => {
+                let addr = addr as usize;
+                if addr < self.written.len() && self.is_free(addr) {
+                    self.written[addr] = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
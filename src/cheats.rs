@@ -0,0 +1,306 @@
+// names for addresses found with the debugger's search, like "lives" or "score", and cheats on
+// them: a frozen address is written back with its value every frame. names can also be shown over
+// the game as a HUD, and one of them kept as the score for the high scores. kept per ROM in a
+// file of config lines, "name.lives = 2A4", "name.sprites = 300 8" for a region of memory,
+// "freeze.lives = 03", "hud.score = bcd 3" and "score.score = bcd 3"
+
+use crate::config::Config;
+use crate::dirs;
+use crate::labels::{Labels, Region};
+use crate::sha1;
+use crate::Chip8;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const LIST_BYTES: u16 = 8; // of a region shown by list
+
+// how a HUD shows the bytes at a name
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Dec, // a big-endian number
+    Hex,
+    Bcd, // a decimal digit a byte, the way FX33 stores them
+}
+
+pub const FORMATS: [(&str, Format); 3] = [
+    ("dec", Format::Dec),
+    ("hex", Format::Hex),
+    ("bcd", Format::Bcd),
+];
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        FORMATS.iter().find(|(name, _)| *name == s).map(|&(_, f)| f)
+    }
+
+    pub fn name(self) -> &'static str {
+        FORMATS.iter().find(|&&(_, f)| f == self).unwrap().0
+    }
+
+    pub fn show(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Dec => bytes
+                .iter()
+                .fold(0u64, |n, &b| n << 8 | b as u64)
+                .to_string(),
+            Self::Hex => bytes.iter().map(|b| format!("{:02X}", b)).collect(),
+            Self::Bcd => bytes
+                .iter()
+                .map(|&b| std::char::from_digit(b as u32, 10).unwrap_or('?'))
+                .collect(),
+        }
+    }
+
+    // the bytes as a number, None for BCD that isn't all digits
+    pub fn value(self, bytes: &[u8]) -> Option<u64> {
+        match self {
+            Self::Dec | Self::Hex => Some(bytes.iter().fold(0, |n, &b| n << 8 | b as u64)),
+            Self::Bcd => (bytes.iter()).try_fold(0, |n, &b| (b < 10).then(|| n * 10 + b as u64)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cheats {
+    pub labels: Labels,                // the names, with how many bytes each covers
+    frozen: BTreeMap<String, u8>,      // by name, the value held there
+    hud: Vec<(String, Format, usize)>, // names to show, how, and how many bytes
+    score: Option<(String, Format, usize)>, // the name the score's at, the same way
+    pub enabled: bool,                 // frozen values are only written while on
+    pub changed: bool,                 // since loading, so there's something to save
+}
+
+impl Default for Cheats {
+    fn default() -> Self {
+        Self {
+            labels: Labels::default(),
+            frozen: BTreeMap::new(),
+            hud: Vec::new(),
+            score: None,
+            enabled: true,
+            changed: false,
+        }
+    }
+}
+
+// <data dir>/cheats/<ROM's SHA-1>.cfg
+pub fn path(rom_sha1: &[u8; 20]) -> Option<PathBuf> {
+    Some(
+        dirs::data_dir()?
+            .join("cheats")
+            .join(format!("{}.cfg", sha1::hex(rom_sha1))),
+    )
+}
+
+fn parse_hex(name: &str, value: &str) -> Result<u16, String> {
+    u16::from_str_radix(value, 16).map_err(|_| format!("{} isn't hex: {}", name, value))
+}
+
+// "bcd 3" or just "hex", 1 byte if there's no length
+fn parse_format(key: &str, value: &str) -> Result<(Format, usize), String> {
+    let mut words = value.split_whitespace();
+    let format = words.next().and_then(Format::parse);
+    let len = words.next().map_or(Ok(1), str::parse);
+    match (format, len) {
+        (Some(format), Ok(len @ 1..=8)) => Ok((format, len)),
+        _ => Err(format!("{} isn't a format and length: {}", key, value)),
+    }
+}
+
+impl Cheats {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let mut cheats = Self::default();
+        for (key, value) in config.entries() {
+            match key.split_once('.') {
+                Some(("name", name)) => {
+                    let region = Region::parse(value)
+                        .ok_or_else(|| format!("{} isn't an address and length: {}", key, value))?;
+                    cheats.labels.insert(name, region);
+                }
+                Some(("freeze", name)) => {
+                    let value = u8::try_from(parse_hex(key, value)?)
+                        .map_err(|_| format!("{} isn't a byte: {}", key, value))?;
+                    cheats.frozen.insert(name.to_string(), value);
+                }
+                Some(("hud", name)) => {
+                    let (format, len) = parse_format(key, value)?;
+                    cheats.hud.push((name.to_string(), format, len));
+                }
+                Some(("score", name)) => {
+                    if let Some((other, _, _)) = &cheats.score {
+                        return Err(format!("{} and {} can't both be the score", other, name));
+                    }
+                    let (format, len) = parse_format(key, value)?;
+                    cheats.score = Some((name.to_string(), format, len));
+                }
+                _ => return Err(format!("unknown cheat setting {}", key)),
+            }
+        }
+        let unnamed = (cheats.frozen.keys())
+            .chain(cheats.hud.iter().map(|(name, _, _)| name))
+            .chain(cheats.score.iter().map(|(name, _, _)| name))
+            .find(|n| cheats.labels.get(n).is_none());
+        if let Some(name) = unnamed {
+            return Err(format!("{} has no address", name));
+        }
+        Ok(cheats)
+    }
+
+    pub fn to_config(&self) -> Config {
+        let mut config = Config::default();
+        for (name, region) in self.labels.iter() {
+            config.set(&format!("name.{}", name), &region.to_string());
+        }
+        for (name, value) in &self.frozen {
+            config.set(&format!("freeze.{}", name), &format!("{:02X}", value));
+        }
+        for (name, format, len) in &self.hud {
+            config.set(
+                &format!("hud.{}", name),
+                &format!("{} {}", format.name(), len),
+            );
+        }
+        if let Some((name, format, len)) = &self.score {
+            config.set(
+                &format!("score.{}", name),
+                &format!("{} {}", format.name(), len),
+            );
+        }
+        config
+    }
+
+    // the cheats at path, none if there's no file yet
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let config = Config::load(path)?;
+        Self::from_config(&config).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    // anything is being frozen now
+    pub fn cheating(&self) -> bool {
+        self.enabled && !self.frozen.is_empty()
+    }
+
+    pub fn addr(&self, name: &str) -> Option<u16> {
+        self.labels.get(name).map(|r| r.addr)
+    }
+
+    // the name given to addr, if there is one
+    pub fn name_at(&self, addr: u16) -> Option<&str> {
+        self.labels.name_at(addr)
+    }
+
+    // name len bytes from addr
+    pub fn name(&mut self, name: &str, addr: u16, len: u16) {
+        self.labels.insert(name, Region { addr, len });
+        self.changed = true;
+    }
+
+    // forget a name along with any cheat on it
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.frozen.remove(name);
+        self.hide(name);
+        if self.score.as_ref().is_some_and(|(n, _, _)| n == name) {
+            self.score = None;
+        }
+        self.changed = true;
+        self.labels.remove(name)
+    }
+
+    pub fn freeze(&mut self, name: &str, value: u8) {
+        self.frozen.insert(name.to_string(), value);
+        self.changed = true;
+    }
+
+    pub fn unfreeze(&mut self, name: &str) -> bool {
+        self.changed = true;
+        self.frozen.remove(name).is_some()
+    }
+
+    // show len bytes from name on the HUD, after any already there
+    pub fn show(&mut self, name: &str, format: Format, len: usize) {
+        match self.hud.iter_mut().find(|(n, _, _)| n == name) {
+            Some(widget) => *widget = (name.to_string(), format, len),
+            None => self.hud.push((name.to_string(), format, len)),
+        }
+        self.changed = true;
+    }
+
+    pub fn hide(&mut self, name: &str) -> bool {
+        let len = self.hud.len();
+        self.hud.retain(|(n, _, _)| n != name);
+        self.changed = true;
+        self.hud.len() != len
+    }
+
+    // keep the high scores of len bytes at name
+    pub fn keep_score(&mut self, name: &str, format: Format, len: usize) {
+        self.score = Some((name.to_string(), format, len));
+        self.changed = true;
+    }
+
+    // the score in memory now, if there's a name for it
+    pub fn score(&self, chip8: &Chip8) -> Option<u64> {
+        let (name, format, len) = self.score.as_ref()?;
+        let addr = self.labels.get(name)?.addr as usize;
+        format.value(chip8.memory().get(addr..addr + len)?)
+    }
+
+    // a line for every name on the HUD, e.g. "score 120"
+    pub fn hud(&self, chip8: &Chip8) -> Vec<String> {
+        let memory = chip8.memory();
+        self.hud
+            .iter()
+            .map(|(name, format, len)| {
+                let addr = self.labels.get(name).map_or(0, |r| r.addr as usize);
+                let bytes = &memory[addr.min(memory.len())..(addr + len).min(memory.len())];
+                format!("{} {}", name, format.show(bytes))
+            })
+            .collect()
+    }
+
+    // write the frozen values back, once a frame
+    pub fn apply(&self, chip8: &mut Chip8) {
+        if !self.enabled {
+            return;
+        }
+        let memory = chip8.memory_mut();
+        for (name, &value) in &self.frozen {
+            if let Some(b) = (self.labels.get(name)).and_then(|r| memory.get_mut(r.addr as usize)) {
+                *b = value;
+            }
+        }
+    }
+
+    // every name with its address and value now, and what it's frozen at
+    pub fn list(&self, chip8: &Chip8) -> String {
+        let mut out = String::new();
+        for (name, region) in self.labels.iter() {
+            let byte = |addr: u16| chip8.memory().get(addr as usize).copied().unwrap_or(0);
+            let _ = write!(out, "{} at {:03X}", name, region.addr);
+            if region.len > 1 {
+                let _ = write!(out, ", {} bytes", region.len);
+            }
+            let _ = write!(out, " = ");
+            for (n, addr) in (region.addr..)
+                .take(region.len.min(LIST_BYTES) as usize)
+                .enumerate()
+            {
+                let _ = write!(out, "{}{:02X}", if n > 0 { " " } else { "" }, byte(addr));
+            }
+            if region.len > LIST_BYTES {
+                out += " ..";
+            }
+            if let Some(value) = self.frozen.get(name) {
+                let _ = write!(out, ", frozen at {:02X}", value);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
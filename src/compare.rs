@@ -0,0 +1,186 @@
+// runs a ROM under two sets of quirks in lockstep, with the same keys pressed at the same times,
+// and finds the first instruction after which the two VMs aren't in the same state. whatever
+// that instruction is, it's what the ROM needs one of the quirks for
+
+use crate::chip8::Error;
+use crate::detect;
+use crate::disasm::Instruction;
+use crate::headless;
+use crate::panels;
+use crate::quirks::{self, Quirks};
+use crate::Chip8;
+use std::fmt::{self, Write};
+
+const MAX_BYTES: usize = 8; // differing memory bytes to list before summing up the rest
+
+#[derive(Debug)]
+pub struct Divergence {
+    pub cycle: usize, // the instruction on this cycle left the VMs different
+    pub pc: u16,
+    pub opcode: u16,
+    pub differences: Vec<String>, // e.g. "V3 0A vs 05"
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub a: Quirks,
+    pub b: Quirks,
+    pub cycles: usize, // run in lockstep
+    pub divergence: Option<Divergence>,
+    pub error: Option<Error>, // both VMs stopped with it before diverging
+}
+
+fn differences(
+    a: &Chip8,
+    b: &Chip8,
+    error_a: Option<Error>,
+    error_b: Option<Error>,
+) -> Vec<String> {
+    let mut out = Vec::new();
+    let error = |e: Option<Error>| e.map_or("no error".to_string(), |e| e.to_string());
+    if error_a.map(|e| std::mem::discriminant(&e)) != error_b.map(|e| std::mem::discriminant(&e)) {
+        out.push(format!("{} vs {}", error(error_a), error(error_b)));
+    }
+    if a.pc() != b.pc() {
+        out.push(format!("PC {:03X} vs {:03X}", a.pc(), b.pc()));
+    }
+    for (n, (x, y)) in a.v().iter().zip(b.v()).enumerate() {
+        if x != y {
+            out.push(format!("V{:X} {:02X} vs {:02X}", n, x, y));
+        }
+    }
+    if a.i() != b.i() {
+        out.push(format!("I {:03X} vs {:03X}", a.i(), b.i()));
+    }
+    if a.stack() != b.stack() {
+        out.push(format!("stack {:03X?} vs {:03X?}", a.stack(), b.stack()));
+    }
+    if a.delay_timer() != b.delay_timer() {
+        out.push(format!(
+            "DT {:02X} vs {:02X}",
+            a.delay_timer(),
+            b.delay_timer()
+        ));
+    }
+    if a.sound_timer() != b.sound_timer() {
+        out.push(format!(
+            "ST {:02X} vs {:02X}",
+            a.sound_timer(),
+            b.sound_timer()
+        ));
+    }
+    let bytes: Vec<usize> = (0..a.memory().len())
+        .filter(|&addr| a.memory()[addr] != b.memory()[addr])
+        .collect();
+    for &addr in bytes.iter().take(MAX_BYTES) {
+        out.push(format!(
+            "memory {:03X} {:02X} vs {:02X}",
+            addr,
+            a.memory()[addr],
+            b.memory()[addr]
+        ));
+    }
+    if bytes.len() > MAX_BYTES {
+        out.push(format!("{} more bytes of memory", bytes.len() - MAX_BYTES));
+    }
+    let pixels = a.gfx().iter().zip(b.gfx()).filter(|(x, y)| x != y).count();
+    if pixels > 0 {
+        out.push(format!("{} pixels on screen", pixels));
+    }
+    out
+}
+
+// the quirks that change what an instruction does
+fn quirks_for(instruction: Instruction) -> &'static [&'static str] {
+    match instruction {
+        Instruction::Or(..) | Instruction::And(..) | Instruction::Xor(..) => &["vf-reset"],
+        Instruction::LdIVx(_) | Instruction::LdVxI(_) => &["memory"],
+        Instruction::Drw(..) => &["display-wait", "clipping"],
+        Instruction::Shr(..) | Instruction::Shl(..) => &["shifting"],
+        Instruction::JpV0(_) => &["jumping"],
+        _ => &[],
+    }
+}
+
+pub fn compare(rom: &[u8], cycles: usize, a: Quirks, b: Quirks) -> Comparison {
+    let mut vm_a = headless::machine(rom, a);
+    let mut vm_b = headless::machine(rom, b);
+    let mut comparison = Comparison {
+        a,
+        b,
+        cycles: 0,
+        divergence: None,
+        error: None,
+    };
+    while comparison.cycles < cycles {
+        let cycle = comparison.cycles;
+        detect::mash(&mut vm_a, cycle);
+        detect::mash(&mut vm_b, cycle);
+        let (pc, opcode) = (vm_a.pc(), panels::opcode_at(&vm_a, vm_a.pc() as usize));
+        let error_a = vm_a.emulate_cycle().err();
+        let error_b = vm_b.emulate_cycle().err();
+        comparison.cycles += 1;
+
+        let differences = differences(&vm_a, &vm_b, error_a, error_b);
+        if !differences.is_empty() {
+            comparison.divergence = Some(Divergence {
+                cycle,
+                pc,
+                opcode,
+                differences,
+            });
+            break;
+        }
+        if error_a.is_some() {
+            comparison.error = error_a;
+            break;
+        }
+    }
+    comparison
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let d = match &self.divergence {
+            Some(d) => d,
+            None => {
+                write!(f, "no divergence in {} cycles", self.cycles)?;
+                if let Some(e) = self.error {
+                    write!(f, ", both stopped with {}", e)?;
+                }
+                return writeln!(f);
+            }
+        };
+        writeln!(
+            f,
+            "diverged on cycle {} at {:03X}: {:04X}  {}",
+            d.cycle,
+            d.pc,
+            d.opcode,
+            Instruction::decode(d.opcode)
+        )?;
+        let mut differences = String::new();
+        for difference in &d.differences {
+            let _ = write!(differences, "\n  {}", difference);
+        }
+        writeln!(f, "a vs b:{}", differences)?;
+        let (flags_a, flags_b) = (self.a.flags(), self.b.flags());
+        let culprits = quirks_for(Instruction::decode(d.opcode));
+        for (n, (name, description)) in quirks::NAMES.iter().enumerate() {
+            if flags_a[n] != flags_b[n] {
+                let on = if flags_a[n] { "a" } else { "b" };
+                let mark = if culprits.contains(name) {
+                    ", and changes this instruction"
+                } else {
+                    ""
+                };
+                writeln!(
+                    f,
+                    "quirk {} ({}) is on for {}{}",
+                    name, description, on, mark
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+// shell completion scripts, made by clap_complete from clap's definition of the command line so
+// they keep up with it. options complete their values where there's a list of them or they're
+// files, and positional arguments complete files, or the subcommands where one could go
+
+use clap::Command;
+use clap_complete::Shell;
+
+pub const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+// the completion script for shell, one of SHELLS
+pub fn script(shell: &str, mut command: Command) -> String {
+    let shell = match shell {
+        "bash" => Shell::Bash,
+        "zsh" => Shell::Zsh,
+        _ => Shell::Fish,
+    };
+    let name = command.get_name().to_string();
+    let mut out = Vec::new();
+    clap_complete::generate(shell, &mut command, name, &mut out);
+    String::from_utf8_lossy(&out).into_owned()
+}
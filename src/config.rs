@@ -0,0 +1,154 @@
+// settings kept between runs in a text file of "name = value" lines, with # starting a comment.
+// the file is kept as written, setting a value rewrites just its line
+
+use crate::dirs;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(1); // how often a Watcher looks
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    lines: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize, // from 1
+    pub text: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "config line {} isn't name = value: {}",
+            self.line, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// name and value of a line, None for blank lines and comments
+fn entry(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (name, value) = line.split_once('=')?;
+    Some((name.trim(), value.trim()))
+}
+
+// <config dir>/config
+pub fn path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("config"))
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        for (n, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            let valid = trimmed.is_empty()
+                || trimmed.starts_with('#')
+                || entry(line).is_some_and(|(name, _)| !name.is_empty());
+            if !valid {
+                return Err(ParseError {
+                    line: n + 1,
+                    text: line.to_string(),
+                });
+            }
+        }
+        Ok(Self {
+            lines: text.lines().map(str::to_string).collect(),
+        })
+    }
+
+    // the config at path, empty if there's no file yet
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_string())
+    }
+
+    // the value of the last line setting name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries()
+            .filter(|(n, _)| *n == name)
+            .last()
+            .map(|(_, value)| value)
+    }
+
+    // every name and value, in file order
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| entry(line))
+    }
+
+    // replaces the line setting name, or adds one at the end
+    pub fn set(&mut self, name: &str, value: &str) {
+        let line = format!("{} = {}", name, value);
+        let last = self
+            .lines
+            .iter()
+            .rposition(|l| entry(l).is_some_and(|(n, _)| n == name));
+        match last {
+            Some(i) => self.lines[i] = line,
+            None => self.lines.push(line),
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// notices the config file being changed, by looking at when it was modified every so often
+pub struct Watcher {
+    path: PathBuf,
+    modified: Option<SystemTime>, // None while there's no file
+    checked: Instant,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Watcher {
+    pub fn new(path: &Path, now: Instant) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            modified: modified(path),
+            checked: now,
+        }
+    }
+
+    // whether the file has been written, made or removed since the last time this said so
+    pub fn changed(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.checked) < WATCH_INTERVAL {
+            return false;
+        }
+        self.checked = now;
+        let modified = modified(&self.path);
+        std::mem::replace(&mut self.modified, modified) != modified
+    }
+
+    // the file was just written by whatever is watching it, which isn't a change to tell it about
+    pub fn saw_write(&mut self) {
+        self.modified = modified(&self.path);
+    }
+}
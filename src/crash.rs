@@ -0,0 +1,206 @@
+use crate::bus;
+use crate::chip8::{StateError, PROGRAM_START};
+use crate::dirs;
+use crate::disasm::Instruction;
+use crate::observer::{Event, Observer};
+use crate::sha1::{self, sha1};
+use crate::Chip8;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DUMP_MAGIC: &[u8; 6] = b"C8DUMP";
+const DUMP_VERSION: u8 = 1;
+pub const HISTORY_LEN: usize = 64; // instructions kept for crash dumps
+
+// anything bigger can't be a ROM, even for 64K of xo-memory
+const MAX_ROM_LEN: u64 = (bus::XO_SIZE - PROGRAM_START as usize) as u64;
+
+// the last few instructions executed, oldest first
+pub struct History {
+    pub entries: VecDeque<(u16, u16)>, // pc, opcode
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(HISTORY_LEN)
+    }
+}
+
+impl Observer for History {
+    fn on_event(&mut self, _: &Chip8, event: &Event) {
+        if let Event::Exec { pc, opcode } = *event {
+            if self.entries.len() == self.capacity {
+                self.entries.pop_front();
+            }
+            self.entries.push_back((pc, opcode));
+        }
+    }
+}
+
+// everything needed to look into a crash after the fact
+pub struct Dump {
+    pub rom_sha1: [u8; 20], // of the ROM as loaded, memory in the state may have changed since
+    pub error: String,
+    pub history: Vec<(u16, u16)>,
+    pub state: Vec<u8>, // from Chip8::save_state
+}
+
+impl Dump {
+    pub fn new(
+        chip8: &mut Chip8,
+        rom_sha1: [u8; 20],
+        error: &dyn std::fmt::Display,
+        history: &History,
+    ) -> Self {
+        Self {
+            rom_sha1,
+            error: error.to_string(),
+            history: history.entries.iter().copied().collect(),
+            state: chip8.save_state(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(DUMP_MAGIC);
+        out.push(DUMP_VERSION);
+        out.extend_from_slice(&self.rom_sha1);
+        // its length is a u16, so a longer message is cut short, between characters
+        let mut len = self.error.len().min(u16::MAX as usize);
+        while !self.error.is_char_boundary(len) {
+            len -= 1;
+        }
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+        out.extend_from_slice(&self.error.as_bytes()[..len]);
+        out.extend_from_slice(&(self.history.len() as u16).to_be_bytes());
+        for (pc, opcode) in &self.history {
+            out.extend_from_slice(&pc.to_be_bytes());
+            out.extend_from_slice(&opcode.to_be_bytes());
+        }
+        out.extend_from_slice(&self.state);
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, StateError> {
+        if !data.starts_with(DUMP_MAGIC) {
+            return Err(StateError::NotAState);
+        }
+        let mut data = &data[DUMP_MAGIC.len()..];
+        let mut take = |len: usize| {
+            if data.len() < len {
+                return Err(StateError::Truncated);
+            }
+            let (head, tail) = data.split_at(len);
+            data = tail;
+            Ok(head)
+        };
+        match take(1)?[0] {
+            DUMP_VERSION => {}
+            v => return Err(StateError::UnsupportedVersion(v)),
+        }
+
+        let mut rom_sha1 = [0; 20];
+        rom_sha1.copy_from_slice(take(20)?);
+        let len = take(2)?;
+        let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        let error = String::from_utf8(take(len)?.to_vec()).map_err(|_| StateError::Corrupt)?;
+        let len = take(2)?;
+        let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        let history = take(len * 4)?
+            .chunks(4)
+            .map(|e| {
+                (
+                    u16::from_be_bytes([e[0], e[1]]),
+                    u16::from_be_bytes([e[2], e[3]]),
+                )
+            })
+            .collect();
+        Ok(Self {
+            rom_sha1,
+            error,
+            history,
+            state: data.to_vec(),
+        })
+    }
+
+    // put chip8 back where it crashed with what it ran last in history, keys let go
+    pub fn boot(&self, chip8: &mut Chip8, history: &mut History) -> Result<(), StateError> {
+        chip8.load_state(&self.state)?;
+        chip8.clear_keys();
+        history.entries = self.history.iter().copied().collect();
+        Ok(())
+    }
+
+    pub fn is_dump(data: &[u8]) -> bool {
+        data.starts_with(DUMP_MAGIC)
+    }
+
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "{}\nROM sha1 {}\nlast instructions:\n",
+            self.error,
+            sha1::hex(&self.rom_sha1)
+        );
+        for (pc, opcode) in &self.history {
+            out.push_str(&format!(
+                "  {:03X}: {:04X}  {}\n",
+                pc,
+                opcode,
+                Instruction::decode(*opcode)
+            ));
+        }
+        let mut chip8 = Chip8::new();
+        if chip8.load_state(&self.state).is_ok() {
+            out.push_str("state:\n");
+            out.push_str(&chip8.to_string());
+        }
+        out
+    }
+
+    // write the dump to the crashes directory under the data directory, returning its path
+    pub fn write(&self, rom_path: &Path) -> std::io::Result<PathBuf> {
+        let dir = dirs::data_dir()
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory, set HOME")
+            })?
+            .join("crashes");
+        std::fs::create_dir_all(&dir)?;
+        let stem = rom_path
+            .file_stem()
+            .map(|s| s.to_string_lossy())
+            .unwrap_or_default();
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = dir.join(format!("{}-{}.c8dump", stem, secs));
+        std::fs::write(&path, self.to_bytes())?;
+        Ok(path)
+    }
+}
+
+// look through the files in dirs for a ROM with the given hash
+pub fn find_rom(rom_sha1: &[u8; 20], dirs: &[&Path]) -> Option<PathBuf> {
+    dirs.iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .metadata()
+                .is_ok_and(|m| m.is_file() && m.len() <= MAX_ROM_LEN)
+        })
+        .map(|entry| entry.path())
+        .find(|path| std::fs::read(path).is_ok_and(|rom| &sha1(&rom) == rom_sha1))
+}
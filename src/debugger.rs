@@ -0,0 +1,778 @@
+use crate::cheats::{Cheats, Format};
+use crate::chip8::{opcode_class, OPCODE_CLASSES};
+use crate::crash::History;
+use crate::disasm::{self, Instruction};
+use crate::expr::Expr;
+use crate::movie::Recorder;
+use crate::observer::{Event, Observer};
+use crate::panels;
+use crate::search::{Filter, Search};
+use crate::statediff;
+use crate::Chip8;
+use std::collections::BTreeSet;
+use std::fmt::{self, Write};
+
+const HELP: &str = "\
+c, continue          resume running
+s, step [N]          run N instructions (default 1)
+k, keys [KEY..]      toggle keys to hold for frame advance, or show them
+f, frame [N]         hold those keys and run N frames (default 1)
+u, until draw|vblank run until the screen is next drawn, or the next 60Hz tick
+u, until cycle|frame N
+runto cycle|frame N  run to cycle N as a trace numbers them, or until N frames are drawn
+b, break [ADDR]      set a breakpoint, or list them
+b, break EVENT       stop after the next draw, when the sound starts or on key K pressed
+                     (EVENT is draw, sound or key K)
+d, delete ADDR|EVENT remove a breakpoint
+r, regs              show registers, timers and stack
+m, mem ADDR [LEN]    dump LEN bytes of memory (default 64)
+w, write ADDR BYTE.. write bytes to memory
+l, list [ADDR] [N]   disassemble N instructions from ADDR (default PC, 8)
+h, history           show the last instructions executed
+watch [EXPR]         show EXPR live over the game, or list watches
+unwatch N            remove watch N
+follow [N]           show N instructions around the PC over the game (default 5, 0 stops)
+search [BYTE..|VX]   find bytes or VX's value in memory, or list the matches
+refine BYTE..|VX     keep the matches that hold this now, or that have changed, same, up, down
+name [NAME [ADDR]]   name an address, or the only search match, for cheats, or list names
+name NAME ADDR LEN   name LEN bytes from ADDR, e.g. a table of sprites
+forget NAME          remove a name and its cheat
+freeze NAME [BYTE]   hold NAME at BYTE every frame (default what it is now)
+unfreeze NAME        stop holding NAME
+set NAME BYTE        write BYTE to NAME once
+show NAME [FMT] [N]  show N bytes at NAME over the game as dec, hex or bcd (default dec 1)
+hide NAME            stop showing NAME
+score NAME [FMT] [N] keep high scores of the N bytes at NAME, read as dec, hex or bcd, shown
+                     when paused with F11
+save FILE            write a save state
+export FILE [ADDR [LEN]]
+                     write LEN bytes of memory from ADDR to FILE as a ROM (default 200 up to
+                     the last byte that isn't 0), with whatever writes and cheats changed
+diff FILE            show what changed since the save state FILE
+throttle [CLASS N]   make opcodes of CLASS, e.g. drw or D, take N extra cycles, or list them
+rewind N             go back N instructions (needs --replay or a state saved with it)
+help [OP]            show this, or what an opcode does, e.g. help D125, DXYN or ADD
+q, quit              exit the emulator
+";
+
+// a count to run to, unless it's already been reached
+fn run_to(until: Until, now: u64) -> Result<Until, String> {
+    match until {
+        Until::Cycle(n) if n <= now => Err(format!("already at cycle {}", now)),
+        Until::Frame(n) if n <= now => Err(format!("already at frame {}", now)),
+        _ => Ok(until),
+    }
+}
+
+// the throttled opcode classes, e.g. "drw (DXXX) +20 cycles"
+fn throttled(chip8: &Chip8) -> String {
+    let mut out = String::new();
+    for (n, &extra) in chip8.throttle().iter().enumerate().filter(|(_, &e)| e > 0) {
+        let class = OPCODE_CLASSES[n];
+        let _ = writeln!(out, "{} ({:X}XXX) +{} cycles", class, n, extra);
+    }
+    if out.is_empty() {
+        out.push_str("nothing's throttled\n");
+    }
+    out
+}
+
+const FOLLOW_LINES: usize = 5;
+
+// things that stop the VM when they happen, wherever it is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventBreak {
+    Draw,    // a DXYN ran
+    Sound,   // the sound timer was set going
+    Key(u8), // the key went down
+}
+
+impl EventBreak {
+    fn parse<'a>(word: &str, mut words: impl Iterator<Item = &'a str>) -> Result<Self, String> {
+        match word {
+            "draw" => Ok(Self::Draw),
+            "sound" => Ok(Self::Sound),
+            "key" => {
+                let key = words.next().ok_or("key needs a key, 0-F")?;
+                match u8::from_str_radix(key, 16) {
+                    Ok(k) if k < 16 => Ok(Self::Key(k)),
+                    _ => Err(format!("bad key {}", key)),
+                }
+            }
+            _ => Err(format!("bad address {}", word)),
+        }
+    }
+}
+
+impl fmt::Display for EventBreak {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Draw => write!(f, "draw"),
+            Self::Sound => write!(f, "sound"),
+            Self::Key(k) => write!(f, "key {:X}", k),
+        }
+    }
+}
+
+// where a run started with until stops, whichever comes first of it and a breakpoint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Until {
+    Draw(u64),  // the draw flag is up after the cycle with this count
+    Tick(u64),  // the timers reach this tick
+    Cycle(u64), // this many instructions have run, see Chip8::cycles_executed
+    Frame(u64), // this many frames have been drawn
+}
+
+// what the frontend should do after a command
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Pause,
+    Run,
+    Quit,
+}
+
+// text command debugger, the frontend feeds it lines and shows what comes back
+#[derive(Default)]
+pub struct Debugger {
+    pub breakpoints: BTreeSet<u16>,
+    pub event_breaks: BTreeSet<EventBreak>,
+    hit: Option<EventBreak>, // happened in the last cycle, to stop before the next
+    stopped_on: Option<String>, // what the VM last stopped after, for location
+    until: Option<Until>,
+    keys: u16, // down as of the last cycle, to see new presses
+    pub history: History,
+    pub recorder: Option<Recorder>, // input since the last save state, for replays and rewinding
+    paused: bool,
+    steps: usize,                 // instructions left to run before pausing again
+    frame_end: Option<u64>,       // timer tick to pause again at when advancing frames
+    held: u16,                    // bit per key to hold down for frame advance
+    skip_break: bool,             // continuing from a breakpoint, don't stop on it straight away
+    last_line: String,            // repeated when an empty line is entered
+    watches: Vec<(String, Expr)>, // as typed, and parsed
+    follow: usize,                // instructions around the PC to show over the game
+    search: Option<Search>,
+    pub cheats: Cheats,
+}
+
+impl Observer for Debugger {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        self.history.on_event(chip8, event);
+        self.recorder.on_event(chip8, event);
+        // exec comes before the instruction runs, so the sound timer is as it was
+        let hit = match *event {
+            Event::Exec { opcode, .. } => match Instruction::decode(opcode) {
+                Instruction::Drw(..) => Some(EventBreak::Draw),
+                Instruction::LdStVx(x) if chip8.v()[x as usize] > 0 && chip8.sound_timer() == 0 => {
+                    Some(EventBreak::Sound)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(hit) = hit.filter(|hit| self.event_breaks.contains(hit)) {
+            self.hit = Some(hit);
+        }
+    }
+}
+
+fn parse_addr(s: &str) -> Result<u16, String> {
+    let digits = s.trim_start_matches("0x").trim_start_matches("0X");
+    u16::from_str_radix(digits, 16)
+        .ok()
+        .filter(|&a| a < 0x1000)
+        .ok_or_else(|| format!("bad address {}", s))
+}
+
+// the format and length of bytes to show, from whichever of them is given (default dec 1)
+fn parse_format<'a>(words: impl Iterator<Item = &'a str>) -> Result<(Format, usize), String> {
+    let mut format = Format::Dec;
+    let mut len = 1;
+    for word in words {
+        match (Format::parse(word), word.parse()) {
+            (Some(f), _) => format = f,
+            (None, Ok(n @ 1..=8)) => len = n,
+            _ => return Err(format!("bad format or length {}", word)),
+        }
+    }
+    Ok((format, len))
+}
+
+fn parse_count(s: Option<&str>, default: usize) -> Result<usize, String> {
+    match s {
+        Some(s) => s.parse().map_err(|_| format!("bad count {}", s)),
+        None => Ok(default),
+    }
+}
+
+// hex bytes, or a register for the value in it
+fn parse_bytes<'a>(chip8: &Chip8, words: impl Iterator<Item = &'a str>) -> Result<Vec<u8>, String> {
+    words
+        .map(
+            |w| match w.strip_prefix('v').or_else(|| w.strip_prefix('V')) {
+                Some(x) if x.len() == 1 => u8::from_str_radix(x, 16)
+                    .map(|x| chip8.v()[x as usize])
+                    .map_err(|_| format!("bad register {}", w)),
+                _ => u8::from_str_radix(w, 16).map_err(|_| format!("bad byte {}", w)),
+            },
+        )
+        .collect()
+}
+
+// how many matches a search has and where the first few are
+fn show_matches(search: &Search) -> String {
+    const SHOWN: usize = 32;
+    let matches = search.matches();
+    let mut out = match matches.len() {
+        0 => "no matches".to_string(),
+        1 => "1 match:".to_string(),
+        n => format!("{} matches:", n),
+    };
+    for addr in matches.iter().take(SHOWN) {
+        let _ = write!(out, " {:03X}", addr);
+    }
+    if matches.len() > SHOWN {
+        out.push_str(" ...");
+    }
+    out.push('\n');
+    out
+}
+
+fn lines<T: ToString>(rows: Vec<T>) -> String {
+    rows.iter().map(|r| r.to_string() + "\n").collect()
+}
+
+// keys from a bit per key, e.g. "5 A"
+pub fn key_list(keys: u16) -> String {
+    let keys: Vec<String> = (0..16)
+        .filter(|k| keys & (1 << k) != 0)
+        .map(|k| format!("{:X}", k))
+        .collect();
+    keys.join(" ")
+}
+
+pub fn registers(chip8: &Chip8) -> String {
+    format!(
+        "{}cycle {}  frame {}\n",
+        chip8,
+        chip8.cycles_executed(),
+        chip8.frames_rendered()
+    )
+}
+
+impl Debugger {
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    // paused with no steps left to run
+    pub fn stopped(&self) -> bool {
+        self.paused && self.steps == 0 && self.frame_end.is_none()
+    }
+
+    // stop once n instructions have run, as run --break-at-cycle does, without pausing before
+    pub fn break_at_cycle(&mut self, chip8: &Chip8, n: u64) -> Result<(), String> {
+        self.until = Some(run_to(Until::Cycle(n), chip8.cycles_executed())?);
+        Ok(())
+    }
+
+    // stop once n frames have been drawn
+    pub fn break_at_frame(&mut self, chip8: &Chip8, n: u64) -> Result<(), String> {
+        self.until = Some(run_to(Until::Frame(n), chip8.frames_rendered())?);
+        Ok(())
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.steps = 0;
+        self.frame_end = None;
+        self.until = None;
+    }
+
+    // keys set up for frame advance, bit per key
+    pub fn held_keys(&self) -> u16 {
+        self.held
+    }
+
+    fn show_keys(&self) -> String {
+        match key_list(self.held) {
+            keys if keys.is_empty() => "holding no keys\n".to_string(),
+            keys => format!("holding {}\n", keys),
+        }
+    }
+
+    // every watch with its value now, for frontends to show over the game
+    pub fn watches(&self, chip8: &Chip8) -> Vec<String> {
+        let mut lines: Vec<String> = (self.watches.iter())
+            .map(|(text, e)| match e.eval(chip8) {
+                Ok(value) => format!("{} = {}", text, value),
+                Err(err) => format!("{}: {}", text, err),
+            })
+            .collect();
+        let follow = panels::around_pc(chip8, &self.breakpoints, self.follow);
+        lines.extend(follow.iter().map(|line| self.show_line(line)));
+        lines
+    }
+
+    // a line of disassembly, with the name of where it goes if it has one
+    fn show_line(&self, line: &panels::Line) -> String {
+        match line.target.and_then(|addr| self.cheats.name_at(addr)) {
+            Some(name) => format!("{} ({})", line, name),
+            None => line.to_string(),
+        }
+    }
+
+    // rows of memory, each with the names of the regions that start in it
+    fn show_hex(&self, rows: Vec<panels::HexRow>) -> String {
+        let mut out = String::new();
+        for row in rows {
+            let end = row.addr + row.bytes.len() as u16;
+            let names: Vec<&str> = (self.cheats.labels.iter())
+                .filter(|(_, r)| (row.addr..end).contains(&r.addr))
+                .map(|(name, _)| name)
+                .collect();
+            let _ = if names.is_empty() {
+                writeln!(out, "{}", row)
+            } else {
+                writeln!(out, "{}  {}", row, names.join(", "))
+            };
+        }
+        out
+    }
+
+    // the breakpoints, watches and following the PC as the commands that set them up, to pick
+    // a session up again with restore
+    pub fn session(&self) -> String {
+        let mut out = String::new();
+        for addr in &self.breakpoints {
+            let _ = writeln!(out, "break {:03X}", addr);
+        }
+        for event in &self.event_breaks {
+            let _ = writeln!(out, "break {}", event);
+        }
+        for (text, _) in &self.watches {
+            let _ = writeln!(out, "watch {}", text);
+        }
+        if self.follow > 0 {
+            let _ = writeln!(out, "follow {}", self.follow);
+        }
+        out
+    }
+
+    pub fn restore(&mut self, chip8: &mut Chip8, session: &str) -> Result<(), String> {
+        for (n, line) in session.lines().enumerate() {
+            let result = match line.split_whitespace().next() {
+                None => continue,
+                Some("break") | Some("watch") | Some("follow") => self.run_command(chip8, line),
+                Some(_) => Err(format!("unexpected {}", line)),
+            };
+            result.map_err(|e| format!("line {}: {}", n + 1, e))?;
+        }
+        Ok(())
+    }
+
+    fn list_watches(&self, chip8: &Chip8) -> String {
+        self.watches(chip8)
+            .iter()
+            .enumerate()
+            .map(|(n, watch)| format!("{}: {}\n", n + 1, watch))
+            .collect()
+    }
+
+    // called before every cycle, true if the VM should stop before running it
+    pub fn should_pause(&mut self, chip8: &Chip8) -> bool {
+        let pressed = chip8.keys() & !self.keys;
+        self.keys = chip8.keys();
+        let key = (0..16)
+            .find(|&k| pressed & 1 << k != 0 && self.event_breaks.contains(&EventBreak::Key(k)));
+        let hit = self.hit.take().or(key.map(EventBreak::Key));
+        if self.paused {
+            if let Some(end) = self.frame_end {
+                if chip8.ticks() < end {
+                    return false;
+                }
+                self.frame_end = None;
+            }
+            if self.steps == 0 {
+                return true;
+            }
+            self.steps -= 1;
+            return false;
+        }
+        let reached = match self.until {
+            Some(Until::Draw(start)) if chip8.cycles() > start && chip8.draw_flag() => Some("draw"),
+            Some(Until::Tick(end)) if chip8.ticks() >= end => Some("vblank"),
+            Some(Until::Cycle(n)) if chip8.cycles_executed() >= n => Some("cycle"),
+            Some(Until::Frame(n)) if chip8.frames_rendered() >= n => Some("frame"),
+            _ => None,
+        };
+        // continuing from a breakpoint doesn't stop on it again, an event still stops
+        let skip = std::mem::take(&mut self.skip_break);
+        if self.breakpoints.contains(&chip8.pc()) && !skip || hit.is_some() || reached.is_some() {
+            self.paused = true;
+            self.until = None;
+            self.stopped_on = (hit.map(|hit| hit.to_string())).or(reached.map(String::from));
+            return true;
+        }
+        false
+    }
+
+    // where the VM is stopped, shown whenever it pauses
+    pub fn location(&self, chip8: &Chip8) -> String {
+        let line = &panels::disassembly(chip8, &self.breakpoints, chip8.pc(), 1)[0];
+        let mark = match &self.stopped_on {
+            Some(event) => format!("after {}, ", event),
+            None if line.breakpoint => "breakpoint ".to_string(),
+            None => String::new(),
+        };
+        format!(
+            "{}{:03X}: {:04X}  {}\n",
+            mark, line.addr, line.opcode, line.instruction
+        )
+    }
+
+    pub fn command(&mut self, chip8: &mut Chip8, line: &str) -> (Action, String) {
+        let line = if line.trim().is_empty() {
+            self.last_line.clone()
+        } else {
+            self.last_line = line.trim().to_string();
+            self.last_line.clone()
+        };
+        match self.run_command(chip8, &line) {
+            Ok(result) => result,
+            Err(e) => (Action::Pause, format!("{}\n", e)),
+        }
+    }
+
+    fn run_command(&mut self, chip8: &mut Chip8, line: &str) -> Result<(Action, String), String> {
+        let mut words = line.split_whitespace();
+        let cmd = words.next().unwrap_or("");
+        let out = match cmd {
+            "" => String::new(),
+            "c" | "continue" => {
+                self.paused = false;
+                self.stopped_on = None;
+                self.until = None;
+                self.skip_break = true;
+                return Ok((Action::Run, String::new()));
+            }
+            "u" | "until" | "runto" => {
+                self.until = Some(match words.next() {
+                    Some("draw") => Until::Draw(chip8.cycles()),
+                    Some("vblank") => Until::Tick(chip8.ticks() + 1),
+                    Some(which @ ("cycle" | "frame")) => {
+                        let n = words.next().and_then(|n| n.parse().ok());
+                        let n = n.ok_or_else(|| format!("{} {} needs a number", cmd, which))?;
+                        match which {
+                            "cycle" => run_to(Until::Cycle(n), chip8.cycles_executed())?,
+                            _ => run_to(Until::Frame(n), chip8.frames_rendered())?,
+                        }
+                    }
+                    _ => return Err(format!("{} needs draw, vblank, cycle N or frame N", cmd)),
+                });
+                self.paused = false;
+                self.stopped_on = None;
+                self.skip_break = true;
+                return Ok((Action::Run, String::new()));
+            }
+            "s" | "step" => {
+                self.paused = true;
+                self.stopped_on = None;
+                self.until = None;
+                self.steps = parse_count(words.next(), 1)?;
+                return Ok((Action::Run, String::new()));
+            }
+            "k" | "keys" => {
+                for key in words {
+                    let k = u8::from_str_radix(key, 16)
+                        .ok()
+                        .filter(|&k| k < 16)
+                        .ok_or_else(|| format!("bad key {}", key))?;
+                    self.held ^= 1 << k;
+                }
+                self.show_keys()
+            }
+            "f" | "frame" => {
+                let frames = parse_count(words.next(), 1)? as u64;
+                chip8.set_keys(self.held);
+                self.paused = true;
+                self.steps = 0;
+                self.frame_end = Some(chip8.ticks() + frames);
+                return Ok((Action::Run, String::new()));
+            }
+            "b" | "break" => match words.next() {
+                Some(word) => match parse_addr(word) {
+                    Ok(addr) => {
+                        self.breakpoints.insert(addr);
+                        format!("breakpoint at {:03X}\n", addr)
+                    }
+                    Err(_) => {
+                        let event = EventBreak::parse(word, words)?;
+                        self.event_breaks.insert(event);
+                        format!("breakpoint on {}\n", event)
+                    }
+                },
+                None => {
+                    let mut out = lines(panels::breakpoints(chip8, &self.breakpoints));
+                    for event in &self.event_breaks {
+                        let _ = writeln!(out, " on {}", event);
+                    }
+                    out
+                }
+            },
+            "d" | "delete" => {
+                let word = words.next().ok_or("delete needs an address")?;
+                match parse_addr(word) {
+                    Ok(addr) if !self.breakpoints.remove(&addr) => {
+                        return Err(format!("no breakpoint at {:03X}", addr))
+                    }
+                    Ok(_) => {}
+                    Err(_) => {
+                        let event = EventBreak::parse(word, words)?;
+                        if !self.event_breaks.remove(&event) {
+                            return Err(format!("no breakpoint on {}", event));
+                        }
+                    }
+                }
+                String::new()
+            }
+            "r" | "regs" => registers(chip8),
+            "m" | "mem" => {
+                let addr = parse_addr(words.next().ok_or("mem needs an address")?)?;
+                let len = parse_count(words.next(), 64)?;
+                self.show_hex(panels::hex_rows(chip8, addr, len))
+            }
+            "w" | "write" => {
+                let addr = parse_addr(words.next().ok_or("write needs an address")?)? as usize;
+                let bytes = words
+                    .map(|b| u8::from_str_radix(b, 16).map_err(|_| format!("bad byte {}", b)))
+                    .collect::<Result<Vec<u8>, String>>()?;
+                if bytes.is_empty() {
+                    return Err("write needs some bytes".to_string());
+                }
+                let memory = chip8.memory_mut();
+                if addr + bytes.len() > memory.len() {
+                    return Err("write runs past the end of memory".to_string());
+                }
+                memory[addr..addr + bytes.len()].copy_from_slice(&bytes);
+                self.show_hex(panels::hex_rows(chip8, addr as u16, bytes.len()))
+            }
+            "l" | "list" => {
+                let addr = match words.next() {
+                    Some(a) => parse_addr(a)?,
+                    None => chip8.pc(),
+                };
+                let count = parse_count(words.next(), 8)?;
+                let listing = panels::disassembly(chip8, &self.breakpoints, addr, count);
+                lines(listing.iter().map(|line| self.show_line(line)).collect())
+            }
+            "h" | "history" => lines(panels::timeline(chip8, &self.breakpoints, &self.history)),
+            "watch" => {
+                let text = words.collect::<Vec<&str>>().join(" ");
+                if !text.is_empty() {
+                    let e = Expr::parse_with(&text, &self.cheats.labels)?;
+                    self.watches.push((text, e));
+                }
+                self.list_watches(chip8)
+            }
+            "search" => {
+                let pattern = parse_bytes(chip8, words)?;
+                if !pattern.is_empty() {
+                    self.search = Some(Search::new(chip8.memory(), &pattern));
+                }
+                show_matches(self.search.as_ref().ok_or("search needs some bytes")?)
+            }
+            "refine" => {
+                let search = self
+                    .search
+                    .as_mut()
+                    .ok_or("nothing to refine, search first")?;
+                let words: Vec<&str> = words.collect();
+                let filter = match words[..] {
+                    [] => return Err("refine needs bytes or how they changed".to_string()),
+                    [word] => Filter::parse_word(word),
+                    _ => None,
+                };
+                let filter = match filter {
+                    Some(filter) => filter,
+                    None => Filter::Equal(parse_bytes(chip8, words.into_iter())?),
+                };
+                search.refine(chip8.memory(), &filter);
+                show_matches(search)
+            }
+            "name" => {
+                if let Some(name) = words.next() {
+                    let addr = match (words.next(), self.search.as_ref().map(Search::matches)) {
+                        (Some(addr), _) => parse_addr(addr)?,
+                        (None, Some(&[addr])) => addr as u16,
+                        (None, _) => return Err("name needs an address".to_string()),
+                    };
+                    let len = match words.next() {
+                        Some(len) => len
+                            .parse()
+                            .ok()
+                            .filter(|&len| len > 0)
+                            .ok_or_else(|| format!("bad length {}", len))?,
+                        None => 1,
+                    };
+                    self.cheats.name(name, addr, len);
+                }
+                self.cheats.list(chip8)
+            }
+            "forget" => {
+                let name = words.next().ok_or("forget needs a name")?;
+                if !self.cheats.remove(name) {
+                    return Err(format!("nothing is called {}", name));
+                }
+                self.cheats.list(chip8)
+            }
+            "freeze" | "set" | "unfreeze" => {
+                let name = words.next().ok_or("which name?")?;
+                let addr = self
+                    .cheats
+                    .addr(name)
+                    .ok_or_else(|| format!("nothing is called {}", name))?
+                    as usize;
+                let value = match words.next() {
+                    Some(b) => u8::from_str_radix(b, 16).map_err(|_| format!("bad byte {}", b))?,
+                    None if cmd == "set" => return Err("set needs a byte".to_string()),
+                    None => chip8.memory().get(addr).copied().unwrap_or(0),
+                };
+                match cmd {
+                    "freeze" => {
+                        self.cheats.freeze(name, value);
+                        self.cheats.apply(chip8);
+                    }
+                    "unfreeze" => {
+                        if !self.cheats.unfreeze(name) {
+                            return Err(format!("{} isn't frozen", name));
+                        }
+                    }
+                    _ => {
+                        if let Some(b) = chip8.memory_mut().get_mut(addr) {
+                            *b = value;
+                        }
+                    }
+                }
+                self.cheats.list(chip8)
+            }
+            "show" => {
+                let name = words.next().ok_or("show needs a name")?;
+                if self.cheats.addr(name).is_none() {
+                    return Err(format!("nothing is called {}", name));
+                }
+                let (format, len) = parse_format(words)?;
+                self.cheats.show(name, format, len);
+                lines(self.cheats.hud(chip8))
+            }
+            "score" => {
+                let name = words.next().ok_or("score needs a name")?;
+                if self.cheats.addr(name).is_none() {
+                    return Err(format!("nothing is called {}", name));
+                }
+                let (format, len) = parse_format(words)?;
+                self.cheats.keep_score(name, format, len);
+                match self.cheats.score(chip8) {
+                    Some(score) => format!("the score is {} now\n", score),
+                    None => format!("{} doesn't hold a {} number now\n", name, format.name()),
+                }
+            }
+            "hide" => {
+                let name = words.next().ok_or("hide needs a name")?;
+                if !self.cheats.hide(name) {
+                    return Err(format!("{} isn't shown", name));
+                }
+                lines(self.cheats.hud(chip8))
+            }
+            "follow" => {
+                self.follow = parse_count(words.next(), FOLLOW_LINES)?;
+                String::new()
+            }
+            "unwatch" => {
+                let n = parse_count(Some(words.next().ok_or("unwatch needs a number")?), 0)?;
+                if n == 0 || n > self.watches.len() {
+                    return Err(format!("no watch {}", n));
+                }
+                self.watches.remove(n - 1);
+                self.list_watches(chip8)
+            }
+            "save" => {
+                let path = words.next().ok_or("save needs a file name")?;
+                let state = match &mut self.recorder {
+                    Some(recorder) => recorder.save_state(chip8),
+                    None => chip8.save_state(),
+                };
+                std::fs::write(path, state).map_err(|e| e.to_string())?;
+                format!("saved to {}\n", path)
+            }
+            "export" => {
+                let path = words.next().ok_or("export needs a file name")?;
+                let addr = parse_addr(words.next().unwrap_or("200"))? as usize;
+                let memory = chip8.memory();
+                let used = memory
+                    .iter()
+                    .rposition(|&b| b != 0)
+                    .map_or(0, |end| end + 1);
+                let len = parse_count(words.next(), used.saturating_sub(addr))?;
+                let bytes = memory.get(addr..addr + len);
+                let bytes = bytes.ok_or("export runs past the end of memory")?;
+                std::fs::write(path, bytes).map_err(|e| e.to_string())?;
+                format!("wrote {} bytes from {:03X} to {}\n", len, addr, path)
+            }
+            "diff" => {
+                let path = words.next().ok_or("diff needs a file name")?;
+                let data = std::fs::read(path).map_err(|e| e.to_string())?;
+                let saved = statediff::machine(&data).map_err(|e| e.to_string())?;
+                match statediff::report(&saved, chip8, path, "now") {
+                    report if report.is_empty() => "no changes\n".to_string(),
+                    report => report,
+                }
+            }
+            "throttle" => {
+                if let Some(class) = words.next() {
+                    let n = opcode_class(class).ok_or_else(|| {
+                        let classes = OPCODE_CLASSES.join(" ");
+                        format!("bad opcode class {}, one of {} or 0-F", class, classes)
+                    })?;
+                    let extra = words.next().ok_or("throttle needs a number of cycles")?;
+                    let extra = extra
+                        .parse()
+                        .map_err(|_| format!("bad number of cycles {}", extra))?;
+                    chip8.set_throttle(n, extra);
+                }
+                throttled(chip8)
+            }
+            "rewind" => {
+                let recorder = self
+                    .recorder
+                    .as_mut()
+                    .ok_or("nothing's been recorded, run with --replay")?;
+                let n = parse_count(words.next(), 1)? as u64;
+                let rewound = recorder.rewind(chip8, n).map_err(|e| e.to_string())?;
+                let note = if rewound < n {
+                    " (as far back as the recording goes)"
+                } else {
+                    ""
+                };
+                format!(
+                    "rewound {} instructions{}\n{}",
+                    rewound,
+                    note,
+                    self.location(chip8)
+                )
+            }
+            "q" | "quit" => return Ok((Action::Quit, String::new())),
+            "help" | "?" => match words.next() {
+                Some(query) => {
+                    let docs = disasm::lookup(query);
+                    if docs.is_empty() {
+                        return Err(format!("no instruction {}", query));
+                    }
+                    lines(docs)
+                }
+                None => HELP.to_string(),
+            },
+            other => return Err(format!("unknown command {}, try help", other)),
+        };
+        Ok((Action::Pause, out))
+    }
+}
@@ -0,0 +1,160 @@
+// warnings about things a ROM does that work here but are likely bugs, or only work by luck on
+// some interpreters. the VM keeps running, each warning is given once per instruction that causes
+// it so a loop doesn't repeat it forever
+
+use crate::disasm::Instruction;
+use crate::fonts::{FONT_LEN, FONT_START};
+use crate::observer::{Event, Observer};
+use crate::Chip8;
+use std::collections::HashSet;
+use std::fmt;
+use std::mem::Discriminant;
+
+const ROM_START: usize = 0x200;
+const STACK_LEVELS: usize = 16;
+const STACK_WARNING: usize = STACK_LEVELS - 2; // calls this deep or deeper are nearly out
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Warning {
+    Uninitialized { pc: u16, addr: usize }, // read memory nothing was loaded into or written to
+    FontWrite { pc: u16, addr: usize },     // FX33/FX55 stored over the font
+    StackNearlyFull { pc: u16, depth: usize },
+    OffScreen { pc: u16, x: u8, y: u8 }, // DXYN started past the edges
+    OddJump { pc: u16, target: u16 },    // went from an even address to an odd one
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Uninitialized { pc, addr } => write!(
+                f,
+                "{:03X}: reads {:03X}, which nothing was loaded into or written to",
+                pc, addr
+            ),
+            Self::FontWrite { pc, addr } => {
+                write!(f, "{:03X}: stores into the font at {:03X}", pc, addr)
+            }
+            Self::StackNearlyFull { pc, depth } => write!(
+                f,
+                "{:03X}: calls {} deep, the stack has {} levels",
+                pc, depth, STACK_LEVELS
+            ),
+            Self::OffScreen { pc, x, y } => write!(
+                f,
+                "{:03X}: draws at {},{}, off the screen, which wraps here but not everywhere",
+                pc, x, y
+            ),
+            Self::OddJump { pc, target } => write!(
+                f,
+                "{:03X}: goes to the odd address {:03X}, which the original interpreter runs but \
+                 not every other one does",
+                pc, target
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Warning {}
+
+impl Warning {
+    fn pc(&self) -> u16 {
+        match *self {
+            Self::Uninitialized { pc, .. }
+            | Self::FontWrite { pc, .. }
+            | Self::StackNearlyFull { pc, .. }
+            | Self::OffScreen { pc, .. }
+            | Self::OddJump { pc, .. } => pc,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    initialized: Vec<bool>, // empty when what's in memory isn't known, e.g. booting a state
+    seen: HashSet<(u16, Discriminant<Warning>)>,
+    last_pc: Option<u16>,       // of the instruction run before this one
+    pub warnings: Vec<Warning>, // given since they were last taken
+}
+
+impl Diagnostics {
+    // for a ROM of rom_len bytes just loaded, or None if memory came from somewhere else
+    pub fn new(chip8: &Chip8, rom_len: Option<usize>) -> Self {
+        let mut initialized = Vec::new();
+        if let Some(rom_len) = rom_len {
+            initialized = vec![false; chip8.memory().len()];
+            initialized[FONT_START..FONT_START + FONT_LEN].fill(true);
+            let rom_end = (ROM_START + rom_len).min(initialized.len());
+            initialized[ROM_START..rom_end].fill(true);
+        }
+        Self {
+            initialized,
+            ..Default::default()
+        }
+    }
+
+    fn warn(&mut self, warning: Warning) {
+        if self
+            .seen
+            .insert((warning.pc(), std::mem::discriminant(&warning)))
+        {
+            self.warnings.push(warning);
+        }
+    }
+
+    // warn about the first byte of len from addr that's never been set
+    fn read(&mut self, pc: u16, addr: usize, len: usize) {
+        let unset = (addr..addr + len).find(|&a| self.initialized.get(a) == Some(&false));
+        if let Some(addr) = unset {
+            self.warn(Warning::Uninitialized { pc, addr });
+        }
+    }
+
+    fn store(&mut self, pc: u16, addr: usize, len: usize) {
+        if addr < FONT_START + FONT_LEN && addr + len > FONT_START {
+            self.warn(Warning::FontWrite { pc, addr });
+        }
+    }
+}
+
+impl Observer for Diagnostics {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        let (pc, opcode) = match *event {
+            Event::Exec { pc, opcode } => (pc, opcode),
+            Event::Write { addr, .. } => {
+                if let Some(b) = self.initialized.get_mut(addr as usize) {
+                    *b = true;
+                }
+                return;
+            }
+            _ => return,
+        };
+        self.read(pc, pc as usize, 2);
+        match self.last_pc.replace(pc) {
+            Some(last) if last % 2 == 0 && pc % 2 == 1 => self.warn(Warning::OddJump {
+                pc: last,
+                target: pc,
+            }),
+            _ => {}
+        }
+        let (i, v) = (chip8.i() as usize, chip8.v());
+        match Instruction::decode(opcode) {
+            Instruction::Drw(x, y, n) => {
+                self.read(pc, i, n as usize);
+                let (x, y) = (v[x as usize], v[y as usize]);
+                if x >= 64 || y >= 32 {
+                    self.warn(Warning::OffScreen { pc, x, y });
+                }
+            }
+            Instruction::LdVxI(x) => self.read(pc, i, x as usize + 1),
+            Instruction::LdIVx(x) => self.store(pc, i, x as usize + 1),
+            Instruction::LdB(_) => self.store(pc, i, 3),
+            Instruction::Call(_) if chip8.stack().len() + 1 >= STACK_WARNING => {
+                self.warn(Warning::StackNearlyFull {
+                    pc,
+                    depth: chip8.stack().len() + 1,
+                })
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+// $XDG_DATA_HOME/chip8, or ~/.local/share/chip8 when that isn't set
+pub fn data_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(base.join("chip8"))
+}
+
+// $XDG_CONFIG_HOME/chip8, or ~/.config/chip8 when that isn't set
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("chip8"))
+}
@@ -0,0 +1,128 @@
+// --discord: the ROM being played shown on the player's Discord profile, with how long it's been
+// going, through the Discord client's Rich Presence IPC. that's a unix socket, discord-ipc-0 to 9
+// in the runtime or temp directory, carrying frames of an opcode and a length, both 32-bit little
+// endian, then that much JSON. it's built with the discord feature
+
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_millis(500); // the game doesn't wait longer on Discord
+
+// frame opcodes
+pub const HANDSHAKE: u32 = 0;
+pub const FRAME: u32 = 1;
+pub const CLOSE: u32 = 2;
+
+pub fn frame(opcode: u32, json: &str) -> Vec<u8> {
+    let mut out = opcode.to_le_bytes().to_vec();
+    out.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    out.extend_from_slice(json.as_bytes());
+    out
+}
+
+// s as a JSON string, quotes and all
+pub fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// SET_ACTIVITY for the process pid, with details and state as Discord's two lines of text and
+// start as when it began in seconds since 1970, for Discord to count up from
+pub fn activity(pid: u32, details: &str, state: Option<&str>, start: u64, nonce: u64) -> String {
+    let state = state.map_or(String::new(), |s| format!(",\"state\":{}", json_string(s)));
+    format!(
+        "{{\"cmd\":\"SET_ACTIVITY\",\"args\":{{\"pid\":{},\"activity\":{{\"details\":{}{},\
+         \"timestamps\":{{\"start\":{}}}}}}},\"nonce\":\"{}\"}}",
+        pid,
+        json_string(details),
+        state,
+        start,
+        nonce
+    )
+}
+
+// where the client may be listening, most likely first
+pub fn socket_paths() -> Vec<PathBuf> {
+    let dirs = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(PathBuf::from)
+        .chain(std::iter::once(PathBuf::from("/tmp")));
+    dirs.flat_map(|dir| (0..10).map(move |n| dir.join(format!("discord-ipc-{}", n))))
+        .collect()
+}
+
+pub struct Presence {
+    stream: UnixStream,
+    nonce: u64,
+    pub error: Option<io::Error>, // why it stopped updating, if it has
+}
+
+impl Presence {
+    // the first client found, as the Discord application client_id
+    pub fn connect(client_id: &str) -> io::Result<Self> {
+        let mut last = io::Error::new(io::ErrorKind::NotFound, "Discord isn't running");
+        for path in socket_paths().iter().filter(|p| p.exists()) {
+            match Self::connect_at(path, client_id) {
+                Ok(presence) => return Ok(presence),
+                Err(e) => last = e,
+            }
+        }
+        Err(last)
+    }
+
+    pub fn connect_at(path: &Path, client_id: &str) -> io::Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut presence = Self {
+            stream,
+            nonce: 0,
+            error: None,
+        };
+        let hello = format!("{{\"v\":1,\"client_id\":{}}}", json_string(client_id));
+        presence.stream.write_all(&frame(HANDSHAKE, &hello))?;
+        // READY, or a CLOSE saying why not
+        match presence.read()? {
+            (CLOSE, reply) => Err(io::Error::new(io::ErrorKind::ConnectionRefused, reply)),
+            _ => Ok(presence),
+        }
+    }
+
+    // the opcode and JSON of the next frame from the client
+    fn read(&mut self) -> io::Result<(u32, String)> {
+        let mut header = [0; 8];
+        self.stream.read_exact(&mut header)?;
+        let opcode = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let mut json = vec![0; len as usize];
+        self.stream.read_exact(&mut json)?;
+        Ok((opcode, String::from_utf8_lossy(&json).into_owned()))
+    }
+
+    // show details and state, playing since start in seconds since 1970. after an error it stops
+    // trying, leaving error set
+    pub fn set(&mut self, details: &str, state: Option<&str>, start: u64) {
+        if self.error.is_some() {
+            return;
+        }
+        self.nonce += 1;
+        let json = activity(std::process::id(), details, state, start, self.nonce);
+        // the reply is read so the client's never left blocked writing to us
+        let sent = (self.stream.write_all(&frame(FRAME, &json))).and_then(|_| self.read());
+        if let Err(e) = sent {
+            self.error = Some(e);
+        }
+    }
+}
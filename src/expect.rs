@@ -0,0 +1,51 @@
+// what a test ROM should have done by the end of a run, for scripts and CI to check with the exit
+// code. test ROMs tell pass from fail in different ways: drawing a result screen, leaving a value
+// in memory, or exiting with 00FD when they get to the end
+
+use crate::expr::{Expr, Value};
+use crate::headless::frame_hash;
+use crate::Chip8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expectation {
+    Exit,               // the ROM stopped at 00FD
+    Frame(u64),         // the screen has this frame_hash
+    True(Expr, String), // the expression, and how it was written, is nonzero
+}
+
+impl Expectation {
+    // why the VM doesn't meet it, if it doesn't
+    pub fn check(&self, chip8: &Chip8) -> Result<(), String> {
+        match self {
+            Self::Exit if chip8.exited() => Ok(()),
+            Self::Exit => Err(format!("didn't exit, stopped at {:03X}", chip8.pc())),
+            Self::Frame(hash) => match frame_hash(chip8.screen()) {
+                now if now == *hash => Ok(()),
+                now => Err(format!("frame is {:016x}, not {:016x}", now, hash)),
+            },
+            Self::True(e, text) => match e.eval(chip8) {
+                Ok(Value::Number(0)) => Err(format!("{} is 0", text)),
+                Ok(Value::Number(_)) => Ok(()),
+                Ok(Value::Bytes(_)) => Err(format!("{} is a memory range, not a number", text)),
+                Err(e) => Err(format!("{}: {}", text, e)),
+            },
+        }
+    }
+}
+
+// "exit", "frame=HASH" with the hash in hex, or a debugger expression like mem[0x3FF]==1
+impl std::str::FromStr for Expectation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "exit" {
+            return Ok(Self::Exit);
+        }
+        if let Some(hash) = s.strip_prefix("frame=") {
+            return u64::from_str_radix(hash, 16)
+                .map(Self::Frame)
+                .map_err(|_| format!("{} isn't a hex frame hash", hash));
+        }
+        Ok(Self::True(Expr::parse(s)?, s.to_string()))
+    }
+}
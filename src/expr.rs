@@ -0,0 +1,273 @@
+// expressions over the VM's state for the debugger, like v[3]*2, mem[i] or mem[0x3A0..0x3A8].
+// numbers are decimal or 0x hex and the arithmetic wraps at 32 bits, comparisons give 1 or 0.
+// a label stands for the memory it names, so score is mem[0x2A4] and sprites mem[0x300..0x308]
+
+use crate::labels::Labels;
+use crate::Chip8;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    And,
+    Xor,
+    Or,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+// symbols of the binary operators, loosest binding first, a level to a row
+const OPS: [&[(&str, Op)]; 7] = [
+    &[
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ],
+    &[("|", Op::Or)],
+    &[("^", Op::Xor)],
+    &[("&", Op::And)],
+    &[("<<", Op::Shl), (">>", Op::Shr)],
+    &[("+", Op::Add), ("-", Op::Sub)],
+    &[("*", Op::Mul), ("/", Op::Div), ("%", Op::Rem)],
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Number(u32),
+    I,
+    Pc,
+    Sp,
+    Dt,
+    St,
+    V(Box<Expr>),
+    Mem(Box<Expr>),
+    MemRange(Box<Expr>, Box<Expr>), // end is exclusive
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Number(u32),
+    Bytes(Vec<u8>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "0x{:X} ({})", n, n),
+            Self::Bytes(bytes) => {
+                let hex: Vec<String> = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                write!(f, "{}", hex.join(" "))
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+    labels: &'a Labels,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_space(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    // take sym if it's next
+    fn eat(&mut self, sym: &str) -> bool {
+        self.skip_space();
+        match self.rest.strip_prefix(sym) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, sym: &str) -> Result<(), String> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            Err(format!("expected {} at '{}'", sym, self.rest))
+        }
+    }
+
+    fn word(&mut self) -> &'a str {
+        self.skip_space();
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        word
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        if level == OPS.len() {
+            return self.atom();
+        }
+        let mut lhs = self.binary(level + 1)?;
+        'ops: loop {
+            // the tighter levels have already taken anything like "<<" that starts the same as
+            // a symbol here
+            for &(sym, op) in OPS[level] {
+                if self.eat(sym) {
+                    let rhs = self.binary(level + 1)?;
+                    lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+                    continue 'ops;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn index(&mut self) -> Result<Expr, String> {
+        self.expect("[")?;
+        let e = self.binary(0)?;
+        self.expect("]")?;
+        Ok(e)
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        if self.eat("(") {
+            let e = self.binary(0)?;
+            self.expect(")")?;
+            return Ok(e);
+        }
+        let word = self.word();
+        let lower = word.to_ascii_lowercase();
+        Ok(match lower.as_str() {
+            "" => return Err(format!("expected a value at '{}'", self.rest)),
+            "i" => Expr::I,
+            "pc" => Expr::Pc,
+            "sp" => Expr::Sp,
+            "dt" => Expr::Dt,
+            "st" => Expr::St,
+            "v" => Expr::V(Box::new(self.index()?)),
+            "mem" => {
+                self.expect("[")?;
+                let start = self.binary(0)?;
+                let e = if self.eat("..") {
+                    Expr::MemRange(Box::new(start), Box::new(self.binary(0)?))
+                } else {
+                    Expr::Mem(Box::new(start))
+                };
+                self.expect("]")?;
+                e
+            }
+            _ => if let Some(hex) = lower.strip_prefix("0x") {
+                u32::from_str_radix(hex, 16).map(Expr::Number)
+            } else if let Some(reg) = lower.strip_prefix('v').filter(|r| r.len() == 1) {
+                u32::from_str_radix(reg, 16).map(|r| Expr::V(Box::new(Expr::Number(r))))
+            } else if let Some(region) = self.labels.get(word) {
+                let addr = Box::new(Expr::Number(region.addr as u32));
+                return Ok(match region.len {
+                    1 => Expr::Mem(addr),
+                    len => Expr::MemRange(
+                        addr,
+                        Box::new(Expr::Number(region.addr as u32 + len as u32)),
+                    ),
+                });
+            } else {
+                lower.parse().map(Expr::Number)
+            }
+            .map_err(|_| format!("unknown value {}", word))?,
+        })
+    }
+}
+
+fn number(chip8: &Chip8, e: &Expr) -> Result<u32, String> {
+    match e.eval(chip8)? {
+        Value::Number(n) => Ok(n),
+        Value::Bytes(_) => Err("a memory range can't be used as a number".to_string()),
+    }
+}
+
+fn address(chip8: &Chip8, e: &Expr) -> Result<usize, String> {
+    let addr = number(chip8, e)? as usize;
+    if addr >= chip8.memory().len() {
+        return Err(format!("{:X} is past the end of memory", addr));
+    }
+    Ok(addr)
+}
+
+impl Expr {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        Self::parse_with(s, &Labels::default())
+    }
+
+    // with the labels as names for the memory they cover
+    pub fn parse_with(s: &str, labels: &Labels) -> Result<Self, String> {
+        let mut parser = Parser { rest: s, labels };
+        let e = parser.binary(0)?;
+        parser.skip_space();
+        if !parser.rest.is_empty() {
+            return Err(format!("unexpected '{}'", parser.rest));
+        }
+        Ok(e)
+    }
+
+    pub fn eval(&self, chip8: &Chip8) -> Result<Value, String> {
+        let n = match self {
+            Self::Number(n) => *n,
+            Self::I => chip8.i() as u32,
+            Self::Pc => chip8.pc() as u32,
+            Self::Sp => chip8.stack().len() as u32,
+            Self::Dt => chip8.delay_timer() as u32,
+            Self::St => chip8.sound_timer() as u32,
+            Self::V(x) => {
+                let x = number(chip8, x)?;
+                *chip8
+                    .v()
+                    .get(x as usize)
+                    .ok_or_else(|| format!("there's no V{:X}", x))? as u32
+            }
+            Self::Mem(addr) => chip8.memory()[address(chip8, addr)?] as u32,
+            Self::MemRange(start, end) => {
+                let start = address(chip8, start)?;
+                let end = number(chip8, end)? as usize;
+                if end < start || end > chip8.memory().len() {
+                    return Err(format!("bad range {:X}..{:X}", start, end));
+                }
+                return Ok(Value::Bytes(chip8.memory()[start..end].to_vec()));
+            }
+            Self::Binary(op, a, b) => {
+                let (a, b) = (number(chip8, a)?, number(chip8, b)?);
+                match op {
+                    Op::Mul => a.wrapping_mul(b),
+                    Op::Div => a.checked_div(b).ok_or("division by zero")?,
+                    Op::Rem => a.checked_rem(b).ok_or("division by zero")?,
+                    Op::Add => a.wrapping_add(b),
+                    Op::Sub => a.wrapping_sub(b),
+                    Op::Shl => a.checked_shl(b).unwrap_or(0),
+                    Op::Shr => a.checked_shr(b).unwrap_or(0),
+                    Op::And => a & b,
+                    Op::Xor => a ^ b,
+                    Op::Or => a | b,
+                    Op::Lt => (a < b) as u32,
+                    Op::Le => (a <= b) as u32,
+                    Op::Gt => (a > b) as u32,
+                    Op::Ge => (a >= b) as u32,
+                    Op::Eq => (a == b) as u32,
+                    Op::Ne => (a != b) as u32,
+                }
+            }
+        };
+        Ok(Value::Number(n))
+    }
+}
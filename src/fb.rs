@@ -0,0 +1,482 @@
+// the fb frontend: the screen straight onto a Linux framebuffer device and keys from evdev, for
+// kiosks and small boards with no X, Wayland or SDL. kernels that only have a DRM driver still
+// give it a /dev/fb0 through their fbdev emulation, which is on by default, so this doesn't talk
+// to DRM itself. CHIP8_FB picks another device than /dev/fb0
+//
+// the picture is scaled up as far as it goes, centered, in the framebuffer's own format as long
+// as that's 16, 24 or 32 bits a pixel. reading /dev/input needs the input group or root
+
+use crate::frontend::{Display, Hotkey, Keypad, Machine, Options, ScreenMode};
+use crate::i18n::{tr, trf};
+use crate::overlay::{self, Corner, Toasts};
+use crate::palette::{Palette, Rgb};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Geometry {
+    pub width: usize, // the part that's showing, in pixels
+    pub height: usize,
+    pub stride: usize, // bytes from one row to the next
+    pub bytes_per_pixel: usize,
+}
+
+impl Geometry {
+    // from the device's files in sysfs: virtual_size like "1920,1080", bits_per_pixel, stride in
+    // bytes, and modes, whose first line like "U:1920x1080p-0" is what's showing when the
+    // virtual size is bigger for panning
+    pub fn parse(
+        virtual_size: &str,
+        bits_per_pixel: &str,
+        stride: &str,
+        modes: &str,
+    ) -> Result<Self, String> {
+        let pair = |s: &str, sep| {
+            let (w, h) = s.trim().split_once(sep)?;
+            Some((w.parse().ok()?, h.parse().ok()?))
+        };
+        let (mut width, mut height): (usize, usize) =
+            pair(virtual_size, ',').ok_or_else(|| format!("bad size {}", virtual_size.trim()))?;
+        let mode = (modes.lines().next())
+            .and_then(|m| m.split_once(':'))
+            .and_then(|(_, m)| {
+                let end = (m.find(|c: char| !c.is_ascii_digit() && c != 'x')).unwrap_or(m.len());
+                pair(&m[..end], 'x')
+            });
+        if let Some((w, h)) = mode.filter(|&(w, h)| w <= width && h <= height) {
+            (width, height) = (w, h);
+        }
+        let bits: usize = (bits_per_pixel.trim().parse())
+            .map_err(|_| format!("bad bits per pixel {}", bits_per_pixel.trim()))?;
+        if ![16, 24, 32].contains(&bits) {
+            return Err(format!(
+                "{} bits a pixel, only 16, 24 and 32 are drawn",
+                bits
+            ));
+        }
+        let stride: usize =
+            (stride.trim().parse()).map_err(|_| format!("bad stride {}", stride.trim()))?;
+        if stride < width * bits / 8 {
+            return Err(format!(
+                "a stride of {} is too short for {} pixels",
+                stride, width
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            stride,
+            bytes_per_pixel: bits / 8,
+        })
+    }
+
+    // for a device like /dev/fb0, from /sys/class/graphics/fb0
+    pub fn read(device: &Path) -> Result<Self, String> {
+        let name = device.file_name().unwrap_or_default();
+        let sys = Path::new("/sys/class/graphics").join(name);
+        let read = |file| {
+            std::fs::read_to_string(sys.join(file))
+                .map_err(|e| format!("{}: {}", sys.join(file).display(), e))
+        };
+        let modes = read("modes").unwrap_or_default();
+        Self::parse(
+            &read("virtual_size")?,
+            &read("bits_per_pixel")?,
+            &read("stride")?,
+            &modes,
+        )
+    }
+
+    // a color as the framebuffer stores it: XRGB8888, RGB888 or RGB565, all little endian
+    pub fn pack(&self, [r, g, b]: Rgb) -> Vec<u8> {
+        match self.bytes_per_pixel {
+            2 => {
+                let v = (r as u16 >> 3) << 11 | (g as u16 >> 2) << 5 | b as u16 >> 3;
+                v.to_le_bytes().to_vec()
+            }
+            3 => vec![b, g, r],
+            _ => vec![b, g, r, 0],
+        }
+    }
+
+    // how much every screen pixel is blown up by, and where the top left of the picture goes,
+    // for a screen of width by height
+    pub fn layout(&self, width: usize, height: usize) -> (usize, usize, usize) {
+        let scale = (self.width / width).min(self.height / height).max(1);
+        let x = self.width.saturating_sub(width * scale) / 2;
+        let y = self.height.saturating_sub(height * scale) / 2;
+        (scale, x, y)
+    }
+
+    // fill a rectangle in frame, clipped to the part that's showing
+    fn fill(&self, frame: &mut [u8], (x, y, w, h): (usize, usize, usize, usize), color: &[u8]) {
+        let (right, bottom) = ((x + w).min(self.width), (y + h).min(self.height));
+        for row in y.min(bottom)..bottom {
+            let start = row * self.stride + x.min(right) * self.bytes_per_pixel;
+            let end = row * self.stride + right * self.bytes_per_pixel;
+            for pixel in frame[start..end].chunks_exact_mut(self.bytes_per_pixel) {
+                pixel.copy_from_slice(color);
+            }
+        }
+    }
+}
+
+// a line of overlay text over a dark box at x, y, with each glyph pixel scale across
+fn draw_line(
+    frame: &mut [u8],
+    geometry: &Geometry,
+    text: &str,
+    (x, y): (usize, usize),
+    scale: usize,
+    color: Rgb,
+) {
+    let box_width = (overlay::text_width(text) + 2) * scale;
+    let box_height = (overlay::GLYPH_HEIGHT + 2) * scale;
+    geometry.fill(
+        frame,
+        (x, y, box_width, box_height),
+        &geometry.pack([0, 0, 0]),
+    );
+    let color = geometry.pack(color);
+    overlay::draw_text(text, |px, py| {
+        let at = (x + (px + 1) * scale, y + (py + 1) * scale, scale, scale);
+        geometry.fill(frame, at, &color);
+    });
+}
+
+// what's drawn over the picture
+#[derive(Default)]
+pub struct Overlay<'a> {
+    pub watches: &'a [String],
+    pub toasts: &'a [&'a str],
+    pub keypad: Option<(u16, Corner)>, // the keys held, while it's shown
+}
+
+// the screens side by side, one byte a pixel, into a frame stride bytes a row for geometry, laid
+// out as mode says, with the watches in cyan at the picture's top left, the toasts in yellow at
+// its bottom left and the keypad in white in its corner
+pub fn render(
+    frame: &mut [u8],
+    geometry: &Geometry,
+    gfx: &[u8],
+    screens: usize,
+    palette: &Palette,
+    mode: ScreenMode,
+    over: &Overlay,
+) {
+    let width = 64 * screens;
+    let height = gfx.len() / width;
+    let (scale, left, top) = geometry.layout(width, height);
+    // where the picture goes: as much of the framebuffer as whole pixels fill, or all of it
+    let (left, top, across, down) = match mode {
+        ScreenMode::Stretch => (0, 0, geometry.width, geometry.height),
+        _ => (left, top, width * scale, height * scale),
+    };
+    let gap = mode.gap(scale);
+    let colors = [0, 1, 2, 3].map(|p| geometry.pack(palette.color(p)));
+    geometry.fill(frame, (0, 0, geometry.width, geometry.height), &colors[0]);
+    for (i, &p) in gfx.iter().enumerate().filter(|(_, p)| **p & 3 != 0) {
+        let (x, y) = (i % width, i / width);
+        let (x0, x1) = (left + x * across / width, left + (x + 1) * across / width);
+        let (y0, y1) = (top + y * down / height, top + (y + 1) * down / height);
+        let pixel = (x0 + gap / 2, y0 + gap / 2, x1 - x0 - gap, y1 - y0 - gap);
+        geometry.fill(frame, pixel, &colors[(p & 3) as usize]);
+    }
+    // a line between screens
+    for n in 1..screens {
+        let x = left + n * 64 * across / width;
+        let line = (x.saturating_sub(1), top, 2, down);
+        geometry.fill(frame, line, &geometry.pack([96, 96, 96]));
+    }
+    let text_scale = (scale / 3).max(1);
+    let line_height = (overlay::GLYPH_HEIGHT + 2) * text_scale;
+    for (n, text) in over.watches.iter().enumerate() {
+        let at = (left, top + n * line_height);
+        draw_line(frame, geometry, text, at, text_scale, [0, 255, 255]);
+    }
+    let bottom = top + down;
+    for (n, text) in over.toasts.iter().rev().enumerate() {
+        let at = (left, bottom.saturating_sub((n + 1) * line_height));
+        draw_line(frame, geometry, text, at, text_scale, [255, 255, 0]);
+    }
+    if let Some((held, corner)) = over.keypad {
+        let keypad_scale = (scale / 2).max(1);
+        let size = (
+            (overlay::KEYPAD_WIDTH + 2) * keypad_scale,
+            (overlay::KEYPAD_HEIGHT + 2) * keypad_scale,
+        );
+        let (x, y) = corner.place(size, (across, down), 0);
+        let (x, y) = (left + x, top + y);
+        geometry.fill(frame, (x, y, size.0, size.1), &geometry.pack([0, 0, 0]));
+        let white = geometry.pack([255, 255, 255]);
+        overlay::draw_keypad(held, |px, py| {
+            let at = (x + (px + 1) * keypad_scale, y + (py + 1) * keypad_scale);
+            geometry.fill(frame, (at.0, at.1, keypad_scale, keypad_scale), &white);
+        });
+    }
+}
+
+// struct input_event is a timeval, two longs, then a u16 type, a u16 code and an i32 value
+pub const EVENT_SIZE: usize = 2 * std::mem::size_of::<usize>() + 8;
+const EV_KEY: u16 = 1;
+
+// a key going down (true) or up in an input event, None for anything else, repeats included
+pub fn key_event(event: &[u8]) -> Option<(u16, bool)> {
+    let rest = event.get(EVENT_SIZE - 8..EVENT_SIZE)?;
+    let kind = u16::from_ne_bytes([rest[0], rest[1]]);
+    let code = u16::from_ne_bytes([rest[2], rest[3]]);
+    let value = i32::from_ne_bytes([rest[4], rest[5], rest[6], rest[7]]);
+    match (kind, value) {
+        (EV_KEY, 0) => Some((code, false)),
+        (EV_KEY, 1) => Some((code, true)),
+        _ => None,
+    }
+}
+
+// Linux key codes for CHIP-8 keys 0-F, laid out like frontend::QWERTY
+const KEYPAD: [u16; 16] = [
+    45, // X
+    2,  // 1
+    3,  // 2
+    4,  // 3
+    16, // Q
+    17, // W
+    18, // E
+    30, // A
+    31, // S
+    32, // D
+    44, // Z
+    46, // C
+    5,  // 4
+    19, // R
+    33, // F
+    47, // V
+];
+
+// for the second screen, laid out like frontend::QWERTY_RIGHT
+const KEYPAD_RIGHT: [u16; 16] = [
+    51, // ,
+    8,  // 7
+    9,  // 8
+    10, // 9
+    22, // U
+    23, // I
+    24, // O
+    36, // J
+    37, // K
+    38, // L
+    49, // N
+    52, // .
+    11, // 0
+    25, // P
+    39, // ;
+    53, // /
+];
+
+const KEY_ESC: u16 = 1;
+
+const HOTKEYS: [(u16, Hotkey); 13] = [
+    (50, Hotkey::Mute),        // M
+    (59, Hotkey::Macro(0)),    // F1
+    (60, Hotkey::Macro(1)),    // F2
+    (61, Hotkey::Macro(2)),    // F3
+    (62, Hotkey::Macro(3)),    // F4
+    (63, Hotkey::SaveState),   // F5
+    (64, Hotkey::PrevSlot),    // F6
+    (65, Hotkey::NextSlot),    // F7
+    (66, Hotkey::RecordMacro), // F8
+    (67, Hotkey::LoadState),   // F9
+    (68, Hotkey::Cheats),      // F10
+    (87, Hotkey::Pause),       // F11
+    (88, Hotkey::Keypad),      // F12
+];
+
+// the CHIP-8 key a key code is, keys on the right of the keyboard are 16 and up for the second
+// screen when there is one
+pub fn keypad(code: u16, screens: usize) -> Option<usize> {
+    match KEYPAD.iter().position(|&k| k == code) {
+        Some(i) => Some(i),
+        None if screens > 1 => KEYPAD_RIGHT.iter().position(|&k| k == code).map(|i| 16 + i),
+        None => None,
+    }
+}
+
+pub fn hotkey(code: u16) -> Option<Hotkey> {
+    if code == KEY_ESC {
+        return Some(Hotkey::Quit);
+    }
+    HOTKEYS.iter().find(|&&(k, _)| k == code).map(|&(_, h)| h)
+}
+
+pub struct Fb {
+    device: File,
+    geometry: Geometry,
+    frame: Vec<u8>,
+    keys: Receiver<(u16, bool)>, // from a thread for each input device
+    screens: usize,
+    palette: Palette,
+    toasts: Toasts,
+    shown_toasts: usize, // how many were on screen when it was last drawn
+    watches: Vec<String>,
+    held: Option<u16>, // while the keypad's shown
+    keypad_corner: Corner,
+    screen_mode: ScreenMode,
+    gfx: Vec<u8>,
+}
+
+impl Fb {
+    pub fn new(options: &Options) -> Result<Self, String> {
+        let path = std::env::var_os("CHIP8_FB").map_or(PathBuf::from("/dev/fb0"), PathBuf::from);
+        let device = (std::fs::OpenOptions::new().write(true).open(&path))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        let geometry = Geometry::read(&path)?;
+
+        let (send, keys) = mpsc::channel();
+        let mut inputs = 0;
+        let mut entries: Vec<PathBuf> = std::fs::read_dir("/dev/input")
+            .map(|dir| dir.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        entries.retain(|p| {
+            p.file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with("event"))
+        });
+        for mut input in entries.iter().filter_map(|p| File::open(p).ok()) {
+            let send = send.clone();
+            inputs += 1;
+            std::thread::spawn(move || {
+                let mut event = [0; EVENT_SIZE];
+                while input.read_exact(&mut event).is_ok() {
+                    if let Some(key) = key_event(&event) {
+                        if send.send(key).is_err() {
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+        if inputs == 0 {
+            eprintln!(
+                "{}",
+                tr("no input devices could be read, is this user in the input group?")
+            );
+        }
+
+        // hide the console's cursor so it doesn't blink over the picture
+        print!("\x1b[?25l");
+        let _ = std::io::stdout().flush();
+        let mut fb = Self {
+            device,
+            frame: vec![0; geometry.stride * geometry.height],
+            geometry,
+            keys,
+            screens: options.screens,
+            palette: options.palette,
+            toasts: Toasts::new(options.toast_time),
+            shown_toasts: 0,
+            watches: Vec::new(),
+            held: None,
+            keypad_corner: options.keypad_corner,
+            screen_mode: options.screen_mode,
+            gfx: vec![0; 64 * 32 * options.screens],
+        };
+        let gfx = std::mem::take(&mut fb.gfx);
+        fb.present(&gfx);
+        Ok(fb)
+    }
+}
+
+impl Drop for Fb {
+    fn drop(&mut self) {
+        // leave the console as it was, apart from whatever's still on the framebuffer
+        let black = self.geometry.pack([0, 0, 0]);
+        let all = (0, 0, self.geometry.width, self.geometry.height);
+        self.geometry.fill(&mut self.frame, all, &black);
+        let _ = self.device.write_all_at(&self.frame, 0);
+        print!("\x1b[?25h");
+        let _ = std::io::stdout().flush();
+    }
+}
+
+impl Display for Fb {
+    fn present(&mut self, gfx: &[u8]) {
+        let visible = self.toasts.visible(Instant::now());
+        let toasts: Vec<&str> = visible.iter().map(|(text, _)| *text).collect();
+        self.shown_toasts = toasts.len();
+        let corner = self.keypad_corner;
+        let over = Overlay {
+            watches: &self.watches,
+            toasts: &toasts,
+            keypad: self.held.map(|held| (held, corner)),
+        };
+        let frame = &mut self.frame;
+        let (geometry, mode) = (&self.geometry, self.screen_mode);
+        render(
+            frame,
+            geometry,
+            gfx,
+            self.screens,
+            &self.palette,
+            mode,
+            &over,
+        );
+        if let Err(e) = self.device.write_all_at(&self.frame, 0) {
+            eprintln!("{}", trf("couldn't draw: {}", &[&e]));
+        }
+        self.gfx.clear();
+        self.gfx.extend_from_slice(gfx);
+    }
+
+    fn notify(&mut self, text: &str) {
+        self.toasts.notify(text, Instant::now());
+        let gfx = std::mem::take(&mut self.gfx);
+        self.present(&gfx);
+    }
+
+    fn watches(&mut self, lines: &[String]) {
+        if self.watches != lines {
+            self.watches = lines.to_vec();
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+    }
+
+    fn keys(&mut self, held: Option<u16>) {
+        if self.held != held {
+            self.held = held;
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+    }
+
+    fn set_screen_mode(&mut self, mode: ScreenMode) {
+        if self.screen_mode != mode {
+            self.screen_mode = mode;
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+    }
+}
+
+impl Keypad for Fb {
+    fn poll(&mut self, machine: &mut dyn Machine) -> Vec<Hotkey> {
+        // toasts that have timed out come off even when the ROM isn't drawing
+        if self.toasts.visible(Instant::now()).len() != self.shown_toasts {
+            let gfx = std::mem::take(&mut self.gfx);
+            self.present(&gfx);
+        }
+        let mut hotkeys = Vec::new();
+        for (code, down) in self.keys.try_iter() {
+            match (keypad(code, self.screens), down) {
+                (Some(i), true) => machine.press_key(i),
+                (Some(i), false) => machine.release_key(i),
+                (None, true) => hotkeys.extend(hotkey(code)),
+                (None, false) => {}
+            }
+        }
+        hotkeys
+    }
+}
@@ -0,0 +1,459 @@
+pub use chip8_core::frontend::*;
+
+use crate::i18n::{tr, trf};
+use crate::overlay::{Toasts, KEYPAD_ROWS};
+use crate::palette::Palette;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+// keys on a QWERTY keyboard for CHIP-8 keys 0-F, laid out like the COSMAC VIP's hex keypad
+//   1 2 3 C    1 2 3 4
+//   4 5 6 D    Q W E R
+//   7 8 9 E    A S D F
+//   A 0 B F    Z X C V
+pub const QWERTY: [char; 16] = [
+    'x', '1', '2', '3', 'q', 'w', 'e', 'a', 's', 'd', 'z', 'c', '4', 'r', 'f', 'v',
+];
+
+// the same for a second player, on the right of the keyboard, with N for A as M mutes
+//   7 8 9 0
+//   U I O P
+//   J K L ;
+//   N , . /
+pub const QWERTY_RIGHT: [char; 16] = [
+    ',', '7', '8', '9', 'u', 'i', 'o', 'j', 'k', 'l', 'n', '.', '0', 'p', ';', '/',
+];
+
+// lines showing which keyboard keys do anything, from the keys a ROM was found to test (a bit
+// each) and whether it reads ones that couldn't be worked out. keys that do nothing are dots
+pub fn keypad_hint(keys: u16, any_key: bool) -> Vec<String> {
+    if keys == 0 {
+        return if any_key {
+            vec![tr("any key").to_string()]
+        } else {
+            vec![]
+        };
+    }
+    let mut lines: Vec<String> = KEYPAD_ROWS
+        .iter()
+        .map(|row| {
+            let keys = row.iter().map(|&k| match keys & 1 << k {
+                0 => '.',
+                _ => QWERTY[k].to_ascii_uppercase(),
+            });
+            keys.map(String::from).collect::<Vec<_>>().join(" ")
+        })
+        .collect();
+    if any_key {
+        lines.push(tr("and maybe others").to_string());
+    }
+    lines
+}
+
+// no output or input at all, for benchmarking and for --stats and traces on their own. stops
+// when the ROM halts
+pub struct Null;
+
+impl Display for Null {
+    fn present(&mut self, _: &[u8]) {}
+
+    fn exit_on_halt(&self) -> bool {
+        true
+    }
+}
+
+impl Keypad for Null {
+    fn poll(&mut self, _: &mut dyn Machine) -> Vec<Hotkey> {
+        Vec::new()
+    }
+}
+
+pub fn render_text(gfx: &[u8]) -> String {
+    let mut out = String::new();
+    for row in gfx.chunks(64) {
+        out.extend(row.iter().map(|&p| if p == 0 { '.' } else { '#' }));
+        out.push('\n');
+    }
+    out
+}
+
+// the screen as a plain PBM image, which most image tools can read or convert
+pub fn render_pbm(gfx: &[u8]) -> String {
+    let mut out = String::from("P1\n64 32\n");
+    for row in gfx.chunks(64) {
+        let row: Vec<&str> = row
+            .iter()
+            .map(|&p| if p == 0 { "0" } else { "1" })
+            .collect();
+        out.push_str(&row.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+// a palette's name and what it's for over a block of each of its colors, in a truecolor
+// terminal, and the hex of each under that, for chip8 palettes
+pub fn swatches(palette: &Palette) -> String {
+    let (mut blocks, mut codes) = (String::new(), String::new());
+    for [r, g, b] in palette.colors {
+        blocks.push_str(&format!("  \x1b[48;2;{};{};{}m{:6}\x1b[0m", r, g, b, ""));
+        codes.push_str(&format!("  {:02X}{:02X}{:02X}", r, g, b));
+    }
+    format!(
+        "{}: {}\n{}\n{}\n",
+        palette.name,
+        tr(palette.about),
+        blocks,
+        codes
+    )
+}
+
+// no window, prints the screen as text when the ROM halts or the emulator stops, for scripts
+// and CI
+pub struct Headless;
+
+impl Display for Headless {
+    fn present(&mut self, _: &[u8]) {}
+
+    fn finish(&mut self, gfx: &[u8]) {
+        print!("{}", render_text(gfx));
+    }
+
+    fn exit_on_halt(&self) -> bool {
+        true
+    }
+}
+
+impl Keypad for Headless {
+    fn poll(&mut self, _: &mut dyn Machine) -> Vec<Hotkey> {
+        Vec::new()
+    }
+}
+
+// for the binary's own tests: input comes from stdin, read to the end up front, a line for each
+// poll, e.g. "press 5", "release 5", "focus off" or "pause", blank for nothing. everything
+// it's shown is printed as lines on stdout. it only stops on a "quit" line or a signal
+#[cfg(feature = "testing")]
+pub struct Script {
+    steps: std::collections::VecDeque<String>,
+    buzzing: bool,
+    rumbling: bool,
+    flashing: bool,
+    watches: Vec<String>,
+}
+
+#[cfg(feature = "testing")]
+impl Script {
+    pub fn from_stdin() -> Self {
+        let mut script = String::new();
+        let _ = std::io::stdin().read_to_string(&mut script);
+        Self {
+            steps: script.lines().map(String::from).collect(),
+            buzzing: false,
+            rumbling: false,
+            flashing: false,
+            watches: Vec::new(),
+        }
+    }
+
+    fn say(&self, line: &str) {
+        println!("{}", line);
+        let _ = std::io::stdout().flush();
+    }
+}
+
+// say what's turned on or off, if anything has
+#[cfg(feature = "testing")]
+fn switch(what: &str, was: &mut bool, on: bool) {
+    if on != *was {
+        println!("{} {}", what, if on { "on" } else { "off" });
+        let _ = std::io::stdout().flush();
+        *was = on;
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Display for Script {
+    fn present(&mut self, gfx: &[u8]) {
+        self.say(&format!(
+            "present {:016x}",
+            crate::headless::frame_hash(gfx)
+        ));
+    }
+
+    fn buzzer(&mut self, on: bool) {
+        switch("buzzer", &mut self.buzzing, on);
+    }
+
+    fn rumble(&mut self, on: bool) {
+        switch("rumble", &mut self.rumbling, on);
+    }
+
+    fn flash(&mut self, on: bool) {
+        switch("flash", &mut self.flashing, on);
+    }
+
+    fn notify(&mut self, text: &str) {
+        self.say(&format!("notify {}", text));
+    }
+
+    fn watches(&mut self, lines: &[String]) {
+        if lines != self.watches {
+            self.say(&format!("watches {}", lines.join(" | ")));
+            self.watches = lines.to_vec();
+        }
+    }
+
+    fn finish(&mut self, gfx: &[u8]) {
+        print!("{}", render_text(gfx));
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Keypad for Script {
+    fn poll(&mut self, machine: &mut dyn Machine) -> Vec<Hotkey> {
+        let step = self.steps.pop_front().unwrap_or_default();
+        let (verb, arg) = step.split_once(' ').unwrap_or((&step, ""));
+        let key = usize::from_str_radix(arg, 16).ok().filter(|&k| k < 16);
+        let hotkey = match (verb, key) {
+            ("press", Some(key)) => {
+                machine.press_key(key);
+                None
+            }
+            ("release", Some(key)) => {
+                machine.release_key(key);
+                None
+            }
+            ("focus", _) => Some(Hotkey::Focus(arg == "on")),
+            ("quit", _) => Some(Hotkey::Quit),
+            ("mute", _) => Some(Hotkey::Mute),
+            ("save", _) => Some(Hotkey::SaveState),
+            ("load", _) => Some(Hotkey::LoadState),
+            ("pause", _) => Some(Hotkey::Pause),
+            ("cheats", _) => Some(Hotkey::Cheats),
+            _ => None,
+        };
+        hotkey.into_iter().collect()
+    }
+}
+
+// draws in the terminal with half-block characters, two pixels to a character cell, with
+// notifications on the line below. terminals only report key presses, so every key read is a
+// quick tap
+pub struct Tui {
+    saved_tty: Option<String>, // stty settings to put back when done
+    stdin: std::io::Stdin,
+    buzzing: bool,
+    flashing: bool,
+    toasts: Toasts,
+    toast_line: String, // what the notification line says now
+    announce: bool,
+    watches: Vec<String>,     // the lines last announced
+    palette: Option<Palette>, // None for classic, which keeps the terminal's own colors
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+impl Tui {
+    pub fn new(options: &Options) -> Self {
+        // read keys as they're typed without echoing them, and without blocking
+        let saved_tty = stty(&["-g"]);
+        if saved_tty.is_some() {
+            stty(&["-icanon", "-echo", "-isig", "min", "0", "time", "0"]);
+        }
+        if !options.announce {
+            print!("\x1b[?25l\x1b[2J"); // hide the cursor, clear the screen
+        }
+        Self {
+            saved_tty,
+            stdin: std::io::stdin(),
+            buzzing: false,
+            flashing: false,
+            toasts: Toasts::new(options.toast_time),
+            toast_line: String::new(),
+            announce: options.announce,
+            watches: Vec::new(),
+            palette: Some(options.palette).filter(|p| *p != Palette::default()),
+        }
+    }
+
+    fn say(&self, line: &str) {
+        println!("{}", line);
+        let _ = std::io::stdout().flush();
+    }
+
+    // the latest notification goes below the screen, terminals can't fade it so it just goes
+    fn draw_toasts(&mut self) {
+        if self.announce {
+            return; // they're said as they come instead
+        }
+        let line = self
+            .toasts
+            .visible(Instant::now())
+            .last()
+            .map_or(String::new(), |(text, _)| text.to_string());
+        if line != self.toast_line {
+            print!("\x1b[17;1H\x1b[2K{}", line);
+            let _ = std::io::stdout().flush();
+            self.toast_line = line;
+        }
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        if !self.announce {
+            println!("\x1b[?25h");
+        }
+        let _ = std::io::stdout().flush();
+        if let Some(saved) = &self.saved_tty {
+            stty(&[saved]);
+        }
+    }
+}
+
+impl Display for Tui {
+    fn present(&mut self, gfx: &[u8]) {
+        if self.announce {
+            return;
+        }
+        let mut out = String::from("\x1b[H");
+        for rows in gfx.chunks(128) {
+            let (top, bottom) = rows.split_at(64);
+            match &self.palette {
+                // the top pixel in the foreground color and the bottom one in the background
+                Some(palette) => {
+                    for (&up, &down) in top.iter().zip(bottom) {
+                        let ([r, g, b], [br, bg, bb]) = (palette.color(up), palette.color(down));
+                        out.push_str(&format!(
+                            "\x1b[38;2;{};{};{};48;2;{};{};{}m▀",
+                            r, g, b, br, bg, bb
+                        ));
+                    }
+                    out.push_str("\x1b[0m");
+                }
+                None => out.extend(top.iter().zip(bottom).map(|(&t, &b)| match (t, b) {
+                    (0, 0) => ' ',
+                    (_, 0) => '▀',
+                    (0, _) => '▄',
+                    _ => '█',
+                })),
+            }
+            out.push_str("\r\n");
+        }
+        print!("{}", out);
+        let _ = std::io::stdout().flush();
+    }
+
+    fn buzzer(&mut self, on: bool) {
+        if on && !self.buzzing {
+            print!("\x07");
+        }
+        self.buzzing = on;
+    }
+
+    // a line of notes under the notification line
+    fn flash(&mut self, on: bool) {
+        if on == self.flashing {
+            return;
+        }
+        self.flashing = on;
+        if self.announce {
+            self.say(tr(if on { "sound on" } else { "sound off" }));
+        } else {
+            print!("\x1b[18;1H\x1b[2K{}", if on { "♪ ♪ ♪" } else { "" });
+            let _ = std::io::stdout().flush();
+        }
+    }
+
+    fn notify(&mut self, text: &str) {
+        if self.announce {
+            self.say(text);
+            return;
+        }
+        self.toasts.notify(text, Instant::now());
+        self.draw_toasts();
+    }
+
+    // say any HUD or watch line that's changed
+    fn watches(&mut self, lines: &[String]) {
+        if !self.announce {
+            return;
+        }
+        for (n, line) in lines.iter().enumerate() {
+            if self.watches.get(n) != Some(line) {
+                self.say(line);
+            }
+        }
+        self.watches = lines.to_vec();
+    }
+}
+
+// escape sequences for the function keys used as hotkeys
+const TUI_HOTKEYS: [(&[u8], Hotkey); 11] = [
+    (b"\x1bOP", Hotkey::Macro(0)),      // F1
+    (b"\x1bOQ", Hotkey::Macro(1)),      // F2
+    (b"\x1bOR", Hotkey::Macro(2)),      // F3
+    (b"\x1bOS", Hotkey::Macro(3)),      // F4
+    (b"\x1b[15~", Hotkey::SaveState),   // F5
+    (b"\x1b[17~", Hotkey::PrevSlot),    // F6
+    (b"\x1b[18~", Hotkey::NextSlot),    // F7
+    (b"\x1b[19~", Hotkey::RecordMacro), // F8
+    (b"\x1b[20~", Hotkey::LoadState),   // F9
+    (b"\x1b[21~", Hotkey::Cheats),      // F10
+    (b"\x1b[23~", Hotkey::Pause),       // F11
+];
+
+impl Keypad for Tui {
+    fn poll(&mut self, machine: &mut dyn Machine) -> Vec<Hotkey> {
+        self.draw_toasts();
+        let mut buf = [0; 64];
+        let n = self.stdin.lock().read(&mut buf).unwrap_or(0);
+        let mut input = &buf[..n];
+        let mut hotkeys = Vec::new();
+        while let Some(&b) = input.first() {
+            if let Some((seq, hotkey)) = TUI_HOTKEYS.iter().find(|(seq, _)| input.starts_with(seq))
+            {
+                hotkeys.push(*hotkey);
+                input = &input[seq.len()..];
+                continue;
+            }
+            input = &input[1..];
+            match b {
+                0x03 => hotkeys.push(Hotkey::Quit), // ctrl-c
+                // escape on its own, other sequences are skipped
+                0x1b if input.first() == Some(&b'[') => {
+                    let end = input
+                        .iter()
+                        .position(|c| c.is_ascii_alphabetic() || *c == b'~');
+                    input = &input[end.map_or(input.len(), |e| e + 1)..];
+                }
+                0x1b => hotkeys.push(Hotkey::Quit),
+                b'm' | b'M' => hotkeys.push(Hotkey::Mute),
+                _ => {
+                    let c = (b as char).to_ascii_lowercase();
+                    if let Some(key) = QWERTY.iter().position(|&k| k == c) {
+                        machine.press_key(key);
+                        machine.release_key(key);
+                        if self.announce {
+                            self.say(&trf("key {}", &[&format!("{:X}", key)]));
+                        }
+                    }
+                }
+            }
+        }
+        hotkeys
+    }
+}
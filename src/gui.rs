@@ -0,0 +1,325 @@
+// the debugger in a window of its own, drawn with egui for chip8 gui. the game, registers,
+// disassembly following the PC, a memory editor, breakpoints and the timeline are panels that
+// can be docked anywhere in the window or torn off into windows of their own
+
+pub use eframe::egui; // for the Context Gui::ui takes
+
+use crate::frontend::QWERTY;
+use crate::panels::{Line, Session, HEX_ROW_LEN};
+use eframe::egui::{Color32, ColorImage, RichText, TextureHandle, TextureOptions};
+use egui_dock::{DockArea, DockState, NodeIndex, Style, TabViewer};
+
+const DISASSEMBLY_LEN: usize = 32; // instructions shown around the PC
+const MEMORY_LEN: usize = 256; // bytes the memory panel shows from where it's at
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Panel {
+    Game,
+    Registers,
+    Disassembly,
+    Memory,
+    Breakpoints,
+    Timeline,
+}
+
+pub const PANELS: [Panel; 6] = [
+    Panel::Game,
+    Panel::Registers,
+    Panel::Disassembly,
+    Panel::Memory,
+    Panel::Breakpoints,
+    Panel::Timeline,
+];
+
+impl Panel {
+    pub fn title(self) -> &'static str {
+        match self {
+            Panel::Game => "Game",
+            Panel::Registers => "Registers",
+            Panel::Disassembly => "Disassembly",
+            Panel::Memory => "Memory",
+            Panel::Breakpoints => "Breakpoints",
+            Panel::Timeline => "Timeline",
+        }
+    }
+}
+
+// the game top left with memory under it, registers and breakpoints top right and the code
+// under them
+pub fn layout() -> DockState<Panel> {
+    let mut dock = DockState::new(vec![Panel::Game]);
+    let tree = dock.main_surface_mut();
+    let [game, side] = tree.split_right(
+        NodeIndex::root(),
+        0.6,
+        vec![Panel::Registers, Panel::Breakpoints],
+    );
+    tree.split_below(side, 0.35, vec![Panel::Disassembly, Panel::Timeline]);
+    tree.split_below(game, 0.6, vec![Panel::Memory]);
+    dock
+}
+
+pub struct Gui {
+    pub session: Session,
+    pub dock: DockState<Panel>,
+    command: String,                // being typed into the toolbar
+    breakpoint: String,             // address being typed into the breakpoints panel
+    editing: Option<(u16, String)>, // byte being changed in the memory panel, and to what
+    screen: Option<TextureHandle>,
+}
+
+impl Gui {
+    pub fn new(session: Session) -> Self {
+        Self {
+            session,
+            dock: layout(),
+            command: String::new(),
+            breakpoint: String::new(),
+            editing: None,
+            screen: None,
+        }
+    }
+
+    // everything a frame does but paint, so it can run without a window
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        if !ctx.wants_keyboard_input() {
+            let keys = ctx.input(|input| {
+                (0..16).fold(0, |keys, k| {
+                    let name = QWERTY[k].to_ascii_uppercase().to_string();
+                    match egui::Key::from_name(&name) {
+                        Some(key) if input.key_down(key) => keys | 1 << k,
+                        _ => keys,
+                    }
+                })
+            });
+            self.session.chip8.set_keys(keys);
+        }
+        self.session.frame();
+
+        let screen = screen_image(self.session.chip8.screen());
+        match &mut self.screen {
+            Some(texture) => texture.set(screen, TextureOptions::NEAREST),
+            None => self.screen = Some(ctx.load_texture("screen", screen, TextureOptions::NEAREST)),
+        }
+
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| self.toolbar(ui));
+        let mut viewer = Viewer {
+            session: &mut self.session,
+            breakpoint: &mut self.breakpoint,
+            editing: &mut self.editing,
+            screen: self.screen.as_ref(),
+        };
+        DockArea::new(&mut self.dock)
+            .style(Style::from_egui(ctx.style().as_ref()))
+            .show(ctx, &mut viewer);
+
+        if !self.session.paused() {
+            ctx.request_repaint();
+        }
+    }
+
+    fn toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let session = &mut self.session;
+            if session.paused() {
+                if ui.button("Run").clicked() {
+                    session.command("c");
+                }
+            } else if ui.button("Pause").clicked() {
+                session.debugger.pause();
+                session.output = session.debugger.location(&session.chip8);
+            }
+            if ui.button("Step").clicked() {
+                session.command("s");
+            }
+            if ui.button("Frame").clicked() {
+                session.command("f");
+            }
+            let dock = &mut self.dock;
+            ui.menu_button("Panels", |ui| {
+                for panel in PANELS {
+                    let open = dock.find_tab(&panel).is_some();
+                    if ui
+                        .add_enabled(!open, egui::Button::new(panel.title()))
+                        .clicked()
+                    {
+                        dock.push_to_focused_leaf(panel);
+                        ui.close_menu();
+                    }
+                }
+            });
+            let typed = ui.add(
+                egui::TextEdit::singleline(&mut self.command)
+                    .hint_text("debugger command")
+                    .font(egui::TextStyle::Monospace),
+            );
+            if typed.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                session.command(&std::mem::take(&mut self.command));
+                typed.request_focus();
+            }
+        });
+        if !self.session.output.is_empty() {
+            ui.monospace(self.session.output.trim_end());
+        }
+    }
+}
+
+impl eframe::App for Gui {
+    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        self.ui(ctx);
+    }
+}
+
+fn screen_image(gfx: &[u8]) -> ColorImage {
+    let pixels = gfx
+        .iter()
+        .map(|&p| {
+            if p == 0 {
+                Color32::BLACK
+            } else {
+                Color32::WHITE
+            }
+        })
+        .collect();
+    ColorImage {
+        size: [64, gfx.len() / 64],
+        pixels,
+    }
+}
+
+struct Viewer<'a> {
+    session: &'a mut Session,
+    breakpoint: &'a mut String,
+    editing: &'a mut Option<(u16, String)>,
+    screen: Option<&'a TextureHandle>,
+}
+
+// a disassembly line, clicked to set or clear a breakpoint on it
+fn code_line(ui: &mut egui::Ui, session: &mut Session, line: &Line) {
+    let mut text = RichText::new(line.to_string()).monospace();
+    if line.breakpoint {
+        text = text.color(Color32::LIGHT_RED);
+    }
+    if ui.selectable_label(line.current, text).clicked() {
+        session.toggle_breakpoint(line.addr);
+    }
+}
+
+impl TabViewer for Viewer<'_> {
+    type Tab = Panel;
+
+    fn title(&mut self, panel: &mut Panel) -> egui::WidgetText {
+        panel.title().into()
+    }
+
+    // the game stays, the others can be closed and put back from the toolbar's panels menu
+    fn closeable(&mut self, panel: &mut Panel) -> bool {
+        *panel != Panel::Game
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, panel: &mut Panel) {
+        let (session, breakpoint, editing) = (
+            &mut *self.session,
+            &mut *self.breakpoint,
+            &mut *self.editing,
+        );
+        match panel {
+            Panel::Game => {
+                if let Some(texture) = self.screen {
+                    let size = ui.available_size();
+                    let scale = (size.x / 64.0).min(size.y / 32.0).max(1.0).floor();
+                    let size = egui::vec2(64.0 * scale, 32.0 * scale);
+                    ui.add(egui::Image::new(texture).fit_to_exact_size(size));
+                }
+            }
+            Panel::Registers => {
+                ui.monospace(session.registers());
+            }
+            Panel::Disassembly => {
+                for line in session.disassembly(DISASSEMBLY_LEN) {
+                    code_line(ui, session, &line);
+                }
+            }
+            Panel::Memory => {
+                let mut addr = format!("{:03X}", session.memory_addr);
+                ui.horizontal(|ui| {
+                    ui.label("address");
+                    if ui.text_edit_singleline(&mut addr).changed() {
+                        if let Ok(addr) = u16::from_str_radix(addr.trim(), 16) {
+                            let last = session.chip8.memory().len() - 1;
+                            session.memory_addr = addr.min(last as u16) / 16 * 16;
+                        }
+                    }
+                });
+                egui::Grid::new("memory")
+                    .spacing([4.0, 2.0])
+                    .show(ui, |ui| {
+                        for row in session.memory(MEMORY_LEN) {
+                            ui.monospace(format!("{:03X}:", row.addr));
+                            for (n, byte) in row.bytes.iter().enumerate() {
+                                let at = row.addr + n as u16;
+                                let text = RichText::new(format!("{:02X}", byte)).monospace();
+                                let selected = matches!(editing, Some((a, _)) if *a == at);
+                                if ui.selectable_label(selected, text).clicked() {
+                                    *editing = Some((at, format!("{:02X}", byte)));
+                                }
+                            }
+                            for _ in row.bytes.len()..HEX_ROW_LEN {
+                                ui.label("");
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some((at, value)) = editing {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format!("{:03X} =", at));
+                        let typed = ui.text_edit_singleline(value);
+                        if typed.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            session.write(*at, value);
+                        }
+                    });
+                }
+            }
+            Panel::Breakpoints => {
+                ui.horizontal(|ui| {
+                    let typed = ui.add(
+                        egui::TextEdit::singleline(breakpoint)
+                            .hint_text("address")
+                            .desired_width(60.0),
+                    );
+                    let entered =
+                        typed.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if entered || ui.button("Add").clicked() {
+                        session.command(&format!("b {}", std::mem::take(breakpoint)));
+                    }
+                });
+                for line in session.breakpoints() {
+                    code_line(ui, session, &line);
+                }
+            }
+            Panel::Timeline => {
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in session.timeline() {
+                            ui.monospace(line.to_string());
+                        }
+                    });
+            }
+        }
+    }
+}
+
+pub fn run(session: Session, title: &str) -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title(title)
+            .with_inner_size([1100.0, 700.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        title,
+        options,
+        Box::new(|_| Ok(Box::new(Gui::new(session)))),
+    )
+}
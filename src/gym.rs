@@ -0,0 +1,100 @@
+use crate::chip8::{Error, CYCLES_PER_TICK};
+use crate::headless;
+use crate::quirks::Quirks;
+use crate::Chip8;
+
+// reinforcement-learning style environment around a ROM. a step holds the keys in the action
+// for frame_skip frames (60Hz ticks) and hands back the screen after them
+pub struct Env {
+    rom: Vec<u8>,
+    quirks: Quirks,
+    seed: u64,
+    frame_skip: usize,
+    max_frames: Option<usize>, // episodes end after this many frames, most games never end on their own
+    chip8: Chip8,
+    frames: usize,
+    error: Option<Error>,
+}
+
+impl Env {
+    pub fn new(rom: &[u8]) -> Self {
+        Self {
+            rom: rom.to_vec(),
+            quirks: Quirks::default(),
+            seed: headless::SEED,
+            frame_skip: 4,
+            max_frames: None,
+            chip8: headless::machine(rom, Quirks::default()),
+            frames: 0,
+            error: None,
+        }
+    }
+
+    // these take effect on the next reset
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn set_frame_skip(&mut self, frames: usize) {
+        self.frame_skip = frames.max(1);
+    }
+
+    pub fn set_max_frames(&mut self, frames: Option<usize>) {
+        self.max_frames = frames;
+    }
+
+    // start a new episode, returning the first screen
+    pub fn reset(&mut self) -> &[u8] {
+        self.chip8 = headless::machine(&self.rom, self.quirks);
+        self.chip8.seed_rng(self.seed);
+        self.frames = 0;
+        self.error = None;
+        self.chip8.gfx()
+    }
+
+    // keys has bit n set to hold key n. returns the screen and whether the episode is over,
+    // stepping again after that just returns the same screen
+    pub fn step(&mut self, keys: u16) -> (&[u8], bool) {
+        if !self.done() {
+            for key in 0..16 {
+                self.chip8.set_key(key, keys & (1 << key) != 0);
+            }
+            'frames: for _ in 0..self.frame_skip {
+                for _ in 0..CYCLES_PER_TICK {
+                    if let Err(e) = self.chip8.emulate_cycle() {
+                        self.error = Some(e);
+                        break 'frames;
+                    }
+                }
+                self.frames += 1;
+                if self.done() {
+                    break;
+                }
+            }
+        }
+        (self.chip8.gfx(), self.done())
+    }
+
+    pub fn done(&self) -> bool {
+        self.error.is_some() || self.max_frames.is_some_and(|max| self.frames >= max)
+    }
+
+    // frames run since the last reset
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    // what ended the episode, if the VM stopped on its own
+    pub fn error(&self) -> Option<Error> {
+        self.error
+    }
+
+    // for reading scores and the like out of memory
+    pub fn chip8(&self) -> &Chip8 {
+        &self.chip8
+    }
+}
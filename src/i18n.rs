@@ -0,0 +1,654 @@
+// translations of the text the emulator shows people: command line help, errors and
+// notifications. text is looked up by its English wording, so anything without a translation
+// comes out in English. placeholders are {} and are filled in order
+
+use crate::chip8::{Error, StateError};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type Table = &'static [(&'static str, &'static str)];
+
+// language code and translations, the first is the default
+const LANGS: [(&str, Table); 2] = [("en", &[]), ("de", DE)];
+pub const NAMES: [&str; 2] = [LANGS[0].0, LANGS[1].0];
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+// pick the language by code, e.g. "de", false if there's no such language
+pub fn set_lang(name: &str) -> bool {
+    match LANGS.iter().position(|(n, _)| *n == name) {
+        Some(i) => {
+            CURRENT.store(i, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+// the language asked for by the environment the way gettext looks for it, e.g. "de" from
+// LANG=de_DE.UTF-8
+pub fn lang_from_env() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| {
+            let end = value.find(['_', '.', '@']).unwrap_or(value.len());
+            value[..end].to_ascii_lowercase()
+        })
+}
+
+pub fn tr(text: &str) -> &str {
+    let (_, table) = LANGS[CURRENT.load(Ordering::Relaxed)];
+    table
+        .iter()
+        .find(|(en, _)| *en == text)
+        .map_or(text, |(_, translated)| translated)
+}
+
+// tr with the {}s filled in from args
+pub fn trf(text: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(text).split("{}");
+    out.push_str(parts.next().unwrap_or(""));
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+pub fn error(e: &Error) -> String {
+    match *e {
+        Error::UnknownOpcode { pc, opcode } => trf(
+            "unhandled opcode {} at {}",
+            &[&format!("{:04X}", opcode), &format!("{:03X}", pc)],
+        ),
+        Error::StackOverflow { pc } => {
+            trf("stack overflow calling from {}", &[&format!("{:03X}", pc)])
+        }
+        Error::StackUnderflow { pc } => {
+            trf("return with empty stack at {}", &[&format!("{:03X}", pc)])
+        }
+        Error::MemoryOutOfBounds { pc, addr } => trf(
+            "access past end of memory ({}) at {}",
+            &[&format!("{:X}", addr), &format!("{:03X}", pc)],
+        ),
+        Error::PcOutOfBounds { pc } => trf(
+            "program counter out of memory at {}",
+            &[&format!("{:X}", pc)],
+        ),
+        Error::KeyWaitTimeout { pc } => {
+            trf("gave up waiting for a key at {}", &[&format!("{:03X}", pc)])
+        }
+        _ => e.to_string(), // one the core has added since, untranslated
+    }
+}
+
+pub fn state_error(e: &StateError) -> String {
+    match *e {
+        StateError::NotAState => tr("not a CHIP-8 save state").to_string(),
+        StateError::UnsupportedVersion(v) => trf("unsupported save state version {}", &[&v]),
+        StateError::Truncated => tr("save state is truncated").to_string(),
+        StateError::Corrupt => tr("save state is corrupt").to_string(),
+        _ => e.to_string(),
+    }
+}
+
+// any error, translated if it's one of ours
+pub fn any_error(e: &(dyn std::error::Error + 'static)) -> String {
+    if let Some(e) = e.downcast_ref::<Error>() {
+        error(e)
+    } else if let Some(e) = e.downcast_ref::<StateError>() {
+        state_error(e)
+    } else {
+        tr(&e.to_string()).to_string()
+    }
+}
+
+const DE: Table = &[
+    // command line help
+    ("CHIP-8 emulator", "CHIP-8-Emulator"),
+    (
+        "Run a ROM (the default when no command is given)",
+        "Ein ROM ausführen (Standard, wenn kein Befehl angegeben ist)",
+    ),
+    (
+        "Print a listing of a ROM with reachable code decoded and everything else shown as data",
+        "Ein Listing eines ROMs ausgeben, mit erreichbarem Code dekodiert und allem anderen als Daten",
+    ),
+    (
+        "Export the control-flow graph of a ROM in Graphviz DOT format",
+        "Den Kontrollflussgraphen eines ROMs im Graphviz-DOT-Format exportieren",
+    ),
+    (
+        "Export the subroutine call graph instead of basic blocks",
+        "Den Aufrufgraphen der Unterprogramme statt der Grundblöcke exportieren",
+    ),
+    ("Write to this file instead of stdout", "In diese Datei statt auf stdout schreiben"),
+    (
+        "Strip padding from a ROM, checking that it still runs the same",
+        "Padding aus einem ROM entfernen und prüfen, dass es noch genauso läuft",
+    ),
+    (
+        "Where to write the trimmed ROM (defaults to <ROM>.trim.ch8)",
+        "Wohin das gekürzte ROM geschrieben wird (Standard: <ROM>.trim.ch8)",
+    ),
+    (
+        "Also close up unreferenced runs of zeroes between code and data, patching addresses",
+        "Auch unreferenzierte Nullfolgen zwischen Code und Daten schließen und Adressen anpassen",
+    ),
+    (
+        "Number of cycles to run both ROMs for when comparing them",
+        "Anzahl der Zyklen, die beide ROMs zum Vergleich laufen",
+    ),
+    ("Quirks to run both ROMs with", "Quirks, mit denen beide ROMs laufen"),
+    (
+        "Run every ROM in a directory headless, several at once, and print a CSV of what each needs and does: the quirks it runs under, opcodes used, draws a second and any crash",
+        "Jedes ROM in einem Verzeichnis ohne Fenster ausführen, mehrere gleichzeitig, und als CSV ausgeben, was jedes braucht und tut: die Quirks, unter denen es läuft, benutzte Opcodes, Zeichnungen pro Sekunde und Abstürze",
+    ),
+    ("60Hz frames to run each ROM for", "Wie viele 60-Hz-Frames jedes ROM läuft"),
+    (
+        "ROMs to run at once, defaults to one for each CPU",
+        "Wie viele ROMs gleichzeitig laufen, standardmäßig eines pro CPU",
+    ),
+    (
+        "Guess which quirks a ROM needs by running it under every combination of them",
+        "Erraten, welche Quirks ein ROM braucht, indem es mit jeder Kombination ausgeführt wird",
+    ),
+    (
+        "Number of cycles to run each combination for",
+        "Anzahl der Zyklen, die jede Kombination läuft",
+    ),
+    (
+        "Run a ROM under two sets of quirks in lockstep and report where they first differ",
+        "Ein ROM mit zwei Quirk-Sätzen im Gleichschritt ausführen und melden, wo sie zuerst abweichen",
+    ),
+    ("Quirks for the first run", "Quirks für den ersten Lauf"),
+    ("Quirks for the second run", "Quirks für den zweiten Lauf"),
+    ("Number of cycles to run for", "Anzahl der auszuführenden Zyklen"),
+    (
+        "Run two ROMs side by side in one window, the second played with 7890/UIOP/JKL;/N,./",
+        "Zwei ROMs nebeneinander in einem Fenster ausführen, das zweite mit 7890/UIOP/JKL;/N,./ gespielt",
+    ),
+    (
+        "Defaults to the first ROM, to compare quirks or race",
+        "Standardmäßig das erste ROM, um Quirks zu vergleichen oder um die Wette zu spielen",
+    ),
+    ("Quirks for the left screen", "Quirks für den linken Bildschirm"),
+    ("Quirks for the right screen", "Quirks für den rechten Bildschirm"),
+    (
+        "Show the bytes that differ between two ROMs, as disassembly where they're code",
+        "Die Bytes zeigen, in denen sich zwei ROMs unterscheiden, als Disassembly, wo sie Code sind",
+    ),
+    (
+        "Instructions or bytes to show either side of each difference",
+        "Anweisungen oder Bytes, die vor und nach jedem Unterschied gezeigt werden",
+    ),
+    (
+        "Find the first instruction where two traces from --trace disagree",
+        "Die erste Anweisung finden, bei der sich zwei Traces von --trace unterscheiden",
+    ),
+    (
+        "Show the palettes --palette can pick from as swatches of their colors",
+        "Die Paletten, aus denen --palette wählen kann, als Farbmuster zeigen",
+    ),
+    (
+        "Print a script that completes chip8's commands, options and ROM paths in a shell",
+        "Ein Skript ausgeben, das die Befehle, Optionen und ROM-Pfade von chip8 in einer Shell vervollständigt",
+    ),
+    (
+        "List the ROMs that have been played, most recently first, with how long and how often",
+        "Die gespielten ROMs auflisten, die zuletzt gespielten zuerst, mit wie lange und wie oft",
+    ),
+    (
+        "Lines of each trace to show either side of the divergence",
+        "Zeilen jedes Traces, die vor und nach der Abweichung gezeigt werden",
+    ),
+    (
+        "Show what changed between two save states or crash dumps: registers, memory and the screen",
+        "Zeigen, was sich zwischen zwei Spielständen oder Absturzabbildern geändert hat: Register, Speicher und Bildschirm",
+    ),
+    (
+        "Also write the screens as a PPM image, with pixels that came on green and ones that went off red",
+        "Die Bildschirme auch als PPM-Bild schreiben, angegangene Pixel grün und ausgegangene rot",
+    ),
+    ("Path to ROM", "Pfad zum ROM"),
+    (
+        "Play every ROM in this directory in turn, like an arcade machine's attract mode",
+        "Alle ROMs in diesem Verzeichnis nacheinander spielen, wie der Demo-Modus eines Spielautomaten",
+    ),
+    (
+        "How long each ROM plays for with --kiosk",
+        "Wie lange jedes ROM mit --kiosk läuft",
+    ),
+    (
+        "With --kiosk, keep a ROM somebody is playing going until no key has been pressed for this long",
+        "Mit --kiosk ein ROM, das gerade gespielt wird, weiterlaufen lassen, bis so lange keine Taste gedrückt wurde",
+    ),
+    (
+        "After this long without a key pressed, start the ROM again, or go on to the next one in --kiosk with --idle-action next. 0 is off, which a rom.NAME.idle line can set for one ROM",
+        "Nach so langer Zeit ohne Tastendruck das ROM neu starten, oder mit --idle-action next zum nächsten in --kiosk gehen. 0 schaltet es aus, was eine Zeile rom.NAME.idle für ein ROM einstellen kann",
+    ),
+    (
+        "What happens after --idle: reset the ROM, or next for the next ROM in --kiosk",
+        "Was nach --idle passiert: reset startet das ROM neu, next geht zum nächsten ROM in --kiosk",
+    ),
+    ("bad --idle {}", "ungültiges --idle {}"),
+    ("bad --key-wait-timeout {}", "ungültiges --key-wait-timeout {}"),
+    ("bad --idle-action {}", "ungültige --idle-action {}"),
+    (
+        "--idle-action next is for --kiosk",
+        "--idle-action next gibt es nur mit --kiosk",
+    ),
+    (
+        "Serve a page at this address, like localhost:8080, where browsers can watch the game live",
+        "Unter dieser Adresse, z. B. localhost:8080, eine Seite bereitstellen, auf der Browser dem Spiel live zusehen können",
+    ),
+    (
+        "Publish the sound going on and off, sprites drawn and keys to osc://HOST:PORT or mqtt://HOST:PORT, for installations that react to the game. A /PREFIX after either is put before the addresses or topics, chip8 if it's left out",
+        "Das An- und Ausgehen des Tons, gezeichnete Sprites und Tasten an osc://HOST:PORT oder mqtt://HOST:PORT senden, für Installationen, die auf das Spiel reagieren. Ein /PRÄFIX dahinter wird den Adressen oder Topics vorangestellt, chip8, wenn es fehlt",
+    ),
+    ("bad --bridge {}: {}", "ungültiges --bridge {}: {}"),
+    ("the bridge stopped publishing: {}", "die Bridge hat das Senden eingestellt: {}"),
+    (
+        "Show the ROM being played and for how long on the Discord profile of whoever's signed in to the Discord client here. APP_ID is the ID of the Discord application to show it as, whose name reads as what's being done, like CHIP-8",
+        "Die gespielte ROM und wie lange schon im Discord-Profil dessen zeigen, der hier im Discord-Client angemeldet ist. APP_ID ist die ID der Discord-Anwendung, als die es gezeigt wird, deren Name sagt, was gerade getan wird, z. B. CHIP-8",
+    ),
+    ("couldn't reach Discord: {}", "Discord nicht erreichbar: {}"),
+    (
+        "Discord stopped showing the game: {}",
+        "Discord zeigt das Spiel nicht mehr: {}",
+    ),
+    ("{} played before", "schon {} gespielt"),
+    (
+        "Write the screen to this file or named pipe, or - for stdout, as raw RGB video at 60 frames a second of the VM's time, for ffmpeg to encode",
+        "Den Bildschirm in diese Datei oder Named Pipe schreiben, oder mit - auf stdout, als rohes RGB-Video mit 60 Bildern pro Sekunde in der Zeit der VM, zum Kodieren mit ffmpeg",
+    ),
+    (
+        "How many pixels across each of the screen's is in --pipe-video",
+        "Wie viele Pixel breit jedes Bildschirmpixel in --pipe-video ist",
+    ),
+    ("bad --pipe-video-scale {}", "ungültige --pipe-video-scale {}"),
+    (
+        "--pipe-video - needs stdout, which the tui and headless frontends and the debugger use",
+        "--pipe-video - braucht stdout, das die Frontends tui und headless und der Debugger benutzen",
+    ),
+    (
+        "Write the buzzer to this WAV file, muted or not, lined up with --pipe-video",
+        "Den Summer in diese WAV-Datei schreiben, stummgeschaltet oder nicht, passend zu --pipe-video",
+    ),
+    (
+        "How the buzzer sounds: a clean square wave, or the COSMAC VIP's, a higher tone with a click as it starts and stops that short beeps are mostly made of",
+        "Wie der Summer klingt: eine saubere Rechteckwelle, oder der des COSMAC VIP, ein höherer Ton mit einem Klicken beim Ein- und Ausschalten, aus dem kurze Pieptöne größtenteils bestehen",
+    ),
+    ("bad --buzzer {}", "ungültiger --buzzer {}"),
+    ("couldn't write the audio: {}", "konnte den Ton nicht schreiben: {}"),
+    ("encode the video with: {}", "das Video kodieren mit: {}"),
+    ("the video pipe closed: {}", "die Video-Pipe wurde geschlossen: {}"),
+    ("couldn't listen on {}: {}", "konnte nicht auf {} lauschen: {}"),
+    ("watch at http://{}/", "zusehen unter http://{}/"),
+    ("Reset after no input", "Neu gestartet, da keine Eingabe kam"),
+    ("no ROMs in {}", "keine ROMs in {}"),
+    (
+        "none of the kiosk's ROMs could be read",
+        "keines der ROMs für --kiosk konnte gelesen werden",
+    ),
+    (
+        "Options that aren't given are taken from the --preset, then from the environment as CHIP8_ and the option in capitals, e.g. CHIP8_QUIRKS=schip, then from name = value lines in the config file, e.g. quirks = schip",
+        "Nicht angegebene Optionen werden aus --preset genommen, dann aus der Umgebung als CHIP8_ und die Option in Großbuchstaben, z. B. CHIP8_QUIRKS=schip, dann aus Zeilen name = Wert in der Konfigurationsdatei, z. B. quirks = schip",
+    ),
+    (
+        "Run the ROM that was run last again, with the same arguments",
+        "Die zuletzt gestartete ROM noch einmal starten, mit denselben Argumenten",
+    ),
+    (
+        "Boot into a save state or crash dump, which also sets the quirks it was made with",
+        "Aus einem Spielstand oder Absturzabbild starten, was auch dessen Quirks setzt",
+    ),
+    (
+        "Where to show the game, tui draws in the terminal, fb on a Linux console's framebuffer with no X or Wayland, and headless prints the last screen at exit",
+        "Wo das Spiel angezeigt wird, tui zeichnet im Terminal, fb auf dem Framebuffer einer Linux-Konsole ohne X oder Wayland, und headless gibt beim Beenden das letzte Bild aus",
+    ),
+    (
+        "no input devices could be read, is this user in the input group?",
+        "keine Eingabegeräte lesbar, ist dieser Benutzer in der Gruppe input?",
+    ),
+    ("couldn't draw: {}", "konnte nicht zeichnen: {}"),
+    ("Colors to draw the screen in, see chip8 palettes", "Farben, in denen der Bildschirm gezeichnet wird, siehe chip8 palettes"),
+    (
+        "Turn the picture clockwise by 90, 180 or 270 degrees, for games made to be held on their side or a display mounted that way. The keys that move each way on it are shown at start",
+        "Das Bild um 90, 180 oder 270 Grad im Uhrzeigersinn drehen, für Spiele, die seitlich gehalten werden, oder ein so montiertes Display. Welche Tasten in welche Richtung bewegen, wird beim Start angezeigt",
+    ),
+    (
+        "How long notifications like \"state 1 saved\" show for, 0 to turn them off",
+        "Wie lange Meldungen wie \"Spielstand 1 gespeichert\" angezeigt werden, 0 schaltet sie ab",
+    ),
+    (
+        "Print statistics about the instructions the ROM ran at exit, and show the instruction and frame counts over the game",
+        "Beim Beenden Statistiken über die ausgeführten Anweisungen ausgeben und die Anweisungs- und Bildzähler über dem Spiel anzeigen",
+    ),
+    (
+        "Rumble game controllers while the sound timer runs, even when muted",
+        "Gamecontroller vibrieren lassen, solange der Sound-Timer läuft, auch stummgeschaltet",
+    ),
+    (
+        "Fill memory the ROM doesn't use with a canary byte, and report how much of the stack and memory it used at exit",
+        "Vom ROM nicht genutzten Speicher mit einem Canary-Byte füllen und beim Beenden melden, wie viel Stapel und Speicher es genutzt hat",
+    ),
+    (
+        "Keep running when the window loses focus, rather than pausing and going quiet until it's back. The debugger always keeps running, since it's typed at in another window",
+        "Weiterlaufen, wenn das Fenster den Fokus verliert, statt anzuhalten und still zu sein, bis es zurück ist. Mit dem Debugger läuft es immer weiter, da er in einem anderen Fenster bedient wird",
+    ),
+    (
+        "Start paused in the debugger, which reads commands from stdin. Its breakpoints and watches are kept for the next time the ROM is debugged",
+        "Angehalten im Debugger starten, der Befehle von stdin liest. Haltepunkte und Watches bleiben bis zum nächsten Debuggen der ROM erhalten",
+    ),
+    (
+        "Run until N instructions have run, up to cycle N in a --trace, then stop in the debugger. The same ROM with the same input, as from a --replay, stops at the same point every time",
+        "Laufen, bis N Anweisungen ausgeführt sind, bis Zyklus N in einem --trace, dann im Debugger anhalten. Dasselbe ROM mit denselben Eingaben, etwa aus einem --replay, hält jedes Mal an derselben Stelle",
+    ),
+    (
+        "Run until N frames have been drawn, then stop in the debugger",
+        "Laufen, bis N Bilder gezeichnet sind, dann im Debugger anhalten",
+    ),
+    (
+        "Record input so save states carry a replay since the one before, and the debugger can rewind",
+        "Eingaben aufzeichnen, damit Spielstände eine Wiederholung seit dem vorherigen enthalten und der Debugger zurückspulen kann",
+    ),
+    (
+        "Write a trace of every executed instruction to this file",
+        "Einen Trace jeder ausgeführten Anweisung in diese Datei schreiben",
+    ),
+    (
+        "Interpreter quirks to emulate, a profile optionally followed by quirks, e.g. chip8,no-clipping",
+        "Zu emulierende Interpreter-Quirks, ein Profil optional gefolgt von Quirks, z. B. chip8,no-clipping",
+    ),
+    (
+        "Stop after this long in the VM's time, as frames, seconds or cycles, e.g. 600f, 10s or 5000c",
+        "Nach dieser Zeit in der Zeit der VM anhalten, in Bildern, Sekunden oder Zyklen, z. B. 600f, 10s oder 5000c",
+    ),
+    (
+        "Stop with an error once the ROM has waited this long for a key (FX0A), in the VM's time, for running headless where no key is coming",
+        "Mit einem Fehler anhalten, sobald das ROM so lange in der Zeit der VM auf eine Taste gewartet hat (FX0A), für Läufe ohne Anzeige, bei denen keine Taste kommt",
+    ),
+    ("Write a save state of the VM here at exit", "Beim Beenden einen Spielstand der VM hierhin schreiben"),
+    (
+        "Frames to drop after each one shown, or auto to drop them only when the host can't keep up. The VM runs in full either way",
+        "Wie viele Bilder nach jedem gezeigten ausgelassen werden, oder auto, um nur dann welche auszulassen, wenn der Rechner nicht mitkommt. Die VM läuft so oder so vollständig",
+    ),
+    (
+        "Flash a border round the screen while the sound timer runs, for playing without sound",
+        "Einen Rahmen um das Bild blinken lassen, solange der Sound-Timer läuft, zum Spielen ohne Ton",
+    ),
+    (
+        "Show the keypad over the game with the keys held lit, for streams and tutorials. F12 turns it on and off either way",
+        "Das Tastenfeld über dem Spiel zeigen, mit den gehaltenen Tasten hell, für Streams und Tutorials. F12 schaltet es so oder so an und aus",
+    ),
+    (
+        "How the picture fills the window or framebuffer: fit keeps pixels square, stretch fills it all and dot-matrix leaves a gap round each pixel like an LED matrix",
+        "Wie das Bild Fenster oder Framebuffer ausfüllt: fit hält die Pixel quadratisch, stretch füllt alles aus und dot-matrix lässt eine Lücke um jedes Pixel wie bei einer LED-Matrix",
+    ),
+    (
+        "The corner of the screen the keypad shows in: top-left, top-right, bottom-left or bottom-right",
+        "Die Ecke des Bildes, in der das Tastenfeld erscheint: top-left, top-right, bottom-left oder bottom-right",
+    ),
+    (
+        "Show which keys do anything in the ROM over the game, as far as a look through its code can tell",
+        "Über dem Spiel zeigen, welche Tasten in der ROM etwas tun, soweit ein Blick in ihren Code das sagen kann",
+    ),
+    (
+        "With the tui frontend, write keys pressed, the sound going on and off, notifications and changes to HUD lines as plain lines of text for a screen reader, instead of the screen",
+        "Mit dem tui-Frontend gedrückte Tasten, das An- und Ausgehen des Tons, Benachrichtigungen und Änderungen an HUD-Zeilen als einfache Textzeilen für einen Screenreader ausgeben statt des Bildes",
+    ),
+    (
+        "Apply an IPS patch to the ROM as it's loaded, leaving the file alone. Can be given more than once, the patches are applied in order",
+        "Beim Laden einen IPS-Patch auf die ROM anwenden, ohne die Datei zu ändern. Kann mehrfach angegeben werden, die Patches werden der Reihe nach angewendet",
+    ),
+    (
+        "Load the ROM at this hex address instead of 200, for ROMs assembled elsewhere or code fragments. It's run from there too unless --entry says otherwise",
+        "Die ROM an dieser Hex-Adresse statt 200 laden, für anderswo assemblierte ROMs oder Codefragmente. Sie wird auch dort gestartet, außer --entry sagt etwas anderes",
+    ),
+    (
+        "Start running at this hex address instead of where the ROM was loaded",
+        "An dieser Hex-Adresse statt dort, wo die ROM geladen wurde, zu laufen beginnen",
+    ),
+    (
+        "Load a file into memory at a hex address as well as the ROM, e.g. sprites.bin@800. Can be given more than once, for code and data kept in files of their own",
+        "Zusätzlich zur ROM eine Datei an einer Hex-Adresse in den Speicher laden, z. B. sprites.bin@800. Kann mehrfach angegeben werden, für Code und Daten in eigenen Dateien",
+    ),
+    (
+        "SHA-1s of known-good ROMs in sha1sum's format, to warn about a ROM that's a bad copy of one of them. Defaults to roms.sha1 in the data directory, if it's there",
+        "SHA-1-Summen bekannter guter ROMs im Format von sha1sum, um vor einer ROM zu warnen, die eine fehlerhafte Kopie einer davon ist. Standardmäßig roms.sha1 im Datenverzeichnis, falls vorhanden",
+    ),
+    (
+        "Refuse to run a ROM that looks broken, and stop with a crash dump at the first thing --warn would warn about, rather than warning",
+        "Eine ROM, die kaputt aussieht, nicht starten und beim ersten Punkt, vor dem --warn warnen würde, mit einem Absturzabbild anhalten, statt nur zu warnen",
+    ),
+    (
+        "Carry on past common ROM bugs instead of stopping: addresses past the end of memory wrap around and a PC on an odd address is moved back to an even one",
+        "Über häufige ROM-Fehler hinweg weiterlaufen, statt anzuhalten: Adressen hinter dem Speicherende laufen zum Anfang um und ein PC auf einer ungeraden Adresse wird auf eine gerade zurückgesetzt",
+    ),
+    (
+        "Warn about things the ROM does that are likely bugs, like reading memory it never set, storing over the font, calling nearly out of stack or drawing off the screen",
+        "Vor Dingen warnen, die die ROM tut und die wahrscheinlich Fehler sind, etwa Speicher lesen, den sie nie gesetzt hat, den Font überschreiben, mit fast vollem Stack aufrufen oder außerhalb des Bildes zeichnen",
+    ),
+    (
+        "Print the registers, timers, stack and the instruction at the PC at exit",
+        "Beim Beenden Register, Timer, Stack und den Befehl am PC ausgeben",
+    ),
+    (
+        "Write the last screen here at exit, as a PBM image",
+        "Beim Beenden das letzte Bild als PBM-Bild hierhin schreiben",
+    ),
+    (
+        "Fail with exit code 1 unless this holds at exit: exit for a ROM that ends with 00FD, frame=HASH for the screen (the hash is printed when it differs), or an expression like mem[0x3FF]==1. Can be given more than once",
+        "Mit Exit-Code 1 fehlschlagen, wenn dies beim Beenden nicht gilt: exit für eine ROM, die mit 00FD endet, frame=HASH für das Bild (der Hash wird ausgegeben, wenn er abweicht) oder ein Ausdruck wie mem[0x3FF]==1. Kann mehrfach angegeben werden",
+    ),
+    (
+        "Map the time, a frame counter and random bytes into memory at 0xFF0-0xFFF for homebrew",
+        "Uhrzeit, einen Bildzähler und Zufallsbytes für Homebrew bei 0xFF0-0xFFF in den Speicher legen",
+    ),
+    (
+        "Fill memory and registers with random garbage at boot, as on real hardware, to catch ROMs relying on them being zero, the seed is printed so a run can be repeated",
+        "Speicher und Register beim Start wie bei echter Hardware mit Zufallswerten füllen, um ROMs zu finden, die sich auf Nullen verlassen, der Seed wird ausgegeben, damit sich ein Lauf wiederholen lässt",
+    ),
+    ("random init seed: {}", "Seed der Zufallsbelegung: {}"),
+    (
+        "Start from a bundle of settings: authentic-vip, modern, speedrun or one made with preset.NAME.OPTION = VALUE lines in the config file",
+        "Mit einer Sammlung von Einstellungen beginnen: authentic-vip, modern, speedrun oder einer aus Zeilen preset.NAME.OPTION = WERT in der Konfigurationsdatei",
+    ),
+    (
+        "Font for the hex digits: vip, schip, dream6800, eti660, fishnchips or an 80 byte font file",
+        "Schrift der Hex-Ziffern: vip, schip, dream6800, eti660, fishnchips oder eine 80 Byte große Schriftdatei",
+    ),
+    (
+        "Language for messages, taken from LANG when not given",
+        "Sprache der Meldungen, ohne Angabe aus LANG",
+    ),
+    // notifications
+    ("Muted", "Stumm"),
+    ("Unmuted", "Ton an"),
+    ("Slot {}", "Platz {}"),
+    ("State {} saved", "Spielstand {} gespeichert"),
+    ("State {} not saved: {}", "Spielstand {} nicht gespeichert: {}"),
+    ("State {} loaded", "Spielstand {} geladen"),
+    ("State {} not loaded: {}", "Spielstand {} nicht geladen: {}"),
+    ("No state in slot {}", "Kein Spielstand auf Platz {}"),
+    ("No data directory, set HOME", "Kein Datenverzeichnis, HOME setzen"),
+    ("Holding {}", "Gehalten: {}"),
+    ("Recording macro, press F1-F4 to bind it", "Makro wird aufgenommen, F1-F4 zum Belegen"),
+    ("Macro recording cancelled", "Makroaufnahme abgebrochen"),
+    ("Nothing recorded for the macro", "Nichts für das Makro aufgenommen"),
+    ("Macro bound to {}", "Makro auf {} gelegt"),
+    ("Macro on {} not saved: {}", "Makro auf {} nicht gespeichert: {}"),
+    ("Macro on {} not played: {}", "Makro auf {} nicht abgespielt: {}"),
+    ("No macro on {}", "Kein Makro auf {}"),
+    ("No config directory, set HOME", "Kein Konfigurationsverzeichnis, HOME setzen"),
+    ("Config reloaded", "Konfiguration neu geladen"),
+    ("Config not reloaded: {}", "Konfiguration nicht neu geladen: {}"),
+    ("Holding no keys", "Keine Tasten gehalten"),
+    // messages and errors
+    ("unknown frontend {}", "unbekanntes Frontend {}"),
+    (
+        "Run a small built in ROM slowly, with a note on what each instruction does and what it changed",
+        "Ein kleines eingebautes ROM langsam laufen lassen, mit einer Notiz, was jeder Befehl tut und was er geändert hat",
+    ),
+    ("Instructions to run a second", "Befehle pro Sekunde"),
+    ("bad --speed {}", "ungültiges --speed {}"),
+    ("the tour starts at {}", "die Tour beginnt bei {}"),
+    ("clear the screen", "den Bildschirm löschen"),
+    ("VA is where across to draw", "VA ist, wo quer gezeichnet wird"),
+    ("and VB how far down", "und VB, wie weit unten"),
+    ("VC counts the digits drawn", "VC zählt die gezeichneten Ziffern"),
+    ("point I at VC's digit's sprite", "I auf das Sprite der Ziffer VC"),
+    ("draw 5 rows of sprite from I", "5 Zeilen Sprite ab I zeichnen"),
+    ("move right for the next one", "nach rechts für die nächste"),
+    ("count the digit", "die Ziffer zählen"),
+    ("skip the jump after 4 digits", "nach 4 Ziffern den Sprung überspringen"),
+    ("back for the next digit", "zurück zur nächsten Ziffer"),
+    ("call a subroutine at 242", "Unterprogramm bei 242 aufrufen"),
+    ("VE is 123, 7B in hex", "VE ist 123, hex 7B"),
+    ("point I at some spare memory", "I auf freien Speicher"),
+    ("store VE's digits at I, I+1, I+2", "Ziffern von VE bei I, I+1, I+2 ablegen"),
+    ("load them into V0, V1 and V2", "sie in V0, V1 und V2 laden"),
+    ("back to the left", "zurück nach links"),
+    ("and further down", "und weiter unten"),
+    ("the hundreds digit in V0", "die Hunderter in V0"),
+    ("draw it", "zeichnen"),
+    ("move right", "nach rechts"),
+    ("the tens in V1", "die Zehner in V1"),
+    ("the ones in V2", "die Einer in V2"),
+    ("3 ticks of the 60Hz timers", "3 Takte der 60-Hz-Timer"),
+    ("start the delay timer", "den Verzögerungstimer starten"),
+    ("see how far it's counted down", "nachsehen, wie weit er ist"),
+    ("skip the jump once it's 0", "bei 0 den Sprung überspringen"),
+    ("not yet, look again", "noch nicht, nochmal nachsehen"),
+    ("one tick of sound", "ein Takt Ton"),
+    ("beep while the sound timer runs", "piepen, solange der Tontimer läuft"),
+    ("jump here forever: the end", "für immer hierher springen: Ende"),
+    ("point I at the smiley's sprite", "I auf das Smiley-Sprite"),
+    ("V0 is where across", "V0 ist, wo quer"),
+    ("V1 how far down", "V1, wie weit unten"),
+    ("draw 8 rows, VF is 1 on a hit", "8 Zeilen zeichnen, VF ist 1 bei Treffer"),
+    ("return to after the call", "zurück hinter den Aufruf"),
+    (
+        "the debugger reads stdin, which the tui frontend needs for keys",
+        "der Debugger liest stdin, das das tui-Frontend für Tasten braucht",
+    ),
+    ("bad --toast-secs {}", "ungültiges --toast-secs {}"),
+    ("crash dump written to {}", "Absturzabbild nach {} geschrieben"),
+    ("couldn't write crash dump: {}", "Absturzabbild konnte nicht geschrieben werden: {}"),
+    ("--announce is for the tui frontend", "--announce ist für das tui-Frontend"),
+    (
+        "{} doesn't match the known-good SHA-1 {}, the file may be truncated or corrupted",
+        "{} passt nicht zur bekannten guten SHA-1 {}, die Datei ist vielleicht abgeschnitten oder beschädigt",
+    ),
+    ("warning: {}", "Warnung: {}"),
+    ("any key", "beliebige Taste"),
+    ("and maybe others", "und vielleicht weitere"),
+    ("sound on", "Ton an"),
+    ("sound off", "Ton aus"),
+    ("key {}", "Taste {}"),
+    ("couldn't write the trace: {}", "Trace konnte nicht geschrieben werden: {}"),
+    ("expectation not met: {}", "Erwartung nicht erfüllt: {}"),
+    ("couldn't save cheats: {}", "Cheats konnten nicht gespeichert werden: {}"),
+    ("No cheats for this ROM", "Keine Cheats für diese ROM"),
+    ("Cheats on", "Cheats an"),
+    ("Cheats off", "Cheats aus"),
+    ("Paused", "Pausiert"),
+    ("Carrying on", "Weiter geht's"),
+    ("High scores", "Bestenliste"),
+    ("No high scores yet", "Noch keine Bestleistungen"),
+    ("Achievements {} of {}", "Erfolge {} von {}"),
+    ("Achievement unlocked: {}", "Erfolg freigeschaltet: {}"),
+    (
+        "couldn't save the achievements: {}",
+        "Erfolge konnten nicht gespeichert werden: {}",
+    ),
+    ("High score {}: {}", "Bestleistung {}: {}"),
+    (
+        "couldn't save the high scores: {}",
+        "Bestenliste konnte nicht gespeichert werden: {}",
+    ),
+    (
+        "couldn't save the debugger session: {}",
+        "Debugger-Sitzung konnte nicht gespeichert werden: {}",
+    ),
+    ("couldn't save the playtime: {}", "Spielzeit konnte nicht gespeichert werden: {}"),
+    (
+        "couldn't save the recent ROMs: {}",
+        "Zuletzt gestartete ROMs konnten nicht gespeichert werden: {}",
+    ),
+    ("no ROM has been run yet", "Es wurde noch keine ROM gestartet"),
+    ("the config file", "der Konfigurationsdatei"),
+    ("the {} preset", "der Voreinstellung {}"),
+    ("{} isn't on or off", "{} ist weder an noch aus"),
+    ("--{} from {}: {}", "--{} aus {}: {}"),
+    ("{} came from {}", "{} kam aus {}"),
+    (
+        "warning: {} isn't the ROM the dump was made with",
+        "Warnung: {} ist nicht das ROM, mit dem das Abbild erstellt wurde",
+    ),
+    (
+        "ROM not found, running from the memory in the dump",
+        "ROM nicht gefunden, es läuft aus dem Speicher im Abbild",
+    ),
+    (
+        "relocating data changed how the ROM runs, only stripping padding",
+        "das Verschieben von Daten ändert, wie das ROM läuft, es wird nur Padding entfernt",
+    ),
+    (
+        "{} -> {} bytes ({}), verified over {} cycles and {} frames",
+        "{} -> {} Bytes ({}), geprüft über {} Zyklen und {} Bilder",
+    ),
+    (
+        "{} of {} quirk combinations ran {} cycles without errors or hanging",
+        "{} von {} Quirk-Kombinationen liefen {} Zyklen ohne Fehler oder Hänger",
+    ),
+    ("needs: {}", "benötigt: {}"),
+    ("suggested: --quirks {}", "empfohlen: --quirks {}"),
+    ("no combination of quirks ran cleanly", "keine Kombination von Quirks lief fehlerfrei"),
+    ("unhandled opcode {} at {}", "unbekannter Opcode {} bei {}"),
+    ("stack overflow calling from {}", "Stapelüberlauf beim Aufruf von {}"),
+    ("return with empty stack at {}", "Rücksprung mit leerem Stapel bei {}"),
+    ("access past end of memory ({}) at {}", "Zugriff hinter das Speicherende ({}) bei {}"),
+    ("program counter out of memory at {}", "Programmzähler außerhalb des Speichers bei {}"),
+    ("gave up waiting for a key at {}", "Warten auf eine Taste bei {} aufgegeben"),
+    ("bad address {}", "ungültige Adresse {}"),
+    ("--rotate is for the sdl frontend", "--rotate ist für das sdl-Frontend"),
+    (
+        "turned {} degrees: for games that move with 2, 8, 4 and 6, up is {}, down {}, left {} and right {}",
+        "um {} Grad gedreht: bei Spielen, die sich mit 2, 8, 4 und 6 bewegen, ist oben {}, unten {}, links {} und rechts {}",
+    ),
+    (
+        "each shows off, then lit on XO-CHIP's first plane, its second and both",
+        "jeweils aus, dann an auf der ersten Ebene von XO-CHIP, auf der zweiten und auf beiden",
+    ),
+    ("white on black, as it's always been", "Weiß auf Schwarz, wie schon immer"),
+    (
+        "orange, sky blue and yellow from Okabe and Ito's set, safe for all common color blindness",
+        "Orange, Himmelblau und Gelb aus der Palette von Okabe und Ito, geeignet bei allen verbreiteten Farbsehschwächen",
+    ),
+    (
+        "gold, blue and magenta from IBM's accessible set, safe for red-green color blindness",
+        "Gold, Blau und Magenta aus IBMs barrierefreier Palette, geeignet bei Rot-Grün-Sehschwäche",
+    ),
+    (
+        "yellow, cyan and white at full brightness, for low vision",
+        "Gelb, Cyan und Weiß in voller Helligkeit, bei Sehbehinderung",
+    ),
+    (
+        "dark on light, with Okabe and Ito's blue and vermilion for the planes",
+        "dunkel auf hell, mit Blau und Zinnoberrot von Okabe und Ito für die Ebenen",
+    ),
+    ("{} {} is past the end of memory", "{} {} liegt hinter dem Speicherende"),
+    ("cycle {}  frame {}", "Zyklus {}  Bild {}"),
+    ("not a CHIP-8 save state", "kein CHIP-8-Spielstand"),
+    ("unsupported save state version {}", "nicht unterstützte Spielstandversion {}"),
+    ("save state is truncated", "Spielstand ist abgeschnitten"),
+    ("save state is corrupt", "Spielstand ist beschädigt"),
+];
@@ -0,0 +1,100 @@
+// --kiosk: play every ROM in a directory in turn, like an arcade machine's attract mode. each runs
+// for a while in the VM's own time, longer while somebody's playing it, then the screen is wiped
+// away a row a frame and the next one starts
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const EXTENSIONS: [&str; 4] = ["ch8", "c8", "sc8", "xo8"];
+
+const ROWS: usize = 32;
+const WIPE_ROW: Duration = Duration::from_micros(16_667); // a 60Hz frame
+
+// the ROMs in dir by name, any file with one of EXTENSIONS
+pub fn roms(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut roms = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase());
+        if path.is_file() && ext.is_some_and(|ext| EXTENSIONS.contains(&ext.as_str())) {
+            roms.push(path);
+        }
+    }
+    roms.sort();
+    Ok(roms)
+}
+
+pub struct Kiosk {
+    roms: Vec<PathBuf>,
+    current: usize,
+    run_ticks: u64,                   // how long each ROM gets
+    idle: Option<Duration>,           // how long without a key before a ROM being played is left
+    wipe: Option<(Vec<u8>, Instant)>, // the last screen of the ROM being left, and when it began
+}
+
+impl Kiosk {
+    // secs for each ROM, and if idle is given, a ROM somebody's playing runs on until nobody has
+    // touched a key for that many seconds
+    pub fn new(roms: Vec<PathBuf>, secs: f64, idle: Option<f64>) -> Self {
+        Self {
+            roms,
+            current: 0,
+            run_ticks: (secs * 60.0) as u64,
+            idle: idle.map(Duration::from_secs_f64),
+            wipe: None,
+        }
+    }
+
+    pub fn current(&self) -> &Path {
+        &self.roms[self.current]
+    }
+
+    pub fn len(&self) -> usize {
+        self.roms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roms.is_empty()
+    }
+
+    // move on to the next ROM, round to the first after the last
+    pub fn advance(&mut self) -> &Path {
+        self.current = (self.current + 1) % self.roms.len();
+        self.wipe = None;
+        self.current()
+    }
+
+    // true once it's time to leave the ROM, given its tick count and how long it's been since a
+    // key was last pressed in it, if one has been
+    pub fn due(&self, ticks: u64, idle: Option<Duration>) -> bool {
+        let played = |wait| idle.is_some_and(|idle| idle < wait);
+        ticks >= self.run_ticks && !self.idle.is_some_and(played)
+    }
+
+    pub fn wiping(&self) -> bool {
+        self.wipe.is_some()
+    }
+
+    pub fn start_wipe(&mut self, screen: &[u8], now: Instant) {
+        self.wipe = Some((screen.to_vec(), now));
+    }
+
+    // the screen as the wipe has it now: cleared down to a line that sweeps to the bottom. none
+    // once it's done
+    pub fn wipe_frame(&self, now: Instant) -> Option<Vec<u8>> {
+        let (screen, start) = self.wipe.as_ref()?;
+        let row = (now.duration_since(*start).as_micros() / WIPE_ROW.as_micros()) as usize;
+        if row >= ROWS {
+            return None;
+        }
+        let width = screen.len() / ROWS;
+        let mut frame = screen.clone();
+        frame[..row * width].iter_mut().for_each(|p| *p = 0);
+        frame[row * width..(row + 1) * width]
+            .iter_mut()
+            .for_each(|p| *p = 1);
+        Some(frame)
+    }
+}
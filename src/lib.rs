@@ -0,0 +1,57 @@
+// the emulator around the VM. the VM is the chip8-core crate and the ROM tools chip8-tools, their
+// modules are re-exported below so they're still chip8::chip8, chip8::detect and so on
+
+pub mod achievements;
+#[cfg(feature = "bridge")]
+pub mod bridge;
+pub mod canary;
+pub mod cheats;
+pub mod compare;
+pub mod config;
+pub mod crash;
+pub mod debugger;
+pub mod diagnostics;
+pub mod dirs;
+#[cfg(all(feature = "discord", unix))]
+pub mod discord;
+pub mod expect;
+pub mod expr;
+#[cfg(target_os = "linux")]
+pub mod fb;
+pub mod frontend;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod gym;
+pub mod i18n;
+pub mod kiosk;
+pub mod macros;
+pub mod movie;
+pub mod panels;
+pub mod playtime;
+pub mod recent;
+pub mod romdb;
+pub mod runner;
+pub mod scheduler;
+pub mod scores;
+pub mod screenshot;
+pub mod search;
+pub mod segment;
+pub mod settings;
+pub mod sha1;
+pub mod shutdown;
+pub mod spectate;
+pub mod statediff;
+pub mod stats;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tour;
+pub mod trace;
+pub mod tracediff;
+pub mod video;
+pub mod wav;
+
+pub use chip8_core::{
+    bus, chip8, clock, disasm, fonts, headless, mmio, observer, overlay, palette, prelude, quirks,
+    synth, Chip8,
+};
+pub use chip8_tools::{analysis, batch, detect, labels, patch, romdiff, trim};
@@ -0,0 +1,118 @@
+// input macros: a sequence of keys to hold, each for some number of frames, recorded from play
+// and bound to a key of their own. written as steps like "5*3 -*2 5A", keys as hex digits or - for
+// none, held for the frame count after the * or for one frame without one
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub keys: u16, // bit per key
+    pub frames: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Macro {
+    pub steps: Vec<Step>,
+}
+
+fn parse_step(s: &str) -> Option<Step> {
+    let (keys, frames) = match s.split_once('*') {
+        Some((keys, frames)) => (keys, frames.parse().ok().filter(|&f| f > 0)?),
+        None => (s, 1),
+    };
+    let keys = match keys {
+        "-" => 0,
+        "" => return None,
+        keys => keys
+            .chars()
+            .try_fold(0, |held, c| c.to_digit(16).map(|k| held | 1 << k))?,
+    };
+    Some(Step { keys, frames })
+}
+
+impl Macro {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let steps = s
+            .split_whitespace()
+            .map(|step| parse_step(step).ok_or_else(|| format!("bad macro step {}", step)))
+            .collect::<Result<Vec<Step>, String>>()?;
+        Ok(Self { steps })
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.steps.iter().map(|s| s.frames as u64).sum()
+    }
+}
+
+impl fmt::Display for Macro {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, step) in self.steps.iter().enumerate() {
+            if n > 0 {
+                write!(f, " ")?;
+            }
+            if step.keys == 0 {
+                write!(f, "-")?;
+            }
+            for k in (0..16).filter(|k| step.keys & (1 << k) != 0) {
+                write!(f, "{:X}", k)?;
+            }
+            if step.frames > 1 {
+                write!(f, "*{}", step.frames)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// builds a macro from the keys held on each frame
+#[derive(Default)]
+pub struct MacroRecorder {
+    recorded: Macro,
+}
+
+impl MacroRecorder {
+    pub fn frame(&mut self, keys: u16) {
+        match self.recorded.steps.last_mut() {
+            Some(last) if last.keys == keys => last.frames += 1,
+            // waiting for the first key isn't part of the macro
+            None if keys == 0 => {}
+            _ => self.recorded.steps.push(Step { keys, frames: 1 }),
+        }
+    }
+
+    // the macro without the wait after the last key was let go
+    pub fn finish(mut self) -> Macro {
+        if self.recorded.steps.last().is_some_and(|s| s.keys == 0) {
+            self.recorded.steps.pop();
+        }
+        self.recorded
+    }
+}
+
+// plays a macro back a frame at a time
+pub struct MacroPlayer {
+    steps: std::vec::IntoIter<Step>,
+    step: Option<Step>, // frames left of the step being played
+}
+
+impl MacroPlayer {
+    pub fn new(m: Macro) -> Self {
+        Self {
+            steps: m.steps.into_iter(),
+            step: None,
+        }
+    }
+
+    // the keys to hold for the next frame, None once the macro's done
+    pub fn frame(&mut self) -> Option<u16> {
+        loop {
+            match &mut self.step {
+                Some(step) if step.frames > 0 => {
+                    step.frames -= 1;
+                    return Some(step.keys);
+                }
+                _ => self.step = Some(self.steps.next()?),
+            }
+        }
+    }
+}
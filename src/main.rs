@@ -1,133 +1,2097 @@
-extern crate sdl2;
+mod completions;
 
-mod audio;
-mod chip8;
+use chip8::achievements::{self, Achievements, Unlocked};
+use chip8::analysis::{self, Analysis};
+use chip8::batch;
+#[cfg(feature = "bridge")]
+use chip8::bridge::Bridge;
+use chip8::canary::Canaries;
+use chip8::cheats::{self, Cheats};
+use chip8::chip8::{CYCLES_PER_TICK, PROGRAM_START};
+use chip8::clock::RealClock;
+use chip8::compare;
+use chip8::config::{self, Config};
+use chip8::crash::{self, Dump};
+use chip8::debugger::{self, Action, Debugger};
+use chip8::detect;
+use chip8::diagnostics::Diagnostics;
+use chip8::dirs;
+#[cfg(all(feature = "discord", unix))]
+use chip8::discord::Presence;
+use chip8::expect::Expectation;
+#[cfg(target_os = "linux")]
+use chip8::fb::Fb;
+use chip8::fonts::{self, Font};
+use chip8::frontend::{self, Frontend, Headless, Hotkey, Null, Rotation, ScreenMode, Stamped, Tui};
+#[cfg(feature = "sdl")]
+use chip8::frontend::{Display, Keypad, Machines};
+use chip8::i18n::{self, tr, trf};
+use chip8::kiosk::{self, Kiosk};
+use chip8::labels::Labels;
+use chip8::macros::{Macro, MacroPlayer, MacroRecorder};
+use chip8::mmio;
+use chip8::movie::{self, Recorder};
+use chip8::observer::SelfModifyLog;
+use chip8::overlay::Corner;
+use chip8::palette::{self, Palette};
+use chip8::patch;
+use chip8::playtime::{self, Playtime};
+use chip8::quirks::Quirks;
+use chip8::recent::{self, Recent};
+use chip8::romdb::{self, RomDb, Verdict};
+use chip8::romdiff;
+use chip8::runner::{Budget, Elapsed, CYCLE_TIME};
+use chip8::scheduler::{FrameSkip, FrameSkipper, Scheduler, MAX_CATCH_UP};
+use chip8::scores::{self, Scores};
+use chip8::segment::{self, Segment};
+use chip8::settings::{self, Source};
+use chip8::sha1::{self, sha1};
+use chip8::shutdown;
+use chip8::spectate;
+use chip8::statediff;
+use chip8::stats::Stats;
+use chip8::synth::{self, Model};
+use chip8::tour;
+use chip8::trace::Tracer;
+use chip8::tracediff;
+use chip8::trim;
+use chip8::video::VideoPipe;
+use chip8::wav::WavWriter;
+#[cfg(feature = "sdl")]
+use chip8_sdl::Sdl;
+use clap::error::{ContextKind, ContextValue};
+use clap::parser::ValueSource;
+use clap::{ArgAction, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
 
-use sdl2::audio::AudioSpecDesired;
-use sdl2::event::Event;
-use sdl2::keyboard::{Keycode, Scancode};
-use sdl2::pixels;
-use sdl2::rect::Rect;
+#[derive(Parser)]
+#[command(
+    version,
+    about = "CHIP-8 emulator",
+    after_help = "Options that aren't given are taken from the --preset, then from the environment as CHIP8_ and the option in capitals, e.g. CHIP8_QUIRKS=schip, then from name = value lines in the config file, e.g. quirks = schip",
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: Option<RunArgs>,
+
+    /// Run the ROM that was run last again, with the same arguments
+    #[arg(long, exclusive = true)]
+    last: bool,
+
+    /// Language for messages, taken from LANG when not given
+    #[arg(long, global = true, value_parser = i18n::NAMES)]
+    lang: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a ROM (the default when no command is given)
+    Run(Box<RunArgs>),
+    /// Print a listing of a ROM with reachable code decoded and everything else shown as data
+    Disasm { rom: PathBuf },
+    /// Export the control-flow graph of a ROM in Graphviz DOT format
+    Cfg {
+        rom: PathBuf,
+        /// Export the subroutine call graph instead of basic blocks
+        #[arg(long)]
+        calls: bool,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Strip padding from a ROM, checking that it still runs the same
+    Trim {
+        rom: PathBuf,
+        /// Where to write the trimmed ROM (defaults to <ROM>.trim.ch8)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Also close up unreferenced runs of zeroes between code and data, patching addresses
+        #[arg(long)]
+        relocate: bool,
+        /// Number of cycles to run both ROMs for when comparing them
+        #[arg(long, default_value_t = 100_000)]
+        cycles: usize,
+        /// Quirks to run both ROMs with
+        #[arg(long, default_value = "default", long_help = Quirks::help())]
+        quirks: Quirks,
+    },
+    /// Run every ROM in a directory headless, several at once, and print a CSV of what each
+    /// needs and does: the quirks it runs under, opcodes used, draws a second and any crash
+    Analyze {
+        dir: PathBuf,
+        /// 60Hz frames to run each ROM for
+        #[arg(long, default_value_t = 600)]
+        frames: u64,
+        /// ROMs to run at once, defaults to one for each CPU
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Guess which quirks a ROM needs by running it under every combination of them
+    DetectQuirks {
+        rom: PathBuf,
+        /// Number of cycles to run each combination for
+        #[arg(long, default_value_t = 20_000)]
+        cycles: usize,
+    },
+    /// Run a ROM under two sets of quirks in lockstep and report where they first differ
+    Compare {
+        rom: PathBuf,
+        /// Quirks for the first run
+        #[arg(long, default_value = "default", long_help = Quirks::help())]
+        quirks_a: Quirks,
+        /// Quirks for the second run
+        #[arg(long, default_value = "default", long_help = Quirks::help())]
+        quirks_b: Quirks,
+        /// Number of cycles to run for
+        #[arg(long, default_value_t = 100_000)]
+        cycles: usize,
+    },
+    /// Run two ROMs side by side in one window, the second played with 7890/UIOP/JKL;/N,./
+    #[cfg(feature = "sdl")]
+    Split {
+        a: PathBuf,
+        /// Defaults to the first ROM, to compare quirks or race
+        b: Option<PathBuf>,
+        /// Quirks for the left screen
+        #[arg(long, default_value = "default", long_help = Quirks::help())]
+        quirks_a: Quirks,
+        /// Quirks for the right screen
+        #[arg(long, default_value = "default", long_help = Quirks::help())]
+        quirks_b: Quirks,
+    },
+    /// Open a ROM paused in a debugger window, with the game, registers, disassembly, memory,
+    /// breakpoints and the timeline as panels that can be docked anywhere
+    #[cfg(feature = "gui")]
+    Gui {
+        rom: PathBuf,
+        /// Quirks to run the ROM with
+        #[arg(long, default_value = "default", long_help = Quirks::help())]
+        quirks: Quirks,
+    },
+    /// Show the bytes that differ between two ROMs, as disassembly where they're code
+    Diff {
+        a: PathBuf,
+        b: PathBuf,
+        /// Instructions or bytes to show either side of each difference
+        #[arg(short = 'C', long, default_value_t = 3)]
+        context: usize,
+    },
+    /// Find the first instruction where two traces from --trace disagree
+    TraceDiff {
+        a: PathBuf,
+        b: PathBuf,
+        /// Lines of each trace to show either side of the divergence
+        #[arg(short = 'C', long, default_value_t = 5)]
+        context: usize,
+    },
+    /// Show what changed between two save states or crash dumps: registers, memory and the
+    /// screen
+    StateDiff {
+        a: PathBuf,
+        b: PathBuf,
+        /// Also write the screens as a PPM image, with pixels that came on green and ones that
+        /// went off red
+        #[arg(long, value_name = "FILE")]
+        image: Option<PathBuf>,
+    },
+    /// List the ROMs that have been played, most recently first, with how long and how often
+    Played,
+    /// Run a small built in ROM slowly, with a note on what each instruction does and what it
+    /// changed
+    Tour {
+        /// Instructions to run a second
+        #[arg(long, default_value_t = 2.0)]
+        speed: f64,
+        /// Where to show the game, tui draws in the terminal, fb on a Linux console's
+        /// framebuffer with no X or Wayland, and headless prints the last screen at exit
+        #[arg(long, default_value = FRONTENDS[0].0, value_parser = FRONTENDS.map(|(name, _)| name))]
+        frontend: String,
+    },
+    /// Show the palettes --palette can pick from as swatches of their colors
+    Palettes,
+    /// Print a script that completes chip8's commands, options and ROM paths in a shell
+    Completions {
+        #[arg(value_parser = completions::SHELLS)]
+        shell: String,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(skip)]
+    command_line: Vec<String>, // as given, without the program name, for --last
+    #[arg(skip)]
+    fixed: Vec<String>, // of LIVE_OPTIONS and ROM_OPTIONS, the ones the config file can't change
+
+    /// Path to ROM
+    #[arg(required_unless_present_any = ["state", "kiosk", "load"])]
+    rom: Option<PathBuf>,
+    /// Apply an IPS patch to the ROM as it's loaded, leaving the file alone. Can be given more
+    /// than once, the patches are applied in order
+    #[arg(long, value_name = "FILE")]
+    patch: Vec<PathBuf>,
+    /// Load the ROM at this hex address instead of 200, for ROMs assembled elsewhere or code
+    /// fragments. It's run from there too unless --entry says otherwise
+    #[arg(long, value_name = "ADDR", value_parser = address, conflicts_with_all = ["state", "canaries"])]
+    org: Option<u16>,
+    /// Start running at this hex address instead of where the ROM was loaded
+    #[arg(long, value_name = "ADDR", value_parser = address, conflicts_with = "state")]
+    entry: Option<u16>,
+    /// Load a file into memory at a hex address as well as the ROM, e.g. sprites.bin@800. Can be
+    /// given more than once, for code and data kept in files of their own
+    #[arg(long, value_name = "FILE@ADDR", conflicts_with_all = ["state", "canaries"])]
+    load: Vec<Segment>,
+    /// SHA-1s of known-good ROMs in sha1sum's format, to warn about a ROM that's a bad copy of
+    /// one of them. Defaults to roms.sha1 in the data directory, if it's there
+    #[arg(long, value_name = "FILE")]
+    rom_db: Option<PathBuf>,
+    /// Refuse to run a ROM that looks broken, and stop with a crash dump at the first thing --warn
+    /// would warn about, rather than warning
+    #[arg(long)]
+    strict: bool,
+    /// Carry on past common ROM bugs instead of stopping: addresses past the end of memory wrap
+    /// around and a PC on an odd address is moved back to an even one
+    #[arg(long, conflicts_with = "strict")]
+    permissive: bool,
+    /// Boot into a save state or crash dump, which also sets the quirks it was made with
+    #[arg(long, value_name = "FILE")]
+    state: Option<PathBuf>,
+    /// Where to show the game, tui draws in the terminal, fb on a Linux console's framebuffer
+    /// with no X or Wayland, and headless prints the last screen at exit
+    #[arg(long, default_value = FRONTENDS[0].0, value_parser = FRONTENDS.map(|(name, _)| name))]
+    frontend: String,
+    /// Colors to draw the screen in, see chip8 palettes
+    #[arg(long, value_name = "NAME", default_value = "classic")]
+    palette: Palette,
+    /// Turn the picture clockwise by 90, 180 or 270 degrees, for games made to be held on their
+    /// side or a display mounted that way. The keys that move each way on it are shown at start
+    #[arg(long, value_name = "DEGREES", default_value = "0")]
+    rotate: Rotation,
+    /// How the picture fills the window or framebuffer: fit keeps pixels square, stretch fills
+    /// it all and dot-matrix leaves a gap round each pixel like an LED matrix
+    #[arg(long, value_name = "MODE", default_value = "fit")]
+    screen_mode: ScreenMode,
+    /// Play every ROM in this directory in turn, like an arcade machine's attract mode
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["rom", "state", "debug", "run_for", "canaries"])]
+    kiosk: Option<PathBuf>,
+    /// How long each ROM plays for with --kiosk
+    #[arg(long, value_name = "SECS", default_value_t = 60.0)]
+    kiosk_secs: f64,
+    /// With --kiosk, keep a ROM somebody is playing going until no key has been pressed for this
+    /// long
+    #[arg(long, value_name = "SECS")]
+    kiosk_idle: Option<f64>,
+    /// After this long without a key pressed, start the ROM again, or go on to the next one in
+    /// --kiosk with --idle-action next. 0 is off, which a rom.NAME.idle line can set for one ROM
+    #[arg(long, value_name = "SECS", conflicts_with = "debug")]
+    idle: Option<f64>,
+    /// What happens after --idle: reset the ROM, or next for the next ROM in --kiosk
+    #[arg(long, value_parser = IDLE_ACTIONS, default_value = IDLE_ACTIONS[0])]
+    idle_action: String,
+    /// Serve a page at this address, like localhost:8080, where browsers can watch the game live
+    #[arg(long, value_name = "ADDR")]
+    spectate: Option<String>,
+    /// Publish the sound going on and off, sprites drawn and keys to osc://HOST:PORT or
+    /// mqtt://HOST:PORT, for installations that react to the game. A /PREFIX after either is put
+    /// before the addresses or topics, chip8 if it's left out
+    #[cfg(feature = "bridge")]
+    #[arg(long, value_name = "URL")]
+    bridge: Option<String>,
+    /// Show the ROM being played and for how long on the Discord profile of whoever's signed in
+    /// to the Discord client here. APP_ID is the ID of the Discord application to show it as,
+    /// whose name reads as what's being done, like CHIP-8
+    #[cfg(all(feature = "discord", unix))]
+    #[arg(long, value_name = "APP_ID")]
+    discord: Option<String>,
+    /// How long notifications like "state 1 saved" show for, 0 to turn them off
+    #[arg(long, value_name = "SECS", default_value_t = 1.0)]
+    toast_secs: f64,
+    /// Frames to drop after each one shown, or auto to drop them only when the host can't keep
+    /// up. The VM runs in full either way
+    #[arg(long, value_name = "auto|N", default_value = "0")]
+    frameskip: FrameSkip,
+    /// Print statistics about the instructions the ROM ran at exit, and show the instruction and
+    /// frame counts over the game
+    #[arg(long)]
+    stats: bool,
+    /// Keep running when the window loses focus, rather than pausing and going quiet until it's
+    /// back. The debugger always keeps running, since it's typed at in another window
+    #[arg(long)]
+    no_focus_pause: bool,
+    /// Rumble game controllers while the sound timer runs, even when muted
+    #[arg(long)]
+    rumble: bool,
+    /// Flash a border round the screen while the sound timer runs, for playing without sound
+    #[arg(long)]
+    flash_sound: bool,
+    /// Show the keypad over the game with the keys held lit, for streams and tutorials. F12
+    /// turns it on and off either way
+    #[arg(long)]
+    show_keypad: bool,
+    /// The corner of the screen the keypad shows in: top-left, top-right, bottom-left or
+    /// bottom-right
+    #[arg(long, value_name = "CORNER", default_value = "bottom-right")]
+    keypad_corner: Corner,
+    /// Show which keys do anything in the ROM over the game, as far as a look through its code
+    /// can tell
+    #[arg(long)]
+    keypad_hint: bool,
+    /// With the tui frontend, write keys pressed, the sound going on and off, notifications and
+    /// changes to HUD lines as plain lines of text for a screen reader, instead of the screen
+    #[arg(long)]
+    announce: bool,
+    /// Fill memory the ROM doesn't use with a canary byte, and report how much of the stack and
+    /// memory it used at exit
+    #[arg(long, conflicts_with = "state")]
+    canaries: bool,
+    /// Warn about things the ROM does that are likely bugs, like reading memory it never set,
+    /// storing over the font, calling nearly out of stack or drawing off the screen
+    #[arg(long)]
+    warn: bool,
+    /// Fill memory and registers with random garbage at boot, as on real hardware, to catch ROMs
+    /// relying on them being zero, the seed is printed so a run can be repeated
+    #[arg(long, value_name = "SEED", num_args = 0..=1, conflicts_with_all = ["state", "canaries"])]
+    random_init: Option<Option<u64>>,
+    /// Start paused in the debugger, which reads commands from stdin. Its breakpoints and watches
+    /// are kept for the next time the ROM is debugged
+    #[arg(long)]
+    debug: bool,
+    /// Run until N instructions have run, up to cycle N in a --trace, then stop in the debugger.
+    /// The same ROM with the same input, as from a --replay, stops at the same point every time
+    #[arg(long, value_name = "N", conflicts_with = "break_at_frame")]
+    break_at_cycle: Option<u64>,
+    /// Run until N frames have been drawn, then stop in the debugger
+    #[arg(long, value_name = "N")]
+    break_at_frame: Option<u64>,
+    /// Record input so save states carry a replay since the one before, and the debugger can
+    /// rewind
+    #[arg(long)]
+    replay: bool,
+    /// Write a trace of every executed instruction to this file
+    #[arg(long, value_name = "FILE")]
+    trace: Option<PathBuf>,
+    /// At exit, list the bytes of code the ROM overwrote after running them and what wrote them
+    #[arg(long)]
+    trace_smc: bool,
+    /// Start from a bundle of settings: authentic-vip, modern, speedrun or one made with
+    /// preset.NAME.OPTION = VALUE lines in the config file
+    #[arg(long, value_name = "NAME", value_parser = settings::PRESETS.map(|(name, _)| name))]
+    preset: Option<String>,
+    /// Interpreter quirks to emulate, a profile optionally followed by quirks, e.g. chip8,no-clipping
+    #[arg(long, default_value = "default", long_help = Quirks::help())]
+    quirks: Quirks,
+    /// Font for the hex digits: vip, schip, dream6800, eti660, fishnchips or an 80 byte font file
+    #[arg(long, value_name = "NAME|FILE", default_value = "schip", value_parser = fonts::parse)]
+    font: Font,
+    /// Stop after this long in the VM's time, as frames, seconds or cycles, e.g. 600f, 10s or 5000c
+    #[arg(long, value_name = "N[f|s|c]")]
+    run_for: Option<Budget>,
+    /// Stop with an error once the ROM has waited this long for a key (FX0A), in the VM's time, for
+    /// running headless where no key is coming
+    #[arg(long, value_name = "SECS")]
+    key_wait_timeout: Option<f64>,
+    /// Write a save state of the VM here at exit
+    #[arg(long, value_name = "FILE")]
+    dump_state: Option<PathBuf>,
+    /// Print the registers, timers, stack and the instruction at the PC at exit
+    #[arg(long)]
+    dump_on_exit: bool,
+    /// Write the last screen here at exit, as a PBM image
+    #[arg(long, value_name = "FILE")]
+    screenshot: Option<PathBuf>,
+    /// Write the screen to this file or named pipe, or - for stdout, as raw RGB video at 60 frames
+    /// a second of the VM's time, for ffmpeg to encode
+    #[arg(long, value_name = "FILE")]
+    pipe_video: Option<PathBuf>,
+    /// How many pixels across each of the screen's is in --pipe-video
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pipe_video_scale: usize,
+    /// Write the buzzer to this WAV file, muted or not, lined up with --pipe-video
+    #[arg(long, value_name = "FILE")]
+    record_audio: Option<PathBuf>,
+    /// How the buzzer sounds: a clean square wave, or the COSMAC VIP's, a higher tone with a click
+    /// as it starts and stops that short beeps are mostly made of
+    #[arg(long, value_parser = synth::MODELS, default_value = synth::MODELS[0])]
+    buzzer: String,
+    /// Fail with exit code 1 unless this holds at exit: exit for a ROM that ends with 00FD,
+    /// frame=HASH for the screen (the hash is printed when it differs), or an expression like
+    /// mem[0x3FF]==1. Can be given more than once
+    #[arg(long, value_name = "CHECK")]
+    expect: Vec<Expectation>,
+    /// Map the time, a frame counter and random bytes into memory at 0xFF0-0xFFF for homebrew
+    #[arg(long)]
+    ext_mmio: bool,
+}
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!(
-            "Usage: {} <path to ROM>",
-            if !args.is_empty() {
-                &args[0]
+    // the language has to be known before the arguments are parsed, for --help. it's taken out
+    // here since clap won't take arguments before a subcommand, clap only sees it if it's not a
+    // language we have so it can say so
+    let mut lang = None;
+    let mut args = Vec::new();
+    let mut argv = std::env::args();
+    while let Some(arg) = argv.next() {
+        let value = match arg.strip_prefix("--lang=") {
+            Some(value) => Some(value.to_string()),
+            None if arg == "--lang" => argv.next(),
+            None => None,
+        };
+        match value {
+            Some(value) if i18n::NAMES.contains(&value.as_str()) => lang = Some(value),
+            Some(value) => args.extend(["--lang".to_string(), value]),
+            None => args.push(arg),
+        }
+    }
+    if let Some(lang) = lang.or_else(i18n::lang_from_env) {
+        i18n::set_lang(&lang);
+    }
+
+    if let Err(e) = try_main(args) {
+        eprintln!("{}", i18n::any_error(e.as_ref()));
+        std::process::exit(1);
+    }
+}
+
+// translate the help for every command and argument
+fn localize(command: clap::Command) -> clap::Command {
+    let command = match command.get_about().map(|about| about.to_string()) {
+        Some(about) => command.about(tr(&about).to_string()),
+        None => command,
+    };
+    let command = match command.get_after_help().map(|help| help.to_string()) {
+        Some(help) => command.after_help(tr(&help).to_string()),
+        None => command,
+    };
+    command
+        .mut_args(|arg| match arg.get_help().map(|help| help.to_string()) {
+            Some(help) => arg.help(tr(&help).to_string()),
+            None => arg,
+        })
+        .mut_subcommands(localize)
+}
+
+// toast for the keys held for frame advance, e.g. "Holding 5 A"
+fn held_note(held: u16) -> String {
+    match debugger::key_list(held) {
+        keys if keys.is_empty() => tr("Holding no keys").to_string(),
+        keys => trf("Holding {}", &[&keys]),
+    }
+}
+
+// options set from the environment or the config file, e.g. --quirks, and where from
+type Sources = Vec<(String, Source)>;
+
+// options the config file can change while a ROM runs
+const LIVE_OPTIONS: [&str; 4] = ["quirks", "font", "frameskip", "screen-mode"];
+
+// can be set for one ROM with rom.NAME.OPTION lines in the config file, unless they're fixed too
+const ROM_OPTIONS: [&str; 2] = ["idle", "idle-action"];
+
+const IDLE_ACTIONS: [&str; 2] = ["reset", "next"];
+
+fn source_name(source: &Source) -> String {
+    match source {
+        Source::Env(name) => name.clone(),
+        Source::Config => tr("the config file").to_string(),
+        Source::Preset(name) => trf("the {} preset", &[name]),
+    }
+}
+
+// options left off the command line default to settings from the preset, the environment or the
+// config file, the ones run takes both at the top level and under the run command
+fn with_settings(
+    mut command: clap::Command,
+    args: &[String],
+) -> Result<(clap::Command, Sources), Box<dyn Error>> {
+    let config = match config::path() {
+        Some(path) => Config::load(&path)?,
+        None => Config::default(),
+    };
+    let env = |v: &str| std::env::var(v).ok();
+    // a preset that isn't there is left for clap to complain about, once it knows the config's too
+    let names = settings::preset_names(&config);
+    let name = (settings::preset_arg(args).map(str::to_string))
+        .or_else(|| settings::lookup("preset", &config, None, env).map(|(name, _)| name));
+    let preset = name.and_then(|name| settings::preset(&name, &config));
+    command = command
+        .mut_arg("preset", |arg| arg.value_parser(names.clone()))
+        .mut_subcommand("run", |run| {
+            run.mut_arg("preset", |arg| arg.value_parser(names))
+        });
+    let (mut defaults, mut sources) = (Vec::new(), Vec::new());
+    for arg in command.get_arguments() {
+        let option = match arg.get_long() {
+            Some(option) if !["help", "version", "lang", "last"].contains(&option) => option,
+            _ => continue,
+        };
+        if matches!(arg.get_action(), ArgAction::Append | ArgAction::Count) {
+            continue; // there's no one value to default to
+        }
+        let (value, source) = match settings::lookup(option, &config, preset.as_ref(), env) {
+            Some(setting) => setting,
+            None => continue,
+        };
+        // values are checked as the command line is parsed, flags have to be true or false
+        let value = match settings::flag(&value) {
+            _ if arg.get_action().takes_values() => value,
+            Some(on) => on.to_string(),
+            None => {
+                let reason = trf("{} isn't on or off", &[&value]);
+                let source = source_name(&source);
+                return Err(trf("--{} from {}: {}", &[&option, &source, &reason]).into());
+            }
+        };
+        defaults.push((arg.get_id().clone(), value));
+        sources.push((format!("--{}", option), source));
+    }
+    for (id, value) in defaults {
+        let value = clap::builder::OsStr::from(value);
+        command = command
+            .mut_arg(&id, |arg| arg.default_value(value.clone()))
+            .mut_subcommand("run", |run| {
+                run.mut_arg(&id, |arg| arg.default_value(value))
+            });
+    }
+    Ok((command, sources))
+}
+
+fn try_main(args: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let command_line = args.iter().skip(1).cloned().collect();
+    let (command, sources) = with_settings(localize(Cli::command()), &args)?;
+    let matches = command.try_get_matches_from(&args).unwrap_or_else(|e| {
+        let _ = e.print();
+        // a bad value from a setting reads as if it was given as the option
+        if let Some(ContextValue::String(invalid)) = e.get(ContextKind::InvalidArg) {
+            let option = invalid.split(' ').next().unwrap_or_default();
+            if let Some((_, source)) = sources.iter().find(|(o, _)| o == option) {
+                let source = source_name(source);
+                eprintln!("{}", trf("{} came from {}", &[&option, &source]));
+            }
+        }
+        std::process::exit(e.exit_code());
+    });
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // what was given on the command line, in the preset or in the environment stays as it is when
+    // the config file changes
+    let run_matches = matches.subcommand_matches("run").unwrap_or(&matches);
+    let fixed: Vec<String> = (LIVE_OPTIONS.iter().chain(&ROM_OPTIONS))
+        .filter(|&&option| {
+            let env = |(o, s): &(String, Source)| {
+                *o == format!("--{}", option) && matches!(s, Source::Env(_) | Source::Preset(_))
+            };
+            let id = option.replace('-', "_");
+            run_matches.value_source(&id) == Some(ValueSource::CommandLine)
+                || sources.iter().any(env)
+        })
+        .map(|option| option.to_string())
+        .collect();
+    if cli.last {
+        return run_last(&args[0]);
+    }
+    match cli.command {
+        Some(Command::Run(mut args)) => {
+            args.command_line = command_line;
+            args.fixed = fixed;
+            run(&args)?
+        }
+        Some(Command::Disasm { rom }) => {
+            let rom = std::fs::read(&rom)?;
+            print!("{}", Analysis::new(&rom).disassemble());
+        }
+        Some(Command::Cfg { rom, calls, output }) => {
+            let analysis = Analysis::new(&std::fs::read(&rom)?);
+            let dot = if calls {
+                analysis.call_graph_dot()
             } else {
-                "<program>"
+                analysis.cfg_dot()
+            };
+            match output {
+                Some(path) => std::fs::write(path, dot)?,
+                None => print!("{}", dot),
+            }
+        }
+        Some(Command::Trim {
+            rom,
+            output,
+            relocate,
+            cycles,
+            quirks,
+        }) => {
+            let original = std::fs::read(&rom)?;
+            let trimmed = trim::trim(&original, relocate, cycles, quirks)?;
+            let output = output.unwrap_or_else(|| rom.with_extension("trim.ch8"));
+            std::fs::write(&output, &trimmed.rom)?;
+            if relocate && !trimmed.relocated {
+                eprintln!(
+                    "{}",
+                    tr("relocating data changed how the ROM runs, only stripping padding")
+                );
             }
+            println!(
+                "{}",
+                trf(
+                    "{} -> {} bytes ({}), verified over {} cycles and {} frames",
+                    &[
+                        &original.len(),
+                        &trimmed.rom.len(),
+                        &output.display(),
+                        &cycles,
+                        &trimmed.frames
+                    ]
+                )
+            );
+        }
+        Some(Command::Analyze {
+            dir,
+            frames,
+            threads,
+            output,
+        }) => {
+            let roms = kiosk::roms(&dir)?;
+            if roms.is_empty() {
+                return Err(trf("no ROMs in {}", &[&dir.display()]).into());
+            }
+            let threads = threads
+                .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+            let csv = batch::csv(&batch::analyze_all(&roms, frames, threads));
+            match output {
+                Some(path) => std::fs::write(path, csv)?,
+                None => print!("{}", csv),
+            }
+        }
+        Some(Command::DetectQuirks { rom, cycles }) => {
+            let report = detect::detect(&std::fs::read(&rom)?, cycles);
+            let clean = report.clean().count();
+            println!(
+                "{}",
+                trf(
+                    "{} of {} quirk combinations ran {} cycles without errors or hanging",
+                    &[&clean, &report.trials.len(), &cycles]
+                )
+            );
+            for (name, _) in &Quirks::PROFILES {
+                let quirks: Quirks = name.parse()?;
+                if let Some(failure) = report.profile(quirks).and_then(|t| t.failure()) {
+                    println!("  {}: {}", name, failure);
+                }
+            }
+            let required = report.required();
+            if !required.is_empty() {
+                let names: Vec<String> = required
+                    .iter()
+                    .map(|(name, on)| format!("{}{}", if *on { "" } else { "no-" }, name))
+                    .collect();
+                println!("{}", trf("needs: {}", &[&names.join(", ")]));
+            }
+            match report.suggestion() {
+                Some(quirks) => println!("{}", trf("suggested: --quirks {}", &[&quirks])),
+                None => return Err(tr("no combination of quirks ran cleanly").into()),
+            }
+        }
+        Some(Command::Compare {
+            rom,
+            quirks_a,
+            quirks_b,
+            cycles,
+        }) => {
+            let comparison = compare::compare(&std::fs::read(&rom)?, cycles, quirks_a, quirks_b);
+            print!("{}", comparison);
+            if comparison.divergence.is_some() {
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "sdl")]
+        Some(Command::Split {
+            a,
+            b,
+            quirks_a,
+            quirks_b,
+        }) => {
+            let b = b.as_ref().unwrap_or(&a);
+            split(&[(&a, quirks_a), (b, quirks_b)])?;
+        }
+        #[cfg(feature = "gui")]
+        Some(Command::Gui { rom, quirks }) => {
+            let mut emu = chip8::Chip8::new();
+            emu.set_quirks(quirks);
+            emu.load_rom(&std::fs::read(&rom)?);
+            let title = format!("chip8 - {}", rom.display());
+            chip8::gui::run(chip8::panels::Session::new(emu), &title)?;
+        }
+        Some(Command::Tour { speed, frontend }) => tour(speed, &frontend)?,
+        Some(Command::Diff { a, b, context }) => {
+            let (a_rom, b_rom) = (std::fs::read(&a)?, std::fs::read(&b)?);
+            let (a, b) = (a.display().to_string(), b.display().to_string());
+            let report = romdiff::report(&a_rom, &b_rom, &a, &b, context);
+            print!("{}", report);
+            if !report.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::TraceDiff { a, b, context }) => {
+            let a_steps = tracediff::parse(&std::fs::read_to_string(&a)?)?;
+            let b_steps = tracediff::parse(&std::fs::read_to_string(&b)?)?;
+            let diff = tracediff::diff(&a_steps, &b_steps);
+            let (a, b) = (a.display().to_string(), b.display().to_string());
+            print!("{}", diff.report(&a, &b, context));
+            if diff.diverged().is_some() {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::StateDiff { a, b, image }) => {
+            let a_vm = statediff::machine(&std::fs::read(&a)?)?;
+            let b_vm = statediff::machine(&std::fs::read(&b)?)?;
+            if let Some(path) = image {
+                std::fs::write(path, statediff::screen_image(&a_vm, &b_vm))?;
+            }
+            let (a, b) = (a.display().to_string(), b.display().to_string());
+            let report = statediff::report(&a_vm, &b_vm, &a, &b);
+            print!("{}", report);
+            if !report.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Completions { shell }) => {
+            print!("{}", completions::script(&shell, localize(Cli::command())));
+        }
+        Some(Command::Palettes) => {
+            println!(
+                "{}",
+                tr("each shows off, then lit on XO-CHIP's first plane, its second and both")
+            );
+            for palette in &palette::PALETTES {
+                print!("\n{}", frontend::swatches(palette));
+            }
+        }
+        Some(Command::Played) => {
+            if let Some(path) = playtime::path() {
+                print!("{}", Playtime::load(&path)?.report());
+            }
+        }
+        None => match cli.run {
+            Some(mut args) => {
+                args.command_line = command_line;
+                args.fixed = fixed;
+                run(&args)?
+            }
+            None => {
+                let _ = localize(Cli::command()).print_help();
+                std::process::exit(1);
+            }
+        },
+    }
+    Ok(())
+}
+
+type FrontendFn = fn(&frontend::Options) -> Result<Box<dyn Frontend>, Box<dyn Error>>;
+
+// every frontend run --frontend can pick, the first is the default
+const FRONTENDS: [(&str, FrontendFn);
+    3 + cfg!(feature = "sdl") as usize
+        + cfg!(target_os = "linux") as usize
+        + cfg!(feature = "testing") as usize] = [
+    #[cfg(feature = "sdl")]
+    ("sdl", |options| Ok(Box::new(Sdl::new(options)?))),
+    ("tui", |options| Ok(Box::new(Tui::new(options)))),
+    #[cfg(target_os = "linux")]
+    ("fb", |options| Ok(Box::new(Fb::new(options)?))),
+    ("headless", |_| Ok(Box::new(Headless))),
+    ("null", |_| Ok(Box::new(Null))),
+    #[cfg(feature = "testing")]
+    ("script", |_| Ok(Box::new(frontend::Script::from_stdin()))), // for the tests, see Script
+];
+
+const STATE_SLOTS: usize = 10;
+
+// where quick save slot `slot` for the ROM at rom_path lives
+fn slot_path(rom_path: &Path, slot: usize) -> Option<PathBuf> {
+    let stem = rom_path
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_default();
+    Some(
+        dirs::data_dir()?
+            .join("states")
+            .join(format!("{}.{}.c8st", stem, slot)),
+    )
+}
+
+// the debugger is reading stdin, now or once a --break-at stops it
+fn debugging(args: &RunArgs) -> bool {
+    args.debug || args.break_at_cycle.is_some() || args.break_at_frame.is_some()
+}
+
+fn buzzer(args: &RunArgs) -> Result<Model, String> {
+    Model::from_name(&args.buzzer).ok_or_else(|| trf("bad --buzzer {}", &[&args.buzzer]))
+}
+
+// --pipe-video's output, with how to encode it
+fn video_pipe(args: &RunArgs, path: &Path) -> Result<VideoPipe<Box<dyn Write>>, Box<dyn Error>> {
+    if args.pipe_video_scale == 0 {
+        return Err(trf("bad --pipe-video-scale {}", &[&args.pipe_video_scale]).into());
+    }
+    let out: Box<dyn Write> = if path == Path::new("-") {
+        if args.frontend == "tui" || args.frontend == "headless" || debugging(args) {
+            return Err(tr("--pipe-video - needs stdout, which the tui and headless frontends and the debugger use").into());
+        }
+        Box::new(BufWriter::new(std::io::stdout()))
+    } else {
+        Box::new(BufWriter::new(File::create(path)?))
+    };
+    let video = VideoPipe::new(out, args.pipe_video_scale).with_palette(args.palette);
+    let command = video.command(&path.display().to_string(), "out.mp4");
+    eprintln!("{}", trf("encode the video with: {}", &[&command]));
+    Ok(video)
+}
+
+// where the debugger's breakpoints and watches for a ROM are kept between runs
+fn session_path(rom_sha1: &[u8; 20]) -> Option<PathBuf> {
+    if *rom_sha1 == [0; 20] {
+        return None; // no ROM to keep them for
+    }
+    Some(
+        dirs::data_dir()?
+            .join("debug")
+            .join(format!("{}.txt", sha1::hex(rom_sha1))),
+    )
+}
+
+// write text to path, or remove the file when there's nothing to keep
+fn save_or_remove(path: &Path, text: &str) -> std::io::Result<()> {
+    if text.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, text)
+}
+
+// quick save, returning what to tell the user
+fn save_slot(emu: &mut chip8::Chip8, debugger: &mut Debugger, path: &Path, slot: usize) -> String {
+    let state = match &mut debugger.recorder {
+        Some(recorder) => recorder.save_state(emu),
+        None => emu.save_state(),
+    };
+    let written = match path.parent() {
+        Some(dir) => std::fs::create_dir_all(dir).and_then(|_| std::fs::write(path, state)),
+        None => std::fs::write(path, state),
+    };
+    match written {
+        Ok(()) => trf("State {} saved", &[&slot]),
+        Err(e) => trf("State {} not saved: {}", &[&slot, &e]),
+    }
+}
+
+// bind a just recorded macro to key and save it in the config
+fn bind_macro(config: &mut Config, path: Option<&Path>, key: &str, recorded: Macro) -> String {
+    if recorded.steps.is_empty() {
+        return tr("Nothing recorded for the macro").to_string();
+    }
+    config.set(&format!("macro.{}", key), &recorded.to_string());
+    match path.map(|path| config.save(path)) {
+        Some(Ok(())) => trf("Macro bound to {}", &[&key]),
+        Some(Err(e)) => trf("Macro on {} not saved: {}", &[&key, &e]),
+        None => tr("No config directory, set HOME").to_string(),
+    }
+}
+
+// take up a changed config file: its macros, and the LIVE_OPTIONS in it that the command line and
+// the environment didn't set. nothing changes unless the whole file is good
+fn reload_config(
+    path: &Path,
+    args: &RunArgs,
+    emu: &mut chip8::Chip8,
+    skipper: &mut FrameSkipper,
+    frontend: &mut dyn Frontend,
+) -> Result<Config, String> {
+    let config = Config::load(path).map_err(|e| e.to_string())?;
+    let quirks = live_setting(&config, &args.fixed, "quirks", str::parse::<Quirks>)?;
+    let font = live_setting(&config, &args.fixed, "font", fonts::parse)?;
+    let frameskip = live_setting(&config, &args.fixed, "frameskip", str::parse::<FrameSkip>)?;
+    let mode = live_setting(
+        &config,
+        &args.fixed,
+        "screen-mode",
+        str::parse::<ScreenMode>,
+    )?;
+    if let Some(quirks) = quirks {
+        emu.set_quirks(quirks);
+    }
+    if let Some(font) = font {
+        emu.set_font(&font);
+    }
+    if let Some(frameskip) = frameskip {
+        *skipper = FrameSkipper::new(frameskip);
+    }
+    if let Some(mode) = mode {
+        frontend.set_screen_mode(mode);
+    }
+    Ok(config)
+}
+
+// how long --idle waits for a ROM and whether it goes on to the next ROM rather than resetting,
+// from rom.NAME lines in the config file if there are any and the options aren't fixed. None if
+// it's off
+fn idle_setting(
+    args: &RunArgs,
+    config: &Config,
+    rom: &Path,
+) -> Result<Option<(Duration, bool)>, String> {
+    let rom_option = |option: &str| {
+        settings::rom_setting(option, config, rom)
+            .filter(|_| !args.fixed.iter().any(|f| f == option))
+    };
+    let secs = match rom_option("idle") {
+        Some(value) => Some(
+            value
+                .parse::<f64>()
+                .map_err(|_| trf("bad --idle {}", &[&value]))?,
+        ),
+        None => args.idle,
+    };
+    let action = rom_option("idle-action").unwrap_or(&args.idle_action);
+    if !IDLE_ACTIONS.contains(&action) {
+        return Err(trf("bad --idle-action {}", &[&action]));
+    }
+    let after = match secs.map(Duration::try_from_secs_f64) {
+        None => return Ok(None),
+        Some(Ok(after)) if after.is_zero() => return Ok(None),
+        Some(Ok(after)) => after,
+        Some(Err(_)) => return Err(trf("bad --idle {}", &[&secs.unwrap()])),
+    };
+    let next = action == "next";
+    if next && args.kiosk.is_none() {
+        return Err(tr("--idle-action next is for --kiosk").to_string());
+    }
+    Ok(Some((after, next)))
+}
+
+// one of LIVE_OPTIONS from the config file, None if it's not there or it's fixed
+fn live_setting<T>(
+    config: &Config,
+    fixed: &[String],
+    option: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<Option<T>, String> {
+    match config.get(option) {
+        Some(value) if !fixed.iter().any(|f| f == option) => {
+            (parse(value).map(Some)).map_err(|e| format!("{}: {}", option, e))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn load_slot(emu: &mut chip8::Chip8, debugger: &mut Debugger, path: &Path, slot: usize) -> String {
+    let state = match std::fs::read(path) {
+        Ok(state) => state,
+        Err(_) => return trf("No state in slot {}", &[&slot]),
+    };
+    match load_state(emu, debugger, &state, false) {
+        Ok(()) => trf("State {} loaded", &[&slot]),
+        Err(e) => trf(
+            "State {} not loaded: {}",
+            &[&slot, &i18n::any_error(e.as_ref())],
+        ),
+    }
+}
+
+fn run(args: &RunArgs) -> Result<(), Box<dyn Error>> {
+    let make = FRONTENDS
+        .iter()
+        .find(|(name, _)| *name == args.frontend)
+        .map(|(_, make)| make)
+        .ok_or_else(|| trf("unknown frontend {}", &[&args.frontend]))?;
+
+    if let Some(secs) = args
+        .key_wait_timeout
+        .filter(|s| !(s.is_finite() && *s >= 0.0))
+    {
+        return Err(trf("bad --key-wait-timeout {}", &[&secs]).into());
+    }
+    let (mut emu, mut debugger, mut rom_sha1) = boot(args)?;
+    let mut kiosk = match &args.kiosk {
+        Some(dir) => {
+            let mut kiosk = Kiosk::new(kiosk::roms(dir)?, args.kiosk_secs, args.kiosk_idle);
+            if kiosk.is_empty() {
+                return Err(trf("no ROMs in {}", &[&dir.display()]).into());
+            }
+            (emu, rom_sha1) = kiosk_machine(args, &mut kiosk)?;
+            Some(kiosk)
+        }
+        None => None,
+    };
+    if let Some(n) = args.break_at_cycle {
+        debugger.break_at_cycle(&emu, n)?;
+    }
+    if let Some(n) = args.break_at_frame {
+        debugger.break_at_frame(&emu, n)?;
+    }
+    if args.frontend == "tui" && (debugger.paused() || debugging(args)) {
+        return Err(tr("the debugger reads stdin, which the tui frontend needs for keys").into());
+    }
+    if args.announce && args.frontend != "tui" {
+        return Err(tr("--announce is for the tui frontend").into());
+    }
+    if args.rotate != Rotation::None {
+        if args.frontend != "sdl" {
+            return Err(tr("--rotate is for the sdl frontend").into());
+        }
+        let [up, down, left, right] = args
+            .rotate
+            .directions()
+            .map(|k| format!("{} ({})", k, frontend::QWERTY[k].to_ascii_uppercase()));
+        eprintln!(
+            "{}",
+            trf(
+                "turned {} degrees: for games that move with 2, 8, 4 and 6, up is {}, down {}, left {} and right {}",
+                &[&args.rotate, &up, &down, &left, &right]
+            )
         );
-        std::process::exit(1);
     }
+    let mut cheats_path = load_cheats(&mut debugger, &rom_sha1)?;
+    // pick the last debugging session on this ROM back up
+    let session = session_path(&rom_sha1).filter(|_| debugging(args));
+    if let Some(path) = &session {
+        match std::fs::read_to_string(path) {
+            Ok(saved) => debugger
+                .restore(&mut emu, &saved)
+                .map_err(|e| format!("{}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let mut name = match &kiosk {
+        Some(kiosk) => kiosk.current().to_path_buf(),
+        None => (args.rom.clone().or_else(|| args.state.clone()))
+            .or_else(|| args.load.first().map(|s| s.path.clone()))
+            .unwrap(),
+    };
+    let mut spectators = match &args.spectate {
+        Some(addr) => {
+            let server = spectate::Server::bind(addr)
+                .map_err(|e| trf("couldn't listen on {}: {}", &[addr, &e]))?;
+            eprintln!("{}", trf("watch at http://{}/", &[&server.addr()]));
+            Some(server)
+        }
+        None => None,
+    };
+    let options = frontend::Options {
+        toast_time: Duration::try_from_secs_f64(args.toast_secs)
+            .map_err(|_| trf("bad --toast-secs {}", &[&args.toast_secs]))?,
+        announce: args.announce,
+        buzzer: buzzer(args)?,
+        palette: args.palette,
+        rotation: args.rotate,
+        keypad_corner: args.keypad_corner,
+        screen_mode: args.screen_mode,
+        ..Default::default()
+    };
+    let mut frontend = make(&options)?;
+    let watched = !frontend.exit_on_halt(); // a run somebody played, to count in the playtime
+    if let (Some(rom), Some(path)) = (args.rom.as_ref().filter(|_| watched), recent::path()) {
+        if let Err(e) = remember_run(&path, rom, &args.command_line) {
+            eprintln!("{}", trf("couldn't save the recent ROMs: {}", &[&e]));
+        }
+    }
+    let mut kept = Kept::load(&rom_sha1, watched, &debugger.cheats.labels)?;
+    frontend.present(emu.screen());
+    if kiosk.is_some() {
+        frontend.notify(&rom_title(&name));
+    }
+    shutdown::install();
 
-    let keypad = [
-        Scancode::X,    // 0
-        Scancode::Num1, // 1
-        Scancode::Num2, // 2
-        Scancode::Num3, // 3
-        Scancode::Q,    // 4
-        Scancode::W,    // 5
-        Scancode::E,    // 6
-        Scancode::A,    // 7
-        Scancode::S,    // 8
-        Scancode::D,    // 9
-        Scancode::Z,    // A
-        Scancode::C,    // B
-        Scancode::Num4, // C
-        Scancode::R,    // D
-        Scancode::F,    // E
-        Scancode::V,    // F
-    ];
-
-    let sdl_ctx = sdl2::init().unwrap();
-    let video = sdl_ctx.video().unwrap();
-
-    let scale = 4;
-    let window = video
-        .window("CHIP-8", 64 * scale, 32 * scale)
-        .position_centered()
-        .build()
-        .unwrap();
-    let mut canvas = window.into_canvas().build().unwrap();
-
-    let black = pixels::Color::RGB(0, 0, 0);
-    let white = pixels::Color::RGB(255, 255, 255);
-    canvas.set_draw_color(black);
-    canvas.clear();
-    canvas.present();
-
-    let audio_subsystem = sdl_ctx.audio().unwrap();
-    let audio_spec = AudioSpecDesired {
-        freq: Some(44_100),
-        channels: Some(1), // mono
-        samples: None,     // default
-    };
-    let audio_device = audio_subsystem
-        .open_playback(None, &audio_spec, |spec| audio::SquareWave {
-            phase_inc: 440.0 / spec.freq as f32,
-            phase: 0.0,
-            volume: 0.25,
-        })
-        .unwrap();
-    let mut audio_playing = false;
+    let mut tracer = match &args.trace {
+        Some(path) => Some(
+            Tracer::new(BufWriter::new(File::create(path)?))
+                .with_labels(debugger.cheats.labels.clone()),
+        ),
+        None => None,
+    };
+    let mut video = match &args.pipe_video {
+        Some(path) => Some(video_pipe(args, path)?),
+        None => None,
+    };
+    let mut audio = match &args.record_audio {
+        Some(path) => Some(WavWriter::new(
+            BufWriter::new(File::create(path)?),
+            buzzer(args)?,
+        )?),
+        None => None,
+    };
+    let mut smc = args.trace_smc.then(SelfModifyLog::default);
+    let mut stats = if args.stats {
+        Some(Stats::default())
+    } else {
+        None
+    };
+    let mut canaries = match &args.rom {
+        Some(rom) if args.canaries => Some(Canaries::new(
+            &mut emu,
+            std::fs::metadata(rom)?.len() as usize,
+        )),
+        _ => None,
+    };
 
-    let mut event_pump = sdl_ctx.event_pump().unwrap();
+    #[cfg(feature = "bridge")]
+    let mut bridge = match &args.bridge {
+        Some(url) => {
+            Some(Bridge::connect(url).map_err(|e| trf("bad --bridge {}: {}", &[url, &e]))?)
+        }
+        None => None,
+    };
+    #[cfg(not(feature = "bridge"))]
+    let mut bridge: Option<()> = None; // nothing to publish to without the feature
 
-    let mut emu = chip8::Chip8::new();
-    emu.load_game(&args[1]).unwrap();
+    #[cfg(all(feature = "discord", unix))]
+    let mut presence = match args.discord.as_ref().filter(|_| watched) {
+        Some(app) => match Presence::connect(app) {
+            Ok(mut presence) => {
+                let (title, played) = presence_text(args, &name, &rom_sha1);
+                presence.set(&title, played.as_deref(), unix_time());
+                Some(presence)
+            }
+            Err(e) => {
+                eprintln!("{}", trf("couldn't reach Discord: {}", &[&e]));
+                None
+            }
+        },
+        None => None,
+    };
 
+    let mut keypad_hint = Vec::new();
+    if args.keypad_hint {
+        let analysis = Analysis::new(&emu.memory()[analysis::ENTRY as usize..]);
+        keypad_hint = frontend::keypad_hint(analysis.keys, analysis.any_key);
+    }
+
+    let mut diagnostics = None;
+    if args.warn || args.strict {
+        // after booting a state there's no telling what the ROM has set
+        let rom_len = match (&args.rom, &args.state) {
+            // nor, as far as the diagnostics know, with it loaded somewhere other than 200 or
+            // alongside other files
+            (Some(_), None) if args.org.is_some() || !args.load.is_empty() => None,
+            (Some(rom), None) => Some(std::fs::metadata(rom)?.len() as usize),
+            _ => None,
+        };
+        diagnostics = Some(Diagnostics::new(&emu, rom_len));
+    }
+
+    // stdin is read on its own thread so the window stays responsive while paused
+    let commands = if debugger.paused() || debugging(args) {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Some(rx)
+    } else {
+        None
+    };
+    let config_path = config::path();
+    let mut config = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let mut watcher =
+        (config_path.as_deref()).map(|path| config::Watcher::new(path, Instant::now()));
+    // when a key was last pressed in the ROM, for --idle and --kiosk-idle, and when it started
+    let (mut pressed, mut started) = (None, Instant::now());
+    let mut idle = idle_setting(args, &config, &name)?;
+    let mut reset_state = idle.map(|_| emu.save_state());
+    let mut recording: Option<MacroRecorder> = None;
+    let mut playing: Option<MacroPlayer> = None;
+    let mut frame = emu.ticks(); // the last frame macros were fed
+
+    let mut prompted = false;
+    let mut muted = false;
+    let mut unfocused = false; // paused until the window has focus again
+    let mut paused = false; // by the pause hotkey, showing the high scores
+    let mut show_keypad = args.show_keypad;
+    let mut slot = 1;
+
+    let mut elapsed = Elapsed::default(); // for --run-for and the playtime
+    let mut scheduler = Scheduler::new(CYCLE_TIME, MAX_CATCH_UP);
+    let mut skipper = FrameSkipper::new(args.frameskip);
+    let mut result: Result<(), Box<dyn Error>> = Ok(());
     'main: loop {
-        emu.emulate_cycle();
+        scheduler.wait(1);
 
-        if emu.draw_flag() {
-            let gfx = emu.gfx();
-            canvas.set_draw_color(black);
-            canvas.clear();
-            canvas.set_draw_color(white);
-            let mut rects = Vec::new();
-            for (i, p) in gfx.iter().enumerate() {
-                if *p == 0 {
+        let mut stamped = Stamped {
+            machine: &mut emu,
+            pressed: &mut pressed,
+        };
+        for hotkey in frontend.poll(&mut stamped) {
+            let note = match hotkey {
+                Hotkey::Quit => break 'main,
+                Hotkey::Focus(focused) => {
+                    unfocused = !focused && !args.no_focus_pause && !debugging(args);
+                    // the time away doesn't count as idle
+                    started = Instant::now();
+                    pressed = pressed.map(|_| started);
                     continue;
                 }
-                let i = i as i32;
-                let x = (i % 64) * scale as i32;
-                let y = (i / 64) * scale as i32;
-                rects.push(Rect::new(x, y, scale, scale));
+                Hotkey::Mute => {
+                    muted = !muted;
+                    tr(if muted { "Muted" } else { "Unmuted" }).to_string()
+                }
+                Hotkey::NextSlot | Hotkey::PrevSlot => {
+                    let step = if hotkey == Hotkey::NextSlot {
+                        1
+                    } else {
+                        STATE_SLOTS - 1
+                    };
+                    slot = (slot + step) % STATE_SLOTS;
+                    trf("Slot {}", &[&slot])
+                }
+                Hotkey::SaveState => match slot_path(&name, slot) {
+                    Some(path) => save_slot(&mut emu, &mut debugger, &path, slot),
+                    None => tr("No data directory, set HOME").to_string(),
+                },
+                Hotkey::LoadState => match slot_path(&name, slot) {
+                    Some(path) => {
+                        let note = load_slot(&mut emu, &mut debugger, &path, slot);
+                        kept.goals.reset();
+                        scheduler.reset(Instant::now());
+                        frontend.present(emu.screen());
+                        note
+                    }
+                    None => tr("No data directory, set HOME").to_string(),
+                },
+                Hotkey::Cheats => {
+                    let cheats = &mut debugger.cheats;
+                    if cheats.is_empty() {
+                        tr("No cheats for this ROM").to_string()
+                    } else {
+                        cheats.enabled = !cheats.enabled;
+                        tr(if cheats.enabled {
+                            "Cheats on"
+                        } else {
+                            "Cheats off"
+                        })
+                        .to_string()
+                    }
+                }
+                Hotkey::Pause => {
+                    paused = !paused;
+                    if paused {
+                        let board = debugger
+                            .cheats
+                            .score(&emu)
+                            .map(|_| kept.high_scores.board());
+                        let mut lines = vec![tr("Paused").to_string()];
+                        lines.extend(board.into_iter().flatten());
+                        if !kept.goals.is_empty() {
+                            let got = kept.unlocked.entries().len();
+                            let of = kept.goals.defined().len();
+                            lines.push(trf("Achievements {} of {}", &[&got, &of]));
+                        }
+                        frontend.watches(&lines);
+                    } else {
+                        started = Instant::now();
+                        pressed = pressed.map(|_| started);
+                    }
+                    tr(if paused { "Paused" } else { "Carrying on" }).to_string()
+                }
+                Hotkey::Keypad => {
+                    show_keypad = !show_keypad;
+                    frontend.keys(Some(emu.keys()).filter(|_| show_keypad));
+                    continue;
+                }
+                Hotkey::RecordMacro => match recording.take() {
+                    Some(_) => tr("Macro recording cancelled").to_string(),
+                    None => {
+                        recording = Some(MacroRecorder::default());
+                        tr("Recording macro, press F1-F4 to bind it").to_string()
+                    }
+                },
+                Hotkey::Macro(n) => {
+                    let key = frontend::MACRO_KEYS[n];
+                    match recording.take() {
+                        Some(recorder) => {
+                            let note = bind_macro(
+                                &mut config,
+                                config_path.as_deref(),
+                                key,
+                                recorder.finish(),
+                            );
+                            if let Some(watcher) = &mut watcher {
+                                watcher.saw_write(); // not a change to reload
+                            }
+                            note
+                        }
+                        None => match config.get(&format!("macro.{}", key)).map(Macro::parse) {
+                            Some(Ok(m)) => {
+                                playing = Some(MacroPlayer::new(m));
+                                continue;
+                            }
+                            Some(Err(e)) => trf("Macro on {} not played: {}", &[&key, &e]),
+                            None => trf("No macro on {}", &[&key]),
+                        },
+                    }
+                }
+                _ => continue, // a frontend newer than this loop
+            };
+            frontend.notify(&note);
+        }
+        if let (Some(path), Some(watcher)) = (&config_path, &mut watcher) {
+            if watcher.changed(Instant::now()) {
+                let note = match reload_config(path, args, &mut emu, &mut skipper, &mut *frontend) {
+                    Ok(reloaded) => {
+                        config = reloaded;
+                        tr("Config reloaded").to_string()
+                    }
+                    Err(e) => trf("Config not reloaded: {}", &[&e]),
+                };
+                frontend.notify(&note);
             }
-            canvas.fill_rects(&rects).unwrap();
-            canvas.present();
+        }
+        if shutdown::requested() || frontend.exit_on_halt() && emu.halted() {
+            break;
+        }
+        if unfocused || paused {
+            // the time away isn't something to catch up on either
+            scheduler.reset(Instant::now());
+            emu.resync_clock();
+            frontend.buzzer(false);
+            frontend.rumble(false);
+            frontend.flash(false);
+            continue;
         }
 
-        if audio_playing != emu.sound_flag() {
-            if emu.sound_flag() {
-                audio_playing = true;
-                audio_device.resume();
-            } else {
-                audio_playing = false;
-                audio_device.pause();
+        let quiet = pressed.unwrap_or(started).elapsed(); // for --idle
+        if let Some(kiosk) = &mut kiosk {
+            if kiosk.wiping() {
+                match kiosk.wipe_frame(Instant::now()) {
+                    Some(frame) => frontend.present(&frame),
+                    None => {
+                        kiosk.advance();
+                        (emu, rom_sha1) = kiosk_machine(args, kiosk)?;
+                        name = kiosk.current().to_path_buf();
+                        idle = idle_setting(args, &config, &name)?;
+                        reset_state = idle.map(|_| emu.save_state());
+                        (pressed, started) = (None, Instant::now());
+                        // the last ROM's cheats, scores and achievements kept, and this one's
+                        // picked up
+                        save_cheats(cheats_path.as_deref(), &debugger);
+                        kept.save();
+                        debugger = Debugger::default();
+                        cheats_path = load_cheats(&mut debugger, &rom_sha1)?;
+                        kept = Kept::load(&rom_sha1, watched, &debugger.cheats.labels)?;
+                        diagnostics = diagnostics.map(|_| Diagnostics::new(&emu, None));
+                        if args.keypad_hint {
+                            let analysis = Analysis::new(&emu.memory()[analysis::ENTRY as usize..]);
+                            keypad_hint = frontend::keypad_hint(analysis.keys, analysis.any_key);
+                        }
+                        frame = emu.ticks();
+                        scheduler.reset(Instant::now());
+                        frontend.present(emu.screen());
+                        frontend.notify(&rom_title(&name));
+                        #[cfg(all(feature = "discord", unix))]
+                        if let Some(presence) = &mut presence {
+                            let (title, played) = presence_text(args, &name, &rom_sha1);
+                            presence.set(&title, played.as_deref(), unix_time());
+                        }
+                    }
+                }
+                continue;
+            }
+            let next = idle.is_some_and(|(after, next)| next && quiet >= after);
+            if next || kiosk.due(emu.ticks(), pressed.map(|p| p.elapsed())) {
+                kiosk.start_wipe(emu.screen(), Instant::now());
+                frontend.buzzer(false);
+                frontend.rumble(false);
+                frontend.flash(false);
+                continue;
+            }
+        }
+        if let Some(state) = reset_state
+            .as_ref()
+            .filter(|_| idle.is_some_and(|(after, next)| !next && quiet >= after))
+        {
+            // attract mode: back to the ROM's title for whoever comes along next
+            load_state(&mut emu, &mut debugger, state, false)?;
+            frame = emu.ticks();
+            (pressed, started) = (None, Instant::now());
+            scheduler.reset(Instant::now());
+            frontend.present(emu.screen());
+            frontend.notify(tr("Reset after no input"));
+        }
+
+        let mut drawn = false;
+        let due = scheduler.due(Instant::now());
+        let behind = due > CYCLES_PER_TICK as u64; // more than a frame late
+        for _ in 0..due {
+            if args.run_for.is_some_and(|budget| budget.spent(&elapsed)) {
+                break 'main;
+            }
+            if debugger.should_pause(&emu) {
+                break;
+            }
+            let observer = &mut (
+                &mut smc,
+                (
+                    &mut tracer,
+                    (
+                        &mut debugger,
+                        (&mut stats, (&mut canaries, (&mut diagnostics, &mut bridge))),
+                    ),
+                ),
+            );
+            let before = (emu.cycles(), emu.ticks());
+            let mut failed = (emu.emulate_cycle_observed(observer).err())
+                .map(|e| -> Box<dyn Error> { Box::new(e) });
+            elapsed.add(&emu, before);
+            for warning in diagnostics.iter_mut().flat_map(|d| d.warnings.drain(..)) {
+                if args.strict {
+                    failed.get_or_insert(Box::new(warning));
+                } else {
+                    eprintln!("{}", trf("warning: {}", &[&warning]));
+                }
+            }
+            if let Some(e) = failed {
+                let dump = Dump::new(&mut emu, rom_sha1, &e, &debugger.history);
+                match dump.write(&name) {
+                    Ok(path) => {
+                        eprintln!("{}", trf("crash dump written to {}", &[&path.display()]))
+                    }
+                    Err(err) => eprintln!("{}", trf("couldn't write crash dump: {}", &[&err])),
+                }
+                result = Err(e);
+                break 'main;
+            }
+            drawn |= emu.draw_flag();
+        }
+
+        if debugger.stopped() {
+            // time spent paused isn't something to catch up on
+            scheduler.reset(Instant::now());
+            emu.resync_clock();
+            if !prompted {
+                print!("{}(chip8) ", debugger.location(&emu));
+                let _ = std::io::stdout().flush();
+                prompted = true;
+            }
+            if let Some(commands) = &commands {
+                match commands.try_recv() {
+                    Ok(line) => {
+                        let held = debugger.held_keys();
+                        let (action, out) = debugger.command(&mut emu, &line);
+                        print!("{}", out);
+                        if debugger.held_keys() != held {
+                            frontend.notify(&held_note(debugger.held_keys()));
+                        }
+                        match action {
+                            Action::Quit => break,
+                            Action::Run => prompted = false,
+                            Action::Pause => print!("(chip8) "),
+                        }
+                        let _ = std::io::stdout().flush();
+                    }
+                    Err(mpsc::TryRecvError::Disconnected) => break,
+                    Err(mpsc::TryRecvError::Empty) => {}
+                }
+            }
+        }
+
+        // macros are recorded and played back a frame at a time, and cheats applied
+        let ticks = emu.ticks();
+        if ticks != frame {
+            debugger.cheats.apply(&mut emu);
+            let over = (debugger.cheats.score(&emu)).and_then(|score| kept.game.frame(score));
+            if let Some(score) = over.filter(|_| kept.scores_path.is_some()) {
+                if let Some(place) = kept.high_scores.record(score, unix_time()) {
+                    frontend.notify(&trf("High score {}: {}", &[&place, &score]));
+                    kept.scored = true;
+                }
+            }
+            if !debugger.cheats.cheating() {
+                for name in (kept.goals).frame(&emu, ticks.saturating_sub(frame), &kept.unlocked) {
+                    kept.unlocked.unlock(&name, unix_time());
+                    frontend.notify(&trf("Achievement unlocked: {}", &[&name]));
+                }
+            }
+            if let Some(server) = &mut spectators {
+                server.update(emu.screen(), emu.sound_flag());
+            }
+            let piped = video
+                .as_mut()
+                .map(|v| v.frames(emu.screen(), ticks.saturating_sub(frame)));
+            if let Some(Err(e)) = piped {
+                eprintln!("{}", trf("the video pipe closed: {}", &[&e]));
+                video = None;
+            }
+            let recorded =
+                (audio.as_mut()).map(|a| a.frames(emu.sound_flag(), ticks.saturating_sub(frame)));
+            if let Some(Err(e)) = recorded {
+                eprintln!("{}", trf("couldn't write the audio: {}", &[&e]));
+                audio = None;
+            }
+        }
+        for _ in 0..ticks.saturating_sub(frame) {
+            if let Some(recorder) = &mut recording {
+                recorder.frame(emu.keys());
+            }
+            if let Some(player) = &mut playing {
+                match player.frame() {
+                    Some(keys) => emu.set_keys(keys),
+                    None => {
+                        emu.set_keys(0);
+                        playing = None;
+                    }
+                }
+            }
+        }
+        frame = ticks;
+
+        let mut overlay = debugger.cheats.hud(&emu);
+        overlay.extend(debugger.watches(&emu));
+        overlay.extend_from_slice(&keypad_hint);
+        if stats.is_some() {
+            overlay.push(trf(
+                "cycle {}  frame {}",
+                &[&emu.cycles_executed(), &emu.frames_rendered()],
+            ));
+        }
+        frontend.watches(&overlay);
+        frontend.keys(Some(emu.keys()).filter(|_| show_keypad));
+        // stepping in the debugger always shows what it drew
+        if skipper.frame(drawn, behind) || drawn && debugger.stopped() {
+            frontend.present(emu.screen());
+        }
+        frontend.buzzer(emu.sound_flag() && !muted);
+        frontend.rumble(args.rumble && emu.sound_flag());
+        frontend.flash((args.flash_sound || args.announce) && emu.sound_flag());
+    }
+
+    frontend.finish(emu.screen());
+    drop(frontend); // put the terminal back before printing reports
+    if let Some(path) = &args.dump_state {
+        std::fs::write(path, emu.save_state())?;
+    }
+    if let Some(path) = &args.screenshot {
+        std::fs::write(path, frontend::render_pbm(emu.screen()))?;
+    }
+    if let Some(Err(e)) = video.as_mut().map(|v| v.flush()) {
+        eprintln!("{}", trf("the video pipe closed: {}", &[&e]));
+    }
+    if let Some(Err(e)) = audio.map(|a| a.finish()) {
+        eprintln!("{}", trf("couldn't write the audio: {}", &[&e]));
+    }
+    if let Some(tracer) = &mut tracer {
+        if let Err(e) = tracer.flush() {
+            eprintln!("{}", trf("couldn't write the trace: {}", &[&e]));
+        }
+    }
+    save_cheats(cheats_path.as_deref(), &debugger);
+    kept.save();
+    if let Some(path) = &session {
+        if let Err(e) = save_or_remove(path, &debugger.session()) {
+            eprintln!("{}", trf("couldn't save the debugger session: {}", &[&e]));
+        }
+    }
+    if let (Some(rom), Some(path)) = (args.rom.as_ref().filter(|_| watched), playtime::path()) {
+        let seconds = elapsed.ticks / 60; // leaving out time spent paused
+        if let Err(e) = record_playtime(&path, rom, &rom_sha1, seconds) {
+            eprintln!("{}", trf("couldn't save the playtime: {}", &[&e]));
+        }
+    }
+    #[cfg(feature = "bridge")]
+    if let Some(e) = bridge.and_then(|b| b.error) {
+        eprintln!("{}", trf("the bridge stopped publishing: {}", &[&e]));
+    }
+    #[cfg(all(feature = "discord", unix))]
+    if let Some(e) = presence.and_then(|p| p.error) {
+        eprintln!("{}", trf("Discord stopped showing the game: {}", &[&e]));
+    }
+    if let Some(smc) = &smc {
+        eprint!("{}", smc.report());
+    }
+    if let Some(stats) = &stats {
+        print!("{}", stats.report());
+    }
+    if let Some(canaries) = &canaries {
+        print!("{}", canaries.report(&emu));
+    }
+    if args.dump_on_exit {
+        print!("{}", emu);
+    }
+    result?;
+    let failed: Vec<String> = (args.expect.iter())
+        .filter_map(|expectation| expectation.check(&emu).err())
+        .collect();
+    if !failed.is_empty() {
+        return Err(trf("expectation not met: {}", &[&failed.join("; ")]).into());
+    }
+    Ok(())
+}
+
+fn remember_run(path: &Path, rom: &Path, command_line: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut recent = Recent::load(path)?;
+    recent.add(recent::Run {
+        rom: std::fs::canonicalize(rom)?,
+        dir: std::env::current_dir()?,
+        args: command_line.to_vec(),
+    });
+    Ok(recent.save(path)?)
+}
+
+// --last, the arguments being relative to where they were given
+fn run_last(program: &str) -> Result<(), Box<dyn Error>> {
+    let recent = match recent::path() {
+        Some(path) => Recent::load(&path)?,
+        None => Recent::default(),
+    };
+    let last = (recent.runs().first()).ok_or_else(|| tr("no ROM has been run yet"))?;
+    std::env::set_current_dir(&last.dir).map_err(|e| format!("{}: {}", last.dir.display(), e))?;
+    try_main(
+        std::iter::once(program.to_string())
+            .chain(last.args.iter().cloned())
+            .collect(),
+    )
+}
+
+// seconds since 1970, or 0 with the clock before it
+fn unix_time() -> u64 {
+    (SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)).map_or(0, |d| d.as_secs())
+}
+
+// what Discord shows for the ROM at path: its name in the ROM database or its file's, and how
+// long it's been played before if it has. neither file is needed, so a bad one is passed over
+#[cfg(all(feature = "discord", unix))]
+fn presence_text(args: &RunArgs, path: &Path, rom_sha1: &[u8; 20]) -> (String, Option<String>) {
+    let title = match rom_db(args)
+        .ok()
+        .flatten()
+        .map(|db| db.check(path, rom_sha1))
+    {
+        Some(Verdict::Good(name)) => rom_title(Path::new(&name)),
+        _ => rom_title(path),
+    };
+    let played = (playtime::path()).and_then(|path| Playtime::load(&path).ok());
+    let hex = sha1::hex(rom_sha1);
+    let before = (played.iter().flat_map(|p| p.entries())).find(|e| e.sha1 == hex && e.seconds > 0);
+    let before = before.map(|e| trf("{} played before", &[&playtime::duration(e.seconds)]));
+    (title, before)
+}
+
+// the ROM's cheats into the debugger, returning where they're kept
+fn load_cheats(
+    debugger: &mut Debugger,
+    rom_sha1: &[u8; 20],
+) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let path = cheats::path(rom_sha1).filter(|_| *rom_sha1 != [0; 20]);
+    if let Some(path) = &path {
+        debugger.cheats = Cheats::load(path)?;
+    }
+    Ok(path)
+}
+
+fn save_cheats(path: Option<&Path>, debugger: &Debugger) {
+    if let Some(path) = path.filter(|_| debugger.cheats.changed) {
+        let cheats = debugger.cheats.to_config().to_string();
+        if let Err(e) = save_or_remove(path, &cheats) {
+            eprintln!("{}", trf("couldn't save cheats: {}", &[&e]));
+        }
+    }
+}
+
+// the high scores and achievements of a ROM somebody's playing, kept to if its cheats say where
+// the score is
+struct Kept {
+    scores_path: Option<PathBuf>,
+    high_scores: Scores,
+    game: scores::Game,
+    scored: bool, // a game made the board, so it needs saving
+    unlocked_path: Option<PathBuf>,
+    goals: Achievements, // not unlocked while cheating
+    unlocked: Unlocked,
+    unlocked_before: usize,
+}
+
+impl Kept {
+    fn load(rom_sha1: &[u8; 20], watched: bool, labels: &Labels) -> Result<Self, Box<dyn Error>> {
+        let scores_path = scores::path(rom_sha1).filter(|_| watched && *rom_sha1 != [0; 20]);
+        let high_scores = match &scores_path {
+            Some(path) => Scores::load(path)?,
+            None => Scores::default(),
+        };
+        let unlocked_path = achievements::unlocked_path(rom_sha1).filter(|_| scores_path.is_some());
+        let goals = match achievements::path(rom_sha1).filter(|_| unlocked_path.is_some()) {
+            Some(path) => Achievements::load(&path, labels)?,
+            None => Achievements::default(),
+        };
+        let unlocked = match unlocked_path.as_ref().filter(|_| !goals.is_empty()) {
+            Some(path) => Unlocked::load(path)?,
+            None => Unlocked::default(),
+        };
+        Ok(Self {
+            scores_path,
+            high_scores,
+            game: scores::Game::default(),
+            scored: false,
+            unlocked_path,
+            goals,
+            unlocked_before: unlocked.entries().len(),
+            unlocked,
+        })
+    }
+
+    fn save(&mut self) {
+        if let Some(path) = &self.scores_path {
+            // a game still going when the emulator stopped counts as over
+            let best = self.game.best();
+            self.scored |= self.high_scores.record(best, unix_time()).is_some();
+            if self.scored {
+                if let Err(e) = self.high_scores.save(path) {
+                    eprintln!("{}", trf("couldn't save the high scores: {}", &[&e]));
+                }
+            }
+        }
+        let unlocked = self.unlocked.entries().len() > self.unlocked_before;
+        if let Some(path) = self.unlocked_path.as_ref().filter(|_| unlocked) {
+            if let Err(e) = self.unlocked.save(path) {
+                eprintln!("{}", trf("couldn't save the achievements: {}", &[&e]));
             }
         }
+    }
+}
+
+fn record_playtime(
+    path: &Path,
+    rom: &Path,
+    rom_sha1: &[u8; 20],
+    seconds: u64,
+) -> Result<(), Box<dyn Error>> {
+    let mut played = Playtime::load(path)?;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+    let name = rom.file_name().unwrap_or_default().to_string_lossy();
+    played.record(rom_sha1, &name, seconds, now.as_secs());
+    Ok(played.save(path)?)
+}
 
-        for e in event_pump.poll_iter() {
-            match e {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => break 'main,
+// load a save state, carrying on recording from the replay in it if it has one or from the
+// state itself if record is set or a recording was already going
+fn load_state(
+    emu: &mut chip8::Chip8,
+    debugger: &mut Debugger,
+    state: &[u8],
+    record: bool,
+) -> Result<(), Box<dyn Error>> {
+    let replay = movie::replay_in_state(state)?;
+    emu.load_state(state)?;
+    emu.clear_keys(); // whatever was held when saving isn't held now
+    debugger.recorder = match replay {
+        Some(movie) => Some(Recorder::resume(movie, emu)),
+        None if record || debugger.recorder.is_some() => Some(Recorder::new(emu)),
+        None => None,
+    };
+    Ok(())
+}
+
+// run several ROMs, each with its own quirks, side by side in an SDL window until it's closed
+#[cfg(feature = "sdl")]
+fn split(roms: &[(&PathBuf, Quirks)]) -> Result<(), Box<dyn Error>> {
+    let mut emus = Vec::new();
+    for (path, quirks) in roms {
+        let mut emu = chip8::Chip8::new();
+        emu.set_quirks(*quirks);
+        emu.set_clock(Box::new(RealClock::default()));
+        emu.load_rom(&std::fs::read(path)?);
+        emus.push(emu);
+    }
+    let mut frontend = Sdl::new(&frontend::Options {
+        screens: emus.len(),
+        ..Default::default()
+    })?;
+    let screens = |emus: &[chip8::Chip8]| {
+        let gfx: Vec<&[u8]> = emus.iter().map(|emu| emu.screen()).collect();
+        frontend::side_by_side(&gfx)
+    };
+    frontend.present(&screens(&emus));
+    shutdown::install();
+
+    let mut muted = false;
+    let mut scheduler = Scheduler::new(CYCLE_TIME, MAX_CATCH_UP);
+    while !shutdown::requested() {
+        scheduler.wait(1);
+        for hotkey in frontend.poll(&mut Machines(&mut emus)) {
+            match hotkey {
+                Hotkey::Quit => return Ok(()),
+                Hotkey::Mute => {
+                    muted = !muted;
+                    frontend.notify(tr(if muted { "Muted" } else { "Unmuted" }));
+                }
+                // states and macros are for one VM
                 _ => {}
             }
         }
 
-        emu.clear_keys();
+        let mut drawn = false;
+        for _ in 0..scheduler.due(Instant::now()) {
+            for (emu, (path, _)) in emus.iter_mut().zip(roms) {
+                emu.emulate_cycle()
+                    .map_err(|e| format!("{}: {}", path.display(), i18n::error(&e)))?;
+                drawn |= emu.draw_flag();
+            }
+        }
+        if drawn {
+            frontend.present(&screens(&emus));
+        }
+        frontend.buzzer(!muted && emus.iter().any(|emu| emu.sound_flag()));
+    }
+    Ok(())
+}
+
+fn tour(speed: f64, frontend: &str) -> Result<(), Box<dyn Error>> {
+    let step = (Duration::try_from_secs_f64(1.0 / speed).ok())
+        .filter(|_| speed > 0.0)
+        .ok_or_else(|| trf("bad --speed {}", &[&speed]))?;
+    let make = FRONTENDS
+        .iter()
+        .find(|(name, _)| *name == frontend)
+        .map(|(_, make)| make)
+        .ok_or_else(|| trf("unknown frontend {}", &[&frontend]))?;
+    let mut frontend = make(&frontend::Options {
+        announce: frontend == "tui", // the tui only shows the notes as announcements
+        ..Default::default()
+    })?;
+    let mut emu = chip8::Chip8::new();
+    emu.load_rom(&tour::ROM);
+    frontend.present(emu.screen());
+    shutdown::install();
+
+    let mut before = None; // the registers before the last instruction
+    let mut next = Instant::now() + step;
+    loop {
+        if shutdown::requested() || frontend.exit_on_halt() && emu.halted() {
+            break;
+        }
+        frontend.watches(&tour::overlay(&emu, before.as_ref()));
+        if frontend.poll(&mut emu).contains(&Hotkey::Quit) {
+            break;
+        }
+        let now = Instant::now();
+        if now < next {
+            std::thread::sleep((next - now).min(Duration::from_millis(10)));
+            continue;
+        }
+        next += step;
+        if emu.halted() && before.is_some_and(|b: tour::Registers| b.pc == emu.pc()) {
+            continue; // leave the last note up until the window's closed
+        }
+        before = Some(tour::Registers::of(&emu));
+        emu.emulate_cycle().map_err(|e| i18n::error(&e))?;
+        if emu.draw_flag() {
+            frontend.present(emu.screen());
+        }
+        frontend.buzzer(emu.sound_flag());
+    }
+    frontend.buzzer(false);
+    frontend.finish(emu.screen());
+    Ok(())
+}
+
+// the database of known-good ROMs, if there is one
+fn rom_db(args: &RunArgs) -> Result<Option<RomDb>, Box<dyn Error>> {
+    match &args.rom_db {
+        Some(path) => Ok(Some(RomDb::load(path)?)),
+        None => match romdb::default_path().filter(|path| path.exists()) {
+            Some(path) => Ok(Some(RomDb::load(&path)?)),
+            None => Ok(None),
+        },
+    }
+}
+
+// check the ROM against the database of known-good ones
+fn verify(args: &RunArgs, rom_path: &Path, rom_sha1: &[u8; 20]) -> Result<(), Box<dyn Error>> {
+    let db = match rom_db(args)? {
+        Some(db) => db,
+        None => return Ok(()),
+    };
+    if let Verdict::Mismatch(good) = db.check(rom_path, rom_sha1) {
+        let problem = trf(
+            "{} doesn't match the known-good SHA-1 {}, the file may be truncated or corrupted",
+            &[&rom_path.display(), &good],
+        );
+        if args.strict {
+            return Err(problem.into());
+        }
+        eprintln!("{}", trf("warning: {}", &[&problem]));
+    }
+    Ok(())
+}
+
+// a VM set up as the command line asks, with nothing loaded
+fn machine(args: &RunArgs) -> chip8::Chip8 {
+    let mut emu = chip8::Chip8::new();
+    emu.set_quirks(args.quirks);
+    emu.set_permissive(args.permissive);
+    emu.set_key_wait_timeout(
+        args.key_wait_timeout
+            .map(|secs| (secs * 60.0).round() as u64),
+    );
+    emu.set_font(&args.font);
+    if let Some(seed) = args.random_init {
+        let seed = seed.unwrap_or_else(rand::random);
+        eprintln!("{}", trf("random init seed: {}", &[&seed]));
+        emu.randomize(seed);
+    }
+    emu.set_clock(Box::new(RealClock::default()));
+    if args.ext_mmio {
+        emu.set_mmio(Some(Box::new(mmio::Host::default())));
+    }
+    emu
+}
+
+// a hex address for --org and --entry, with or without 0x
+fn address(s: &str) -> Result<u16, String> {
+    let digits = s.trim_start_matches("0x").trim_start_matches("0X");
+    u16::from_str_radix(digits, 16).map_err(|_| trf("bad address {}", &[&s]))
+}
+
+// put a ROM in memory where --org says along with any --load segments, and point the PC at
+// --entry
+fn load(
+    args: &RunArgs,
+    emu: &mut chip8::Chip8,
+    rom: Option<(&Path, &[u8])>,
+) -> Result<(), Box<dyn Error>> {
+    let org = args.org.unwrap_or(PROGRAM_START);
+    let entry = args.entry.unwrap_or(org);
+    for (flag, addr) in [("--org", org), ("--entry", entry)] {
+        if addr as usize >= emu.memory().len() {
+            let addr = format!("{:03X}", addr);
+            return Err(trf("{} {} is past the end of memory", &[&flag, &addr]).into());
+        }
+    }
+    let files = (args.load.iter())
+        .map(|s| std::fs::read(&s.path).map_err(|e| format!("{}: {}", s.path.display(), e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    // a ROM too big for memory is cut short, as it always has been
+    let room = emu.memory().len() - org as usize;
+    let mut segments: Vec<(&Path, u16, &[u8])> = (rom.into_iter())
+        .map(|(path, rom)| (path, org, &rom[..rom.len().min(room)]))
+        .collect();
+    segments.extend(
+        (args.load.iter().zip(&files)).map(|(s, data)| (s.path.as_path(), s.addr, &data[..])),
+    );
+    segment::load(emu, &segments)?;
+    emu.set_pc(entry);
+    Ok(())
+}
+
+// a VM with the kiosk's current ROM loaded, or the next one after it that can be read, and the
+// ROM's hash
+fn kiosk_machine(
+    args: &RunArgs,
+    kiosk: &mut Kiosk,
+) -> Result<(chip8::Chip8, [u8; 20]), Box<dyn Error>> {
+    for _ in 0..kiosk.len() {
+        match std::fs::read(kiosk.current()) {
+            Ok(rom) => {
+                let mut emu = machine(args);
+                load(args, &mut emu, Some((kiosk.current(), &rom)))?;
+                return Ok((emu, sha1(&rom)));
+            }
+            Err(e) => {
+                let problem = format!("{}: {}", kiosk.current().display(), e);
+                eprintln!("{}", trf("warning: {}", &[&problem]));
+            }
+        }
+        kiosk.advance();
+    }
+    Err(tr("none of the kiosk's ROMs could be read").into())
+}
+
+// what a ROM is called, from its file name
+fn rom_title(path: &Path) -> String {
+    (path.file_stem().map(|s| s.to_string_lossy().to_string())).unwrap_or_default()
+}
+
+// set up the VM from the ROM and/or state given on the command line, returning it along with
+// a debugger (paused if it should start in it) and the hash of the ROM for crash dumps
+fn boot(args: &RunArgs) -> Result<(chip8::Chip8, Debugger, [u8; 20]), Box<dyn Error>> {
+    let mut emu = machine(args);
+    let mut debugger = Debugger::default();
+    let mut rom_sha1 = [0; 20];
+    if let Some(path) = &args.rom {
+        let mut rom = std::fs::read(path)?;
+        verify(args, path, &sha1(&rom))?;
+        for patch in &args.patch {
+            rom = patch::apply_ips(&rom, &std::fs::read(patch)?)
+                .map_err(|e| format!("{}: {}", patch.display(), e))?;
+        }
+        load(args, &mut emu, Some((path, &rom)))?;
+        rom_sha1 = sha1(&rom);
+    } else if !args.load.is_empty() {
+        load(args, &mut emu, None)?;
+    }
+    if args.debug {
+        debugger.pause();
+    }
+
+    let path = match &args.state {
+        Some(path) => path,
+        None => {
+            if args.replay {
+                debugger.recorder = Some(Recorder::new(&mut emu));
+            }
+            return Ok((emu, debugger, rom_sha1));
+        }
+    };
+    let state = std::fs::read(path)?;
+    if !Dump::is_dump(&state) {
+        load_state(&mut emu, &mut debugger, &state, args.replay)?;
+        return Ok((emu, debugger, rom_sha1));
+    }
 
-        for key in event_pump.keyboard_state().pressed_scancodes() {
-            if let Some(i) = keypad.iter().position(|&k| k == key) {
-                emu.press_key(i);
+    // the state has the whole of memory so the ROM isn't needed to run, but it's good to know
+    // that it's the right one
+    let dump = Dump::from_bytes(&state)?;
+    print!("{}", dump.report());
+    match &args.rom {
+        Some(rom) if rom_sha1 != dump.rom_sha1 => {
+            eprintln!(
+                "{}",
+                trf(
+                    "warning: {} isn't the ROM the dump was made with",
+                    &[&rom.display()]
+                )
+            )
+        }
+        Some(_) => {}
+        None => {
+            let dir = path.parent().unwrap_or_else(|| Path::new("."));
+            match crash::find_rom(&dump.rom_sha1, &[dir, Path::new(".")]) {
+                Some(rom) => println!("ROM: {}", rom.display()),
+                None => println!(
+                    "{}",
+                    tr("ROM not found, running from the memory in the dump")
+                ),
             }
         }
     }
+    dump.boot(&mut emu, &mut debugger.history)?;
+    debugger.pause();
+    Ok((emu, debugger, dump.rom_sha1))
 }
@@ -0,0 +1,291 @@
+// input recordings that replay deterministically. a movie is a save state to start from plus the
+// keys held and the timer ticks seen on every cycle where they changed, which together with the
+// RNG seed in the state is everything that decides how the VM runs. save states can carry the
+// movie since the state before them, so loading one can also rewind into that stretch
+
+use crate::chip8::{self, Error, StateError, StateReader};
+use crate::clock::Clock;
+use crate::observer::{Event, Observer};
+use crate::Chip8;
+use std::fmt;
+
+const MOVIE_MAGIC: &[u8; 4] = b"C8MV";
+const MOVIE_VERSION: u8 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Input {
+    pub cycle: u64, // from the start of the movie
+    pub keys: u16,  // bit per key held before the instruction on this cycle ran
+    pub ticks: u64, // timer ticks seen since the start of the movie
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Movie {
+    start: Vec<u8>, // save state to play from
+    start_cycle: u64,
+    inputs: Vec<Input>, // in cycle order, the first on cycle 0
+    len: u64,           // in cycles
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    State(StateError),
+    Crashed(Error), // the VM hit an error part way through, it went differently when recorded
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::State(e) => write!(f, "{}", e),
+            Self::Crashed(e) => write!(f, "replay didn't play back the same way: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+// plays the recorded ticks back, cycle by cycle
+struct ReplayClock {
+    start_cycle: u64,
+    ticks: Vec<(u64, u64)>, // cycle, ticks
+}
+
+impl Clock for ReplayClock {
+    fn ticks(&mut self, cycles: u64) -> u64 {
+        let cycle = cycles.saturating_sub(self.start_cycle);
+        let i = self.ticks.partition_point(|&(c, _)| c <= cycle);
+        self.ticks[..i].last().map_or(0, |&(_, ticks)| ticks)
+    }
+}
+
+impl Movie {
+    // the keys held and ticks seen as of cycle, given as an input if they've changed. keys is None
+    // to keep the keys as they were
+    fn record(&mut self, cycle: u64, keys: Option<u16>, ticks: u64) {
+        let last = *self.inputs.last().unwrap();
+        let keys = keys.unwrap_or(last.keys);
+        if (keys, ticks) == (last.keys, last.ticks) {
+            return;
+        }
+        let input = Input { cycle, keys, ticks };
+        if last.cycle == cycle {
+            *self.inputs.last_mut().unwrap() = input;
+        } else {
+            self.inputs.push(input);
+        }
+    }
+
+    // ticks seen by the start of cycle
+    fn ticks_at(&self, cycle: u64) -> u64 {
+        let i = self.inputs.partition_point(|i| i.cycle <= cycle);
+        self.inputs[..i].last().map_or(0, |i| i.ticks)
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn inputs(&self) -> &[Input] {
+        &self.inputs
+    }
+
+    // load the start state and run the first `cycles` cycles of the movie. the VM keeps the clock
+    // it had, and carries on from there in real time
+    pub fn play(&self, chip8: &mut Chip8, cycles: u64) -> Result<(), ReplayError> {
+        chip8.load_state(&self.start).map_err(ReplayError::State)?;
+        let clock = chip8.set_clock(Box::new(ReplayClock {
+            start_cycle: self.start_cycle,
+            ticks: self.inputs.iter().map(|i| (i.cycle, i.ticks)).collect(),
+        }));
+        let mut inputs = self.inputs.iter().peekable();
+        let mut result = Ok(());
+        for cycle in 0..cycles.min(self.len) {
+            if let Some(input) = inputs.next_if(|i| i.cycle == cycle) {
+                chip8.set_keys(input.keys);
+            }
+            if let Err(e) = chip8.emulate_cycle() {
+                result = Err(ReplayError::Crashed(e));
+                break;
+            }
+        }
+        chip8.set_clock(clock);
+        result
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.start.len() + 64 + self.inputs.len() * 18);
+        out.extend_from_slice(MOVIE_MAGIC);
+        out.push(MOVIE_VERSION);
+        out.extend_from_slice(&self.start_cycle.to_be_bytes());
+        out.extend_from_slice(&self.len.to_be_bytes());
+        out.extend_from_slice(&(self.start.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.start);
+        out.extend_from_slice(&(self.inputs.len() as u32).to_be_bytes());
+        for input in &self.inputs {
+            out.extend_from_slice(&input.cycle.to_be_bytes());
+            out.extend_from_slice(&input.keys.to_be_bytes());
+            out.extend_from_slice(&input.ticks.to_be_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, StateError> {
+        let mut r = StateReader::new(data);
+        if r.bytes(4).map_err(|_| StateError::NotAState)? != MOVIE_MAGIC {
+            return Err(StateError::NotAState);
+        }
+        match r.u8()? {
+            MOVIE_VERSION => {}
+            v => return Err(StateError::UnsupportedVersion(v)),
+        }
+        let start_cycle = r.u64()?;
+        let len = r.u64()?;
+        let start_len = r.u32()? as usize;
+        let start = r.bytes(start_len)?.to_vec();
+        Chip8::new().load_state(&start)?;
+        let count = r.u32()? as usize;
+        let mut inputs = Vec::with_capacity(count.min(r.remaining() / 18));
+        for _ in 0..count {
+            inputs.push(Input {
+                cycle: r.u64()?,
+                keys: r.u16()?,
+                ticks: r.u64()?,
+            });
+        }
+        let ordered = inputs.windows(2).all(|w| w[0].cycle < w[1].cycle);
+        if !ordered || inputs.first().is_none_or(|i| i.cycle != 0) {
+            return Err(StateError::Corrupt);
+        }
+        Ok(Self {
+            start,
+            start_cycle,
+            inputs,
+            len,
+        })
+    }
+}
+
+// a save state with the movie that led up to it, which older versions load as a plain state
+pub fn save_state_with_replay(state: &[u8], movie: &Movie) -> Vec<u8> {
+    let mut out = state.to_vec();
+    out.extend_from_slice(&movie.to_bytes());
+    out
+}
+
+// the movie saved with a state, if there is one
+pub fn replay_in_state(state: &[u8]) -> Result<Option<Movie>, StateError> {
+    match chip8::state_len(state).and_then(|len| state.get(len..)) {
+        Some(tail) if !tail.is_empty() => Movie::from_bytes(tail).map(Some),
+        _ => Ok(None),
+    }
+}
+
+// records a movie as the VM runs, as an observer. every save state starts a new movie, the old
+// ones are kept so rewinding can go back past the last save
+pub struct Recorder {
+    past: Vec<Movie>, // up to the last save state, oldest first
+    movie: Movie,     // since then
+    tick_base: u64,   // the VM's tick count at the start of the movie
+}
+
+fn start_movie(chip8: &mut Chip8) -> Movie {
+    Movie {
+        start: chip8.save_state(),
+        start_cycle: chip8.cycles(),
+        inputs: vec![Input {
+            cycle: 0,
+            keys: chip8.keys(),
+            ticks: 0,
+        }],
+        len: 0,
+    }
+}
+
+impl Recorder {
+    // start recording from where the VM is now
+    pub fn new(chip8: &mut Chip8) -> Self {
+        Self {
+            past: Vec::new(),
+            movie: start_movie(chip8),
+            tick_base: chip8.ticks(),
+        }
+    }
+
+    // carry on recording a movie that ends where the VM is now, e.g. one loaded with a state.
+    // starts a new one if the VM isn't at its end
+    pub fn resume(movie: Movie, chip8: &mut Chip8) -> Self {
+        if chip8.cycles() != movie.start_cycle + movie.len {
+            return Self::new(chip8);
+        }
+        Self {
+            past: Vec::new(),
+            tick_base: chip8.ticks().wrapping_sub(movie.ticks_at(movie.len)),
+            movie,
+        }
+    }
+
+    // everything recorded since the last save state up to where the VM is now
+    pub fn movie(&self, chip8: &Chip8) -> Movie {
+        let mut movie = self.movie.clone();
+        movie.len = chip8.cycles() - movie.start_cycle;
+        movie.record(movie.len, None, self.ticks(chip8));
+        movie
+    }
+
+    fn ticks(&self, chip8: &Chip8) -> u64 {
+        chip8.ticks().wrapping_sub(self.tick_base)
+    }
+
+    // a save state of the VM carrying the movie since the last one, recording carries on from it
+    pub fn save_state(&mut self, chip8: &mut Chip8) -> Vec<u8> {
+        let movie = self.movie(chip8);
+        self.movie = start_movie(chip8);
+        self.tick_base = chip8.ticks();
+        let state = save_state_with_replay(&self.movie.start, &movie);
+        self.past.push(movie);
+        state
+    }
+
+    // replay the VM to `cycles` instructions ago, or as far back as the recording goes, and
+    // carry on recording from there. returns how far back it went
+    pub fn rewind(&mut self, chip8: &mut Chip8, cycles: u64) -> Result<u64, ReplayError> {
+        let mut movie = self.movie(chip8);
+        let mut left = cycles;
+        let mut rewound = 0;
+        while left > movie.len {
+            match self.past.pop() {
+                Some(earlier) => {
+                    left -= movie.len;
+                    rewound += movie.len;
+                    movie = earlier;
+                }
+                None => break,
+            }
+        }
+        let to = movie.len.saturating_sub(left);
+        rewound += movie.len - to;
+        movie.play(chip8, to)?;
+
+        self.tick_base = chip8.ticks().wrapping_sub(movie.ticks_at(to));
+        // the keys on the input for cycle `to` itself haven't been used yet, the next one recorded
+        // takes its place
+        movie.inputs.retain(|i| i.cycle < to.max(1));
+        movie.len = to;
+        self.movie = movie;
+        Ok(rewound)
+    }
+}
+
+impl Observer for Recorder {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        if let Event::Exec { .. } = event {
+            let cycle = chip8.cycles() - self.movie.start_cycle;
+            let ticks = self.ticks(chip8);
+            self.movie.record(cycle, Some(chip8.keys()), ticks);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+// what a debugger shows, worked out once so the text debugger and any graphical one lay out the
+// same views: registers, disassembly around the PC, memory as hex, breakpoints and the timeline
+// of recently executed instructions. each panel is plain data, drawing it is up to the frontend
+
+use crate::chip8::{Error, CYCLES_PER_TICK, PROGRAM_START};
+use crate::crash::History;
+use crate::debugger::{self, Debugger};
+use crate::disasm::Instruction;
+use crate::i18n;
+use crate::Chip8;
+use std::collections::BTreeSet;
+use std::fmt;
+
+pub const HEX_ROW_LEN: usize = 16;
+
+// one instruction in the disassembly or timeline
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub addr: u16,
+    pub opcode: u16,
+    pub instruction: Instruction,
+    pub current: bool,       // at the PC
+    pub breakpoint: bool,    // a breakpoint is set here
+    pub target: Option<u16>, // where it jumps or calls to, BNNN's as the registers are now
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mark = match (self.current, self.breakpoint) {
+            (true, _) => '>',
+            (false, true) => '*',
+            _ => ' ',
+        };
+        write!(
+            f,
+            "{}{:03X}: {:04X}  {}",
+            mark, self.addr, self.opcode, self.instruction
+        )?;
+        if let (Some(target), Instruction::JpV0(_)) = (self.target, self.instruction) {
+            write!(f, "  -> {:03X}", target)?;
+        }
+        Ok(())
+    }
+}
+
+// sixteen bytes of memory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexRow {
+    pub addr: u16,
+    pub bytes: Vec<u8>,
+}
+
+impl fmt::Display for HexRow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:03X}:", self.addr)?;
+        for b in &self.bytes {
+            write!(f, " {:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn opcode_at(chip8: &Chip8, addr: usize) -> u16 {
+    let memory = chip8.memory();
+    (memory[addr % memory.len()] as u16) << 8 | memory[(addr + 1) % memory.len()] as u16
+}
+
+// where an instruction goes, if it's a jump or a call
+fn target(chip8: &Chip8, instruction: Instruction) -> Option<u16> {
+    match instruction {
+        Instruction::Jp(a) | Instruction::Call(a) => Some(a),
+        Instruction::JpV0(a) => {
+            let x = if chip8.quirks().jumping { a >> 8 } else { 0 };
+            Some(a.wrapping_add(chip8.v()[x as usize] as u16))
+        }
+        _ => None,
+    }
+}
+
+fn line(chip8: &Chip8, breakpoints: &BTreeSet<u16>, addr: u16, opcode: u16) -> Line {
+    let instruction = Instruction::decode(opcode);
+    Line {
+        addr,
+        opcode,
+        instruction,
+        current: addr == chip8.pc(),
+        breakpoint: breakpoints.contains(&addr),
+        target: target(chip8, instruction),
+    }
+}
+
+// count instructions from start, wrapping at the end of memory
+pub fn disassembly(
+    chip8: &Chip8,
+    breakpoints: &BTreeSet<u16>,
+    start: u16,
+    count: usize,
+) -> Vec<Line> {
+    let len = chip8.memory().len();
+    (0..count)
+        .map(|n| {
+            let addr = ((start as usize + n * 2) % len) as u16;
+            line(chip8, breakpoints, addr, opcode_at(chip8, addr as usize))
+        })
+        .collect()
+}
+
+// count instructions with the PC in the middle, for following it as the VM runs. before the
+// PC is counted back two bytes at a time, which an odd instruction length would throw off
+pub fn around_pc(chip8: &Chip8, breakpoints: &BTreeSet<u16>, count: usize) -> Vec<Line> {
+    let len = chip8.memory().len();
+    let back = (count / 2 * 2) % len;
+    let start = (chip8.pc() as usize + len - back) % len;
+    disassembly(chip8, breakpoints, start as u16, count)
+}
+
+// every breakpoint with the instruction it's on
+pub fn breakpoints(chip8: &Chip8, breakpoints: &BTreeSet<u16>) -> Vec<Line> {
+    breakpoints
+        .iter()
+        .map(|&addr| line(chip8, breakpoints, addr, opcode_at(chip8, addr as usize)))
+        .collect()
+}
+
+// len bytes of memory from addr, stopping at the end of memory
+pub fn hex_rows(chip8: &Chip8, addr: u16, len: usize) -> Vec<HexRow> {
+    let addr = (addr as usize).min(chip8.memory().len());
+    let end = (addr + len).min(chip8.memory().len());
+    chip8.memory()[addr..end]
+        .chunks(HEX_ROW_LEN)
+        .enumerate()
+        .map(|(row, bytes)| HexRow {
+            addr: (addr + row * HEX_ROW_LEN) as u16,
+            bytes: bytes.to_vec(),
+        })
+        .collect()
+}
+
+// the instructions that got the VM where it is, oldest first. opcodes are as they were when
+// executed, self-modifying code may have changed memory since
+pub fn timeline(chip8: &Chip8, breakpoints: &BTreeSet<u16>, history: &History) -> Vec<Line> {
+    history
+        .entries
+        .iter()
+        .map(|&(pc, opcode)| Line {
+            current: false,
+            ..line(chip8, breakpoints, pc, opcode)
+        })
+        .collect()
+}
+
+// a debugger session for a graphical frontend, which redraws the panels from it every frame.
+// stepping, breakpoints and the timeline are the text debugger's, so both behave the same
+pub struct Session {
+    pub chip8: Chip8,
+    pub debugger: Debugger,
+    pub error: Option<Error>, // what stopped the VM, it can't carry on after
+    pub memory_addr: u16,     // first address the memory panel shows
+    pub output: String,       // what the debugger last said
+}
+
+impl Session {
+    // paused before the first instruction, so breakpoints can go in first
+    pub fn new(chip8: Chip8) -> Self {
+        let mut debugger = Debugger::default();
+        debugger.pause();
+        Self {
+            chip8,
+            debugger,
+            error: None,
+            memory_addr: PROGRAM_START,
+            output: String::new(),
+        }
+    }
+
+    pub fn paused(&self) -> bool {
+        self.debugger.paused() || self.error.is_some()
+    }
+
+    // run a 60Hz frame's worth of instructions, or fewer if something stops the VM
+    pub fn frame(&mut self) {
+        let was_paused = self.debugger.stopped();
+        for _ in 0..CYCLES_PER_TICK {
+            if self.error.is_some() || self.debugger.should_pause(&self.chip8) {
+                break;
+            }
+            if let Err(e) = self.chip8.emulate_cycle_observed(&mut self.debugger) {
+                self.output = format!("{}\n", i18n::error(&e));
+                self.error = Some(e);
+                self.debugger.pause();
+                return;
+            }
+        }
+        if self.debugger.stopped() && !was_paused {
+            self.output = self.debugger.location(&self.chip8);
+        }
+    }
+
+    // a line for the text debugger, e.g. "s" or "c" from the toolbar
+    pub fn command(&mut self, line: &str) {
+        self.output = self.debugger.command(&mut self.chip8, line).1;
+    }
+
+    pub fn toggle_breakpoint(&mut self, addr: u16) {
+        if !self.debugger.breakpoints.remove(&addr) {
+            self.debugger.breakpoints.insert(addr);
+        }
+    }
+
+    // a byte typed into the memory panel, as hex
+    pub fn write(&mut self, addr: u16, byte: &str) {
+        self.command(&format!("w {:03X} {}", addr, byte.trim()));
+    }
+
+    pub fn registers(&self) -> String {
+        debugger::registers(&self.chip8)
+    }
+
+    pub fn disassembly(&self, count: usize) -> Vec<Line> {
+        around_pc(&self.chip8, &self.debugger.breakpoints, count)
+    }
+
+    pub fn memory(&self, len: usize) -> Vec<HexRow> {
+        hex_rows(&self.chip8, self.memory_addr, len)
+    }
+
+    pub fn breakpoints(&self) -> Vec<Line> {
+        breakpoints(&self.chip8, &self.debugger.breakpoints)
+    }
+
+    pub fn timeline(&self) -> Vec<Line> {
+        timeline(
+            &self.chip8,
+            &self.debugger.breakpoints,
+            &self.debugger.history,
+        )
+    }
+}
@@ -0,0 +1,148 @@
+// how often and how long each ROM has been played, kept in <data dir>/playtime with a line for
+// each ROM: its SHA-1, times launched, seconds played, when it was last played in seconds since
+// 1970 and its file name
+
+use crate::dirs;
+use crate::sha1;
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub sha1: String, // lowercase hex
+    pub launches: u32,
+    pub seconds: u64,
+    pub last_played: u64, // seconds since 1970, UTC
+    pub name: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Playtime {
+    entries: Vec<Entry>, // most recently played first
+}
+
+// <data dir>/playtime
+pub fn path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("playtime"))
+}
+
+fn entry(line: &str) -> Option<Entry> {
+    let mut fields = line.splitn(5, ' ');
+    let sha1 = fields.next()?;
+    if sha1.len() != 40 || !sha1.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(Entry {
+        sha1: sha1.to_ascii_lowercase(),
+        launches: fields.next()?.parse().ok()?,
+        seconds: fields.next()?.parse().ok()?,
+        last_played: fields.next()?.parse().ok()?,
+        name: fields.next()?.to_string(),
+    })
+}
+
+impl Playtime {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            if !line.trim().is_empty() {
+                entries.push(entry(line).ok_or_else(|| format!("line {} is bad", n + 1))?);
+            }
+        }
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_played));
+        Ok(Self { entries })
+    }
+
+    // nothing has been played yet if the file isn't there
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    // a run of the ROM that played for seconds and ended at now
+    pub fn record(&mut self, rom_sha1: &[u8; 20], name: &str, seconds: u64, now: u64) {
+        let hash = sha1::hex(rom_sha1);
+        let mut entry = match self.entries.iter().position(|e| e.sha1 == hash) {
+            Some(n) => self.entries.remove(n),
+            None => Entry {
+                sha1: hash,
+                launches: 0,
+                seconds: 0,
+                last_played: 0,
+                name: String::new(),
+            },
+        };
+        entry.launches += 1;
+        entry.seconds += seconds;
+        entry.last_played = now;
+        entry.name = name.to_string();
+        self.entries.insert(0, entry);
+    }
+
+    // one line for each ROM, most recently played first
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        for e in &self.entries {
+            let _ = writeln!(
+                out,
+                "{}  {:>9}  {:>4}x  {}",
+                date(e.last_played),
+                duration(e.seconds),
+                e.launches,
+                e.name
+            );
+        }
+        out
+    }
+}
+
+impl fmt::Display for Playtime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for e in &self.entries {
+            writeln!(
+                f,
+                "{} {} {} {} {}",
+                e.sha1, e.launches, e.seconds, e.last_played, e.name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// H:MM:SS
+pub fn duration(seconds: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// YYYY-MM-DD of seconds since 1970 in UTC, by Howard Hinnant's civil_from_days
+pub fn date(seconds: u64) -> String {
+    let z = (seconds / 86400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
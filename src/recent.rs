@@ -0,0 +1,97 @@
+// the ROMs run most recently, for --last. kept in <data dir>/recent with a line for each, newest
+// first: the full path of the ROM, the directory it was run from and the arguments it was run
+// with, all split by tabs
+
+use crate::dirs;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const MAX_RECENT: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub rom: PathBuf,
+    pub dir: PathBuf, // what the arguments are relative to
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Recent {
+    runs: Vec<Run>,
+}
+
+// <data dir>/recent
+pub fn path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("recent"))
+}
+
+impl Recent {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut runs = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let mut fields = line.split('\t');
+            match (fields.next(), fields.next()) {
+                (Some(rom), Some(dir)) if !rom.is_empty() && !dir.is_empty() => runs.push(Run {
+                    rom: PathBuf::from(rom),
+                    dir: PathBuf::from(dir),
+                    args: fields.map(String::from).collect(),
+                }),
+                _ if line.trim().is_empty() => {}
+                _ => {
+                    return Err(format!(
+                        "line {} isn't a ROM, directory and arguments",
+                        n + 1
+                    ))
+                }
+            }
+        }
+        Ok(Self { runs })
+    }
+
+    // nothing has been run yet if the file isn't there
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    // put a run first, replacing the last one of the same ROM. a run that can't be written on a
+    // line, with a tab or a line break in it, is left out
+    pub fn add(&mut self, run: Run) {
+        let paths = [run.rom.to_string_lossy(), run.dir.to_string_lossy()];
+        let mut fields =
+            (paths.iter().map(|p| p.as_ref())).chain(run.args.iter().map(String::as_str));
+        if fields.any(|s| s.contains(['\t', '\n', '\r'])) {
+            return;
+        }
+        self.runs.retain(|r| r.rom != run.rom);
+        self.runs.insert(0, run);
+        self.runs.truncate(MAX_RECENT);
+    }
+}
+
+impl fmt::Display for Recent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for run in &self.runs {
+            write!(f, "{}\t{}", run.rom.display(), run.dir.display())?;
+            for arg in &run.args {
+                write!(f, "\t{}", arg)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
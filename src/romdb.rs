@@ -0,0 +1,66 @@
+// known-good SHA-1s of ROMs, to catch a truncated download or a corrupted copy before it just
+// behaves strangely. the database is a list in sha1sum's format, "<sha1>  <file name>" a line, so
+// the one that comes with a ROM pack or one made with `sha1sum *.ch8` works as it is
+
+use crate::dirs;
+use crate::sha1;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct RomDb {
+    entries: Vec<(String, String)>, // lowercase hex SHA-1, file name
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Good(String),     // the ROM is in the database, under this name
+    Mismatch(String), // a ROM of the same name is, with this SHA-1 instead
+    Unknown,
+}
+
+// <data dir>/roms.sha1, used when --rom-db isn't given
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("roms.sha1"))
+}
+
+impl RomDb {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = line
+                .split_once(char::is_whitespace)
+                .and_then(|(hash, name)| {
+                    let name = name.trim_start().trim_start_matches('*'); // * marks binary mode
+                    let hex = hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit());
+                    (hex && !name.is_empty()).then(|| (hash.to_ascii_lowercase(), name.to_string()))
+                });
+            entries.push(entry.ok_or_else(|| format!("line {} isn't a SHA-1 and name", n + 1))?);
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    // look the ROM up by its hash, then by its file name to see if it's a bad copy of one
+    pub fn check(&self, rom_path: &Path, rom_sha1: &[u8; 20]) -> Verdict {
+        let hash = sha1::hex(rom_sha1);
+        if let Some((_, name)) = self.entries.iter().find(|(h, _)| *h == hash) {
+            return Verdict::Good(name.clone());
+        }
+        let file_name = rom_path.file_name().map(|n| n.to_string_lossy());
+        let same_name = self.entries.iter().find(|(_, name)| {
+            Path::new(name).file_name().map(|n| n.to_string_lossy()) == file_name
+        });
+        match same_name {
+            Some((hash, _)) => Verdict::Mismatch(hash.clone()),
+            None => Verdict::Unknown,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::chip8::{Error, CYCLES_PER_TICK};
+use crate::scheduler::{Scheduler, MAX_CATCH_UP};
+use crate::Chip8;
+use std::time::{Duration, Instant};
+
+pub const CYCLE_TIME: Duration = Duration::from_millis(3); // how long one instruction takes
+
+// what an embedding frontend provides to run_with
+pub struct Frontend<R, I, A> {
+    pub render: R, // gets the 64x32 screen, one byte per pixel, whenever it changes
+    pub input: I,  // fills in which keys are held, returns false to stop running
+    pub audio: A,  // told whether the buzzer should be sounding
+}
+
+// run the VM at its normal speed until the frontend asks to stop or the VM hits an error.
+// input and audio are handled once per 60Hz tick, which is as often as a ROM can notice them
+pub fn run_with<R, I, A>(chip8: &mut Chip8, mut frontend: Frontend<R, I, A>) -> Result<(), Error>
+where
+    R: FnMut(&[u8]),
+    I: FnMut(&mut [bool; 16]) -> bool,
+    A: FnMut(bool),
+{
+    let mut scheduler = Scheduler::new(CYCLE_TIME, MAX_CATCH_UP);
+    let mut sound = false;
+    (frontend.audio)(sound);
+    loop {
+        let mut keys = [false; 16];
+        if !(frontend.input)(&mut keys) {
+            return Ok(());
+        }
+        for (key, &held) in keys.iter().enumerate() {
+            chip8.set_key(key, held);
+        }
+
+        let mut drawn = false;
+        for _ in 0..scheduler.due(Instant::now()) {
+            chip8.emulate_cycle()?;
+            drawn |= chip8.draw_flag();
+        }
+        if drawn {
+            (frontend.render)(chip8.screen());
+        }
+        if sound != chip8.sound_flag() {
+            sound = chip8.sound_flag();
+            (frontend.audio)(sound);
+        }
+
+        scheduler.wait(CYCLES_PER_TICK as u64);
+    }
+}
+
+// how long to run for before stopping, counted in the VM's own time rather than the wall
+// clock's so the same run always stops in the same place
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Budget {
+    Frames(u64), // 60Hz timer ticks
+    Seconds(f64),
+    Cycles(u64),
+}
+
+// the VM time a run has used, added up a cycle at a time rather than worked out from the VM's
+// counters, which loading a state winds back
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Elapsed {
+    pub cycles: u64,
+    pub ticks: u64, // 60Hz timer ticks
+}
+
+impl Elapsed {
+    // what chip8 has run since it was at before, (cycles, ticks), nothing for a step back
+    pub fn add(&mut self, chip8: &Chip8, before: (u64, u64)) {
+        self.cycles += chip8.cycles().saturating_sub(before.0);
+        self.ticks += chip8.ticks().saturating_sub(before.1);
+    }
+}
+
+impl Budget {
+    // whether a run that's used elapsed has used up the budget
+    pub fn spent(&self, elapsed: &Elapsed) -> bool {
+        match *self {
+            Self::Frames(n) => elapsed.ticks >= n,
+            Self::Seconds(secs) => elapsed.ticks as f64 >= secs * 60.0,
+            Self::Cycles(n) => elapsed.cycles >= n,
+        }
+    }
+}
+
+// a number with a unit, e.g. 600f, 10s or 5000cycles
+impl std::str::FromStr for Budget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+        let (n, unit) = s.split_at(split);
+        let bad = || {
+            format!(
+                "{} isn't a number of frames, seconds or cycles, e.g. 600f, 10s or 5000c",
+                s
+            )
+        };
+        Ok(match unit {
+            "f" | "frames" => Self::Frames(n.trim().parse().map_err(|_| bad())?),
+            "s" | "secs" | "seconds" => Self::Seconds(
+                n.trim()
+                    .parse()
+                    .ok()
+                    .filter(|s: &f64| *s >= 0.0)
+                    .ok_or_else(bad)?,
+            ),
+            "c" | "cycles" => Self::Cycles(n.trim().parse().map_err(|_| bad())?),
+            _ => return Err(bad()),
+        })
+    }
+}
@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+
+pub const MAX_CATCH_UP: Duration = Duration::from_millis(100); // longest host stall made up for
+
+// keeps the VM running at one instruction per cycle_time of wall-clock time. after the host
+// stalls the missed instructions are run in a burst, up to max_catch_up worth, anything beyond
+// that is dropped so a slow host can't fall further and further behind
+pub struct Scheduler {
+    cycle_time: Duration,
+    max_catch_up: u64, // in cycles
+    start: Instant,    // when cycle 0 was due
+    cycles: u64,       // cycles run, or skipped, since start
+}
+
+impl Scheduler {
+    pub fn new(cycle_time: Duration, max_catch_up: Duration) -> Self {
+        Self {
+            cycle_time,
+            max_catch_up: (max_catch_up.as_nanos() / cycle_time.as_nanos()).max(1) as u64,
+            start: Instant::now(),
+            cycles: 0,
+        }
+    }
+
+    // start counting again from now, e.g. after being paused
+    pub fn reset(&mut self, now: Instant) {
+        self.start = now;
+        self.cycles = 0;
+    }
+
+    // how many cycles should be run now to be back on schedule. the caller has to run them
+    // (or call reset) before asking again
+    pub fn due(&mut self, now: Instant) -> u64 {
+        let elapsed = now.saturating_duration_since(self.start);
+        let target = (elapsed.as_nanos() / self.cycle_time.as_nanos()) as u64;
+        let behind = target.saturating_sub(self.cycles);
+        if behind > self.max_catch_up {
+            // give up on the part of the stall we won't make up
+            self.cycles = target - self.max_catch_up;
+        }
+        let due = target.saturating_sub(self.cycles);
+        self.cycles += due;
+        due
+    }
+
+    // when `cycles` more will be due
+    pub fn next_due(&self, cycles: u64) -> Instant {
+        let nanos = self.cycle_time.as_nanos() as u64 * (self.cycles + cycles);
+        self.start + Duration::from_nanos(nanos)
+    }
+
+    // sleep until `cycles` more are due
+    pub fn wait(&self, cycles: u64) {
+        let now = Instant::now();
+        let next = self.next_due(cycles);
+        if next > now {
+            std::thread::sleep(next - now);
+        }
+    }
+}
+
+const MAX_AUTO_SKIP: u32 = 5; // frames auto frame skip will drop in a row, so the screen still moves
+
+// how many of the frames a ROM draws to actually show. showing them can be what makes a slow
+// host fall behind, and the VM doesn't need them to keep running
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameSkip {
+    Auto,       // drop frames only while behind schedule
+    Fixed(u32), // show one frame, then drop this many
+}
+
+impl Default for FrameSkip {
+    fn default() -> Self {
+        Self::Fixed(0)
+    }
+}
+
+// "auto" or a number of frames
+impl std::str::FromStr for FrameSkip {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            n => n
+                .parse()
+                .map(Self::Fixed)
+                .map_err(|_| format!("{} isn't auto or a number of frames", s)),
+        }
+    }
+}
+
+// decides, a pass of the run loop at a time, whether to show the screen
+#[derive(Debug)]
+pub struct FrameSkipper {
+    skip: FrameSkip,
+    pending: bool, // drawn on since it was last shown
+    since: u32,    // passes since then, not counting ones with nothing new to show
+}
+
+impl FrameSkipper {
+    pub fn new(skip: FrameSkip) -> Self {
+        Self {
+            skip,
+            pending: false,
+            since: u32::MAX,
+        }
+    }
+
+    // whether to show the screen now, given whether the VM drew on it this pass and whether the
+    // loop is behind schedule. a dropped frame is kept to show on a later pass
+    pub fn frame(&mut self, drawn: bool, behind: bool) -> bool {
+        self.pending |= drawn;
+        if !self.pending {
+            return false;
+        }
+        self.since = self.since.saturating_add(1);
+        let drop = match self.skip {
+            FrameSkip::Auto => behind && self.since <= MAX_AUTO_SKIP,
+            FrameSkip::Fixed(n) => self.since <= n,
+        };
+        if drop {
+            return false;
+        }
+        self.pending = false;
+        self.since = 0;
+        true
+    }
+}
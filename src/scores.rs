@@ -0,0 +1,135 @@
+// the best scores a ROM has had here, for ROMs whose cheats say where their score is kept
+// ("score.score = bcd 3"), shown while paused. a game is over when the score goes down, as it
+// does back to zero for the next one, or when the emulator stops. kept in
+// <data dir>/scores/<ROM's SHA-1> with a line for each score: the score, then when it was made
+// in seconds since 1970
+
+use crate::dirs;
+use crate::i18n::tr;
+use crate::playtime;
+use crate::sha1;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const KEEP: usize = 10; // scores on the board
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub score: u64,
+    pub when: u64, // seconds since 1970, UTC
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Scores {
+    entries: Vec<Entry>, // best first, the earlier of a tie first
+}
+
+// <data dir>/scores/<ROM's SHA-1>
+pub fn path(rom_sha1: &[u8; 20]) -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("scores").join(sha1::hex(rom_sha1)))
+}
+
+impl Scores {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut scores = Self::default();
+        for (n, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.split_whitespace().map(str::parse);
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(Ok(score)), Some(Ok(when)), None) => {
+                    scores.record(score, when);
+                }
+                _ => return Err(format!("line {} is bad", n + 1)),
+            }
+        }
+        Ok(scores)
+    }
+
+    // none yet if the file isn't there
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn best(&self) -> Option<u64> {
+        self.entries.first().map(|e| e.score)
+    }
+
+    // a game that ended on score at when, returning its place on the board from 1, or None if it
+    // didn't make it. nothing's kept for scoring nothing
+    pub fn record(&mut self, score: u64, when: u64) -> Option<usize> {
+        let place = self.entries.iter().position(|e| e.score < score);
+        let place = place.unwrap_or(self.entries.len());
+        if score == 0 || place >= KEEP {
+            return None;
+        }
+        self.entries.insert(place, Entry { score, when });
+        self.entries.truncate(KEEP);
+        Some(place + 1)
+    }
+
+    // the board as overlay lines, e.g. "1. 4200  2026-10-14"
+    pub fn board(&self) -> Vec<String> {
+        if self.entries.is_empty() {
+            return vec![tr("No high scores yet").to_string()];
+        }
+        let mut lines = vec![tr("High scores").to_string()];
+        for (n, e) in self.entries.iter().enumerate() {
+            let place = format!("{}.", n + 1);
+            lines.push(format!(
+                "{:<3} {:>6}  {}",
+                place,
+                e.score,
+                playtime::date(e.when)
+            ));
+        }
+        lines
+    }
+}
+
+impl fmt::Display for Scores {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for e in &self.entries {
+            writeln!(f, "{} {}", e.score, e.when)?;
+        }
+        Ok(())
+    }
+}
+
+// the game on screen's score and the best it's got to, so the board can be told when it's over
+#[derive(Debug, Default)]
+pub struct Game {
+    best: u64,
+}
+
+impl Game {
+    // the score as of this frame, returning the score of a game that's just ended, when it drops
+    pub fn frame(&mut self, score: u64) -> Option<u64> {
+        let over = (score < self.best).then_some(self.best);
+        if over.is_some() || score > self.best {
+            self.best = score;
+        }
+        over
+    }
+
+    // the score so far, for when the emulator stops mid game
+    pub fn best(&self) -> u64 {
+        self.best
+    }
+}
@@ -0,0 +1,172 @@
+// the screen as an image to compare against a reference, for golden tests: ours, and ROM authors'
+// own CI through the library, e.g.
+//
+//     screenshot::assert_matches(chip8.screen(), Path::new("tests/golden/title.txt"), 0);
+//
+// references are the text the headless frontend prints (. and #) or a plain PBM, by extension.
+// set CHIP8_BLESS=1 to write them from the screen instead, after a deliberate change
+
+use std::fmt;
+use std::path::Path;
+
+pub const WIDTH: usize = 64;
+pub const HEIGHT: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Screenshot {
+    pixels: Vec<bool>, // row major
+}
+
+impl Screenshot {
+    // from a 64x32 framebuffer, one byte per pixel
+    pub fn of(gfx: &[u8]) -> Self {
+        Self {
+            pixels: (0..WIDTH * HEIGHT)
+                .map(|i| gfx.get(i).is_some_and(|&p| p != 0))
+                .collect(),
+        }
+    }
+
+    // either render_text's rows of . and #, or a PBM as render_pbm writes it
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let pixels: Vec<bool> = match text.strip_prefix("P1") {
+            Some(pbm) => {
+                let mut fields = (pbm.lines())
+                    .map(|l| l.split('#').next().unwrap_or(""))
+                    .flat_map(str::split_whitespace);
+                let size = (fields.next(), fields.next());
+                if size != (Some("64"), Some("32")) {
+                    return Err("the PBM isn't 64x32".to_string());
+                }
+                (fields.flat_map(str::chars))
+                    .map(|c| match c {
+                        '0' => Ok(false),
+                        '1' => Ok(true),
+                        _ => Err(format!("{} in a PBM", c)),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            None => {
+                let rows: Vec<&str> = text.lines().collect();
+                if rows.len() != HEIGHT || rows.iter().any(|r| r.chars().count() != WIDTH) {
+                    return Err("the screen isn't 32 rows of 64".to_string());
+                }
+                (rows.iter().flat_map(|r| r.chars()))
+                    .map(|c| match c {
+                        '.' => Ok(false),
+                        '#' => Ok(true),
+                        _ => Err(format!("{} in a screen", c)),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        if pixels.len() != WIDTH * HEIGHT {
+            return Err(format!("{} pixels, not {}", pixels.len(), WIDTH * HEIGHT));
+        }
+        Ok(Self { pixels })
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * WIDTH + x]
+    }
+
+    fn rows(&self, on: char, off: char) -> Vec<String> {
+        (self.pixels.chunks(WIDTH))
+            .map(|row| row.iter().map(|&p| if p { on } else { off }).collect())
+            .collect()
+    }
+
+    pub fn text(&self) -> String {
+        self.rows('#', '.')
+            .iter()
+            .map(|r| format!("{}\n", r))
+            .collect()
+    }
+
+    pub fn pbm(&self) -> String {
+        let mut out = format!("P1\n{} {}\n", WIDTH, HEIGHT);
+        for row in self.rows('1', '0') {
+            let row: Vec<String> = row.chars().map(String::from).collect();
+            out.push_str(&row.join(" "));
+            out.push('\n');
+        }
+        out
+    }
+
+    // actual matches if no more than tolerance pixels differ from this
+    pub fn compare(&self, actual: &Self, tolerance: usize) -> Result<(), Mismatch> {
+        let differing = (self.pixels.iter().zip(&actual.pixels))
+            .filter(|(e, a)| e != a)
+            .count();
+        if differing <= tolerance {
+            return Ok(());
+        }
+        Err(Mismatch {
+            differing,
+            tolerance,
+            expected: self.clone(),
+            actual: actual.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub differing: usize, // pixels
+    pub tolerance: usize,
+    pub expected: Screenshot,
+    pub actual: Screenshot,
+}
+
+// how many pixels differ, then expected, actual and an X for every pixel that differs side by
+// side, with the rows that differ marked
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} pixels differ, {} allowed",
+            self.differing, self.tolerance
+        )?;
+        writeln!(f, "{:<64}   {:<64}   diff", "expected", "actual")?;
+        let expected = self.expected.rows('#', '.');
+        let actual = self.actual.rows('#', '.');
+        for (e, a) in expected.iter().zip(&actual) {
+            let diff: String = (e.chars().zip(a.chars()))
+                .map(|(e, a)| if e == a { '.' } else { 'X' })
+                .collect();
+            let mark = if e == a { ' ' } else { '!' };
+            writeln!(f, "{} {} {} {} {}", e, mark, a, mark, diff)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+// panic unless the screen matches the reference at golden to within tolerance pixels, or write
+// it there with CHIP8_BLESS set
+#[track_caller]
+pub fn assert_matches(gfx: &[u8], golden: &Path, tolerance: usize) {
+    let actual = Screenshot::of(gfx);
+    let pbm = golden.extension().is_some_and(|e| e == "pbm");
+    if std::env::var_os("CHIP8_BLESS").is_some() {
+        let out = if pbm { actual.pbm() } else { actual.text() };
+        if let Err(e) = std::fs::write(golden, out) {
+            panic!("couldn't write {}: {}", golden.display(), e);
+        }
+        return;
+    }
+    let expected = match std::fs::read_to_string(golden) {
+        Ok(text) => Screenshot::parse(&text),
+        Err(e) => Err(format!("{}, CHIP8_BLESS=1 writes it", e)),
+    };
+    match expected.map(|e| e.compare(&actual, tolerance)) {
+        Ok(Ok(())) => {}
+        Ok(Err(mismatch)) => panic!(
+            "the screen doesn't match {}: {}",
+            golden.display(),
+            mismatch
+        ),
+        Err(e) => panic!("{}: {}", golden.display(), e),
+    }
+}
@@ -0,0 +1,73 @@
+// finding where a ROM keeps things like lives or the score, the way cheat finders do it: search
+// memory for a value, change it in the game, then keep only the matches that changed the same
+// way. memory is snapshotted at each step to compare the next one against
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    Equal(Vec<u8>), // the bytes there now, which needn't be as many as the pattern
+    Changed,
+    Unchanged,
+    Increased, // read as a big-endian number as long as the search pattern
+    Decreased,
+}
+
+impl Filter {
+    pub fn parse_word(word: &str) -> Option<Self> {
+        Some(match word {
+            "changed" => Self::Changed,
+            "same" => Self::Unchanged,
+            "up" => Self::Increased,
+            "down" => Self::Decreased,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Search {
+    len: usize,          // of the pattern searched for
+    matches: Vec<usize>, // addresses still in the running
+    snapshot: Vec<u8>,   // memory as of the last step
+}
+
+fn value(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |n, &b| n << 8 | b as u64)
+}
+
+impl Search {
+    // every place pattern is in memory
+    pub fn new(memory: &[u8], pattern: &[u8]) -> Self {
+        let matches = (0..(memory.len() + 1).saturating_sub(pattern.len()))
+            .filter(|&addr| memory[addr..].starts_with(pattern))
+            .collect();
+        Self {
+            len: pattern.len(),
+            matches,
+            snapshot: memory.to_vec(),
+        }
+    }
+
+    // drop the matches that don't pass filter, comparing memory now with the last snapshot
+    pub fn refine(&mut self, memory: &[u8], filter: &Filter) {
+        let len = self.len;
+        let before = &self.snapshot;
+        self.matches.retain(|&addr| {
+            if addr + len > memory.len().min(before.len()) {
+                return false;
+            }
+            let (old, new) = (&before[addr..addr + len], &memory[addr..addr + len]);
+            match filter {
+                Filter::Equal(bytes) => memory[addr..].starts_with(bytes),
+                Filter::Changed => old != new,
+                Filter::Unchanged => old == new,
+                Filter::Increased => value(new) > value(old),
+                Filter::Decreased => value(new) < value(old),
+            }
+        });
+        self.snapshot = memory.to_vec();
+    }
+
+    pub fn matches(&self) -> &[usize] {
+        &self.matches
+    }
+}
@@ -0,0 +1,66 @@
+// run --load FILE@ADDR: files put in memory at addresses of their own alongside the ROM, for
+// projects that keep code and data apart and would otherwise glue them together to test. the
+// ROM counts as one more segment, so nothing is allowed to overlap anything else
+
+use crate::Chip8;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub path: PathBuf,
+    pub addr: u16,
+}
+
+// FILE@ADDR with ADDR in hex, the last @ splitting them so a file name can have one in it
+impl FromStr for Segment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (path, addr) = s.rsplit_once('@').ok_or("needs FILE@ADDR")?;
+        let digits = addr.trim_start_matches("0x").trim_start_matches("0X");
+        let addr = u16::from_str_radix(digits, 16).map_err(|_| format!("bad address {}", addr))?;
+        if path.is_empty() {
+            return Err("needs FILE@ADDR".to_string());
+        }
+        Ok(Self {
+            path: PathBuf::from(path),
+            addr,
+        })
+    }
+}
+
+// write each of the named blobs into memory at its address, as long as all of them fit and none
+// of them overlap, otherwise memory is left alone
+pub fn load(chip8: &mut Chip8, segments: &[(&Path, u16, &[u8])]) -> Result<(), String> {
+    let mut spans: Vec<(usize, usize, &Path)> = (segments.iter())
+        .map(|&(path, addr, data)| (addr as usize, addr as usize + data.len(), path))
+        .collect();
+    spans.sort();
+    let len = chip8.memory().len();
+    for &(start, end, path) in &spans {
+        if end > len {
+            return Err(format!(
+                "{} at {:03X} runs {} bytes past the end of memory",
+                path.display(),
+                start,
+                end - len
+            ));
+        }
+    }
+    for pair in spans.windows(2) {
+        let ((_, end, a), (start, _, b)) = (pair[0], pair[1]);
+        if start < end {
+            return Err(format!(
+                "{} and {} overlap at {:03X}",
+                a.display(),
+                b.display(),
+                start
+            ));
+        }
+    }
+    for &(_, addr, data) in segments {
+        chip8.load_rom_at(data, addr);
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+// where options left off the command line come from. first the preset chosen with --preset, then
+// the environment, CHIP8_ and the option's name in capitals with _ for -, e.g. CHIP8_QUIRKS=schip
+// or CHIP8_TOAST_SECS=0, then a "name = value" line in the config file, e.g. quirks = schip, and
+// last the option's own default. the command line always wins. a few options can also be set for
+// one ROM with "rom.NAME.option = value" lines, see rom_setting
+
+use crate::config::Config;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Env(String), // the variable's name
+    Config,
+    Preset(String), // its name
+}
+
+// a few presets come built in, the config file can add more or change these with lines like
+// preset.speedrun.frameskip = 2
+pub const PRESETS: [(&str, &[(&str, &str)]); 3] = [
+    ("authentic-vip", &[("quirks", "chip8"), ("font", "vip")]),
+    ("modern", &[("quirks", "schip"), ("font", "schip")]),
+    (
+        "speedrun",
+        &[
+            ("frameskip", "auto"),
+            ("toast-secs", "0"),
+            ("no-focus-pause", "true"),
+        ],
+    ),
+];
+
+// a named bundle of settings
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Preset {
+    pub name: String,
+    pub settings: Vec<(String, String)>, // option and value
+}
+
+// the built in presets and then the ones only in the config file
+pub fn preset_names(config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = PRESETS.iter().map(|(name, _)| name.to_string()).collect();
+    for (option, _) in config.entries() {
+        let name = option
+            .strip_prefix("preset.")
+            .and_then(|o| o.split_once('.'));
+        if let Some((name, _)) = name.filter(|(name, _)| !names.iter().any(|n| n == name)) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+// the preset called name, built in with the config file's changes or only in the config file
+pub fn preset(name: &str, config: &Config) -> Option<Preset> {
+    let builtin = PRESETS.iter().find(|(n, _)| *n == name);
+    let mut settings: Vec<(String, String)> = (builtin.iter().flat_map(|(_, s)| s.iter()))
+        .map(|(option, value)| (option.to_string(), value.to_string()))
+        .collect();
+    let prefix = format!("preset.{}.", name);
+    let mut found = builtin.is_some();
+    for (option, value) in config.entries() {
+        if let Some(option) = option.strip_prefix(&prefix) {
+            settings.retain(|(o, _)| o != option);
+            settings.push((option.to_string(), value.to_string()));
+            found = true;
+        }
+    }
+    found.then(|| Preset {
+        name: name.to_string(),
+        settings,
+    })
+}
+
+// the value of the first --preset in a command line, which is needed before it's parsed
+pub fn preset_arg(args: &[String]) -> Option<&str> {
+    let mut args = args.iter().take_while(|a| *a != "--");
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--preset=") {
+            Some(value) => return Some(value),
+            None if arg == "--preset" => return args.next().map(String::as_str),
+            None => {}
+        }
+    }
+    None
+}
+
+// an option for one ROM from a rom.NAME.option line in the config file, NAME being the ROM's
+// file name without its extension, e.g. rom.pong.idle = 30
+pub fn rom_setting<'a>(option: &str, config: &'a Config, rom: &Path) -> Option<&'a str> {
+    let name = rom.file_stem()?.to_string_lossy();
+    config.get(&format!("rom.{}.{}", name, option))
+}
+
+// CHIP8_TOAST_SECS for toast-secs
+pub fn env_name(option: &str) -> String {
+    format!("CHIP8_{}", option.to_ascii_uppercase().replace('-', "_"))
+}
+
+// the value for an option that isn't on the command line, and where it came from. env looks up
+// an environment variable, it's a parameter so this can be tried without touching the real one
+pub fn lookup(
+    option: &str,
+    config: &Config,
+    preset: Option<&Preset>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<(String, Source)> {
+    let preset = preset.and_then(|p| Some((p, p.settings.iter().find(|(o, _)| o == option)?)));
+    if let Some((preset, (_, value))) = preset {
+        return Some((value.clone(), Source::Preset(preset.name.clone())));
+    }
+    let name = env_name(option);
+    match env(&name) {
+        Some(value) => Some((value, Source::Env(name))),
+        None => config
+            .get(option)
+            .map(|value| (value.to_string(), Source::Config)),
+    }
+}
+
+// a value for an option that's a flag, like CHIP8_STRICT=1
+pub fn flag(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" | "" => Some(false),
+        _ => None,
+    }
+}
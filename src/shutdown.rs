@@ -0,0 +1,34 @@
+// stopping when asked to from outside, with SIGINT or SIGTERM, the same way as quitting with a
+// key so reports get printed and cheats, sessions and traces get saved rather than lost. SDL
+// turns these signals into quit events itself, this covers every frontend
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_signal(_: libc::c_int) {
+    REQUESTED.store(true, Ordering::Relaxed);
+}
+
+// a second signal while shutting down still kills the process, in case that's hung
+#[cfg(unix)]
+pub fn install() {
+    let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handler;
+            action.sa_flags = libc::SA_RESETHAND;
+            libc::sigaction(signal, &action, std::ptr::null_mut());
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install() {}
+
+// a signal has come in, the main loop should stop
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::Relaxed)
+}
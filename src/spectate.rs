@@ -0,0 +1,275 @@
+// --spectate: a small web server so browsers can watch a session live, for streaming or teaching.
+// the page it serves opens a websocket back to it and gets a binary message whenever the screen
+// or the sound changes, at most once a frame: a byte of flags, then either the whole screen at a
+// bit a pixel or, when it's shorter, the offset and new value of each byte of that which changed
+
+use crate::sha1::sha1;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::time::Duration;
+
+pub const SCREEN_BYTES: usize = 64 * 32 / 8;
+
+// flags
+pub const SOUND: u8 = 1; // the sound timer is running
+pub const WHOLE: u8 = 2; // the whole screen follows, not changes to it
+
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11"; // from RFC 6455
+const TIMEOUT: Duration = Duration::from_secs(5); // for reading a request
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100); // a spectator slower than this is dropped
+
+const PAGE: &str = r#"<!doctype html>
+<meta charset="utf-8">
+<title>chip8</title>
+<style>
+body { background: #222; margin: 0; height: 100vh; display: flex; align-items: center; justify-content: center }
+canvas { width: 90vw; image-rendering: pixelated; border: 6px solid #222 }
+canvas.sound { border-color: #4c4 }
+</style>
+<canvas width="64" height="32"></canvas>
+<script>
+const canvas = document.querySelector("canvas"), ctx = canvas.getContext("2d");
+const screen = new Uint8Array(256), image = ctx.createImageData(64, 32);
+let audio, gain;
+function sound(on) {
+  if (!audio && on) {
+    audio = new AudioContext();
+    gain = audio.createGain();
+    gain.connect(audio.destination);
+    const osc = audio.createOscillator();
+    osc.type = "square";
+    osc.frequency.value = 440;
+    osc.connect(gain);
+    osc.start();
+  }
+  if (gain) gain.gain.value = on ? 0.1 : 0;
+}
+document.onclick = () => audio && audio.resume();
+const ws = new WebSocket("ws://" + location.host + "/ws");
+ws.binaryType = "arraybuffer";
+ws.onmessage = (e) => {
+  const m = new Uint8Array(e.data);
+  if (m[0] & 2) screen.set(m.subarray(1));
+  else for (let i = 1; i + 1 < m.length; i += 2) screen[m[i]] = m[i + 1];
+  for (let p = 0; p < 64 * 32; p++) {
+    const v = (screen[p >> 3] >> (7 - (p & 7))) & 1 ? 255 : 0;
+    image.data.set([v, v, v, 255], p * 4);
+  }
+  ctx.putImageData(image, 0, 0);
+  canvas.className = m[0] & 1 ? "sound" : "";
+  sound(m[0] & 1);
+};
+ws.onclose = () => document.title = "chip8 (ended)";
+</script>
+"#;
+
+// the screen at a bit a pixel, the leftmost pixel of each 8 in the top bit
+pub fn pack(gfx: &[u8]) -> [u8; SCREEN_BYTES] {
+    let mut out = [0; SCREEN_BYTES];
+    for (byte, pixels) in out.iter_mut().zip(gfx.chunks(8)) {
+        *byte = pixels.iter().fold(0, |b, &p| b << 1 | (p != 0) as u8);
+    }
+    out
+}
+
+// the message that takes a spectator who has prev, or nothing yet, to screen and sound. None if
+// there's nothing new
+pub fn encode(
+    prev: Option<&([u8; SCREEN_BYTES], bool)>,
+    screen: &[u8; SCREEN_BYTES],
+    sound: bool,
+) -> Option<Vec<u8>> {
+    let flags = if sound { SOUND } else { 0 };
+    let whole = || {
+        let mut out = vec![flags | WHOLE];
+        out.extend_from_slice(screen);
+        Some(out)
+    };
+    let (before, was_sounding) = match prev {
+        Some(prev) => prev,
+        None => return whole(),
+    };
+    let changed: Vec<usize> = (0..SCREEN_BYTES)
+        .filter(|&n| before[n] != screen[n])
+        .collect();
+    if changed.is_empty() && *was_sounding == sound {
+        return None;
+    }
+    if changed.len() * 2 >= SCREEN_BYTES {
+        return whole();
+    }
+    let mut out = vec![flags];
+    for n in changed {
+        out.extend_from_slice(&[n as u8, screen[n]]);
+    }
+    Some(out)
+}
+
+// what the page makes of the messages, to check them against
+pub struct View {
+    pub screen: [u8; SCREEN_BYTES],
+    pub sound: bool,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            screen: [0; SCREEN_BYTES],
+            sound: false,
+        }
+    }
+}
+
+impl View {
+    pub fn apply(&mut self, message: &[u8]) -> Result<(), String> {
+        let (&flags, rest) = message.split_first().ok_or("empty message")?;
+        self.sound = flags & SOUND != 0;
+        if flags & WHOLE != 0 {
+            if rest.len() != SCREEN_BYTES {
+                return Err(format!("a whole screen of {} bytes", rest.len()));
+            }
+            self.screen.copy_from_slice(rest);
+            return Ok(());
+        }
+        if rest.len() % 2 != 0 {
+            return Err("half a change".to_string());
+        }
+        for change in rest.chunks(2) {
+            self.screen[change[0] as usize] = change[1];
+        }
+        Ok(())
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const DIGITS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let n = (chunk.iter().enumerate()).fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            out.push(match i <= chunk.len() {
+                true => DIGITS[(n >> (18 - 6 * i) & 63) as usize] as char,
+                false => '=',
+            });
+        }
+    }
+    out
+}
+
+// the Sec-WebSocket-Accept answer to a handshake's Sec-WebSocket-Key
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key.trim(), GUID).as_bytes()))
+}
+
+// a websocket frame with one binary message in it, unmasked as from a server
+pub fn frame(message: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x82];
+    match message.len() {
+        len @ 0..=125 => out.push(len as u8),
+        len @ 126..=0xFFFF => {
+            out.push(126);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            out.push(127);
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(message);
+    out
+}
+
+// answer one connection: the page, or the websocket, which goes to joined once it's open
+fn answer(stream: TcpStream, joined: &mpsc::Sender<TcpStream>) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    let mut key = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.trim().to_string());
+            }
+        }
+    }
+    let mut stream = stream;
+    let path = request.split_whitespace().nth(1).unwrap_or("");
+    match (path, key) {
+        ("/ws", Some(key)) => {
+            write!(
+                stream,
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                accept_key(&key)
+            )?;
+            stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+            let _ = joined.send(stream);
+        }
+        ("/", _) => write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            PAGE.len(),
+            PAGE
+        )?,
+        _ => write!(
+            stream,
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )?,
+    }
+    Ok(())
+}
+
+// the server, connections are answered on threads of their own so the VM never waits on them
+pub struct Server {
+    addr: SocketAddr,
+    joined: mpsc::Receiver<TcpStream>, // spectators whose websocket has just opened
+    spectators: Vec<TcpStream>,
+    last: Option<([u8; SCREEN_BYTES], bool)>, // what the spectators have
+}
+
+impl Server {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let (tx, joined) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                std::thread::spawn(move || answer(stream, &tx));
+            }
+        });
+        Ok(Self {
+            addr,
+            joined,
+            spectators: Vec::new(),
+            last: None,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // send everyone watching what's changed since last time, and anyone new the whole screen.
+    // spectators that can't keep up or have gone are dropped
+    pub fn update(&mut self, gfx: &[u8], sound: bool) {
+        let screen = pack(gfx);
+        if let Some(message) = encode(self.last.as_ref(), &screen, sound) {
+            let message = frame(&message);
+            self.spectators
+                .retain_mut(|s| s.write_all(&message).is_ok());
+        }
+        self.last = Some((screen, sound));
+        let whole = frame(&encode(None, &screen, sound).unwrap_or_default());
+        for mut spectator in self.joined.try_iter() {
+            if spectator.write_all(&whole).is_ok() {
+                self.spectators.push(spectator);
+            }
+        }
+    }
+}
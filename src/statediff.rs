@@ -0,0 +1,115 @@
+// what changed between two save states: the registers, the bytes of memory that differ as hex
+// rows from both, and the screen as an image with what went on and off picked out
+
+use crate::chip8::StateError;
+use crate::crash::Dump;
+use crate::romdiff;
+use crate::Chip8;
+use std::fmt::Write;
+
+const ROW: usize = 16; // bytes on a row of the memory hexdump
+
+// a VM from a save state, or from the state in a crash dump
+pub fn machine(data: &[u8]) -> Result<Chip8, StateError> {
+    let mut chip8 = Chip8::new();
+    if Dump::is_dump(data) {
+        chip8.load_state(&Dump::from_bytes(data)?.state)?;
+    } else {
+        chip8.load_state(data)?;
+    }
+    Ok(chip8)
+}
+
+fn stack(chip8: &Chip8) -> String {
+    let stack: Vec<String> = chip8.stack().iter().map(|s| format!("{:03X}", s)).collect();
+    format!("[{}]", stack.join(" "))
+}
+
+// (name, value) of everything but memory and the screen, for both to be set side by side
+fn registers(chip8: &Chip8) -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = (chip8.v().iter().enumerate())
+        .map(|(x, v)| (format!("V{:X}", x), format!("{:02X}", v)))
+        .collect();
+    out.push(("I".to_string(), format!("{:03X}", chip8.i())));
+    out.push(("PC".to_string(), format!("{:03X}", chip8.pc())));
+    out.push(("DT".to_string(), format!("{:02X}", chip8.delay_timer())));
+    out.push(("ST".to_string(), format!("{:02X}", chip8.sound_timer())));
+    out.push(("stack".to_string(), stack(chip8)));
+    out.push(("quirks".to_string(), chip8.quirks().to_string()));
+    out.push(("cycles".to_string(), chip8.cycles().to_string()));
+    out.push((
+        "instructions".to_string(),
+        chip8.cycles_executed().to_string(),
+    ));
+    out.push(("frames".to_string(), chip8.frames_rendered().to_string()));
+    out
+}
+
+// a row of memory from addr, with -- where it runs past the end
+fn hex_row(memory: &[u8], addr: usize) -> String {
+    let bytes: Vec<String> = (addr..addr + ROW)
+        .map(|a| {
+            memory
+                .get(a)
+                .map_or("--".to_string(), |b| format!("{:02X}", b))
+        })
+        .collect();
+    bytes.join(" ")
+}
+
+// pixels turned on and off going from a to b
+pub fn pixel_changes(a: &Chip8, b: &Chip8) -> (usize, usize) {
+    let pairs = || a.gfx().iter().zip(b.gfx());
+    let on = pairs().filter(|(&a, &b)| a == 0 && b != 0).count();
+    let off = pairs().filter(|(&a, &b)| a != 0 && b == 0).count();
+    (on, off)
+}
+
+// the registers that differ, every 16 byte row where memory does as a - line from a and a + line
+// from b, and how much of the screen changed. empty if the states are the same
+pub fn report(a: &Chip8, b: &Chip8, a_name: &str, b_name: &str) -> String {
+    let mut out = String::new();
+    for ((name, before), (_, after)) in registers(a).into_iter().zip(registers(b)) {
+        if before != after {
+            let _ = writeln!(out, "{} {} -> {}", name, before, after);
+        }
+    }
+    let mut last_row = None;
+    for hunk in romdiff::diff(a.memory(), b.memory()) {
+        for row in (hunk.start / ROW * ROW..hunk.end).step_by(ROW) {
+            if last_row.is_some_and(|last| last >= row) {
+                continue;
+            }
+            last_row = Some(row);
+            let _ = writeln!(out, "-{:03X}: {}", row, hex_row(a.memory(), row));
+            let _ = writeln!(out, "+{:03X}: {}", row, hex_row(b.memory(), row));
+        }
+    }
+    let (on, off) = pixel_changes(a, b);
+    if on + off > 0 {
+        let _ = writeln!(out, "screen {} pixels on, {} off", on, off);
+    }
+    if out.is_empty() {
+        return out;
+    }
+    format!("--- {}\n+++ {}\n{}", a_name, b_name, out)
+}
+
+// the screens as a plain PPM image: white where a pixel is on in both, green where it came on
+// going from a to b and red where it went off
+pub fn screen_image(a: &Chip8, b: &Chip8) -> String {
+    let mut out = String::from("P3\n64 32\n255\n");
+    for (a, b) in a.gfx().chunks(64).zip(b.gfx().chunks(64)) {
+        let row: Vec<&str> = (a.iter().zip(b))
+            .map(|(&a, &b)| match (a != 0, b != 0) {
+                (true, true) => "255 255 255",
+                (false, true) => "0 255 0",
+                (true, false) => "255 0 0",
+                (false, false) => "0 0 0",
+            })
+            .collect();
+        out.push_str(&row.join("  "));
+        out.push('\n');
+    }
+    out
+}
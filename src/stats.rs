@@ -0,0 +1,125 @@
+use crate::disasm::Instruction;
+use crate::observer::{Event, Observer};
+use crate::runner::CYCLE_TIME;
+use crate::Chip8;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// counts of what a ROM spent its time doing, printed by run --stats
+#[derive(Default)]
+pub struct Stats {
+    counts: HashMap<&'static str, (u64, &'static str)>, // pattern -> executions, mnemonic
+    cycles: u64,
+    frames: (Option<u64>, u64), // frames_rendered when first and last seen
+    draws: u64,                 // DXYN that actually drew, not display-wait retries
+    sprite_rows: u64,           // summed height of those sprites
+    key_wait: u64,              // cycles spent blocked in FX0A
+    delay_sets: u64,            // FX15
+    delay_reads: u64,           // FX07
+    sound_sets: u64,            // FX18
+    sound_cycles: u64,          // cycles the buzzer was on for
+    last: Option<Instruction>,  // previous instruction, to see what it did once it's run
+}
+
+impl Observer for Stats {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        let opcode = match *event {
+            Event::Exec { opcode, .. } => opcode,
+            _ => return,
+        };
+
+        if let Some(Instruction::Drw(_, _, n)) = self.last {
+            if chip8.draw_flag() {
+                self.draws += 1;
+                self.sprite_rows += n as u64;
+            }
+        }
+
+        let instruction = Instruction::decode(opcode);
+        self.cycles += 1;
+        self.frames = (
+            self.frames.0.or(Some(chip8.frames_rendered())),
+            chip8.frames_rendered(),
+        );
+        let entry = self
+            .counts
+            .entry(instruction.pattern())
+            .or_insert((0, instruction.mnemonic()));
+        entry.0 += 1;
+        match instruction {
+            Instruction::LdVxK(_) if self.last == Some(instruction) => self.key_wait += 1,
+            Instruction::LdDtVx(_) => self.delay_sets += 1,
+            Instruction::LdVxDt(_) => self.delay_reads += 1,
+            Instruction::LdStVx(_) => self.sound_sets += 1,
+            _ => {}
+        }
+        if chip8.sound_flag() {
+            self.sound_cycles += 1;
+        }
+        self.last = Some(instruction);
+    }
+}
+
+fn seconds(cycles: u64) -> f64 {
+    cycles as f64 * CYCLE_TIME.as_secs_f64()
+}
+
+impl Stats {
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        let total = self.cycles.max(1) as f64;
+        let _ = writeln!(
+            out,
+            "{} instructions, {} frames, {:.1}s of CHIP-8 time",
+            self.cycles,
+            self.frames.1.saturating_sub(self.frames.0.unwrap_or(0)),
+            seconds(self.cycles)
+        );
+
+        let mut counts: Vec<(&&str, &(u64, &str))> = self.counts.iter().collect();
+        counts.sort_by(|a, b| (b.1).0.cmp(&(a.1).0).then(a.0.cmp(b.0)));
+        let _ = writeln!(
+            out,
+            "\n{:<7} {:<8} {:>10} {:>6}",
+            "opcode", "mnemonic", "count", "%"
+        );
+        for (pattern, (count, mnemonic)) in counts {
+            let _ = writeln!(
+                out,
+                "{:<7} {:<8} {:>10} {:>6.2}",
+                pattern,
+                mnemonic,
+                count,
+                *count as f64 * 100.0 / total
+            );
+        }
+
+        let secs = seconds(self.cycles).max(f64::EPSILON);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "draws: {} ({:.1}/s), average sprite height {:.1}",
+            self.draws,
+            self.draws as f64 / secs,
+            self.sprite_rows as f64 / self.draws.max(1) as f64
+        );
+        let _ = writeln!(
+            out,
+            "waiting for keys: {:.1}s ({:.1}%)",
+            seconds(self.key_wait),
+            self.key_wait as f64 * 100.0 / total
+        );
+        let _ = writeln!(
+            out,
+            "delay timer: set {} times, read {} times",
+            self.delay_sets, self.delay_reads
+        );
+        let _ = writeln!(
+            out,
+            "sound timer: set {} times, buzzer on for {:.1}s",
+            self.sound_sets,
+            seconds(self.sound_cycles)
+        );
+        out
+    }
+}
@@ -0,0 +1,130 @@
+// a few lines per opcode test: run_ops! assembles opcodes into a ROM, runs it to the end and
+// hands back the VM to check with chained assertions, e.g.
+//
+//     run_ops!([0x6005, 0x7001]).v(0, 6).vf(0);
+//     run_ops!(Quirks::CHIP8; [0x60FF, 0x8006]).v(0, 0x7F).vf(1);
+//
+// only built with the testing feature, which the crate's own tests turn on
+
+use crate::chip8::Error;
+use crate::headless;
+use crate::quirks::Quirks;
+use crate::Chip8;
+
+const START: u16 = 0x200;
+const MAX_CYCLES: usize = 10_000; // for programs that loop, so a broken one fails rather than hangs
+
+#[macro_export]
+macro_rules! run_ops {
+    ($quirks:expr; $ops:expr) => {
+        $crate::testing::Ops::run(&$ops, $quirks)
+    };
+    ($ops:expr) => {
+        $crate::testing::Ops::run(&$ops, $crate::quirks::Quirks::default())
+    };
+}
+
+pub struct Ops {
+    pub chip8: Chip8,
+    pub error: Option<Error>, // what stopped the program before its end, if anything
+    end: u16,
+}
+
+impl Ops {
+    // run ops from 0x200 until control leaves them, the VM halts or there's an error
+    pub fn run(ops: &[u16], quirks: Quirks) -> Self {
+        let rom: Vec<u8> = ops.iter().flat_map(|op| op.to_be_bytes()).collect();
+        let mut chip8 = headless::machine(&rom, quirks);
+        let end = START + rom.len() as u16;
+        let mut error = None;
+        for _ in 0..MAX_CYCLES {
+            if !(START..end).contains(&chip8.pc()) || chip8.halted() {
+                break;
+            }
+            if let Err(e) = chip8.emulate_cycle() {
+                error = Some(e);
+                break;
+            }
+        }
+        Self { chip8, error, end }
+    }
+
+    #[track_caller]
+    pub fn v(&self, x: usize, value: u8) -> &Self {
+        assert_eq!(self.chip8.v()[x], value, "V{:X}", x);
+        self
+    }
+
+    #[track_caller]
+    pub fn vf(&self, value: u8) -> &Self {
+        self.v(0xF, value)
+    }
+
+    #[track_caller]
+    pub fn i(&self, value: u16) -> &Self {
+        assert_eq!(self.chip8.i(), value, "I");
+        self
+    }
+
+    #[track_caller]
+    pub fn pc(&self, value: u16) -> &Self {
+        assert_eq!(self.chip8.pc(), value, "PC");
+        self
+    }
+
+    // control ran off the end of the program rather than jumping away or stopping
+    #[track_caller]
+    pub fn finished(&self) -> &Self {
+        self.ok().pc(self.end)
+    }
+
+    #[track_caller]
+    pub fn mem(&self, addr: usize, bytes: &[u8]) -> &Self {
+        let memory = &self.chip8.memory()[addr..addr + bytes.len()];
+        assert_eq!(memory, bytes, "memory at {:03X}", addr);
+        self
+    }
+
+    #[track_caller]
+    pub fn stack(&self, stack: &[u16]) -> &Self {
+        assert_eq!(self.chip8.stack(), stack, "stack");
+        self
+    }
+
+    #[track_caller]
+    pub fn delay(&self, value: u8) -> &Self {
+        assert_eq!(self.chip8.delay_timer(), value, "delay timer");
+        self
+    }
+
+    #[track_caller]
+    pub fn sound(&self, value: u8) -> &Self {
+        assert_eq!(self.chip8.sound_timer(), value, "sound timer");
+        self
+    }
+
+    #[track_caller]
+    pub fn pixel(&self, x: usize, y: usize, on: bool) -> &Self {
+        assert_eq!(self.chip8.gfx()[y * 64 + x] != 0, on, "pixel {},{}", x, y);
+        self
+    }
+
+    #[track_caller]
+    pub fn lit(&self, count: usize) -> &Self {
+        let lit = self.chip8.gfx().iter().filter(|&&p| p != 0).count();
+        assert_eq!(lit, count, "lit pixels");
+        self
+    }
+
+    #[track_caller]
+    pub fn ok(&self) -> &Self {
+        assert_eq!(self.error, None, "error");
+        self
+    }
+
+    #[track_caller]
+    pub fn err(&self, error: Error) -> &Self {
+        assert_eq!(self.error, Some(error), "error");
+        self
+    }
+}
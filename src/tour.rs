@@ -0,0 +1,136 @@
+// chip8 tour: a small ROM run slowly with a note on each instruction as it runs, and the
+// registers it changed, to watch how the VM works from the inside
+
+use crate::disasm::Instruction;
+use crate::i18n::{tr, trf};
+use crate::Chip8;
+
+// draws the digits 0 to 3, a smiley in a subroutine and 123 from its decimal digits, waits on
+// the delay timer, beeps and stops
+pub const ROM: [u8; 84] = [
+    0x00, 0xE0, 0x6A, 0x08, 0x6B, 0x0E, 0x6C, 0x00, 0xFC, 0x29, 0xDA, 0xB5, 0x7A, 0x05, 0x7C, 0x01,
+    0x3C, 0x04, 0x12, 0x08, 0x22, 0x42, 0x6E, 0x7B, 0xA2, 0x54, 0xFE, 0x33, 0xF2, 0x65, 0x6A, 0x08,
+    0x6B, 0x16, 0xF0, 0x29, 0xDA, 0xB5, 0x7A, 0x05, 0xF1, 0x29, 0xDA, 0xB5, 0x7A, 0x05, 0xF2, 0x29,
+    0xDA, 0xB5, 0x6D, 0x03, 0xFD, 0x15, 0xFD, 0x07, 0x3D, 0x00, 0x12, 0x36, 0x6D, 0x01, 0xFD, 0x18,
+    0x12, 0x40, 0xA2, 0x4C, 0x60, 0x30, 0x61, 0x0E, 0xD0, 0x18, 0x00, 0xEE, 0x3C, 0x42, 0xA5, 0x81,
+    0xA5, 0x99, 0x42, 0x3C,
+];
+
+// short enough to fit across the SDL window
+pub const NOTES: [(u16, &str); 38] = [
+    (0x200, "clear the screen"),
+    (0x202, "VA is where across to draw"),
+    (0x204, "and VB how far down"),
+    (0x206, "VC counts the digits drawn"),
+    (0x208, "point I at VC's digit's sprite"),
+    (0x20A, "draw 5 rows of sprite from I"),
+    (0x20C, "move right for the next one"),
+    (0x20E, "count the digit"),
+    (0x210, "skip the jump after 4 digits"),
+    (0x212, "back for the next digit"),
+    (0x214, "call a subroutine at 242"),
+    (0x216, "VE is 123, 7B in hex"),
+    (0x218, "point I at some spare memory"),
+    (0x21A, "store VE's digits at I, I+1, I+2"),
+    (0x21C, "load them into V0, V1 and V2"),
+    (0x21E, "back to the left"),
+    (0x220, "and further down"),
+    (0x222, "the hundreds digit in V0"),
+    (0x224, "draw it"),
+    (0x226, "move right"),
+    (0x228, "the tens in V1"),
+    (0x22A, "draw it"),
+    (0x22C, "move right"),
+    (0x22E, "the ones in V2"),
+    (0x230, "draw it"),
+    (0x232, "3 ticks of the 60Hz timers"),
+    (0x234, "start the delay timer"),
+    (0x236, "see how far it's counted down"),
+    (0x238, "skip the jump once it's 0"),
+    (0x23A, "not yet, look again"),
+    (0x23C, "one tick of sound"),
+    (0x23E, "beep while the sound timer runs"),
+    (0x240, "jump here forever: the end"),
+    (0x242, "point I at the smiley's sprite"),
+    (0x244, "V0 is where across"),
+    (0x246, "V1 how far down"),
+    (0x248, "draw 8 rows, VF is 1 on a hit"),
+    (0x24A, "return to after the call"),
+];
+
+pub fn note(pc: u16) -> Option<&'static str> {
+    NOTES.iter().find(|(a, _)| *a == pc).map(|(_, note)| *note)
+}
+
+// what an instruction can change, to show which of it did
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Registers {
+    pub v: [u8; 16],
+    pub i: u16,
+    pub pc: u16,
+    pub dt: u8,
+    pub st: u8,
+    pub depth: usize, // of the stack
+}
+
+impl Registers {
+    pub fn of(chip8: &Chip8) -> Self {
+        let mut v = [0; 16];
+        v.copy_from_slice(chip8.v());
+        Self {
+            v,
+            i: chip8.i(),
+            pc: chip8.pc(),
+            dt: chip8.delay_timer(),
+            st: chip8.sound_timer(),
+            depth: chip8.stack().len(),
+        }
+    }
+}
+
+// what changed between before and after the instruction at before.pc, e.g. "VA 0D  I 05A".
+// the PC only when it went somewhere other than the next instruction
+pub fn changes(before: &Registers, after: &Registers) -> String {
+    let mut out: Vec<String> = (0..16)
+        .filter(|&x| before.v[x] != after.v[x])
+        .map(|x| format!("V{:X} {:02X}", x, after.v[x]))
+        .collect();
+    if before.i != after.i {
+        out.push(format!("I {:03X}", after.i));
+    }
+    if before.dt != after.dt {
+        out.push(format!("DT {:02X}", after.dt));
+    }
+    if before.st != after.st {
+        out.push(format!("ST {:02X}", after.st));
+    }
+    if before.depth != after.depth {
+        out.push(format!("stack {}", after.depth));
+    }
+    if after.pc != before.pc.wrapping_add(2) {
+        out.push(format!("PC {:03X}", after.pc));
+    }
+    out.join("  ")
+}
+
+// the lines shown over the screen: the instruction that just ran, the note on it and what it
+// changed, or before anything has run where the tour starts
+pub fn overlay(chip8: &Chip8, before: Option<&Registers>) -> Vec<String> {
+    let before = match before {
+        Some(before) => before,
+        None => {
+            return vec![trf(
+                "the tour starts at {}",
+                &[&format!("{:03X}", chip8.pc())],
+            )]
+        }
+    };
+    let memory = chip8.memory();
+    let pc = before.pc as usize;
+    let opcode = u16::from_be_bytes([memory[pc], memory[pc + 1]]);
+    vec![
+        format!("{:03X} {}", pc, Instruction::decode(opcode)),
+        note(before.pc).map(tr).unwrap_or_default().to_string(),
+        changes(before, &Registers::of(chip8)),
+    ]
+}
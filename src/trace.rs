@@ -0,0 +1,86 @@
+use crate::disasm::Instruction;
+use crate::labels::Labels;
+use crate::observer::{Event, Observer};
+use crate::Chip8;
+use std::collections::HashMap;
+use std::io::Write;
+
+// writes one line per executed instruction along with the register state before it ran, and
+// after a ; its cycle number and the label I points into if it has one. a comment marks where
+// each frame was drawn
+pub struct Tracer<W: Write> {
+    out: W,
+    decoded: HashMap<u16, String>, // disassembly by address, dropped when the code there is overwritten
+    labels: Labels,
+    frames: u64, // frames_rendered as of the last line
+}
+
+impl<W: Write> Tracer<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            decoded: HashMap::new(),
+            labels: Labels::default(),
+            frames: 0,
+        }
+    }
+
+    pub fn with_labels(self, labels: Labels) -> Self {
+        Self { labels, ..self }
+    }
+
+    // " (score+1)" for an address in a labelled region
+    fn label(&self, addr: u16) -> String {
+        (self.labels.describe(addr)).map_or(String::new(), |label| format!(" ({})", label))
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+impl<W: Write> Observer for Tracer<W> {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        match *event {
+            Event::Exec { pc, opcode } => {
+                if chip8.frames_rendered() != self.frames {
+                    self.frames = chip8.frames_rendered();
+                    let _ = writeln!(self.out, "; frame {}", self.frames);
+                }
+                let text = self
+                    .decoded
+                    .entry(pc)
+                    .or_insert_with(|| Instruction::decode(opcode).to_string());
+                let _ = write!(self.out, "{:03X}: {:04X}  {:<18}", pc, opcode, text);
+                for v in chip8.v() {
+                    let _ = write!(self.out, " {:02X}", v);
+                }
+                let _ = write!(
+                    self.out,
+                    "  I={:03X}  ; cycle {}",
+                    chip8.i(),
+                    chip8.cycles_executed()
+                );
+                let _ = match self.labels.describe(chip8.i()) {
+                    Some(label) => writeln!(self.out, ", {}", label),
+                    None => writeln!(self.out),
+                };
+            }
+            Event::SelfModify { pc, addr, old, new } => {
+                // the instruction could start at addr or the byte before it
+                self.decoded.remove(&addr);
+                self.decoded.remove(&addr.wrapping_sub(1));
+                let _ = writeln!(
+                    self.out,
+                    "; self-modifying write to {:03X}{} by {:03X}, {:02X} -> {:02X}",
+                    addr,
+                    self.label(addr),
+                    pc,
+                    old,
+                    new
+                );
+            }
+            _ => {}
+        }
+    }
+}
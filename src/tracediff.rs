@@ -0,0 +1,205 @@
+// compares two traces written by --trace (or converted to its format from another emulator),
+// lining up where they start and finding the first instruction where they disagree
+
+use std::fmt::{self, Write};
+
+const MAX_SKIP: usize = 1000; // furthest into either trace to look for where the other starts
+
+// one executed instruction and the registers before it ran
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub line: usize, // in the trace file, from 1
+    pub text: String,
+    pub pc: u16,
+    pub opcode: u16,
+    pub v: [u8; 16],
+    pub i: u16,
+}
+
+impl Step {
+    fn same_as(&self, other: &Step) -> bool {
+        (self.pc, self.opcode, self.v, self.i) == (other.pc, other.opcode, other.v, other.i)
+    }
+
+    // what's different about other, e.g. "PC 204 vs 206, V3 0A vs 0B"
+    fn differences(&self, other: &Step) -> String {
+        let mut out = Vec::new();
+        if self.pc != other.pc {
+            out.push(format!("PC {:03X} vs {:03X}", self.pc, other.pc));
+        }
+        if self.opcode != other.opcode {
+            out.push(format!(
+                "opcode {:04X} vs {:04X}",
+                self.opcode, other.opcode
+            ));
+        }
+        for (n, (a, b)) in self.v.iter().zip(&other.v).enumerate() {
+            if a != b {
+                out.push(format!("V{:X} {:02X} vs {:02X}", n, a, b));
+            }
+        }
+        if self.i != other.i {
+            out.push(format!("I {:03X} vs {:03X}", self.i, other.i));
+        }
+        out.join(", ")
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} isn't a trace line: {}", self.line, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn hex(s: &str) -> Option<u16> {
+    u16::from_str_radix(s, 16).ok()
+}
+
+// a line is "PC: OPCODE  DISASSEMBLY  V0 .. VF  I=ADDR", lines starting with ; are comments
+fn parse_line(text: &str) -> Option<Step> {
+    // anything after a ; is a note, like the label I is in
+    let fields = text.split(';').next().unwrap_or(text);
+    let words: Vec<&str> = fields.split_whitespace().collect();
+    if words.len() < 2 + 16 + 1 {
+        return None;
+    }
+    let i = words[words.len() - 1].strip_prefix("I=").and_then(hex)?;
+    let mut v = [0; 16];
+    for (reg, word) in v.iter_mut().zip(&words[words.len() - 17..words.len() - 1]) {
+        *reg = u8::from_str_radix(word, 16).ok()?;
+    }
+    Some(Step {
+        line: 0,
+        text: text.to_string(),
+        pc: words[0].strip_suffix(':').and_then(hex)?,
+        opcode: hex(words[1])?,
+        v,
+        i,
+    })
+}
+
+pub fn parse(trace: &str) -> Result<Vec<Step>, ParseError> {
+    let mut steps = Vec::new();
+    for (n, text) in trace.lines().enumerate() {
+        let text = text.trim_end();
+        if text.is_empty() || text.starts_with(';') {
+            continue;
+        }
+        let step = parse_line(text).ok_or_else(|| ParseError {
+            line: n + 1,
+            text: text.to_string(),
+        })?;
+        steps.push(Step {
+            line: n + 1,
+            ..step
+        });
+    }
+    Ok(steps)
+}
+
+pub struct Diff<'a> {
+    pub skipped: (usize, usize), // leading steps of each trace dropped to line them up
+    pub matched: usize,          // steps that agree after that
+    a: &'a [Step],               // the traces from where they line up
+    b: &'a [Step],
+}
+
+// how many steps to drop from the start of a and b so they begin at the same state. traces made
+// by different emulators, or started at different times, don't always begin at the same place
+fn align(a: &[Step], b: &[Step]) -> (usize, usize) {
+    let find =
+        |steps: &[Step], step: &Step| steps.iter().take(MAX_SKIP).position(|s| s.same_as(step));
+    let in_a = b.first().and_then(|first| find(a, first));
+    let in_b = a.first().and_then(|first| find(b, first));
+    match (in_a, in_b) {
+        (Some(skip_a), Some(skip_b)) if skip_b < skip_a => (0, skip_b),
+        (Some(skip_a), _) => (skip_a, 0),
+        (None, Some(skip_b)) => (0, skip_b),
+        (None, None) => (0, 0),
+    }
+}
+
+pub fn diff<'a>(a: &'a [Step], b: &'a [Step]) -> Diff<'a> {
+    let skipped = align(a, b);
+    let (a, b) = (&a[skipped.0..], &b[skipped.1..]);
+    let matched = a.iter().zip(b).take_while(|(a, b)| a.same_as(b)).count();
+    Diff {
+        skipped,
+        matched,
+        a,
+        b,
+    }
+}
+
+impl Diff<'_> {
+    // the first steps that disagree, if they do before one of the traces ends
+    pub fn diverged(&self) -> Option<(&Step, &Step)> {
+        self.a.get(self.matched).zip(self.b.get(self.matched))
+    }
+
+    // a summary of the diff for traces named a_name and b_name, with context lines either side of
+    // the divergence
+    pub fn report(&self, a_name: &str, b_name: &str, context: usize) -> String {
+        let mut out = String::new();
+        if self.skipped.0 > 0 {
+            let _ = writeln!(
+                out,
+                "skipped {} steps of {} to line up",
+                self.skipped.0, a_name
+            );
+        }
+        if self.skipped.1 > 0 {
+            let _ = writeln!(
+                out,
+                "skipped {} steps of {} to line up",
+                self.skipped.1, b_name
+            );
+        }
+        let (step_a, step_b) = match self.diverged() {
+            Some(steps) => steps,
+            None => {
+                let _ = write!(out, "traces agree for {} steps", self.matched);
+                match self.a.len().cmp(&self.b.len()) {
+                    std::cmp::Ordering::Less => {
+                        let _ = write!(out, ", {} ends first", a_name);
+                    }
+                    std::cmp::Ordering::Greater => {
+                        let _ = write!(out, ", {} ends first", b_name);
+                    }
+                    std::cmp::Ordering::Equal => {}
+                }
+                out.push('\n');
+                return out;
+            }
+        };
+
+        let _ = writeln!(
+            out,
+            "traces diverge after {} matching steps, at {} line {} and {} line {}: {}",
+            self.matched,
+            a_name,
+            step_a.line,
+            b_name,
+            step_b.line,
+            step_a.differences(step_b)
+        );
+        let start = self.matched.saturating_sub(context);
+        for (name, steps) in [(a_name, self.a), (b_name, self.b)] {
+            let _ = writeln!(out, "--- {}", name);
+            let end = (self.matched + context + 1).min(steps.len());
+            for (n, step) in steps[start..end].iter().enumerate() {
+                let mark = if start + n == self.matched { '>' } else { ' ' };
+                let _ = writeln!(out, "{}{:>7}  {}", mark, step.line, step.text);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,81 @@
+// --pipe-video: the screen as raw RGB frames for ffmpeg to encode, one for every 60Hz tick of the
+// VM so the video plays at the game's own speed whatever the host managed or the frameskip
+// dropped. there's no header, ffmpeg is told the size and rate, see command()
+
+use crate::palette::Palette;
+use std::io::{self, Write};
+
+pub const WIDTH: usize = 64;
+pub const HEIGHT: usize = 32;
+pub const RATE: u32 = 60;
+
+pub struct VideoPipe<W: Write> {
+    out: W,
+    scale: usize,
+    palette: Palette,
+    frame: Vec<u8>, // the last screen as it's sent, rgb24 rows top to bottom
+    pub frames: u64,
+}
+
+impl<W: Write> VideoPipe<W> {
+    // each pixel of the screen as scale by scale pixels
+    pub fn new(out: W, scale: usize) -> Self {
+        Self {
+            out,
+            scale,
+            palette: Palette::default(),
+            frame: Vec::new(),
+            frames: 0,
+        }
+    }
+
+    pub fn with_palette(self, palette: Palette) -> Self {
+        Self { palette, ..self }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (WIDTH * self.scale, HEIGHT * self.scale)
+    }
+
+    // the ffmpeg arguments to read frames from input and encode them to output
+    pub fn command(&self, input: &str, output: &str) -> String {
+        let (w, h) = self.size();
+        format!(
+            "ffmpeg -f rawvideo -pix_fmt rgb24 -s {}x{} -r {} -i {} {}",
+            w, h, RATE, input, output
+        )
+    }
+
+    fn render(&mut self, screen: &[u8]) {
+        self.frame.clear();
+        for row in screen.chunks(WIDTH) {
+            let start = self.frame.len();
+            for &p in row {
+                for _ in 0..self.scale {
+                    self.frame.extend_from_slice(&self.palette.color(p));
+                }
+            }
+            let end = self.frame.len();
+            for _ in 1..self.scale {
+                self.frame.extend_from_within(start..end);
+            }
+        }
+    }
+
+    // the screen for each of ticks frames, all the same as it's only seen once a frame
+    pub fn frames(&mut self, screen: &[u8], ticks: u64) -> io::Result<()> {
+        if ticks == 0 {
+            return Ok(());
+        }
+        self.render(screen);
+        for _ in 0..ticks {
+            self.out.write_all(&self.frame)?;
+        }
+        self.frames += ticks;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
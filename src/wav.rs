@@ -0,0 +1,70 @@
+// --record-audio: the buzzer as a WAV file, 16 bit mono PCM made a frame at a time from the VM's
+// sound timer, so it lines up with --pipe-video and not with whatever the speakers were doing
+
+use crate::synth::{Buzzer, Model, SAMPLE_RATE};
+use std::io::{self, Seek, SeekFrom, Write};
+
+pub const SAMPLES_PER_TICK: usize = (SAMPLE_RATE / 60) as usize;
+
+const HEADER_LEN: u32 = 44;
+
+pub struct WavWriter<W: Write + Seek> {
+    out: W,
+    buzzer: Buzzer,
+    buf: Vec<f32>,
+    pub samples: u64,
+}
+
+fn header(samples: u32) -> Vec<u8> {
+    let data_len = samples * 2;
+    let mut out = Vec::with_capacity(HEADER_LEN as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(HEADER_LEN - 8 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes()); // the fmt chunk's length
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // mono
+    out.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    out.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // bytes a second
+    out.extend_from_slice(&2u16.to_le_bytes()); // bytes a sample
+    out.extend_from_slice(&16u16.to_le_bytes()); // bits a sample
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    out
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    // the lengths in the header are filled in by finish
+    pub fn new(mut out: W, model: Model) -> io::Result<Self> {
+        out.write_all(&header(0))?;
+        Ok(Self {
+            out,
+            buzzer: Buzzer::new(model, SAMPLE_RATE),
+            buf: vec![0.0; SAMPLES_PER_TICK],
+            samples: 0,
+        })
+    }
+
+    // ticks frames of the buzzer, sounding or not
+    pub fn frames(&mut self, sound: bool, ticks: u64) -> io::Result<()> {
+        for _ in 0..ticks {
+            self.buzzer.fill(sound, &mut self.buf);
+            let bytes: Vec<u8> = (self.buf.iter())
+                .flat_map(|&s| ((s * i16::MAX as f32) as i16).to_le_bytes())
+                .collect();
+            self.out.write_all(&bytes)?;
+            self.samples += SAMPLES_PER_TICK as u64;
+        }
+        Ok(())
+    }
+
+    // write the lengths into the header, the file's no good without
+    pub fn finish(mut self) -> io::Result<W> {
+        let samples = self.samples.min(((u32::MAX - HEADER_LEN) / 2) as u64) as u32;
+        self.out.seek(SeekFrom::Start(0))?;
+        self.out.write_all(&header(samples))?;
+        self.out.seek(SeekFrom::End(0))?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
@@ -0,0 +1,46 @@
+// achievements unlock once, when their condition first holds for long enough, and not again
+
+use chip8::achievements::{Achievements, Unlocked};
+use chip8::config::Config;
+use chip8::labels::{Labels, Region};
+use chip8::Chip8;
+
+fn achievements(text: &str) -> Result<Achievements, String> {
+    let mut labels = Labels::default();
+    labels.insert("lives", Region::parse("300").unwrap());
+    Achievements::from_config(&Config::parse(text).unwrap(), &labels)
+}
+
+#[test]
+fn unlock() {
+    let mut goals = achievements("Full house = lives >= 5\nSurvivor = lives > 0 for 2").unwrap();
+    let mut unlocked = Unlocked::default();
+    let mut chip8 = Chip8::new();
+    chip8.memory_mut()[0x300] = 3;
+    assert!(goals.frame(&chip8, 60, &unlocked).is_empty());
+    chip8.memory_mut()[0x300] = 0;
+    assert!(goals.frame(&chip8, 60, &unlocked).is_empty(), "lost a life");
+    chip8.memory_mut()[0x300] = 5;
+    assert_eq!(goals.frame(&chip8, 60, &unlocked), ["Full house"]);
+    assert!(unlocked.unlock("Full house", 1000));
+    assert!(!unlocked.unlock("Full house", 2000));
+    assert_eq!(goals.frame(&chip8, 60, &unlocked), ["Survivor"]);
+    unlocked.unlock("Survivor", 3000);
+    assert!(goals.frame(&chip8, 60, &unlocked).is_empty());
+
+    let text = unlocked.to_string();
+    assert_eq!(text, "1000 Full house\n3000 Survivor\n");
+    assert_eq!(Unlocked::parse(&text).unwrap(), unlocked);
+    assert!(Unlocked::parse("Full house\n").is_err());
+    assert!(Unlocked::parse("1000\n").is_err());
+}
+
+#[test]
+fn bad() {
+    assert!(achievements("").unwrap().is_empty());
+    assert!(achievements("a = lives > 0\na = 1").is_err(), "twice");
+    assert!(achievements("a = lives >").is_err());
+    assert!(achievements("a = lives for ever").is_err());
+    assert!(achievements("a = mem[0x300..0x302]").is_err());
+    assert!(achievements("a = score > 0").is_err(), "no such name");
+}
@@ -0,0 +1,86 @@
+// what a ROM's control flow is found to be: its blocks and their edges, the subroutines, and
+// which bytes are reachable code rather than data
+
+use chip8::analysis::{Analysis, ByteKind};
+
+// 200: LD V0, 5     a branch on V0, one way into a loop and the other through a subroutine
+// 202: SE V0, 5
+// 204: JP 20A
+// 206: CALL 210
+// 208: JP 208
+// 20A: LD I, 214
+// 20C: JP 208
+// 20E: CLS          never reached
+// 210: LD V1, 1
+// 212: RET
+// 214: sprite
+const ROM: [u8; 22] = [
+    0x60, 0x05, 0x30, 0x05, 0x12, 0x0A, 0x22, 0x10, 0x12, 0x08, 0xA2, 0x14, 0x12, 0x08, 0x00, 0xE0,
+    0x61, 0x01, 0x00, 0xEE, 0xF0, 0x90,
+];
+
+#[test]
+fn blocks() {
+    let analysis = Analysis::new(&ROM);
+    let blocks: Vec<_> = (analysis.blocks.values())
+        .map(|b| (b.start, b.end, b.succs.clone(), b.call))
+        .collect();
+    assert_eq!(
+        blocks,
+        [
+            (0x200, 0x204, vec![0x204, 0x206], None),
+            (0x204, 0x206, vec![0x20A], None),
+            (0x206, 0x208, vec![0x208], Some(0x210)),
+            (0x208, 0x20A, vec![0x208], None),
+            (0x20A, 0x20E, vec![0x208], None),
+            (0x210, 0x214, vec![], None),
+        ]
+    );
+    assert_eq!(analysis.functions.len(), 2);
+    assert!(analysis.functions[&0x200].contains(&0x210));
+    assert!(analysis.functions[&0x210].is_empty());
+    assert_eq!(
+        analysis.data_refs.iter().copied().collect::<Vec<_>>(),
+        [0x214]
+    );
+
+    let dot = analysis.cfg_dot();
+    assert!(dot.contains("\"200\" -> \"206\";"));
+    assert!(dot.contains("\"206\" -> \"210\" [style=dashed];"));
+    assert!(analysis
+        .call_graph_dot()
+        .contains("\"sub_200\" -> \"sub_210\";"));
+}
+
+#[test]
+fn reachability() {
+    let analysis = Analysis::new(&ROM);
+    assert_eq!(analysis.kind(0x20A), Some(ByteKind::Code));
+    assert_eq!(analysis.kind(0x20B), Some(ByteKind::Operand));
+    assert!(analysis.is_code(0x212));
+    assert!(
+        !analysis.is_code(0x20E),
+        "past a jump nothing comes back to"
+    );
+    assert_eq!(analysis.kind(0x20E), Some(ByteKind::Data));
+    assert_eq!(analysis.kind(0x214), Some(ByteKind::Data));
+    assert_eq!(analysis.kind(0x216), None);
+    assert_eq!(analysis.code_bytes(), 9 * 2);
+
+    let listing = analysis.disassemble();
+    assert!(listing.contains("\nsub_210:\n"));
+    assert!(listing.contains("L_208:\n"));
+    assert!(listing.contains("  20E: DB 0x00, 0xE0\n"));
+    assert!(listing.contains("  214: DB 0xF0, 0x90\n"));
+}
+
+// what can't be followed or writes over the code is picked out
+#[test]
+fn indirect_and_self_modifying() {
+    // LD I, 200; LD [I], V0; JP V0, 300
+    let analysis = Analysis::new(&[0xA2, 0x00, 0xF0, 0x55, 0xB3, 0x00]);
+    assert_eq!(analysis.self_modifying.get(&0x202), Some(&0x200));
+    assert!(analysis.indirect_jumps.contains(&0x204));
+    assert_eq!(analysis.blocks[&0x200].succs, Vec::<u16>::new());
+    assert!(analysis.disassemble().contains("; writes into code at 200"));
+}
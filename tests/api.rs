@@ -0,0 +1,114 @@
+// chip8-core's prelude is enough to write a frontend, observer and clock the way a crate outside
+// the workspace would, so anything taken out of it or made private breaks this first
+
+use chip8_core::prelude::*;
+use std::str::FromStr;
+
+// LD V0, 2; DRW V0, V0, 5; LD ST, V0; JP 0x204
+const ROM: [u8; 8] = [0x60, 0x02, 0xD0, 0x05, 0xF0, 0x18, 0x12, 0x04];
+
+#[derive(Default)]
+struct Screen {
+    lit: usize,
+    beeped: bool,
+}
+
+impl Display for Screen {
+    fn present(&mut self, gfx: &[u8]) {
+        self.lit = gfx.iter().filter(|&&p| p != 0).count();
+    }
+
+    fn buzzer(&mut self, on: bool) {
+        self.beeped |= on;
+    }
+}
+
+impl Keypad for Screen {
+    fn poll(&mut self, machine: &mut dyn Machine) -> Vec<Hotkey> {
+        machine.press_key(1);
+        vec![Hotkey::Mute]
+    }
+}
+
+#[derive(Default)]
+struct Count(usize);
+
+impl Observer for Count {
+    fn on_event(&mut self, _: &Chip8, event: &Event) {
+        if let Event::Exec { .. } = event {
+            self.0 += 1;
+        }
+    }
+}
+
+// a byte that's always 7
+struct Sevens;
+
+impl Mmio for Sevens {
+    fn read(&mut self, _: usize, _: u64) -> u8 {
+        7
+    }
+}
+
+// a tick every instruction
+struct Fast;
+
+impl Clock for Fast {
+    fn ticks(&mut self, cycles: u64) -> u64 {
+        cycles
+    }
+}
+
+fn run(frontend: &mut dyn Frontend, chip8: &mut Chip8, count: &mut Count, cycles: usize) {
+    for _ in 0..cycles {
+        chip8.emulate_cycle_observed(count).unwrap();
+        if chip8.draw_flag() {
+            frontend.present(chip8.gfx());
+        }
+        frontend.buzzer(chip8.sound_flag());
+        assert_eq!(frontend.poll(chip8), [Hotkey::Mute]);
+    }
+}
+
+#[test]
+fn prelude() {
+    let mut chip8 = Chip8::new();
+    let mut quirks = Quirks::from_str("chip8").unwrap();
+    quirks.display_wait = false;
+    chip8.set_quirks(quirks);
+    assert_eq!(chip8.quirks(), quirks);
+    chip8.load_rom(&ROM);
+    let mut screen = Screen::default();
+    let mut count = Count::default();
+    run(&mut screen, &mut chip8, &mut count, 4);
+    assert_eq!(count.0, 4);
+    assert!(count.0 < CYCLES_PER_TICK as usize, "all in the first tick");
+    assert_eq!(screen.lit, 14, "the font's 0");
+    assert!(screen.beeped);
+    assert!(chip8.is_key_pressed(1));
+
+    // LD I, 0xFF0; LD V0, [I]; LD DT, V0; LD V1, DT
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0xAF, 0xF0, 0xF0, 0x65, 0xF0, 0x15, 0xF1, 0x07]);
+    chip8.set_clock(Box::new(Fast));
+    chip8.set_mmio(Some(Box::new(Sevens)));
+    for _ in 0..4 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.v()[0], 7);
+    assert_eq!(chip8.v()[1], 6, "a tick went by");
+    assert_eq!(Options::default().buzzer, Model::default());
+    assert_eq!(Instruction::decode(0x00E0).to_string(), "CLS");
+
+    // and errors too
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x00, 0xEE]);
+    let error = chip8.emulate_cycle().unwrap_err();
+    assert_eq!(error, Error::StackUnderflow { pc: 0x200 });
+    let e: &dyn std::error::Error = &error;
+    assert!(e.to_string().contains("empty stack"));
+    assert_eq!(
+        chip8.load_state(b"nope").unwrap_err(),
+        StateError::NotAState
+    );
+}
@@ -0,0 +1,70 @@
+// chip8 analyze runs a directory's ROMs on several threads and has a CSV row for each
+
+use chip8::batch::{self, HEADER};
+use std::path::PathBuf;
+
+// LD V0, 1; DRW V0, V0, 1; JP 0x202
+const DRAWS: [u8; 6] = [0x60, 0x01, 0xD0, 0x01, 0x12, 0x02];
+// RET with nothing to return to
+const CRASHES: [u8; 2] = [0x00, 0xEE];
+
+#[test]
+fn analyze() {
+    let row = batch::analyze("draws.ch8", &DRAWS, 60);
+    assert_eq!(row.bytes, 6);
+    assert_eq!(row.code_bytes, 6);
+    assert_eq!(
+        row.opcodes.iter().copied().collect::<Vec<_>>(),
+        ["1NNN", "6XNN", "DXYN"]
+    );
+    assert!(row.draws_per_sec > 60.0, "{}", row.draws_per_sec);
+    assert_eq!(row.frames, 2, "the pixel goes on and off");
+    assert_eq!(row.crash, None);
+    assert!(row
+        .csv()
+        .starts_with("draws.ch8,6,6,default,default,3,1NNN 6XNN DXYN,"));
+
+    let row = batch::analyze("crashes.ch8", &CRASHES, 60);
+    assert_eq!(row.quirks, None);
+    assert!(row.crash.unwrap().contains("after 0 cycles"));
+}
+
+#[test]
+fn analyze_all() {
+    let dir = std::env::temp_dir().join(format!("chip8-batch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut roms: Vec<PathBuf> = Vec::new();
+    for n in 0..6 {
+        let path = dir.join(format!("{}.ch8", n));
+        std::fs::write(&path, if n % 2 == 0 { &DRAWS[..] } else { &CRASHES[..] }).unwrap();
+        roms.push(path);
+    }
+    roms.push(dir.join("missing.ch8"));
+    let rows = batch::analyze_all(&roms, 30, 3);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let names: Vec<&str> = rows.iter().map(|r| r.rom.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "0.ch8",
+            "1.ch8",
+            "2.ch8",
+            "3.ch8",
+            "4.ch8",
+            "5.ch8",
+            "missing.ch8"
+        ]
+    );
+    for (n, row) in rows[..6].iter().enumerate() {
+        assert_eq!(row.crash.is_some(), n % 2 == 1, "{}", row.rom);
+    }
+    assert!(rows[6].crash.is_some());
+    assert_eq!(batch::analyze_all(&[], 30, 4), []);
+
+    let csv = batch::csv(&rows);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], HEADER);
+    assert_eq!(lines.len(), 8);
+    assert!(lines.iter().all(|l| !l.contains('\n')));
+}
@@ -0,0 +1,108 @@
+// --bridge turns the VM's events into sound, draw and key signals, and publishes them as OSC
+// messages or MQTT packets
+
+use chip8::bridge::{self, Bridge, Signal, Signals};
+use chip8::observer::{Event, Observer};
+use chip8::Chip8;
+use std::io::Read;
+use std::net::{TcpListener, UdpSocket};
+use std::time::Duration;
+
+// LD V0, 2; LD ST, V0; DRW V0, V0, 1; JP 0x206
+const ROM: [u8; 8] = [0x60, 0x02, 0xF0, 0x18, 0xD0, 0x01, 0x12, 0x06];
+
+struct Collect(Signals, Vec<Signal>);
+
+impl Observer for Collect {
+    fn on_event(&mut self, chip8: &Chip8, event: &Event) {
+        let Collect(signals, out) = self;
+        signals.on(chip8, event, |s| out.push(s));
+    }
+}
+
+// run ROM for a few frames, with key 5 held down for part of one after the sound's stopped
+fn run(observer: &mut dyn Observer) {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    for cycle in 0..60 {
+        match cycle {
+            20 => chip8.set_keys(1 << 5),
+            30 => chip8.set_keys(0),
+            _ => {}
+        }
+        chip8.emulate_cycle_observed(observer).unwrap();
+    }
+}
+
+#[test]
+fn signals() {
+    let mut collect = Collect(Signals::default(), Vec::new());
+    run(&mut collect);
+    assert_eq!(
+        collect.1,
+        [
+            Signal::Sound(true),
+            Signal::Draws(1),
+            Signal::Sound(false),
+            Signal::Key(5, true),
+            Signal::Key(5, false),
+        ]
+    );
+}
+
+#[test]
+fn osc() {
+    assert_eq!(bridge::osc("/a", &[1]), b"/a\0\0,i\0\0\0\0\0\x01");
+    assert_eq!(
+        bridge::osc("/chip8/key", &[5, 0]),
+        b"/chip8/key\0\0,ii\0\0\0\0\x05\0\0\0\0"
+    );
+
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let url = format!("osc://{}", receiver.local_addr().unwrap());
+    let mut bridge = Bridge::connect(&url).unwrap();
+    run(&mut bridge);
+    let mut buf = [0; 64];
+    let n = receiver.recv(&mut buf).unwrap();
+    assert_eq!(buf[..n], bridge::osc("/chip8/sound", &[1]));
+}
+
+#[test]
+fn mqtt() {
+    assert_eq!(bridge::mqtt_publish("t", b"1"), [0x30, 4, 0, 1, b't', b'1']);
+    // lengths over 127 take another byte
+    assert_eq!(
+        bridge::mqtt_publish("t", &[0; 200])[..3],
+        [0x30, 0xCB, 0x01]
+    );
+    let connect = bridge::mqtt_connect("c");
+    assert_eq!(
+        connect,
+        [0x10, 13, 0, 4, b'M', b'Q', b'T', b'T', 4, 2, 0, 0, 0, 1, b'c']
+    );
+
+    let broker = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("mqtt://{}/art/", broker.local_addr().unwrap());
+    let mut bridge = Bridge::connect(&url).unwrap();
+    let (mut client, _) = broker.accept().unwrap();
+    run(&mut bridge);
+    drop(bridge);
+    let mut sent = Vec::new();
+    client.read_to_end(&mut sent).unwrap();
+    assert_eq!(sent[0], 0x10);
+    let publishes = &sent[2 + sent[1] as usize..];
+    let mut expected = bridge::mqtt_publish("art/sound", b"1");
+    expected.extend(bridge::mqtt_publish("art/draws", b"1"));
+    expected.extend(bridge::mqtt_publish("art/sound", b"0"));
+    expected.extend(bridge::mqtt_publish("art/key/5", b"1"));
+    assert_eq!(publishes[..expected.len()], expected);
+}
+
+#[test]
+fn bad_urls() {
+    assert!(Bridge::connect("localhost:9000").is_err());
+    assert!(Bridge::connect("http://localhost:9000").is_err());
+}
@@ -0,0 +1,75 @@
+// --run-for budgets parse with their units and run out in the VM's time, state loads or not
+
+use chip8::chip8::CYCLES_PER_TICK;
+use chip8::frontend;
+use chip8::runner::{Budget, Elapsed};
+use chip8::Chip8;
+
+// JP 0x200
+const ROM: [u8; 2] = [0x12, 0x00];
+
+#[test]
+fn budgets() {
+    assert_eq!("600f".parse(), Ok(Budget::Frames(600)));
+    assert_eq!("1.5s".parse(), Ok(Budget::Seconds(1.5)));
+    assert_eq!("5000cycles".parse(), Ok(Budget::Cycles(5000)));
+    assert!("10".parse::<Budget>().is_err());
+    assert!("-1s".parse::<Budget>().is_err());
+
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    let mut elapsed = Elapsed::default();
+    let mut cycles = 0;
+    while !Budget::Seconds(0.5).spent(&elapsed) {
+        step(&mut chip8, &mut elapsed);
+        cycles += 1;
+    }
+    assert_eq!(chip8.ticks(), 30);
+    assert_eq!(cycles, 29 * CYCLES_PER_TICK as u64 + 1);
+    assert!(Budget::Cycles(cycles).spent(&elapsed));
+    assert!(!Budget::Frames(31).spent(&elapsed));
+}
+
+fn step(chip8: &mut Chip8, elapsed: &mut Elapsed) {
+    let before = (chip8.cycles(), chip8.ticks());
+    chip8.emulate_cycle().unwrap();
+    elapsed.add(chip8, before);
+}
+
+#[test]
+fn state_loads() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    for _ in 0..5 * CYCLES_PER_TICK {
+        chip8.emulate_cycle().unwrap();
+    }
+    let early = chip8.save_state();
+    for _ in 0..20 * CYCLES_PER_TICK {
+        chip8.emulate_cycle().unwrap();
+    }
+
+    // started late, then wound back to before the start: the cycles and ticks go backwards
+    let mut elapsed = Elapsed::default();
+    let mut loaded = false;
+    while !Budget::Frames(20).spent(&elapsed) {
+        if elapsed.ticks == 10 && !loaded {
+            chip8.load_state(&early).unwrap();
+            loaded = true;
+        }
+        step(&mut chip8, &mut elapsed);
+    }
+    assert!(loaded);
+    assert_eq!(elapsed.ticks, 20);
+    assert_eq!(chip8.ticks(), 10, "ten before the state load and ten after");
+    assert!(Budget::Cycles(elapsed.cycles).spent(&elapsed));
+    assert!(elapsed.cycles < 21 * CYCLES_PER_TICK as u64);
+}
+
+#[test]
+fn pbm() {
+    let mut gfx = [0; 64 * 32];
+    gfx[1] = 1;
+    let pbm = frontend::render_pbm(&gfx);
+    assert!(pbm.starts_with("P1\n64 32\n0 1 0 0"));
+    assert_eq!(pbm.lines().count(), 2 + 32);
+}
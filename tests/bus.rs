@@ -0,0 +1,142 @@
+// memory accesses past the end follow the bus's bounds policy
+
+use chip8::bus::{Bounds, MemoryBus};
+use chip8::chip8::Error;
+use chip8::quirks::Quirks;
+use chip8::Chip8;
+
+#[test]
+fn bounds() {
+    let mut bus = MemoryBus::new(16);
+    bus.write8(15, 0x12).unwrap();
+    assert_eq!(bus.write8(16, 0), Err(16));
+    assert_eq!(bus.read16(15), Err(16));
+
+    bus.set_bounds(Bounds::Wrap);
+    bus.write8(16, 0x34).unwrap();
+    assert_eq!(bus.read16(15), Ok(0x1234));
+    assert_eq!(bus.ram()[0], 0x34);
+}
+
+// LD I, 0xFFF; LD V1, [I]; JP 0x204
+const ROM: [u8; 6] = [0xAF, 0xFF, 0xF1, 0x65, 0x12, 0x04];
+
+#[test]
+fn vm_bounds() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    chip8.emulate_cycle().unwrap();
+    assert!(matches!(
+        chip8.emulate_cycle(),
+        Err(Error::MemoryOutOfBounds { addr: 0x1000, .. })
+    ));
+
+    let mut chip8 = Chip8::new();
+    chip8.bus_mut().set_bounds(Bounds::Wrap);
+    chip8.load_rom(&ROM);
+    chip8.memory_mut()[0xFFF] = 0xAB;
+    chip8.emulate_cycle().unwrap();
+    chip8.emulate_cycle().unwrap();
+    // V1 comes from the start of the font
+    assert_eq!(&chip8.v()[..2], [0xAB, 0xF0]);
+}
+
+// LD I, 0x1234 (F000 NNNN); SE V0, 0; LD I, 0x5678 (skipped whole); LD V1, [I]; JP 0x20E
+const XO_ROM: [u8; 16] = [
+    0xF0, 0x00, 0x12, 0x34, 0x30, 0x00, 0xF0, 0x00, 0x56, 0x78, 0xF1, 0x65, 0x00, 0xE0, 0x12, 0x0E,
+];
+
+#[test]
+fn xo_memory() {
+    let mut chip8 = Chip8::new();
+    chip8.set_quirks(Quirks::XOCHIP);
+    chip8.load_rom(&XO_ROM);
+    assert_eq!(chip8.memory().len(), 0x10000);
+    chip8.memory_mut()[0x1234..0x1236].copy_from_slice(&[0xAB, 0xCD]);
+    chip8.emulate_cycle().unwrap();
+    assert_eq!((chip8.i(), chip8.pc()), (0x1234, 0x204));
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(chip8.pc(), 0x20A);
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(&chip8.v()[..2], [0xAB, 0xCD]);
+
+    let mut loaded = Chip8::new();
+    loaded.load_state(&chip8.save_state()).unwrap();
+    assert_eq!(loaded.memory().len(), 0x10000);
+    assert_eq!(loaded.memory()[0x1234], 0xAB);
+
+    // everywhere else F000 stays unknown and memory 4K
+    let mut chip8 = Chip8::new();
+    chip8.set_quirks(Quirks::SCHIP);
+    chip8.load_rom(&XO_ROM);
+    assert_eq!(chip8.memory().len(), 0x1000);
+    assert!(matches!(
+        chip8.emulate_cycle(),
+        Err(Error::UnknownOpcode { .. })
+    ));
+}
+
+#[test]
+fn permissive() {
+    // JP 0x205, landing between two instructions, then LD I, 0xFFF; LD V1, [I]
+    let rom = [0x12, 0x05, 0x00, 0xE0, 0xAF, 0xFF, 0xF1, 0x65];
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&rom);
+    chip8.emulate_cycle().unwrap();
+    assert!(chip8.emulate_cycle().is_err());
+
+    let mut chip8 = Chip8::new();
+    chip8.set_permissive(true);
+    chip8.load_rom(&rom);
+    chip8.memory_mut()[0xFFF] = 0xAB;
+    for _ in 0..3 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.pc(), 0x208);
+    assert_eq!(&chip8.v()[..2], [0xAB, 0xF0]);
+}
+
+#[test]
+fn odd_pc() {
+    // JP 0x203, running the LD V0, 0x12 there on the odd address, then JP 0xFFF
+    let rom = [0x12, 0x03, 0x00, 0x60, 0x12, 0x1F, 0xFF];
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&rom);
+    chip8.emulate_cycle().unwrap();
+    chip8.emulate_cycle().unwrap();
+    assert_eq!((chip8.v()[0], chip8.pc()), (0x12, 0x205));
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(chip8.pc(), 0xFFF);
+    // the opcode at 0xFFF would end past memory
+    assert_eq!(
+        chip8.emulate_cycle(),
+        Err(Error::PcOutOfBounds { pc: 0xFFF })
+    );
+
+    // unless it wraps, 0xFFF and 0x000 making 00E0
+    chip8.bus_mut().set_bounds(Bounds::Wrap);
+    chip8.memory_mut()[0xFFF] = 0x00;
+    chip8.memory_mut()[0x000] = 0xE0;
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(chip8.pc(), 0x1001);
+}
+
+#[test]
+fn load_rom_at() {
+    // LD V0, 7; JP 0x300, assembled for 0x300
+    let rom = [0x60, 0x07, 0x13, 0x00];
+    let mut chip8 = Chip8::new();
+    chip8.load_rom_at(&rom, 0x300);
+    chip8.set_pc(0x300);
+    assert_eq!(&chip8.memory()[0x200..0x204], [0; 4]);
+    chip8.emulate_cycle().unwrap();
+    chip8.emulate_cycle().unwrap();
+    assert_eq!((chip8.v()[0], chip8.pc()), (7, 0x300));
+
+    // as much as fits, and nothing past the end
+    let mut chip8 = Chip8::new();
+    chip8.load_rom_at(&rom, 0xFFE);
+    assert_eq!(&chip8.memory()[0xFFE..], [0x60, 0x07]);
+    chip8.load_rom_at(&rom, 0xFFFF);
+    assert_eq!(chip8.memory().len(), 0x1000);
+}
@@ -0,0 +1,29 @@
+// free memory is filled with canaries and the report shows what the ROM used of it
+
+use chip8::canary::{Canaries, CANARY};
+use chip8::Chip8;
+
+// CALL 0x206; JP 0x202; padding; LD I, 0x300; LD [I], V0..V1; RET
+const ROM: [u8; 12] = [
+    0x22, 0x06, 0x12, 0x02, 0x00, 0x00, 0xA3, 0x00, 0xF1, 0x55, 0x00, 0xEE,
+];
+
+#[test]
+fn canaries() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    let mut canaries = Canaries::new(&mut chip8, ROM.len());
+    assert_eq!(chip8.memory()[0x4F], 0x80, "the font is left alone");
+    assert_eq!(chip8.memory()[0x50], CANARY);
+    assert_eq!(chip8.memory()[0x20B], 0xEE, "and so is the ROM");
+    assert_eq!(chip8.memory()[0x20C], CANARY);
+
+    for _ in 0..10 {
+        chip8.emulate_cycle_observed(&mut canaries).unwrap();
+    }
+    assert_eq!(
+        canaries.report(&chip8),
+        "stack: 1 of 16 levels used\n\
+         memory: ROM 12 bytes at 0x200-0x20B, 2 of 4004 free bytes written (0x300-0x301)\n"
+    );
+}
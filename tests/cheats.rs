@@ -0,0 +1,95 @@
+// names found with search get cheats and HUD lines on them that survive a trip through the
+// cheats file
+
+use chip8::cheats::Cheats;
+use chip8::config::Config;
+use chip8::debugger::Debugger;
+use chip8::Chip8;
+
+#[test]
+fn freeze() {
+    let mut chip8 = Chip8::new();
+    chip8.memory_mut()[0x300..0x302].copy_from_slice(&[3, 3]);
+    let mut debugger = Debugger::default();
+    debugger.command(&mut chip8, "search 3");
+    assert!(debugger
+        .command(&mut chip8, "name lives")
+        .1
+        .starts_with("name needs"));
+    chip8.memory_mut()[0x300] = 2;
+    debugger.command(&mut chip8, "refine down");
+    assert_eq!(
+        debugger.command(&mut chip8, "name lives").1,
+        "lives at 300 = 02\n"
+    );
+    assert_eq!(
+        debugger.command(&mut chip8, "freeze lives 9").1,
+        "lives at 300 = 09, frozen at 09\n"
+    );
+
+    chip8.memory_mut()[0x300] = 0;
+    debugger.cheats.apply(&mut chip8);
+    assert_eq!(chip8.memory()[0x300], 9);
+    debugger.cheats.enabled = false;
+    chip8.memory_mut()[0x300] = 0;
+    debugger.cheats.apply(&mut chip8);
+    assert_eq!(chip8.memory()[0x300], 0);
+
+    let config = debugger.cheats.to_config().to_string();
+    assert_eq!(config, "name.lives = 300\nfreeze.lives = 09\n");
+    let cheats = Cheats::from_config(&Config::parse(&config).unwrap()).unwrap();
+    assert_eq!(cheats.addr("lives"), Some(0x300));
+    assert!(Cheats::from_config(&Config::parse("freeze.x = 1").unwrap()).is_err());
+}
+
+#[test]
+fn hud() {
+    let mut chip8 = Chip8::new();
+    chip8.memory_mut()[0x300..0x303].copy_from_slice(&[1, 2, 0]);
+    let mut debugger = Debugger::default();
+    debugger.command(&mut chip8, "name score 300");
+    debugger.command(&mut chip8, "name hi 301");
+    debugger.command(&mut chip8, "show score bcd 3");
+    assert_eq!(
+        debugger.command(&mut chip8, "show hi 2").1,
+        "score 120\nhi 512\n"
+    );
+    debugger.command(&mut chip8, "show hi hex 2");
+    assert_eq!(debugger.cheats.hud(&chip8), ["score 120", "hi 0200"]);
+
+    let config = debugger.cheats.to_config().to_string();
+    assert!(
+        config.ends_with("hud.score = bcd 3\nhud.hi = hex 2\n"),
+        "{}",
+        config
+    );
+    let cheats = Cheats::from_config(&Config::parse(&config).unwrap()).unwrap();
+    assert_eq!(cheats.hud(&chip8), ["score 120", "hi 0200"]);
+    assert!(Cheats::from_config(&Config::parse("name.a = 300\nhud.a = oct").unwrap()).is_err());
+}
+
+#[test]
+fn score() {
+    let mut chip8 = Chip8::new();
+    chip8.memory_mut()[0x300..0x303].copy_from_slice(&[1, 2, 0]);
+    let mut debugger = Debugger::default();
+    debugger.command(&mut chip8, "name score 300");
+    assert_eq!(
+        debugger.command(&mut chip8, "score score bcd 3").1,
+        "the score is 120 now\n"
+    );
+    chip8.memory_mut()[0x300] = 0x0A;
+    assert_eq!(debugger.cheats.score(&chip8), None, "not a digit");
+    assert_eq!(
+        debugger.command(&mut chip8, "score score bcd 3").1,
+        "score doesn't hold a bcd number now\n"
+    );
+
+    let config = debugger.cheats.to_config().to_string();
+    assert!(config.ends_with("score.score = bcd 3\n"), "{}", config);
+    let cheats = Cheats::from_config(&Config::parse(&config).unwrap()).unwrap();
+    chip8.memory_mut()[0x300] = 9;
+    assert_eq!(cheats.score(&chip8), Some(920));
+    let two = "name.a = 300\nname.b = 301\nscore.a = 1\nscore.b = 1";
+    assert!(Cheats::from_config(&Config::parse(two).unwrap()).is_err());
+}
@@ -0,0 +1,102 @@
+// timers count down on the ticks of whatever clock the VM is given
+
+use chip8::chip8::CYCLES_PER_TICK;
+use chip8::clock::ManualClock;
+use chip8::Chip8;
+
+// LD V0, 10; LD DT, V0; LD ST, V0; JP 0x206
+const ROM: [u8; 8] = [0x60, 0x0A, 0xF0, 0x15, 0xF0, 0x18, 0x12, 0x06];
+
+fn run(chip8: &mut Chip8, cycles: usize) {
+    for _ in 0..cycles {
+        chip8.emulate_cycle().unwrap();
+    }
+}
+
+#[test]
+fn manual_clock() {
+    let clock = ManualClock::default();
+    let mut chip8 = Chip8::new();
+    chip8.set_clock(Box::new(clock.clone()));
+    chip8.load_rom(&ROM);
+
+    run(&mut chip8, 100);
+    assert_eq!(
+        chip8.delay_timer(),
+        10,
+        "nothing ticks until the clock does"
+    );
+    clock.advance(3);
+    run(&mut chip8, 1);
+    assert_eq!(chip8.delay_timer(), 7);
+    assert_eq!(chip8.sound_timer(), 7);
+    clock.advance(20);
+    run(&mut chip8, 1);
+    assert_eq!(chip8.delay_timer(), 0);
+    assert!(!chip8.sound_flag());
+}
+
+#[test]
+fn cycle_clock() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    run(&mut chip8, 2);
+    assert_eq!(chip8.delay_timer(), 10);
+    // one tick every CYCLES_PER_TICK instructions from then on
+    run(&mut chip8, 4 * CYCLES_PER_TICK as usize);
+    assert_eq!(chip8.delay_timer(), 6);
+}
+
+#[test]
+fn vblank_draw() {
+    // CLS; LD I, 0 (the font's 0); DRW V0, V0, 5; JP 0x206
+    let rom = [0x00, 0xE0, 0xA0, 0x00, 0xD0, 0x05, 0x12, 0x06];
+    let clock = ManualClock::default();
+    let mut chip8 = Chip8::new();
+    chip8.set_clock(Box::new(clock.clone()));
+    chip8.set_quirks("default,vblank-draw".parse().unwrap());
+    chip8.load_rom(&rom);
+
+    run(&mut chip8, 3);
+    assert_eq!(chip8.gfx()[0], 1);
+    assert_eq!(chip8.screen()[0], 0, "the draw waits for the tick");
+    assert!(!chip8.draw_flag());
+    clock.advance(1);
+    run(&mut chip8, 1);
+    assert!(chip8.draw_flag());
+    assert_eq!(chip8.screen(), chip8.gfx());
+    run(&mut chip8, 1);
+    assert!(!chip8.draw_flag(), "nothing new to show");
+}
+
+#[test]
+fn timer_phase() {
+    // LD V0, 10; LD DT, V0; then LD VX, DT into V1 to V5, a tick landing on the one into V4
+    let rom = [
+        0x60, 0x0A, 0xF0, 0x15, 0xF1, 0x07, 0xF2, 0x07, 0xF3, 0x07, 0xF4, 0x07, 0xF5, 0x07,
+    ];
+    for (quirks, v4) in [("default", 10), ("default,timer-phase", 9)] {
+        let mut chip8 = Chip8::new();
+        chip8.set_quirks(quirks.parse().unwrap());
+        chip8.load_rom(&rom);
+        run(&mut chip8, 7);
+        assert_eq!(chip8.v()[1..6], [10, 10, 10, v4, 9], "{}", quirks);
+    }
+
+    // and on a clock that's ticked since the last instruction
+    let clock = ManualClock::default();
+    let mut chip8 = Chip8::new();
+    chip8.set_clock(Box::new(clock.clone()));
+    chip8.set_quirks("default,timer-phase".parse().unwrap());
+    chip8.load_rom(&rom);
+    run(&mut chip8, 3);
+    clock.advance(1);
+    run(&mut chip8, 2);
+    assert_eq!(chip8.v()[1..3], [10, 9]);
+
+    // it's kept in save states
+    let state = chip8.save_state();
+    let mut loaded = Chip8::new();
+    loaded.load_state(&state).unwrap();
+    assert!(loaded.quirks().timer_phase);
+}
@@ -0,0 +1,27 @@
+// lockstep runs under different quirks stop at the instruction that depends on them
+
+use chip8::compare::compare;
+use chip8::quirks::Quirks;
+
+// LD V0, 5; LD V1, 3; SHR V0, V1; JP 0x206
+const ROM: [u8; 8] = [0x60, 0x05, 0x61, 0x03, 0x80, 0x16, 0x12, 0x06];
+
+#[test]
+fn shift() {
+    let mut no_shifting = Quirks::DEFAULT;
+    no_shifting.shifting = false;
+    let comparison = compare(&ROM, 1000, Quirks::DEFAULT, no_shifting);
+    let d = comparison
+        .divergence
+        .as_ref()
+        .expect("shifting changes SHR");
+    assert_eq!((d.cycle, d.pc, d.opcode), (2, 0x204, 0x8016));
+    assert_eq!(d.differences, ["V0 02 vs 01"]);
+    assert!(comparison
+        .to_string()
+        .contains("quirk shifting (8XY6/8XYE shift VX instead of VY) is on for a, and changes"));
+
+    let same = compare(&ROM, 1000, Quirks::DEFAULT, Quirks::DEFAULT);
+    assert!(same.divergence.is_none());
+    assert_eq!(same.cycles, 1000);
+}
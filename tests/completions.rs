@@ -0,0 +1,65 @@
+// chip8 completions makes scripts that know every subcommand and option chip8 --help lists
+
+use std::process::Command;
+
+fn chip8(args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_chip8"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "chip8 {:?} failed", args);
+    String::from_utf8(out.stdout).unwrap()
+}
+
+// the subcommands under Commands: in some help
+fn subcommands(help: &str) -> Vec<String> {
+    help.lines()
+        .skip_while(|line| !line.starts_with("Commands:"))
+        .skip(1)
+        .take_while(|line| line.starts_with("  "))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(String::from)
+        .collect()
+}
+
+// the long options under Options:, without the dashes
+fn options(help: &str) -> Vec<String> {
+    help.lines()
+        .map(str::trim_start)
+        .filter(|line| line.starts_with('-'))
+        .filter_map(|line| line.split([' ', ',', '=']).find(|w| w.starts_with("--")))
+        .map(|option| option.trim_start_matches('-').to_string())
+        .collect()
+}
+
+#[test]
+fn completions() {
+    let help = chip8(&["--help"]);
+    let commands = subcommands(&help);
+    assert!(commands.len() > 10, "{:?}", commands);
+    let mut flags = options(&help);
+    for command in commands.iter().filter(|c| *c != "help") {
+        flags.extend(options(&chip8(&[command, "--help"])));
+    }
+    assert!(flags.iter().any(|o| o == "frontend"), "{:?}", flags);
+    assert!(flags.iter().any(|o| o == "context"), "diff's options too");
+
+    for shell in ["bash", "zsh", "fish"] {
+        let script = chip8(&["completions", shell]);
+        for command in &commands {
+            assert!(
+                script.contains(command.as_str()),
+                "{} has no {}",
+                shell,
+                command
+            );
+        }
+        for option in &flags {
+            let option = match shell {
+                "fish" => format!("-l {}", option),
+                _ => format!("--{}", option),
+            };
+            assert!(script.contains(&option), "{} has no {}", shell, option);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// a config Watcher notices the file being written, made and removed, looking only every so often
+
+use chip8::config::{Watcher, WATCH_INTERVAL};
+use std::fs::File;
+use std::time::{Duration, Instant, SystemTime};
+
+// write the file as if at the given second, so the changes don't depend on the clock's resolution
+fn write(path: &std::path::Path, text: &str, second: u64) {
+    std::fs::write(path, text).unwrap();
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(second);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(time)
+        .unwrap();
+}
+
+#[test]
+fn watcher() {
+    let path = std::env::temp_dir().join(format!("chip8-config-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let now = Instant::now();
+    let mut watcher = Watcher::new(&path, now);
+    let later = |n: u32| now + WATCH_INTERVAL * n;
+    assert!(!watcher.changed(later(1)), "there's still no file");
+
+    write(&path, "quirks = schip\n", 1_000_000);
+    assert!(!watcher.changed(later(1)), "it looked too recently");
+    assert!(watcher.changed(later(2)), "made");
+    assert!(!watcher.changed(later(3)), "said so already");
+
+    write(&path, "quirks = chip8\n", 1_000_001);
+    assert!(watcher.changed(later(4)), "written");
+
+    write(&path, "macro.F1 = 1\n", 1_000_002);
+    watcher.saw_write();
+    assert!(!watcher.changed(later(5)), "written by whatever's watching");
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(watcher.changed(later(6)), "removed");
+}
@@ -0,0 +1,84 @@
+// the core counts instructions and frames drawn apart from the cycles the clock is given, and
+// keeps both across save states
+use chip8::chip8::state_len;
+use chip8::quirks::Quirks;
+use chip8::stats::Stats;
+use chip8::Chip8;
+
+// LD V0, 1; DRW V0, V0, 1; JP 0x200
+const ROM: [u8; 6] = [0x60, 0x01, 0xD0, 0x01, 0x12, 0x00];
+
+fn vm() -> Chip8 {
+    let mut chip8 = Chip8::new();
+    let mut quirks = Quirks::default();
+    quirks.display_wait = false;
+    chip8.set_quirks(quirks);
+    chip8.load_rom(&ROM);
+    chip8
+}
+
+#[test]
+fn counters() {
+    let mut chip8 = vm();
+    chip8.set_throttle(0xD, 10);
+    for _ in 0..30 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.cycles_executed(), 30);
+    assert_eq!(chip8.frames_rendered(), 10);
+    assert_eq!(chip8.cycles(), 30 + 10 * 10);
+
+    // with the vblank draw quirk a frame is a tick that changed the screen
+    let mut chip8 = vm();
+    let mut quirks = chip8.quirks();
+    quirks.vblank_draw = true;
+    chip8.set_quirks(quirks);
+    while chip8.ticks() < 4 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert!(chip8.frames_rendered() <= 4, "{}", chip8.frames_rendered());
+    assert!(chip8.frames_rendered() > 0);
+}
+
+#[test]
+fn save_states() {
+    let mut chip8 = vm();
+    for _ in 0..7 {
+        chip8.emulate_cycle().unwrap();
+    }
+    let state = chip8.save_state();
+    assert_eq!(state_len(&state), Some(state.len()));
+    let mut loaded = Chip8::new();
+    loaded.load_state(&state).unwrap();
+    assert_eq!((loaded.cycles_executed(), loaded.frames_rendered()), (7, 2));
+
+    // a version 3 state, which didn't have them, counts every cycle as an instruction. it only
+    // had a byte of quirks too, the low one
+    let counts = state.len() - 8 - 0x1000 / 8 - 16;
+    let quirks = counts - 8 - 2 - 2;
+    let mut old = state[..quirks].to_vec();
+    old.extend_from_slice(&state[quirks + 1..counts]);
+    old.extend_from_slice(&state[counts + 16..]);
+    old[4] = 3;
+    assert_eq!(state_len(&old), Some(old.len()));
+    loaded.load_state(&old).unwrap();
+    assert_eq!((loaded.cycles_executed(), loaded.frames_rendered()), (7, 0));
+    assert_eq!(loaded.pc(), chip8.pc());
+}
+
+#[test]
+fn stats() {
+    let mut chip8 = vm();
+    let mut stats = Stats::default();
+    for _ in 0..3 {
+        chip8.emulate_cycle().unwrap();
+    }
+    for _ in 0..31 {
+        chip8.emulate_cycle_observed(&mut stats).unwrap();
+    }
+    assert!(
+        stats.report().starts_with("31 instructions, 10 frames,"),
+        "{}",
+        stats.report()
+    );
+}
@@ -0,0 +1,120 @@
+// a crash dump keeps enough to look into a crash later, reads back as it was written, and boots
+// the VM back where it crashed
+
+use chip8::crash::{self, Dump, History};
+use chip8::quirks::Quirks;
+use chip8::sha1::sha1;
+use chip8::Chip8;
+
+// LD V0, 7; CALL 206; RET with the stack empty, after coming back
+const ROM: [u8; 8] = [0x60, 0x07, 0x22, 0x06, 0x00, 0xEE, 0x00, 0xEE];
+
+// run the ROM until it crashes, keeping its history
+fn crash(rom: &[u8]) -> (Chip8, History, chip8::chip8::Error) {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(rom);
+    let mut history = History::default();
+    loop {
+        if let Err(e) = chip8.emulate_cycle_observed(&mut history) {
+            return (chip8, history, e);
+        }
+    }
+}
+
+#[test]
+fn round_trip() {
+    let (mut chip8, history, e) = crash(&ROM);
+    chip8.press_key(3);
+    let dump = Dump::new(&mut chip8, sha1(&ROM), &e, &history);
+    let bytes = dump.to_bytes();
+    assert!(Dump::is_dump(&bytes));
+    let read = Dump::from_bytes(&bytes).unwrap();
+    assert_eq!(read.rom_sha1, sha1(&ROM));
+    assert_eq!(read.error, e.to_string());
+    assert_eq!(
+        read.history,
+        [
+            (0x200, 0x6007),
+            (0x202, 0x2206),
+            (0x206, 0x00EE),
+            (0x204, 0x00EE)
+        ]
+    );
+    assert_eq!(read.state, dump.state);
+    let report = read.report();
+    assert!(report.starts_with(&format!("{}\nROM sha1 ", e)));
+    assert!(report.contains("  206: 00EE  RET\n"));
+
+    assert!(
+        Dump::from_bytes(&bytes[..bytes.len() / 2]).is_ok(),
+        "the state's checked on boot"
+    );
+    assert!(Dump::from_bytes(&bytes[..20]).is_err());
+    assert!(Dump::from_bytes(b"C8ST").is_err());
+}
+
+#[test]
+fn long_error() {
+    let (mut chip8, history, _) = crash(&ROM);
+    // the cut falls in the middle of the last é
+    let error = format!("{}é", "x".repeat(u16::MAX as usize - 1));
+    let dump = Dump::new(&mut chip8, sha1(&ROM), &error, &history);
+    let read = Dump::from_bytes(&dump.to_bytes()).unwrap();
+    assert_eq!(read.error, error[..error.len() - 2]);
+    assert_eq!(read.history, dump.history);
+    assert_eq!(read.state, dump.state);
+}
+
+// booted from the dump, the VM is where it crashed and crashes the same way again
+#[test]
+fn boot() {
+    let (mut crashed, history, e) = crash(&ROM);
+    crashed.press_key(3);
+    let bytes = Dump::new(&mut crashed, sha1(&ROM), &e, &history).to_bytes();
+
+    let mut chip8 = Chip8::new();
+    let mut booted = History::default();
+    Dump::from_bytes(&bytes)
+        .unwrap()
+        .boot(&mut chip8, &mut booted)
+        .unwrap();
+    assert_eq!(chip8.pc(), crashed.pc());
+    assert_eq!(chip8.v(), crashed.v());
+    assert_eq!(chip8.memory(), crashed.memory());
+    assert_eq!(chip8.keys(), 0, "nothing held from before");
+    assert_eq!(booted.entries, history.entries);
+    assert_eq!(chip8.emulate_cycle().unwrap_err(), e);
+
+    let mut truncated = Dump::from_bytes(&bytes).unwrap();
+    truncated.state.truncate(100);
+    assert!(truncated.boot(&mut Chip8::new(), &mut booted).is_err());
+}
+
+// the ROM a dump was made with is found by its hash, up to the size of 64K XO-CHIP ones
+#[test]
+fn find_rom() {
+    let dir = std::env::temp_dir().join(format!("chip8-crash-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let xo = vec![0x12; 0x10000 - 0x200];
+    std::fs::write(dir.join("small.ch8"), ROM).unwrap();
+    std::fs::write(dir.join("big.xo8"), &xo).unwrap();
+    std::fs::write(dir.join("too big"), [&xo[..], &[0]].concat()).unwrap();
+
+    let found = |rom: &[u8]| crash::find_rom(&sha1(rom), &[&dir]);
+    assert_eq!(found(&ROM), Some(dir.join("small.ch8")));
+    assert_eq!(found(&xo), Some(dir.join("big.xo8")));
+    assert_eq!(found(&[&xo[..], &[0]].concat()), None);
+
+    // and a crash in one boots back into 64K of memory
+    let mut quirks = Quirks::default();
+    quirks.xo_memory = true;
+    let mut chip8 = Chip8::new();
+    chip8.set_quirks(quirks);
+    chip8.load_rom(&[0x00, 0xEE]);
+    let e = chip8.emulate_cycle().unwrap_err();
+    let dump = Dump::new(&mut chip8, sha1(&xo), &e, &History::default());
+    let mut booted = Chip8::new();
+    dump.boot(&mut booted, &mut History::default()).unwrap();
+    assert_eq!(booted.memory().len(), 0x10000);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
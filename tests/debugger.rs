@@ -0,0 +1,350 @@
+// frame advance holds the keys set up for it and stops on the timer tick, sessions carry
+// breakpoints and watches over to the next run, regs shows the machine state, help explains
+// opcodes, follow keeps the code around the PC in view, breaks can be on events, until runs to
+// the next draw or tick and throttle makes a class of opcodes take longer
+
+use chip8::chip8::CYCLES_PER_TICK;
+use chip8::debugger::{Action, Debugger};
+use chip8::disasm::{self, Instruction};
+use chip8::Chip8;
+
+// JP 0x200
+const ROM: [u8; 2] = [0x12, 0x00];
+
+#[test]
+fn frame_advance() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    let mut debugger = Debugger::default();
+    debugger.pause();
+
+    assert_eq!(debugger.command(&mut chip8, "k 5 a 3").1, "holding 3 5 A\n");
+    assert_eq!(debugger.command(&mut chip8, "k 3").1, "holding 5 A\n");
+    assert_eq!(debugger.held_keys(), 1 << 5 | 1 << 0xA);
+    assert!(debugger
+        .command(&mut chip8, "k 10")
+        .1
+        .starts_with("bad key"));
+
+    assert_eq!(debugger.command(&mut chip8, "f 2").0, Action::Run);
+    let mut cycles = 0;
+    while !debugger.should_pause(&chip8) {
+        chip8.emulate_cycle().unwrap();
+        cycles += 1;
+    }
+    assert!(debugger.stopped());
+    assert_eq!(chip8.ticks(), 2);
+    // the first tick lands on the first instruction
+    assert_eq!(cycles, CYCLES_PER_TICK + 1);
+    assert!(chip8.is_key_pressed(5) && chip8.is_key_pressed(0xA));
+    assert!(!chip8.is_key_pressed(3));
+}
+
+#[test]
+fn session() {
+    let mut chip8 = Chip8::new();
+    let mut debugger = Debugger::default();
+    debugger.command(&mut chip8, "b 2a4");
+    debugger.command(&mut chip8, "watch mem[i]");
+    let session = debugger.session();
+    assert_eq!(session, "break 2A4\nwatch mem[i]\n");
+
+    let mut restored = Debugger::default();
+    restored.restore(&mut chip8, &session).unwrap();
+    assert_eq!(restored.session(), session);
+    assert!(restored.breakpoints.contains(&0x2A4));
+    assert_eq!(
+        restored.restore(&mut chip8, "break 2A4\nq\n"),
+        Err("line 2: unexpected q".to_string())
+    );
+}
+
+#[test]
+fn registers() {
+    // LD VA, 0x2B; CALL 0x206; LD I, 0x321; RET
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x6A, 0x2B, 0x22, 0x06, 0x00, 0x00, 0xA3, 0x21, 0x00, 0xEE]);
+    for _ in 0..3 {
+        chip8.emulate_cycle().unwrap();
+    }
+    let mut debugger = Debugger::default();
+    assert_eq!(
+        debugger.command(&mut chip8, "regs").1,
+        "V0 00  V1 00  V2 00  V3 00  V4 00  V5 00  V6 00  V7 00\n\
+         V8 00  V9 00  VA 2B  VB 00  VC 00  VD 00  VE 00  VF 00\n\
+         I  321  PC 208: 00EE  RET\n\
+         DT 00  ST 00\n\
+         stack 1/16: 202\n\
+         cycle 3  frame 0\n"
+    );
+}
+
+#[test]
+fn opcode_help() {
+    let mut chip8 = Chip8::new();
+    let mut debugger = Debugger::default();
+    let help = |debugger: &mut Debugger, chip8: &mut Chip8, line| debugger.command(chip8, line).1;
+    let drw = help(&mut debugger, &mut chip8, "help d125");
+    assert!(drw.starts_with("DXYN  DRW VX, VY, N\n"), "{}", drw);
+    assert!(drw.contains("quirk clipping:"), "{}", drw);
+    assert_eq!(help(&mut debugger, &mut chip8, "help DXYN"), drw);
+    let shl = help(&mut debugger, &mut chip8, "? shl");
+    assert!(shl.starts_with("8XYE  SHL VX, VY\n"), "{}", shl);
+    assert_eq!(
+        help(&mut debugger, &mut chip8, "help add")
+            .matches("ADD ")
+            .count(),
+        3
+    );
+    assert!(help(&mut debugger, &mut chip8, "help 5XY1").starts_with("no instruction"));
+}
+
+#[test]
+fn opcode_table() {
+    // each entry is what the decoder gives for an opcode of its pattern
+    for doc in &disasm::OPCODES {
+        let opcode = doc
+            .pattern
+            .replace('X', "1")
+            .replace('Y', "2")
+            .replace('N', "3");
+        let instruction = Instruction::decode(u16::from_str_radix(&opcode, 16).unwrap());
+        assert_eq!(instruction.doc(), Some(doc), "{}", opcode);
+        assert!(doc.syntax.starts_with(instruction.mnemonic()), "{}", opcode);
+    }
+}
+
+#[test]
+fn follow() {
+    // LD V0, 2; JP V0, 0x206; CALL 0x200; JP 0x202
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x60, 0x02, 0xB2, 0x06, 0x22, 0x00, 0x12, 0x02]);
+    let mut debugger = Debugger::default();
+    assert!(debugger.watches(&chip8).is_empty());
+    debugger.command(&mut chip8, "follow 3");
+    debugger.command(&mut chip8, "name start 200");
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(
+        debugger.watches(&chip8),
+        [
+            " 200: 6002  LD V0, 0x02",
+            ">202: B206  JP V0, 0x206  -> 208",
+            " 204: 2200  CALL 0x200 (start)",
+        ]
+    );
+    let session = debugger.session();
+    assert!(session.contains("follow 3\n"), "{}", session);
+    let mut restored = Debugger::default();
+    restored.restore(&mut chip8, &session).unwrap();
+    assert_eq!(restored.watches(&chip8).len(), 3);
+    debugger.command(&mut chip8, "follow 0");
+    assert!(debugger.watches(&chip8).is_empty());
+}
+
+#[test]
+fn event_breaks() {
+    // LD V0, 5; LD ST, V0; DRW V0, V0, 1; JP 0x206
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x60, 0x05, 0xF0, 0x18, 0xD0, 0x01, 0x12, 0x06]);
+    let mut debugger = Debugger::default();
+    for line in ["break sound", "break draw", "break key a"] {
+        debugger.command(&mut chip8, line);
+    }
+    assert_eq!(
+        debugger.command(&mut chip8, "break").1,
+        " on draw\n on sound\n on key A\n"
+    );
+    let run = |debugger: &mut Debugger, chip8: &mut Chip8| {
+        let mut cycles = 0;
+        while !debugger.should_pause(chip8) {
+            chip8.emulate_cycle_observed(debugger).unwrap();
+            cycles += 1;
+            assert!(cycles < 100, "never stopped");
+        }
+        debugger.location(chip8)
+    };
+    assert_eq!(
+        run(&mut debugger, &mut chip8),
+        "after sound, 204: D001  DRW V0, V0, 1\n"
+    );
+    debugger.command(&mut chip8, "c");
+    assert_eq!(
+        run(&mut debugger, &mut chip8),
+        "after draw, 206: 1206  JP 0x206\n"
+    );
+    debugger.command(&mut chip8, "c");
+    chip8.press_key(0xA);
+    assert_eq!(
+        run(&mut debugger, &mut chip8),
+        "after key A, 206: 1206  JP 0x206\n"
+    );
+
+    assert!(debugger
+        .session()
+        .contains("break draw\nbreak sound\nbreak key A\n"));
+    debugger.command(&mut chip8, "d draw");
+    assert!(debugger
+        .command(&mut chip8, "d draw")
+        .1
+        .starts_with("no breakpoint on draw"));
+}
+
+#[test]
+fn until() {
+    // LD V0, 1; DRW V0, V0, 1; JP 0x204
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x60, 0x01, 0xD0, 0x01, 0x12, 0x04]);
+    let mut debugger = Debugger::default();
+    debugger.pause();
+    let run = |debugger: &mut Debugger, chip8: &mut Chip8| {
+        let mut cycles = 0;
+        while !debugger.should_pause(chip8) {
+            chip8.emulate_cycle_observed(debugger).unwrap();
+            cycles += 1;
+            assert!(cycles < 100, "never stopped");
+        }
+        debugger.location(chip8)
+    };
+    assert_eq!(debugger.command(&mut chip8, "until draw").0, Action::Run);
+    assert_eq!(
+        run(&mut debugger, &mut chip8),
+        "after draw, 204: 1204  JP 0x204\n"
+    );
+    assert_eq!(chip8.cycles(), 2);
+    let ticks = chip8.ticks();
+    debugger.command(&mut chip8, "u vblank");
+    assert_eq!(
+        run(&mut debugger, &mut chip8),
+        "after vblank, 204: 1204  JP 0x204\n"
+    );
+    assert_eq!(chip8.ticks(), ticks + 1);
+    assert!(debugger.stopped());
+    assert!(debugger
+        .command(&mut chip8, "until")
+        .1
+        .starts_with("until needs"));
+
+    // counted in instructions and frames drawn, whatever the clock did
+    chip8.set_throttle(1, 5);
+    let cycles = chip8.cycles_executed();
+    assert_eq!(debugger.command(&mut chip8, "u cycle 10").0, Action::Run);
+    assert_eq!(
+        run(&mut debugger, &mut chip8),
+        "after cycle, 204: 1204  JP 0x204\n"
+    );
+    assert_eq!(chip8.cycles_executed(), 10);
+    assert_eq!(chip8.cycles(), cycles + 6 * (10 - cycles));
+    assert_eq!(chip8.frames_rendered(), 1);
+    assert!(debugger
+        .command(&mut chip8, "until cycle 10")
+        .1
+        .starts_with("already at cycle 10"));
+    assert!(debugger
+        .command(&mut chip8, "until frame")
+        .1
+        .starts_with("until frame needs a number"));
+}
+
+#[test]
+fn run_to() {
+    // LD V0, 1; DRW V0, V0, 1; JP 0x200
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x60, 0x01, 0xD0, 0x01, 0x12, 0x00]);
+    let mut debugger = Debugger::default();
+    // as run --break-at-cycle does it, running from the start
+    debugger.break_at_cycle(&chip8, 100).unwrap();
+    while !debugger.should_pause(&chip8) {
+        chip8.emulate_cycle_observed(&mut debugger).unwrap();
+    }
+    assert_eq!(chip8.cycles_executed(), 100);
+    assert!(debugger.stopped());
+    assert_eq!(
+        debugger.location(&chip8),
+        "after cycle, 202: D001  DRW V0, V0, 1\n"
+    );
+    assert_eq!(
+        debugger.break_at_cycle(&chip8, 100),
+        Err("already at cycle 100".to_string())
+    );
+
+    let frames = chip8.frames_rendered();
+    assert_eq!(
+        debugger
+            .command(&mut chip8, &format!("runto frame {}", frames + 2))
+            .0,
+        Action::Run
+    );
+    while !debugger.should_pause(&chip8) {
+        chip8.emulate_cycle_observed(&mut debugger).unwrap();
+    }
+    assert_eq!(chip8.frames_rendered(), frames + 2);
+    assert!(debugger
+        .command(&mut chip8, "runto")
+        .1
+        .starts_with("runto needs"));
+}
+
+#[test]
+fn throttle() {
+    // DRW V0, V0, 1; JP 0x200
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0xD0, 0x01, 0x12, 0x00]);
+    let mut debugger = Debugger::default();
+    assert_eq!(
+        debugger.command(&mut chip8, "throttle").1,
+        "nothing's throttled\n"
+    );
+    assert_eq!(
+        debugger.command(&mut chip8, "throttle drw 20").1,
+        "drw (DXXX) +20 cycles\n"
+    );
+    assert!(debugger
+        .command(&mut chip8, "throttle blit 1")
+        .1
+        .starts_with("bad opcode class blit"));
+    for _ in 0..4 {
+        chip8.emulate_cycle().unwrap();
+    }
+    // two draws at 21 cycles and two jumps at 1, as far as the timers go
+    assert_eq!(chip8.cycles(), 44);
+    assert_eq!(chip8.ticks(), 9);
+
+    // a debugging setting, kept when a state's loaded
+    let state = chip8.save_state();
+    chip8.set_throttle(1, 3);
+    chip8.load_state(&state).unwrap();
+    assert_eq!(
+        debugger.command(&mut chip8, "throttle D 0").1,
+        "jp (1XXX) +3 cycles\n"
+    );
+}
+
+#[test]
+fn export() {
+    // LD V0, 1; JP 0x200, with a byte poked in after it
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x60, 0x01, 0x12, 0x00]);
+    let mut debugger = Debugger::default();
+    debugger.command(&mut chip8, "write 205 AA");
+    let path = std::env::temp_dir().join(format!("chip8-export-{}.ch8", std::process::id()));
+    let export = format!("export {}", path.display());
+    assert_eq!(
+        debugger.command(&mut chip8, &export).1,
+        format!("wrote 6 bytes from 200 to {}\n", path.display())
+    );
+    assert_eq!(
+        std::fs::read(&path).unwrap(),
+        [0x60, 0x01, 0x12, 0x00, 0x00, 0xAA]
+    );
+    debugger.command(&mut chip8, &format!("{} 202 2", export));
+    assert_eq!(std::fs::read(&path).unwrap(), [0x12, 0x00]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(debugger
+        .command(&mut chip8, &format!("{} FFF 2", export))
+        .1
+        .starts_with("export runs past the end"));
+    assert!(debugger
+        .command(&mut chip8, "export")
+        .1
+        .starts_with("export needs a file name"));
+}
@@ -0,0 +1,83 @@
+// --warn, for things ROMs do that are likely bugs
+
+use chip8::diagnostics::{Diagnostics, Warning};
+use chip8::Chip8;
+
+fn warnings(rom: &[u8], cycles: usize) -> Vec<Warning> {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(rom);
+    let mut diagnostics = Diagnostics::new(&chip8, Some(rom.len()));
+    for _ in 0..cycles {
+        chip8.emulate_cycle_observed(&mut diagnostics).unwrap();
+    }
+    diagnostics.warnings
+}
+
+#[test]
+fn memory() {
+    // LD I, 0x300; LD V1, [I]; LD [I], V1; LD V1, [I]; LD I, 0x004; LD [I], V0; JP 0x20C
+    let rom = [
+        0xA3, 0x00, 0xF1, 0x65, 0xF1, 0x55, 0xF1, 0x65, 0xA0, 0x04, 0xF0, 0x55, 0x12, 0x0C,
+    ];
+    assert_eq!(
+        warnings(&rom, 20),
+        [
+            Warning::Uninitialized {
+                pc: 0x202,
+                addr: 0x300
+            },
+            Warning::FontWrite {
+                pc: 0x20A,
+                addr: 0x004
+            },
+        ]
+    );
+    assert_eq!(
+        Warning::FontWrite { pc: 0x20A, addr: 4 }.to_string(),
+        "20A: stores into the font at 004"
+    );
+}
+
+#[test]
+fn stack_and_screen() {
+    // CALL 0x200 over and over, then the stack overflows
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x22, 0x00]);
+    let mut diagnostics = Diagnostics::new(&chip8, Some(2));
+    while chip8.emulate_cycle_observed(&mut diagnostics).is_ok() {}
+    assert_eq!(
+        diagnostics.warnings,
+        [Warning::StackNearlyFull {
+            pc: 0x200,
+            depth: 14
+        }]
+    );
+
+    // JP 0x203, into LD V1, V0 (8100) at the odd address, then JP 0x203 again
+    let rom = [0x12, 0x03, 0x00, 0x81, 0x00, 0x12, 0x03];
+    assert_eq!(
+        warnings(&rom, 6),
+        [Warning::OddJump {
+            pc: 0x200,
+            target: 0x203
+        }]
+    );
+
+    // LD V0, 70; DRW V0, V0, 1 over twice
+    let rom = [0x60, 70, 0xD0, 0x01, 0xD0, 0x01, 0x12, 0x06];
+    assert_eq!(
+        warnings(&rom, 4),
+        [
+            Warning::OffScreen {
+                pc: 0x202,
+                x: 70,
+                y: 70
+            },
+            Warning::OffScreen {
+                pc: 0x204,
+                x: 70,
+                y: 70
+            },
+        ]
+    );
+}
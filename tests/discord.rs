@@ -0,0 +1,70 @@
+// --discord talks to the Discord client in frames of an opcode, a length and JSON
+#![cfg(unix)]
+
+use chip8::discord::{self, Presence, FRAME, HANDSHAKE};
+use std::convert::TryInto;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixListener;
+
+fn read_frame(stream: &mut impl Read) -> (u32, String) {
+    let mut header = [0; 8];
+    stream.read_exact(&mut header).unwrap();
+    let opcode = u32::from_le_bytes(header[..4].try_into().unwrap());
+    let mut json = vec![0; u32::from_le_bytes(header[4..].try_into().unwrap()) as usize];
+    stream.read_exact(&mut json).unwrap();
+    (opcode, String::from_utf8(json).unwrap())
+}
+
+#[test]
+fn json() {
+    assert_eq!(discord::frame(1, "{}"), b"\x01\0\0\0\x02\0\0\0{}");
+    assert_eq!(
+        discord::json_string("say \"hi\"\\\n"),
+        r#""say \"hi\"\\\u000a""#
+    );
+    assert_eq!(
+        discord::activity(42, "Pong", Some("1:00:00 played before"), 1000, 7),
+        concat!(
+            r#"{"cmd":"SET_ACTIVITY","args":{"pid":42,"activity":{"details":"Pong","#,
+            r#""state":"1:00:00 played before","timestamps":{"start":1000}}},"nonce":"7"}"#
+        )
+    );
+    assert!(
+        discord::activity(42, "Pong", None, 1000, 7).contains(r#""details":"Pong","timestamps""#)
+    );
+}
+
+#[test]
+fn presence() {
+    let dir = std::env::temp_dir().join(format!("chip8-discord-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("discord-ipc-0");
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let client = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let hello = read_frame(&mut stream);
+        stream
+            .write_all(&discord::frame(FRAME, r#"{"evt":"READY"}"#))
+            .unwrap();
+        let set = read_frame(&mut stream);
+        stream.write_all(&discord::frame(FRAME, "{}")).unwrap();
+        (hello, set)
+    });
+
+    let mut presence = Presence::connect_at(&path, "1234").unwrap();
+    presence.set("Pong", None, 1000);
+    assert!(presence.error.is_none());
+    let (hello, set) = client.join().unwrap();
+    assert_eq!(
+        hello,
+        (HANDSHAKE, r#"{"v":1,"client_id":"1234"}"#.to_string())
+    );
+    assert_eq!(set.0, FRAME);
+    assert!(set.1.contains(r#""details":"Pong""#), "{}", set.1);
+
+    // the client's gone, so it gives up until the next run
+    presence.set("Pong", None, 1000);
+    assert!(presence.error.is_some());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,63 @@
+// --expect checks for how test ROMs report passing: exiting, a screen, or a value in memory
+
+use chip8::disasm::Instruction;
+use chip8::expect::Expectation;
+use chip8::headless::frame_hash;
+use chip8::Chip8;
+
+// LD V0, 0x01; LD I, 0x300; LD [I], V0; EXIT
+const ROM: [u8; 8] = [0x60, 0x01, 0xA3, 0x00, 0xF0, 0x55, 0x00, 0xFD];
+
+fn ran() -> Chip8 {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    for _ in 0..10 {
+        chip8.emulate_cycle().unwrap();
+    }
+    chip8
+}
+
+#[test]
+fn exit() {
+    let chip8 = ran();
+    assert!(chip8.exited());
+    assert!(chip8.halted());
+    assert_eq!(chip8.pc(), 0x206);
+    assert_eq!(Instruction::decode(0x00FD).to_string(), "EXIT");
+    assert_eq!("exit".parse(), Ok(Expectation::Exit));
+    assert_eq!(Expectation::Exit.check(&chip8), Ok(()));
+
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x12, 0x00]); // JP 0x200
+    chip8.emulate_cycle().unwrap();
+    assert!(chip8.halted());
+    assert_eq!(
+        Expectation::Exit.check(&chip8),
+        Err("didn't exit, stopped at 200".to_string())
+    );
+}
+
+#[test]
+fn checks() {
+    let chip8 = ran();
+    let pass: Expectation = "mem[0x300] == 1".parse().unwrap();
+    assert_eq!(pass.check(&chip8), Ok(()));
+    let fail: Expectation = "v0 == 2".parse().unwrap();
+    assert_eq!(fail.check(&chip8), Err("v0 == 2 is 0".to_string()));
+    assert!("mem[0x300..0x302]"
+        .parse::<Expectation>()
+        .unwrap()
+        .check(&chip8)
+        .is_err());
+    assert!("v0 ==".parse::<Expectation>().is_err());
+
+    let hash = frame_hash(chip8.screen());
+    let frame: Expectation = format!("frame={:x}", hash).parse().unwrap();
+    assert_eq!(frame.check(&chip8), Ok(()));
+    let other = Expectation::Frame(hash ^ 1);
+    assert_eq!(
+        other.check(&chip8),
+        Err(format!("frame is {:016x}, not {:016x}", hash, hash ^ 1))
+    );
+    assert!("frame=xyz".parse::<Expectation>().is_err());
+}
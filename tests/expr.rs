@@ -0,0 +1,47 @@
+// debugger expressions over registers and memory, and the watches made from them
+
+use chip8::debugger::Debugger;
+use chip8::expr::{Expr, Value};
+use chip8::Chip8;
+
+// LD V3, 0x0F; LD I, 0x3A0
+const ROM: [u8; 4] = [0x63, 0x0F, 0xA3, 0xA0];
+
+fn eval(chip8: &Chip8, s: &str) -> Result<Value, String> {
+    Expr::parse(s)?.eval(chip8)
+}
+
+#[test]
+fn expressions() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    chip8.emulate_cycle().unwrap();
+    chip8.emulate_cycle().unwrap();
+    chip8.memory_mut()[0x3A0..0x3A3].copy_from_slice(&[1, 2, 3]);
+
+    assert_eq!(eval(&chip8, "v[3]*2"), Ok(Value::Number(30)));
+    assert_eq!(eval(&chip8, "v3 + 1 << 1"), Ok(Value::Number(32)));
+    assert_eq!(eval(&chip8, "(vf | 0x10) == 16"), Ok(Value::Number(1)));
+    assert_eq!(eval(&chip8, "mem[i + 2]"), Ok(Value::Number(3)));
+    assert_eq!(
+        eval(&chip8, "mem[0x3A0..0x3A3]"),
+        Ok(Value::Bytes(vec![1, 2, 3]))
+    );
+    assert!(eval(&chip8, "mem[0x1000]").is_err());
+    assert!(eval(&chip8, "v[16]").is_err());
+    assert!(eval(&chip8, "1 / (pc - pc)").is_err());
+    assert!(Expr::parse("v3 +").is_err());
+    assert!(Expr::parse("mem[i").is_err());
+
+    let mut debugger = Debugger::default();
+    debugger.command(&mut chip8, "watch mem[i..i+2]");
+    debugger.command(&mut chip8, "watch v[3]*2");
+    assert_eq!(
+        debugger.watches(&chip8),
+        ["mem[i..i+2] = 01 02", "v[3]*2 = 0x1E (30)"]
+    );
+    assert_eq!(
+        debugger.command(&mut chip8, "unwatch 1").1,
+        "1: v[3]*2 = 0x1E (30)\n"
+    );
+}
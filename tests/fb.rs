@@ -0,0 +1,184 @@
+// the fb frontend's picture and keys, without a framebuffer or input devices
+#![cfg(target_os = "linux")]
+
+use chip8::fb::{self, Geometry, Overlay, EVENT_SIZE};
+use chip8::frontend::{Hotkey, ScreenMode};
+use chip8::overlay::Corner;
+use chip8::palette::Palette;
+use std::str::FromStr;
+
+#[test]
+fn geometry() {
+    let g = Geometry::parse("1920,2160\n", "32\n", "7680\n", "U:1920x1080p-0\n").unwrap();
+    assert_eq!(
+        (g.width, g.height, g.stride, g.bytes_per_pixel),
+        (1920, 1080, 7680, 4)
+    );
+    // with no modes the whole virtual size shows
+    let g = Geometry::parse("800,480", "16", "1600", "").unwrap();
+    assert_eq!((g.width, g.height, g.bytes_per_pixel), (800, 480, 2));
+    assert!(Geometry::parse("800,480", "8", "800", "").is_err());
+    assert!(Geometry::parse("800x480", "32", "3200", "").is_err());
+    assert!(Geometry::parse("800,480", "32", "3000", "").is_err());
+
+    assert_eq!(g.pack([255, 0, 0]), 0xF800u16.to_le_bytes());
+    assert_eq!(g.pack([255, 255, 255]), [0xFF, 0xFF]);
+    let g = Geometry::parse("800,480", "32", "3200", "").unwrap();
+    assert_eq!(g.pack([1, 2, 3]), [3, 2, 1, 0]);
+    // 12 times over, with 16 pixels either side and 48 above and below
+    assert_eq!(g.layout(64, 32), (12, 16, 48));
+    assert_eq!(g.layout(128, 32), (6, 16, 144));
+}
+
+#[test]
+fn render() {
+    // 6 pixels wide and tall per screen pixel, with padding at the end of every row
+    let g = Geometry::parse("400,200", "24", "1204", "").unwrap();
+    let mut frame = vec![0xAA; g.stride * g.height];
+    let mut gfx = vec![0; 64 * 32];
+    gfx[0] = 1;
+    gfx[64 * 31 + 63] = 3;
+    let palette = Palette::from_str("high-contrast").unwrap();
+    fb::render(
+        &mut frame,
+        &g,
+        &gfx,
+        1,
+        &palette,
+        ScreenMode::Fit,
+        &Overlay::default(),
+    );
+    let stride = g.stride;
+    let pixel = |frame: &[u8], x: usize, y: usize| frame[y * stride + x * 3..][..3].to_vec();
+    let (left, top) = ((400 - 64 * 6) / 2, (200 - 32 * 6) / 2);
+    assert_eq!(pixel(&frame, 0, 0), [0, 0, 0]);
+    assert_eq!(
+        pixel(&frame, left, top),
+        [0, 255, 255],
+        "yellow, blue first"
+    );
+    assert_eq!(pixel(&frame, left + 5, top + 5), [0, 255, 255]);
+    assert_eq!(pixel(&frame, left + 6, top), [0, 0, 0]);
+    assert_eq!(
+        pixel(&frame, left + 64 * 6 - 1, top + 32 * 6 - 1),
+        [255, 255, 255]
+    );
+    assert_eq!(&frame[1200..1204], [0xAA; 4], "the padding's left alone");
+
+    // a toast goes over the bottom left of the picture
+    let toasts = Overlay {
+        toasts: &["hi"],
+        ..Default::default()
+    };
+    fb::render(&mut frame, &g, &gfx, 1, &palette, ScreenMode::Fit, &toasts);
+    let lit = (top + 160..top + 192)
+        .flat_map(|y| (left..left + 40).map(move |x| (x, y)))
+        .filter(|&(x, y)| pixel(&frame, x, y) == [0, 255, 255])
+        .count();
+    assert!(lit > 0);
+
+    // the keypad at 3 times over in the top right, with 1 held lit all over but its digit
+    let keypad = Overlay {
+        keypad: Some((1 << 1, Corner::TopRight)),
+        ..Default::default()
+    };
+    fb::render(
+        &mut frame,
+        &g,
+        &[0; 64 * 32],
+        1,
+        &palette,
+        ScreenMode::Fit,
+        &keypad,
+    );
+    let x = left + 64 * 6 - 25 * 3;
+    assert_eq!(pixel(&frame, x, top), [0, 0, 0], "the box");
+    assert_eq!(
+        pixel(&frame, x + 3, top + 3),
+        [255, 255, 255],
+        "round the 1"
+    );
+    assert_eq!(pixel(&frame, x + 6, top + 6), [255, 255, 255]);
+    assert_eq!(pixel(&frame, x + 9, top + 6), [0, 0, 0], "the 1 itself");
+    assert_eq!(pixel(&frame, x + 18 + 6, top + 6), [255, 255, 255], "the 2");
+    assert_eq!(pixel(&frame, x + 18 + 3, top + 3), [0, 0, 0]);
+}
+
+#[test]
+fn screen_modes() {
+    let g = Geometry::parse("400,200", "24", "1200", "").unwrap();
+    let mut frame = vec![0; g.stride * g.height];
+    let mut gfx = vec![0; 64 * 32];
+    gfx[0] = 1;
+    gfx[65] = 1;
+    let palette = Palette::from_str("high-contrast").unwrap();
+    let lit = |frame: &[u8], x: usize, y: usize| frame[y * g.stride + x * 3 + 1] != 0;
+    let over = Overlay::default();
+
+    // stretched to 6.25 by 6.25 a pixel from the very top left
+    fb::render(
+        &mut frame,
+        &g,
+        &gfx,
+        1,
+        &palette,
+        ScreenMode::Stretch,
+        &over,
+    );
+    assert!(lit(&frame, 0, 0));
+    assert!(lit(&frame, 5, 5));
+    assert!(!lit(&frame, 6, 5));
+    assert!(lit(&frame, 6, 6), "the second pixel down and across");
+    assert!(lit(&frame, 11, 11));
+    assert!(!lit(&frame, 12, 12));
+
+    // as dots of 6 less a gap of 1, off to the top left of each 6
+    fb::render(
+        &mut frame,
+        &g,
+        &gfx,
+        1,
+        &palette,
+        ScreenMode::DotMatrix,
+        &over,
+    );
+    let (left, top) = (8, 4);
+    assert!(lit(&frame, left, top));
+    assert!(lit(&frame, left + 4, top + 4));
+    assert!(!lit(&frame, left + 5, top + 5));
+    assert!(lit(&frame, left + 6, top + 6));
+
+    assert_eq!("dot-matrix".parse(), Ok(ScreenMode::DotMatrix));
+    assert!("zoom".parse::<ScreenMode>().is_err());
+    assert_eq!(ScreenMode::default().to_string(), "fit");
+    assert_eq!(ScreenMode::DotMatrix.gap(1), 0);
+    assert_eq!(ScreenMode::DotMatrix.gap(12), 3);
+    assert_eq!(ScreenMode::Stretch.gap(12), 0);
+}
+
+fn event(kind: u16, code: u16, value: i32) -> Vec<u8> {
+    let mut event = vec![0; EVENT_SIZE - 8];
+    event.extend(kind.to_ne_bytes());
+    event.extend(code.to_ne_bytes());
+    event.extend(value.to_ne_bytes());
+    event
+}
+
+#[test]
+fn keys() {
+    assert_eq!(fb::key_event(&event(1, 16, 1)), Some((16, true)));
+    assert_eq!(fb::key_event(&event(1, 16, 0)), Some((16, false)));
+    assert_eq!(fb::key_event(&event(1, 16, 2)), None, "a repeat");
+    assert_eq!(fb::key_event(&event(0, 0, 0)), None, "a sync");
+    assert_eq!(fb::key_event(&[0; 4]), None);
+
+    assert_eq!(fb::keypad(45, 1), Some(0)); // X
+    assert_eq!(fb::keypad(16, 1), Some(4)); // Q
+    assert_eq!(fb::keypad(47, 1), Some(0xF)); // V
+    assert_eq!(fb::keypad(22, 1), None); // U
+    assert_eq!(fb::keypad(22, 2), Some(16 + 4));
+    assert_eq!(fb::hotkey(1), Some(Hotkey::Quit));
+    assert_eq!(fb::hotkey(63), Some(Hotkey::SaveState));
+    assert_eq!(fb::hotkey(88), Some(Hotkey::Keypad));
+    assert_eq!(fb::hotkey(16), None);
+}
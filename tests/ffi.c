@@ -0,0 +1,27 @@
+/* a C program using every function in chip8.h, compiled by tests/ffi.rs to check the header */
+
+#include "chip8.h"
+
+static const uint8_t ROM[] = {0x60, 0x05, 0xF0, 0x29, 0xD0, 0x15, 0x12, 0x06};
+
+int main(void) {
+    chip8_t *vm = chip8_new();
+    size_t lit = 0;
+    int result;
+    if (chip8_load_rom(vm, ROM, sizeof ROM) != CHIP8_OK)
+        return 1;
+    chip8_seed(vm, 1);
+    chip8_set_key(vm, 0xF, true);
+    while ((result = chip8_step(vm)) == CHIP8_OK && !chip8_draw_flag(vm)) {
+    }
+    if (result == CHIP8_OK) {
+        const uint8_t *gfx = chip8_framebuffer(vm);
+        size_t i;
+        for (i = 0; i < CHIP8_WIDTH * CHIP8_HEIGHT; i++)
+            lit += gfx[i];
+    }
+    if (chip8_sound(vm))
+        lit = 0;
+    chip8_free(vm);
+    return lit == 14 ? 0 : 1;
+}
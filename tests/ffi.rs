@@ -0,0 +1,126 @@
+// the C API, called the way a C program would: through the extern "C" functions, with the NULLs
+// and lengths C can hand it. its checked in header has to be what cbindgen makes now, set
+// CHIP8_BLESS=1 to write it again after changing the API, and has to compile as C and C++
+
+use chip8_ffi::*;
+use std::path::Path;
+use std::process::Command;
+use std::ptr;
+
+const CRATE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/crates/chip8-ffi");
+
+// LD V0, 5; LD F, V0; DRW V0, V1, 5; JP 0x206
+const ROM: [u8; 8] = [0x60, 0x05, 0xF0, 0x29, 0xD0, 0x15, 0x12, 0x06];
+
+#[test]
+fn runs() {
+    unsafe {
+        let chip8 = chip8_new();
+        assert_eq!(chip8_load_rom(chip8, ROM.as_ptr(), ROM.len()), CHIP8_OK);
+        chip8_seed(chip8, 1);
+        let mut drawn = 0;
+        for _ in 0..4 {
+            assert_eq!(chip8_step(chip8), CHIP8_OK);
+            drawn += chip8_draw_flag(chip8) as usize;
+        }
+        assert_eq!(drawn, 1);
+        let gfx = std::slice::from_raw_parts(chip8_framebuffer(chip8), CHIP8_WIDTH * CHIP8_HEIGHT);
+        // the font's 5 at 5, 0, its top row all four pixels lit
+        assert_eq!(&gfx[5..10], &[1, 1, 1, 1, 0]);
+        assert_eq!(gfx.iter().filter(|&&p| p == 1).count(), 14);
+        assert!(!chip8_sound(chip8));
+        chip8_set_key(chip8, 0x1F, true); // just the low nibble, key F
+        chip8_free(chip8);
+    }
+}
+
+#[test]
+fn errors() {
+    unsafe {
+        let chip8 = chip8_new();
+        // 00EE with nothing to return to
+        assert_eq!(chip8_load_rom(chip8, [0x00, 0xEE].as_ptr(), 2), CHIP8_OK);
+        assert_eq!(chip8_step(chip8), CHIP8_ERR_STACK_UNDERFLOW);
+        chip8_free(chip8);
+    }
+}
+
+#[test]
+fn nulls() {
+    unsafe {
+        assert_eq!(
+            chip8_load_rom(ptr::null_mut(), ROM.as_ptr(), ROM.len()),
+            CHIP8_ERR_NULL
+        );
+        assert_eq!(chip8_step(ptr::null_mut()), CHIP8_ERR_NULL);
+        assert!(chip8_framebuffer(ptr::null()).is_null());
+        assert!(!chip8_draw_flag(ptr::null()));
+        assert!(!chip8_sound(ptr::null()));
+        chip8_seed(ptr::null_mut(), 1);
+        chip8_set_key(ptr::null_mut(), 1, true);
+        chip8_free(ptr::null_mut());
+
+        let chip8 = chip8_new();
+        assert_eq!(chip8_load_rom(chip8, ptr::null(), 10), CHIP8_ERR_NULL);
+        chip8_free(chip8);
+    }
+}
+
+// a ROM far too big for memory is cut off at its end, only as much as fits read
+#[test]
+fn long_roms() {
+    unsafe {
+        let rom = vec![0x12; 1 << 20];
+        let chip8 = chip8_new();
+        assert_eq!(chip8_load_rom(chip8, rom.as_ptr(), rom.len()), CHIP8_OK);
+        assert_eq!(chip8_step(chip8), CHIP8_OK, "JP 0x212");
+        chip8_free(chip8);
+    }
+}
+
+#[test]
+fn header() {
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", CRATE)).unwrap();
+    let bindings = cbindgen::Builder::new()
+        .with_crate(CRATE)
+        .with_config(config)
+        .generate()
+        .unwrap();
+    let path = Path::new(CRATE).join("include/chip8.h");
+    if std::env::var_os("CHIP8_BLESS").is_some() {
+        bindings.write_to_file(&path);
+    }
+    let mut made = Vec::new();
+    bindings.write(&mut made);
+    assert!(
+        std::fs::read(&path).unwrap() == made,
+        "{} is out of date, run CHIP8_BLESS=1 cargo test --test ffi",
+        path.display()
+    );
+}
+
+#[test]
+fn header_compiles() {
+    let dir = std::env::temp_dir().join(format!("chip8-ffi-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let include = format!("-I{}/include", CRATE);
+    let c = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/ffi.c");
+    for (compiler, args) in [
+        ("cc", &["-std=c99", "-pedantic"][..]),
+        ("c++", &["-x", "c++", "-std=c++11"][..]),
+    ] {
+        let out = Command::new(compiler)
+            .args(args)
+            .args(["-Wall", "-Wextra", "-Werror", "-c", &include, c, "-o"])
+            .arg(dir.join(format!("ffi-{}.o", compiler)))
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{} couldn't compile tests/ffi.c:\n{}",
+            compiler,
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,38 @@
+// FX29 points I at the digits of whichever font the VM was given
+
+use chip8::fonts::{self, FONTS};
+use chip8::Chip8;
+
+// LD V0, 0xB; LD F, V0; JP 0x204
+const ROM: [u8; 6] = [0x60, 0x0B, 0xF0, 0x29, 0x12, 0x04];
+
+#[test]
+fn digits() {
+    for (name, font) in &FONTS {
+        let mut chip8 = Chip8::new();
+        chip8.set_font(&fonts::parse(name).unwrap());
+        chip8.load_rom(&ROM);
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        let i = chip8.i() as usize;
+        assert_eq!(&chip8.memory()[i..i + 5], &font[55..60], "{}", name);
+    }
+}
+
+#[test]
+fn font_file() {
+    let path = std::env::temp_dir().join(format!("chip8-font-{}", std::process::id()));
+    std::fs::write(&path, fonts::ETI660).unwrap();
+    assert_eq!(fonts::parse(path.to_str().unwrap()), Ok(fonts::ETI660));
+    std::fs::write(&path, [0; 16]).unwrap();
+    let err = fonts::parse(path.to_str().unwrap()).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        err.contains("font files are 80 bytes but it's 16"),
+        "{}",
+        err
+    );
+    assert!(fonts::parse("nope")
+        .unwrap_err()
+        .contains("fonts: vip, schip"));
+}
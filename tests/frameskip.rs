@@ -0,0 +1,56 @@
+// --frameskip drops frames without losing the last one drawn
+
+use chip8::scheduler::{FrameSkip, FrameSkipper};
+
+#[test]
+fn fixed() {
+    assert_eq!("2".parse(), Ok(FrameSkip::Fixed(2)));
+    assert_eq!("auto".parse(), Ok(FrameSkip::Auto));
+    assert!("fast".parse::<FrameSkip>().is_err());
+
+    let mut skipper = FrameSkipper::new(FrameSkip::Fixed(2));
+    let shown: Vec<bool> = (0..6).map(|_| skipper.frame(true, false)).collect();
+    assert_eq!(shown, [true, false, false, true, false, false]);
+    // nothing new drawn, but the frame dropped last time still gets shown
+    assert!(skipper.frame(false, false));
+    assert!(!skipper.frame(false, false));
+
+    let mut skipper = FrameSkipper::new(FrameSkip::default());
+    assert!(skipper.frame(true, true));
+    assert!(!skipper.frame(false, true));
+}
+
+#[test]
+fn sparse_draws() {
+    // passes with nothing drawn don't count towards the frames to skip
+    let mut skipper = FrameSkipper::new(FrameSkip::Fixed(1));
+    assert!(skipper.frame(true, false));
+    assert!(!skipper.frame(false, false));
+    assert!(!skipper.frame(false, false));
+    assert!(
+        !skipper.frame(true, false),
+        "the next frame drawn is dropped"
+    );
+    assert!(skipper.frame(false, false), "then shown");
+
+    // a ROM drawing every other pass has half its frames shown
+    let mut skipper = FrameSkipper::new(FrameSkip::Fixed(2));
+    let shown: Vec<usize> = (0..16)
+        .filter(|pass| skipper.frame(pass % 2 == 0, false))
+        .collect();
+    assert_eq!(shown, [0, 4, 8, 12]);
+}
+
+#[test]
+fn auto() {
+    let mut skipper = FrameSkipper::new(FrameSkip::Auto);
+    assert!(skipper.frame(true, false));
+    assert!(!skipper.frame(true, true));
+    assert!(
+        skipper.frame(false, false),
+        "caught up, so the dropped frame is shown"
+    );
+    // even while behind the screen moves now and then
+    let shown = (0..12).filter(|_| skipper.frame(true, true)).count();
+    assert_eq!(shown, 2);
+}
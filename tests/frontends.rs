@@ -0,0 +1,296 @@
+// run goes through the frontend traits and nothing else: the script frontend, only built for the
+// tests, feeds it keys and hotkeys a line per poll and writes down everything it's shown. the
+// tui's --announce is checked the same way, its hotkeys typed into stdin
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+// LD V0, K; LD F, V0; DRW V0, V0, 5; JP 0x206. waits for a key and draws its digit at V0,V0
+const KEY_DIGIT: [u8; 8] = [0xF0, 0x0A, 0xF0, 0x29, 0xD0, 0x05, 0x12, 0x06];
+
+// a data and config directory of its own for each test, with the ROM in it
+fn setup(test: &str, rom: &[u8]) -> (PathBuf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("chip8-frontends-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.ch8", test));
+    std::fs::write(&path, rom).unwrap();
+    (dir, path)
+}
+
+fn run(dir: &Path, rom: &Path, args: &[&str], script: &[&str]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chip8"))
+        .args(["run", "--frontend", "script"])
+        .args(args)
+        .arg(rom)
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(script.join("\n").as_bytes()).unwrap();
+    drop(stdin);
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success(), "{:?}", out);
+    out
+}
+
+// each poll is about a cycle, this is plenty of them for the ROMs here to get where they're going
+fn wait(script: &mut Vec<&str>) {
+    script.extend([""; 200]);
+}
+
+fn lines(out: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn frames_and_keys() {
+    let (dir, rom) = setup("keys", &KEY_DIGIT);
+    let mut script = Vec::new();
+    wait(&mut script);
+    script.push("press 5");
+    wait(&mut script);
+    script.push("release 5");
+    wait(&mut script);
+    script.push("quit");
+    let out = lines(&run(&dir, &rom, &[], &script));
+
+    let presents: Vec<&String> = out.iter().filter(|l| l.starts_with("present ")).collect();
+    assert!(presents.len() >= 2, "{:?}", out);
+    assert_ne!(presents[0], presents[presents.len() - 1], "the 5 was drawn");
+    // the screen handed to finish, with the 5 at 5,5
+    let screen = &out[out.len() - 32..];
+    assert_eq!(&screen[5][5..9], "####");
+    assert_eq!(&screen[6][5..9], "#...");
+    assert_eq!(&screen[9][5..9], "####");
+
+    // with no key it's still waiting at the end, only the blank screen it started with shown
+    let out = lines(&run(&dir, &rom, &[], &["", "", "quit"]));
+    let presents = out.iter().filter(|l| l.starts_with("present ")).count();
+    assert_eq!(presents, 1, "{:?}", out);
+    assert!(out[out.len() - 32..].iter().all(|l| !l.contains('#')));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// LD V0, 0xFF; LD ST, V0; DRW V1, V1, 1; JP 0x200. buzzes and draws for as long as it runs
+const BUSY: [u8; 8] = [0x60, 0xFF, 0xF0, 0x18, 0xD1, 0x11, 0x12, 0x00];
+
+#[test]
+fn focus() {
+    let (dir, rom) = setup("focus", &BUSY);
+    let mut script = Vec::new();
+    wait(&mut script);
+    script.push("focus off");
+    wait(&mut script);
+    script.push("focus on");
+    wait(&mut script);
+    script.push("quit");
+
+    let out = lines(&run(&dir, &rom, &[], &script));
+    let buzzer: Vec<&String> = out.iter().filter(|l| l.starts_with("buzzer")).collect();
+    assert_eq!(
+        buzzer,
+        ["buzzer on", "buzzer off", "buzzer on"],
+        "{:?}",
+        out
+    );
+    // nothing runs, so nothing's drawn, while the window's away
+    let off = out.iter().position(|l| l == "buzzer off").unwrap();
+    let on = off + out[off..].iter().position(|l| l == "buzzer on").unwrap();
+    assert!(
+        out[off..on].iter().all(|l| !l.starts_with("present")),
+        "{:?}",
+        &out[off..on]
+    );
+    assert!(out[on..].iter().any(|l| l.starts_with("present")));
+
+    let out = lines(&run(&dir, &rom, &["--no-focus-pause"], &script));
+    let buzzer: Vec<&String> = out.iter().filter(|l| l.starts_with("buzzer")).collect();
+    assert_eq!(buzzer, ["buzzer on"], "{:?}", out);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// on SIGTERM it stops the way quitting does, so nothing it was writing is lost
+#[cfg(unix)]
+#[test]
+fn terminated() {
+    let (dir, rom) = setup("terminated", &BUSY);
+    let file = |name: &str| dir.join(name).to_str().unwrap().to_string();
+    let (trace, state, audio) = (file("trace"), file("state"), file("audio.wav"));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chip8"))
+        .args(["run", "--frontend", "script", "--stats"])
+        .args([
+            "--trace",
+            &trace,
+            "--dump-state",
+            &state,
+            "--record-audio",
+            &audio,
+        ])
+        .arg(&rom)
+        .env("XDG_DATA_HOME", &dir)
+        .env("XDG_CONFIG_HOME", &dir)
+        .stdin(Stdio::null()) // no quit, it runs until it's stopped
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // a second of frames, well into the main loop
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    let mut presents = 0;
+    while presents < 60 {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "stopped early");
+        presents += line.starts_with("present") as usize;
+    }
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+    let mut rest = String::new();
+    std::io::Read::read_to_string(&mut stdout, &mut rest).unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert!(rest.contains(" instructions, "), "the stats: {}", rest);
+    let trace = std::fs::read_to_string(&trace).unwrap();
+    assert!(trace.ends_with('\n'), "the trace is cut off");
+    let mut chip8 = chip8::Chip8::new();
+    chip8.load_state(&std::fs::read(&state).unwrap()).unwrap();
+    assert!(chip8.cycles_executed() > 0);
+    let audio = std::fs::read(&audio).unwrap();
+    let riff_len = u32::from_le_bytes([audio[4], audio[5], audio[6], audio[7]]);
+    assert_eq!(
+        riff_len as usize,
+        audio.len() - 8,
+        "the wav header is filled in"
+    );
+    let playtime = std::fs::read_to_string(dir.join("chip8/playtime")).unwrap();
+    assert!(playtime.contains("terminated.ch8"), "{}", playtime);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// LD V1, 9; LD V0, 0xFF; LD ST, V0; JP 0x202
+const NINE: [u8; 8] = [0x61, 0x09, 0x60, 0xFF, 0xF0, 0x18, 0x12, 0x02];
+
+// with --announce the tui says what happens a line at a time, for screen readers
+#[cfg(unix)]
+#[test]
+fn announce() {
+    let (dir, rom) = setup("announce", &NINE);
+    let goals = dir.join("chip8/achievements");
+    std::fs::create_dir_all(&goals).unwrap();
+    let sha1 = chip8::sha1::hex(&chip8::sha1::sha1(&NINE));
+    std::fs::write(goals.join(format!("{}.cfg", sha1)), "Nine = v[1] == 9\n").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chip8"))
+        .args(["run", "--frontend", "tui", "--announce"])
+        .arg(&rom)
+        .env("XDG_DATA_HOME", &dir)
+        .env("XDG_CONFIG_HOME", &dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // F5, F11 twice and F9: save, pause, carry on and load. then it runs by itself
+    let mut stdin = child.stdin.take().unwrap();
+    stdin
+        .write_all(b"\x1b[15~\x1b[23~\x1b[23~\x1b[20~")
+        .unwrap();
+    drop(stdin);
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut said = Vec::new();
+    while said.last().map(String::as_str) != Some("Achievement unlocked: Nine") {
+        let mut line = String::new();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "{:?}", said);
+        // the buzzer still rings the terminal's bell
+        said.push(line.trim_end().trim_start_matches('\x07').to_string());
+    }
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+    assert!(child.wait().unwrap().success());
+    assert_eq!(
+        said,
+        [
+            "State 1 saved",
+            "Paused",
+            "Achievements 0 of 1",
+            "Paused",
+            "Carrying on",
+            "State 1 loaded",
+            "sound on",
+            "Achievement unlocked: Nine",
+        ]
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// LD V0, 10; LD ST, V0; JP 0x204. a sixth of a second of sound, then quiet
+const BEEP: [u8; 6] = [0x60, 0x0A, 0xF0, 0x18, 0x12, 0x04];
+
+fn switched(out: &[String], what: &str) -> Vec<String> {
+    let what = format!("{} ", what);
+    (out.iter())
+        .filter(|l| l.starts_with(&what))
+        .cloned()
+        .collect()
+}
+
+// a beep, and while it's pausing and carrying on
+fn beeps(test: &str, args: &[&str]) -> (Vec<String>, Vec<String>) {
+    let (dir, beep) = setup(test, &BEEP);
+    let mut script = Vec::new();
+    wait(&mut script);
+    script.push("quit");
+    let beeped = lines(&run(&dir, &beep, args, &script));
+
+    let busy = dir.join("busy.ch8");
+    std::fs::write(&busy, BUSY).unwrap();
+    let mut script = Vec::new();
+    wait(&mut script);
+    script.push("pause");
+    wait(&mut script);
+    script.push("pause");
+    wait(&mut script);
+    script.push("quit");
+    let paused = lines(&run(&dir, &busy, args, &script));
+    let _ = std::fs::remove_dir_all(&dir);
+    (beeped, paused)
+}
+
+#[test]
+fn rumble() {
+    let (beeped, paused) = beeps("rumble", &["--rumble"]);
+    assert_eq!(switched(&beeped, "rumble"), ["rumble on", "rumble off"]);
+    assert_eq!(
+        switched(&paused, "rumble"),
+        ["rumble on", "rumble off", "rumble on"]
+    );
+    assert!(switched(&beeped, "flash").is_empty());
+
+    let (beeped, paused) = beeps("no-rumble", &[]);
+    assert_eq!(switched(&beeped, "buzzer"), ["buzzer on", "buzzer off"]);
+    assert!(switched(&beeped, "rumble").is_empty());
+    assert!(switched(&paused, "rumble").is_empty());
+}
+
+#[test]
+fn flash() {
+    let (beeped, paused) = beeps("flash", &["--flash-sound"]);
+    assert_eq!(switched(&beeped, "flash"), ["flash on", "flash off"]);
+    assert_eq!(
+        switched(&paused, "flash"),
+        ["flash on", "flash off", "flash on"]
+    );
+    assert!(switched(&beeped, "rumble").is_empty());
+
+    let (beeped, paused) = beeps("no-flash", &[]);
+    assert!(switched(&beeped, "flash").is_empty());
+    assert!(switched(&paused, "flash").is_empty());
+}
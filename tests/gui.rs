@@ -0,0 +1,139 @@
+// the debugger session the gui draws its panels from, and the gui run a frame at a time without
+// a window
+
+use chip8::chip8::{Error, CYCLES_PER_TICK};
+use chip8::panels::Session;
+use chip8::Chip8;
+
+// LD V0, 1; ADD V0, 1; JP 0x202
+const ROM: [u8; 6] = [0x60, 0x01, 0x70, 0x01, 0x12, 0x02];
+
+fn session(rom: &[u8]) -> Session {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(rom);
+    Session::new(chip8)
+}
+
+#[test]
+fn step() {
+    let mut session = session(&ROM);
+    assert!(session.paused());
+    session.frame();
+    assert_eq!(session.chip8.cycles_executed(), 0, "starts paused");
+
+    session.command("s");
+    session.frame();
+    assert_eq!(session.chip8.pc(), 0x202);
+    assert_eq!(session.output, "202: 7001  ADD V0, 0x01\n");
+    let timeline = session.timeline();
+    assert_eq!(timeline.len(), 1);
+    assert_eq!((timeline[0].addr, timeline[0].opcode), (0x200, 0x6001));
+    assert!(session.registers().contains("cycle 1"));
+
+    let code = session.disassembly(4);
+    let current: Vec<u16> = code.iter().filter(|l| l.current).map(|l| l.addr).collect();
+    assert_eq!(current, [0x202]);
+}
+
+#[test]
+fn breakpoints() {
+    let mut session = session(&ROM);
+    session.toggle_breakpoint(0x204);
+    session.command("c");
+    assert!(!session.paused());
+    for _ in 0..10 {
+        session.frame();
+    }
+    assert!(session.paused());
+    assert_eq!(session.chip8.pc(), 0x204);
+    assert_eq!(session.chip8.cycles_executed(), 2);
+    assert!(
+        session.output.starts_with("breakpoint 204:"),
+        "{}",
+        session.output
+    );
+    let listed: Vec<(u16, bool)> = session
+        .breakpoints()
+        .iter()
+        .map(|l| (l.addr, l.current))
+        .collect();
+    assert_eq!(listed, [(0x204, true)]);
+
+    session.toggle_breakpoint(0x204);
+    assert!(session.breakpoints().is_empty());
+    session.command("c");
+    session.frame();
+    assert_eq!(
+        session.chip8.cycles_executed(),
+        2 + CYCLES_PER_TICK as u64,
+        "a frame's worth"
+    );
+}
+
+#[test]
+fn memory() {
+    let mut session = session(&ROM);
+    assert_eq!(session.memory(4)[0].bytes, [0x60, 0x01, 0x70, 0x01]);
+    session.write(0x301, "AB");
+    assert_eq!(session.chip8.memory()[0x301], 0xAB);
+    session.memory_addr = 0x300;
+    let rows = session.memory(32);
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[1].addr, rows[0].bytes[1]), (0x310, 0xAB));
+
+    session.write(0x301, "XY");
+    assert_eq!(session.output, "bad byte XY\n");
+    assert_eq!(session.chip8.memory()[0x301], 0xAB);
+}
+
+#[test]
+fn error() {
+    // LD V0, 1; RET with nothing to return to
+    let mut session = session(&[0x60, 0x01, 0x00, 0xEE]);
+    session.command("c");
+    session.frame();
+    assert!(matches!(session.error, Some(Error::StackUnderflow { .. })));
+    assert!(session.paused());
+    assert!(!session.output.is_empty());
+    session.command("c");
+    session.frame();
+    assert_eq!(session.chip8.cycles_executed(), 1, "can't carry on");
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn gui() {
+    use chip8::gui::{egui, Gui, PANELS};
+
+    let mut gui = Gui::new(session(&ROM));
+    for panel in PANELS {
+        assert!(gui.dock.find_tab(&panel).is_some(), "{:?} is docked", panel);
+    }
+    let ctx = egui::Context::default();
+    let frame = |gui: &mut Gui| {
+        let _ = ctx.run(egui::RawInput::default(), |ctx| gui.ui(ctx));
+    };
+    frame(&mut gui);
+    assert!(!gui.session.chip8.is_key_pressed(5));
+    // W is key 5
+    let input = egui::RawInput {
+        events: vec![egui::Event::Key {
+            key: egui::Key::W,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::default(),
+        }],
+        ..Default::default()
+    };
+    let _ = ctx.run(input, |ctx| gui.ui(ctx));
+    assert!(gui.session.chip8.is_key_pressed(5));
+    assert_eq!(gui.session.chip8.cycles_executed(), 0);
+    gui.session.command("c");
+    frame(&mut gui);
+    frame(&mut gui);
+    assert_eq!(
+        gui.session.chip8.cycles_executed(),
+        2 * CYCLES_PER_TICK as u64
+    );
+}
@@ -0,0 +1,102 @@
+// the gym environment: reset starts an episode over, step holds keys for frame_skip frames, and
+// the reward is whatever the agent reads out of the VM afterwards
+
+use chip8::chip8::{Error, CYCLES_PER_TICK};
+use chip8::gym::Env;
+
+// LD V2, 5; SKNP V2; ADD V0, 1; JP 0x202. V0 counts up while key 5 is held, the "score"
+const SCORE: [u8; 8] = [0x62, 0x05, 0xE2, 0xA1, 0x70, 0x01, 0x12, 0x02];
+
+fn score(env: &Env) -> u8 {
+    env.chip8().v()[0]
+}
+
+#[test]
+fn step() {
+    let mut env = Env::new(&SCORE);
+    assert!(env.reset().iter().all(|&p| p == 0));
+    assert_eq!(env.frames(), 0);
+
+    let (screen, done) = env.step(0);
+    assert_eq!(screen.len(), 64 * 32);
+    assert!(!done, "no max frames, so only an error ends it");
+    assert_eq!(env.frames(), 4, "frame skip defaults to 4");
+    assert_eq!(env.chip8().cycles_executed(), 4 * CYCLES_PER_TICK as u64);
+    assert_eq!(score(&env), 0, "nothing held, nothing scored");
+
+    env.reset();
+    env.step(1 << 5);
+    assert!(env.chip8().is_key_pressed(5));
+    // LD, then SKNP, ADD, JP six times over
+    assert_eq!(score(&env), 6);
+    env.step(1 << 4);
+    assert!(
+        !env.chip8().is_key_pressed(5),
+        "keys not in the action are let go"
+    );
+    let before = score(&env);
+    env.step(0);
+    assert_eq!(score(&env), before);
+
+    env.set_frame_skip(0);
+    env.step(0);
+    assert_eq!(env.frames(), 13, "frame skip is at least one");
+}
+
+#[test]
+fn reset() {
+    let mut env = Env::new(&SCORE);
+    env.step(1 << 5);
+    assert!(score(&env) > 0);
+    env.reset();
+    assert_eq!(score(&env), 0);
+    assert_eq!(env.frames(), 0);
+    assert_eq!(env.chip8().cycles_executed(), 0);
+
+    // RND V0, 0xFF; JP 0x202
+    let rom = [0xC0, 0xFF, 0x12, 0x02];
+    let mut env = Env::new(&rom);
+    let mut rolls = Vec::new();
+    for seed in [7, 7, 8, 8] {
+        env.set_seed(seed);
+        env.reset();
+        env.step(0);
+        rolls.push(env.chip8().v()[0]);
+    }
+    assert_eq!(rolls[0], rolls[1], "the same seed rolls the same");
+    assert_eq!(rolls[2], rolls[3]);
+    assert_ne!(rolls[0], rolls[2]);
+}
+
+#[test]
+fn episode_end() {
+    let mut env = Env::new(&SCORE);
+    env.set_max_frames(Some(6));
+    env.reset();
+    assert!(!env.step(0).1);
+    assert!(env.step(0).1);
+    assert_eq!(
+        env.frames(),
+        6,
+        "stops at max frames, partway through a step"
+    );
+    assert!(env.error().is_none());
+    let cycles = env.chip8().cycles_executed();
+    assert!(env.step(1 << 5).1);
+    assert_eq!(
+        env.chip8().cycles_executed(),
+        cycles,
+        "done, so nothing runs"
+    );
+    env.reset();
+    assert!(!env.done());
+
+    // LD V0, 1; RET with nothing to return to
+    let mut env = Env::new(&[0x60, 0x01, 0x00, 0xEE]);
+    env.reset();
+    assert!(env.step(0).1);
+    assert!(matches!(env.error(), Some(Error::StackUnderflow { .. })));
+    assert_eq!(env.frames(), 0, "the frame it stopped in isn't counted");
+    env.reset();
+    assert!(env.error().is_none());
+}
@@ -0,0 +1,22 @@
+// messages come out in the chosen language, falling back to English
+
+use chip8::chip8::Error;
+use chip8::i18n::{self, tr, trf};
+
+// one test since the language is set for the whole process
+#[test]
+fn translations() {
+    assert!(!i18n::set_lang("xx"));
+    assert_eq!(trf("State {} saved", &[&3]), "State 3 saved");
+
+    assert!(i18n::set_lang("de"));
+    assert_eq!(trf("State {} saved", &[&3]), "Spielstand 3 gespeichert");
+    assert_eq!(tr("not translated"), "not translated");
+    assert_eq!(
+        i18n::error(&Error::StackUnderflow { pc: 0x20A }),
+        "Rücksprung mit leerem Stapel bei 20A"
+    );
+
+    assert!(i18n::set_lang("en"));
+    assert_eq!(tr("Muted"), "Muted");
+}
@@ -0,0 +1,72 @@
+// a key pressed and let go between two instructions still reaches the ROM, and FX0A can be told to
+// give up waiting
+
+use chip8::chip8::{Error, CYCLES_PER_TICK};
+use chip8::Chip8;
+
+// LD V0, K; JP 0x202
+const ROM: [u8; 4] = [0xF0, 0x0A, 0x12, 0x02];
+
+#[test]
+fn quick_tap() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    for _ in 0..10 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.pc(), 0x200, "waiting for a key");
+
+    chip8.press_key(7);
+    chip8.release_key(7);
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(chip8.v()[0], 7);
+    assert_eq!(chip8.pc(), 0x202);
+
+    // and it does come back up
+    for _ in 0..100 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert!(!chip8.is_key_pressed(7));
+}
+
+#[test]
+fn key_wait_timeout() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    for _ in 0..1000 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.pc(), 0x200, "still waiting without a timeout");
+
+    let mut chip8 = Chip8::new();
+    chip8.set_key_wait_timeout(Some(3));
+    chip8.load_rom(&ROM);
+    let mut cycles = 0;
+    let error = loop {
+        match chip8.emulate_cycle() {
+            Ok(()) => cycles += 1,
+            Err(e) => break e,
+        }
+        assert!(cycles < 100, "never gave up");
+    };
+    assert_eq!(error, Error::KeyWaitTimeout { pc: 0x200 });
+    // counted in whole ticks from the one the wait began in
+    let tick = CYCLES_PER_TICK as usize;
+    assert!((2 * tick..=3 * tick).contains(&cycles), "{}", cycles);
+    assert_eq!(chip8.pc(), 0x200);
+
+    // a key in time and the wait starts over at the next FX0A
+    let mut chip8 = Chip8::new();
+    chip8.set_key_wait_timeout(Some(3));
+    chip8.load_rom(&ROM);
+    for _ in 0..10 {
+        chip8.emulate_cycle().unwrap();
+    }
+    chip8.press_key(2);
+    chip8.emulate_cycle().unwrap();
+    chip8.clear_keys();
+    for _ in 0..10 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.v()[0], 2);
+}
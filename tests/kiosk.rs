@@ -0,0 +1,54 @@
+// --kiosk finds the ROMs in a directory, gives each its time, longer while it's being played, and
+// wipes the screen between them
+
+use chip8::kiosk::{self, Kiosk};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[test]
+fn roms() {
+    let dir = std::env::temp_dir().join(format!("chip8-kiosk-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub.ch8")).unwrap();
+    for file in ["pong.ch8", "BLITZ.C8", "notes.txt", "tetris.sc8"] {
+        std::fs::write(dir.join(file), [0x12, 0x00]).unwrap();
+    }
+    let names: Vec<String> = (kiosk::roms(&dir).unwrap().iter())
+        .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(names, ["BLITZ.C8", "pong.ch8", "tetris.sc8"]);
+}
+
+#[test]
+fn timing() {
+    let roms = vec![PathBuf::from("a.ch8"), PathBuf::from("b.ch8")];
+    let mut kiosk = Kiosk::new(roms.clone(), 2.0, None);
+    assert!(!kiosk.due(119, None));
+    assert!(kiosk.due(120, Some(Duration::ZERO)));
+    assert_eq!(kiosk.advance(), roms[1]);
+    assert_eq!(kiosk.advance(), roms[0]);
+
+    // somebody pressing a key keeps the ROM going until they've left it alone for a second
+    let kiosk = Kiosk::new(roms, 2.0, Some(1.0));
+    assert!(!kiosk.due(150, Some(Duration::from_millis(500))));
+    assert!(kiosk.due(150, Some(Duration::from_secs(1))));
+    assert!(kiosk.due(150, None), "nobody played it");
+}
+
+#[test]
+fn wipe() {
+    let mut kiosk = Kiosk::new(vec![PathBuf::from("a.ch8")], 1.0, None);
+    let start = Instant::now();
+    assert!(!kiosk.wiping());
+    kiosk.start_wipe(&[1; 64 * 32], start);
+    assert!(kiosk.wiping());
+    let frame = kiosk
+        .wipe_frame(start + Duration::from_millis(170))
+        .unwrap();
+    // ten rows gone and a line across the eleventh
+    assert!(frame[..64 * 10].iter().all(|&p| p == 0));
+    assert!(frame[64 * 10..].iter().all(|&p| p == 1));
+    assert_eq!(kiosk.wipe_frame(start + Duration::from_secs(1)), None);
+    kiosk.advance();
+    assert!(!kiosk.wiping());
+}
@@ -0,0 +1,98 @@
+// labels on regions of memory, given in the debugger or the cheats file, show up in hex dumps,
+// watch expressions and traces
+
+use chip8::cheats::Cheats;
+use chip8::config::Config;
+use chip8::debugger::Debugger;
+use chip8::labels::{Labels, Region};
+use chip8::trace::Tracer;
+use chip8::tracediff;
+use chip8::Chip8;
+
+#[test]
+fn regions() {
+    assert_eq!(
+        Region::parse("300 8"),
+        Some(Region {
+            addr: 0x300,
+            len: 8
+        })
+    );
+    assert_eq!(Region::parse("2a4").map(|r| r.len), Some(1));
+    assert_eq!(Region::parse("300 0"), None);
+    assert_eq!(Region::parse("300 8 1"), None);
+
+    let mut labels = Labels::default();
+    labels.insert("sprites", Region::parse("300 8").unwrap());
+    labels.insert("ball", Region::parse("304").unwrap());
+    assert_eq!(labels.describe(0x300).as_deref(), Some("sprites"));
+    assert_eq!(labels.describe(0x302).as_deref(), Some("sprites+2"));
+    assert_eq!(labels.describe(0x304).as_deref(), Some("ball"));
+    assert_eq!(labels.describe(0x308), None);
+    assert_eq!(labels.name_at(0x304), Some("ball"));
+}
+
+#[test]
+fn debugger() {
+    let mut chip8 = Chip8::new();
+    chip8.memory_mut()[0x300..0x304].copy_from_slice(&[1, 2, 3, 4]);
+    let mut debugger = Debugger::default();
+    assert_eq!(
+        debugger.command(&mut chip8, "name sprites 300 4").1,
+        "sprites at 300, 4 bytes = 01 02 03 04\n"
+    );
+    debugger.command(&mut chip8, "name score 312");
+    assert!(debugger
+        .command(&mut chip8, "name x 300 0")
+        .1
+        .starts_with("bad length"));
+    let rows: Vec<String> = (debugger.command(&mut chip8, "mem 300 48").1.lines())
+        .map(|row| row[52..].to_string())
+        .collect();
+    assert_eq!(rows, ["  sprites", "  score", ""]);
+
+    assert_eq!(
+        debugger.command(&mut chip8, "watch sprites").1,
+        "1: sprites = 01 02 03 04\n"
+    );
+    assert_eq!(
+        debugger
+            .command(&mut chip8, "watch score + 1")
+            .1
+            .lines()
+            .last(),
+        Some("2: score + 1 = 0x1 (1)")
+    );
+
+    let config = debugger.cheats.to_config().to_string();
+    assert_eq!(config, "name.score = 312\nname.sprites = 300 4\n");
+    let cheats = Cheats::from_config(&Config::parse(&config).unwrap()).unwrap();
+    assert_eq!(cheats.labels, debugger.cheats.labels);
+    assert!(Cheats::from_config(&Config::parse("name.a = 300 x").unwrap()).is_err());
+}
+
+#[test]
+fn trace() {
+    // LD I, 0x302; LD I, 0x310
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0xA3, 0x02, 0xA3, 0x10]);
+    let mut labels = Labels::default();
+    labels.insert("sprites", Region::parse("300 8").unwrap());
+    let mut out = Vec::new();
+    let mut tracer = Tracer::new(&mut out).with_labels(labels);
+    for _ in 0..2 {
+        chip8.emulate_cycle_observed(&mut tracer).unwrap();
+    }
+    drop(tracer);
+    let trace = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = trace.lines().collect();
+    // I as it was before each instruction ran
+    assert!(lines[0].ends_with("I=000  ; cycle 0"), "{}", lines[0]);
+    assert!(
+        lines[1].ends_with("I=302  ; cycle 1, sprites+2"),
+        "{}",
+        lines[1]
+    );
+    let steps = tracediff::parse(&trace).unwrap();
+    assert_eq!(steps[1].i, 0x302);
+}
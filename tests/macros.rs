@@ -0,0 +1,56 @@
+// macros round trip through their text form, and record and play back frame by frame
+
+use chip8::config::Config;
+use chip8::macros::{Macro, MacroPlayer, MacroRecorder, Step};
+
+#[test]
+fn parse() {
+    let m = Macro::parse("5*3 -*2 5a").unwrap();
+    assert_eq!(
+        m.steps,
+        [
+            Step {
+                keys: 1 << 5,
+                frames: 3
+            },
+            Step { keys: 0, frames: 2 },
+            Step {
+                keys: 1 << 5 | 1 << 0xA,
+                frames: 1
+            },
+        ]
+    );
+    assert_eq!(m.to_string(), "5*3 -*2 5A");
+    assert_eq!(m.frames(), 6);
+    for bad in ["5*0", "G", "*2", "5*x"] {
+        assert!(Macro::parse(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn record_and_play() {
+    let mut recorder = MacroRecorder::default();
+    for keys in [0, 0, 1, 1, 0, 2, 0, 0] {
+        recorder.frame(keys);
+    }
+    let m = recorder.finish();
+    assert_eq!(m.to_string(), "0*2 - 1");
+
+    let mut player = MacroPlayer::new(m);
+    let played: Vec<u16> = std::iter::from_fn(|| player.frame()).collect();
+    assert_eq!(played, [1, 1, 0, 2]);
+}
+
+#[test]
+fn config() {
+    let mut config = Config::parse("# macros\nmacro.F1 = 5\n\nmacro.F2 = 6*2\n").unwrap();
+    assert_eq!(config.get("macro.F2"), Some("6*2"));
+    assert_eq!(config.get("macro.F3"), None);
+    config.set("macro.F1", "7");
+    config.set("macro.F3", "8");
+    assert_eq!(
+        config.to_string(),
+        "# macros\nmacro.F1 = 7\n\nmacro.F2 = 6*2\nmacro.F3 = 8\n"
+    );
+    assert_eq!(Config::parse("a\nb").unwrap_err().line, 1);
+}
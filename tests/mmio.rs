@@ -0,0 +1,47 @@
+// with the MMIO extension the top of memory reads from the host and ignores writes
+
+use chip8::mmio::{Host, Mmio};
+use chip8::Chip8;
+
+// LD I, 0xFF2; LD V2, [I]; LD [I], V2; JP 0x206
+const ROM: [u8; 8] = [0xAF, 0xF2, 0xF2, 0x65, 0xF2, 0x55, 0x12, 0x06];
+
+struct Offsets;
+
+impl Mmio for Offsets {
+    fn read(&mut self, offset: usize, _ticks: u64) -> u8 {
+        offset as u8 + 0x10
+    }
+}
+
+#[test]
+fn mapped() {
+    let mut chip8 = Chip8::new();
+    chip8.set_mmio(Some(Box::new(Offsets)));
+    chip8.load_rom(&ROM);
+    for _ in 0..3 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(&chip8.v()[..3], [0x12, 0x13, 0x14]);
+    assert_eq!(
+        &chip8.memory()[0xFF2..0xFF5],
+        [0, 0, 0],
+        "writes go nowhere"
+    );
+
+    let mut plain = Chip8::new();
+    plain.load_rom(&ROM);
+    for _ in 0..3 {
+        plain.emulate_cycle().unwrap();
+    }
+    assert_eq!(&plain.v()[..3], [0, 0, 0]);
+}
+
+#[test]
+fn host() {
+    let mut host = Host::default();
+    assert!(host.read(0, 0) < 60);
+    assert!(host.read(2, 0) < 24);
+    assert_eq!(host.read(3, 0x1234), 0x34);
+    assert_eq!(host.read(15, 0), 0);
+}
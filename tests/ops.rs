@@ -0,0 +1,136 @@
+// opcodes one at a time, with run_ops! from the testing feature
+
+use chip8::chip8::Error;
+use chip8::quirks::Quirks;
+use chip8::run_ops;
+
+#[test]
+fn arithmetic() {
+    run_ops!([0x6005, 0x7001]).finished().v(0, 6);
+    run_ops!([0x60FF, 0x7002]).v(0, 1).vf(0); // 7XNN doesn't carry
+    run_ops!([0x60FF, 0x6102, 0x8014]).v(0, 1).vf(1);
+    run_ops!([0x6001, 0x6102, 0x8015]).v(0, 0xFF).vf(0);
+    run_ops!([0x6001, 0x6102, 0x8017]).v(0, 1).vf(1);
+    // VF is set after the result, so it wins when it's the destination
+    run_ops!([0x6FFF, 0x6101, 0x8F14]).vf(1);
+}
+
+#[test]
+fn logic() {
+    run_ops!([0x600C, 0x610A, 0x6F05, 0x8011]).v(0, 0x0E).vf(5);
+    run_ops!([0x600C, 0x610A, 0x8012]).v(0, 0x08);
+    run_ops!([0x600C, 0x610A, 0x8013]).v(0, 0x06);
+    run_ops!(Quirks::CHIP8; [0x600C, 0x610A, 0x6F05, 0x8011])
+        .v(0, 0x0E)
+        .vf(0);
+}
+
+#[test]
+fn shifts() {
+    run_ops!([0x6081, 0x8006]).v(0, 0x40).vf(1);
+    run_ops!([0x6081, 0x800E]).v(0, 0x02).vf(1);
+    run_ops!(Quirks::CHIP8; [0x6003, 0x6181, 0x8016])
+        .v(0, 0x40)
+        .v(1, 0x81)
+        .vf(1);
+}
+
+#[test]
+fn skips() {
+    run_ops!([0x6005, 0x3005, 0x6101, 0x6202])
+        .finished()
+        .v(1, 0)
+        .v(2, 2);
+    run_ops!([0x6005, 0x4005, 0x6101]).v(1, 1);
+    run_ops!([0x6005, 0x6105, 0x5010, 0x6201]).v(2, 0);
+    run_ops!([0x6005, 0x9010, 0x6201]).v(2, 0);
+    run_ops!([0x6005, 0xE09E, 0x6201]).v(2, 1); // key 5 isn't held
+}
+
+#[test]
+fn flow() {
+    run_ops!([0x2206, 0x6101, 0x1208, 0x00EE])
+        .finished()
+        .v(1, 1)
+        .stack(&[]);
+    run_ops!([0x1204, 0x6101, 0x6202]).v(1, 0).v(2, 2);
+    run_ops!([0x6002, 0xB204, 0x6101, 0x6202]).v(1, 0).v(2, 2);
+    run_ops!([0x00EE]).err(Error::StackUnderflow { pc: 0x200 });
+    run_ops!([0x0123]).err(Error::UnknownOpcode {
+        pc: 0x200,
+        opcode: 0x0123,
+    });
+    run_ops!([0x6001, 0x00FD, 0x6102]).pc(0x202).v(1, 0);
+}
+
+#[test]
+fn memory() {
+    run_ops!([0xA300, 0x6001, 0x6102, 0xF155])
+        .mem(0x300, &[1, 2])
+        .i(0x300);
+    run_ops!(Quirks::CHIP8; [0xA300, 0xF155]).i(0x302);
+    run_ops!([0xA300, 0x607B, 0xF033]).mem(0x300, &[1, 2, 3]);
+    run_ops!([0xA300, 0x6002, 0xF01E]).i(0x302);
+    run_ops!([0x600A, 0xF029]).i(50);
+}
+
+#[test]
+fn timers() {
+    run_ops!([0x6030, 0xF015, 0xF118]).delay(0x30).sound(0);
+    run_ops!([0x6030, 0xF018]).sound(0x30);
+}
+
+#[test]
+fn drawing() {
+    // the font's 0 at the top left, then drawn over to erase it
+    run_ops!([0x00E0, 0xF029, 0xD005])
+        .pixel(0, 0, true)
+        .pixel(1, 1, false)
+        .lit(14)
+        .vf(0);
+    run_ops!([0xF029, 0xD005, 0xD005]).lit(0).vf(1);
+    // wrapping past the right edge, unless sprites clip
+    let ops = [0x60FF, 0xA300, 0xF055, 0x603E, 0x6100, 0xD011];
+    run_ops!(ops).pixel(63, 0, true).pixel(0, 0, true).lit(8);
+    run_ops!(Quirks::SCHIP; ops)
+        .pixel(63, 0, true)
+        .pixel(0, 0, false)
+        .lit(2);
+}
+
+#[test]
+fn scrolling() {
+    // the font's 0, 4 pixels wide and 5 rows with 14 lit, at V0,V1
+    let zero = |x: u16, y: u16, scroll: &[u16]| {
+        let mut ops = vec![0x6000 | x, 0x6100 | y, 0xF229, 0xD015];
+        ops.extend(scroll);
+        run_ops!(ops)
+    };
+    zero(0, 0, &[0x00C2])
+        .finished()
+        .pixel(0, 2, true)
+        .pixel(0, 0, false)
+        .lit(14);
+    zero(0, 0, &[0x00D1])
+        .pixel(0, 0, true)
+        .pixel(1, 0, false)
+        .lit(10);
+    zero(0, 0, &[0x00FB])
+        .pixel(4, 0, true)
+        .pixel(0, 0, false)
+        .lit(14);
+    zero(4, 0, &[0x00FC])
+        .pixel(0, 0, true)
+        .pixel(4, 0, false)
+        .lit(14);
+    // what goes off an edge is gone rather than coming back at the other, and what comes in is
+    // blank
+    zero(0, 0, &[0x00CF, 0x00CF])
+        .pixel(0, 30, true)
+        .pixel(0, 0, false)
+        .lit(6);
+    zero(0, 0, &[0x00D2]).pixel(0, 31, false).lit(8);
+    zero(60, 0, &[0x00FB]).pixel(0, 0, false).lit(0);
+    zero(0, 0, &[0x00FC]).pixel(63, 0, false).lit(0);
+    zero(0, 0, &[0x00C0]).lit(14);
+}
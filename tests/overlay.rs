@@ -0,0 +1,87 @@
+// toasts show for their time, fade out and then go, the keypad hint shows the keys a ROM tests
+// and the keypad overlay the keys held
+
+use chip8::analysis::Analysis;
+use chip8::frontend::keypad_hint;
+use chip8::overlay::{self, Corner, Toasts, FADE_TIME};
+use std::time::{Duration, Instant};
+
+#[test]
+fn toasts() {
+    let start = Instant::now();
+    let second = Duration::from_secs(1);
+    let mut toasts = Toasts::new(second);
+    toasts.notify("State 3 saved", start);
+    assert_eq!(toasts.visible(start + second / 2), [("State 3 saved", 1.0)]);
+    let (_, alpha) = toasts.visible(start + second + FADE_TIME / 2)[0];
+    assert!((alpha - 0.5).abs() < 0.01, "half faded, not {}", alpha);
+    assert!(toasts.visible(start + second + FADE_TIME).is_empty());
+    assert!(toasts.is_empty());
+
+    let mut off = Toasts::new(Duration::ZERO);
+    off.notify("Muted", start);
+    assert!(off.visible(start).is_empty());
+}
+
+#[test]
+fn keypad() {
+    // LD V0, 5; SKNP V0; LD V1, 1; LD V2, 8; SKP V2; ADD V2, 1; SKP V2; JP 0x200
+    let rom = [
+        0x60, 0x05, 0xE0, 0xA1, 0x61, 0x01, 0x62, 0x08, 0xE2, 0x9E, 0x72, 0x01, 0xE2, 0x9E, 0x12,
+        0x00,
+    ];
+    let analysis = Analysis::new(&rom);
+    // V2 is only known in the block it was loaded in
+    assert_eq!((analysis.keys, analysis.any_key), (1 << 5 | 1 << 8, true));
+    assert_eq!(
+        keypad_hint(1 << 5 | 1 << 8 | 1 << 0xF, false),
+        [". . . .", ". W . .", ". S . .", ". . . V"]
+    );
+    assert_eq!(keypad_hint(0, true), ["any key"]);
+    assert!(keypad_hint(0, false).is_empty());
+
+    // FX0A on its own
+    let analysis = Analysis::new(&[0xF0, 0x0A, 0x12, 0x00]);
+    assert_eq!((analysis.keys, analysis.any_key), (0, true));
+}
+
+#[test]
+fn keys() {
+    let mut lit = Vec::new();
+    overlay::draw_keypad(0, |x, y| lit.push((x, y)));
+    let digits: usize = "0123456789ABCDEF"
+        .chars()
+        .map(|c| {
+            overlay::glyph(c)
+                .iter()
+                .map(|r| r.count_ones() as usize)
+                .sum::<usize>()
+        })
+        .sum();
+    assert_eq!(lit.len(), digits);
+    assert!(lit.contains(&(2, 1)), "the top of the 1, top left");
+    assert!(lit
+        .iter()
+        .all(|&(x, y)| x < overlay::KEYPAD_WIDTH && y < overlay::KEYPAD_HEIGHT));
+
+    // F held is lit all over bar its digit, bottom right
+    let mut held = Vec::new();
+    overlay::draw_keypad(1 << 0xF, |x, y| held.push((x, y)));
+    let f = overlay::glyph('F')
+        .iter()
+        .map(|r| r.count_ones() as usize)
+        .sum::<usize>();
+    assert_eq!(held.len(), lit.len() - f + 5 * 7 - f);
+    assert!(held.contains(&(overlay::KEYPAD_WIDTH - 1, overlay::KEYPAD_HEIGHT - 1)));
+
+    assert_eq!("top-right".parse(), Ok(Corner::TopRight));
+    assert!("middle".parse::<Corner>().is_err());
+    assert_eq!(Corner::default().to_string(), "bottom-right");
+    assert_eq!(Corner::TopLeft.place((10, 5), (64, 32), 1), (1, 1));
+    assert_eq!(Corner::BottomRight.place((10, 5), (64, 32), 1), (53, 26));
+    assert_eq!(
+        Corner::BottomLeft.place((10, 5), (8, 4), 0),
+        (0, 0),
+        "too big to fit"
+    );
+}
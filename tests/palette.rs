@@ -0,0 +1,56 @@
+// palettes by name, a color for each plane XO-CHIP can light, used by --pipe-video as the window
+// and tui use them, and shown as swatches by chip8 palettes
+
+use chip8::frontend;
+use chip8::palette::{Palette, PALETTES};
+use chip8::video::{VideoPipe, HEIGHT, WIDTH};
+
+#[test]
+fn names() {
+    assert_eq!(Palette::default().name, "classic");
+    assert_eq!(Palette::default().color(1), [255, 255, 255]);
+    let okabe_ito: Palette = "okabe-ito".parse().unwrap();
+    assert_eq!(okabe_ito.to_string(), "okabe-ito");
+    assert_eq!(okabe_ito.color(0), [0, 0, 0]);
+    assert_eq!(okabe_ito.color(2), [86, 180, 233]);
+    assert_eq!(
+        okabe_ito.color(4 | 3),
+        okabe_ito.color(3),
+        "only two planes"
+    );
+    let error = "sepia".parse::<Palette>().unwrap_err();
+    assert!(error.contains("classic, okabe-ito, ibm"), "{}", error);
+
+    for palette in PALETTES {
+        let mut colors = palette.colors.to_vec();
+        colors.dedup();
+        assert_eq!(colors.len(), 4, "{} has a color for each plane", palette);
+    }
+}
+
+#[test]
+fn video() {
+    let mut screen = vec![0; WIDTH * HEIGHT];
+    screen[0] = 1;
+    let mut out = Vec::new();
+    let paper = "paper".parse().unwrap();
+    VideoPipe::new(&mut out, 1)
+        .with_palette(paper)
+        .frames(&screen, 1)
+        .unwrap();
+    assert_eq!(out[..3], paper.color(1));
+    assert_eq!(out[3..6], paper.color(0));
+}
+
+#[test]
+fn swatches() {
+    let swatches = frontend::swatches(&"ibm".parse().unwrap());
+    let lines: Vec<&str> = swatches.lines().collect();
+    assert!(
+        lines[0].starts_with("ibm: gold, blue and magenta"),
+        "{}",
+        lines[0]
+    );
+    assert!(lines[1].contains("\x1b[48;2;255;176;0m"));
+    assert_eq!(lines[2], "  000000  FFB000  648FFF  DC267F");
+}
@@ -0,0 +1,190 @@
+// the core never panics, whatever it's given to run: a ROM can only make the VM return an error.
+// every opcode is tried after random registers under random quirks, the PC is walked off the end
+// of memory, random memory images run under random quirks with keys pressed at random, and so do
+// random save states, with the PC near the top of 64K XO memory. any case that panics fails the
+// test with what it was, found again from the fixed seeds
+
+use chip8::quirks::Quirks;
+use chip8::Chip8;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::panic::{self, AssertUnwindSafe};
+
+const STEPS: usize = 8; // after the opcode under test, to run whatever it led to
+const IMAGES: usize = 300;
+const IMAGE_STEPS: usize = 2_000;
+const STATES: usize = 200;
+
+// run steps cycles or until an error, false if it panicked
+fn survives(chip8: &mut Chip8, steps: usize, rng: &mut StdRng) -> bool {
+    let keys: Vec<u16> = (0..steps).map(|_| rng.gen()).collect();
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        for keys in keys {
+            chip8.set_keys(keys);
+            if chip8.emulate_cycle().is_err() {
+                break;
+            }
+        }
+    }))
+    .is_ok()
+}
+
+// the cases that panicked, without the default hook printing every one of them
+fn panics(cases: impl FnMut() -> Vec<String>) -> Vec<String> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let failed = (panic::catch_unwind(AssertUnwindSafe(cases))).unwrap_or_else(|_| vec![]);
+    panic::set_hook(hook);
+    failed
+}
+
+#[test]
+fn every_opcode() {
+    let mut rng = StdRng::seed_from_u64(966);
+    let failed = panics(|| {
+        let mut failed = Vec::new();
+        for opcode in 0..=0xFFFFu16 {
+            // 64K of memory is slow to set up each time, so only now and then
+            let mut quirks = Quirks::from_bits(rng.gen());
+            quirks.xo_memory = rng.gen_ratio(1, 16);
+            let permissive = rng.gen();
+            let mut chip8 = Chip8::new();
+            chip8.seed_rng(0);
+            chip8.set_quirks(quirks);
+            chip8.set_permissive(permissive);
+            // random registers and I, then the opcode, then random bytes
+            let mut rom = Vec::new();
+            for x in 0..16 {
+                rom.extend_from_slice(&[0x60 | x, rng.gen()]);
+            }
+            let i: u16 = rng.gen_range(0, 0x1000);
+            rom.extend_from_slice(&(0xA000 | i).to_be_bytes());
+            rom.extend_from_slice(&opcode.to_be_bytes());
+            rom.extend((0..2 * STEPS).map(|_| rng.gen::<u8>()));
+            chip8.load_rom(&rom);
+            if !survives(&mut chip8, 17 + 1 + STEPS, &mut rng) {
+                failed.push(format!(
+                    "{:04X} under {} permissive {}",
+                    opcode, quirks, permissive
+                ));
+            }
+        }
+        failed
+    });
+    assert!(
+        failed.is_empty(),
+        "{} panicked, first {:?}",
+        failed.len(),
+        &failed[..failed.len().min(10)]
+    );
+}
+
+// the PC walking off the end of memory, 4K or 64K, rather than jumping there
+#[test]
+fn off_the_end() {
+    let mut rng = StdRng::seed_from_u64(9661);
+    let failed = panics(|| {
+        let mut failed = Vec::new();
+        for xo_memory in [false, true] {
+            for permissive in [false, true] {
+                let mut chip8 = Chip8::new();
+                let mut quirks = Quirks::default();
+                quirks.xo_memory = xo_memory;
+                chip8.set_quirks(quirks);
+                chip8.set_permissive(permissive);
+                // LD V0, 0 all the way
+                for pair in chip8.memory_mut()[0x200..].chunks_mut(2) {
+                    pair[0] = 0x60;
+                }
+                let steps = chip8.memory().len() / 2 + STEPS;
+                if !survives(&mut chip8, steps, &mut rng) {
+                    failed.push(format!("xo-memory {} permissive {}", xo_memory, permissive));
+                }
+            }
+        }
+        failed
+    });
+    assert!(failed.is_empty(), "{:?}", failed);
+}
+
+#[test]
+fn random_images() {
+    let mut rng = StdRng::seed_from_u64(9660);
+    let failed = panics(|| {
+        let mut failed = Vec::new();
+        for n in 0..IMAGES {
+            let quirks = Quirks::from_bits(rng.gen());
+            let permissive = rng.gen();
+            let mut chip8 = Chip8::new();
+            chip8.seed_rng(n as u64);
+            chip8.set_quirks(quirks);
+            chip8.set_permissive(permissive);
+            rng.fill(chip8.memory_mut());
+            if !survives(&mut chip8, IMAGE_STEPS, &mut rng) {
+                failed.push(format!(
+                    "image {} under {} permissive {}",
+                    n, quirks, permissive
+                ));
+            }
+        }
+        failed
+    });
+    assert!(
+        failed.is_empty(),
+        "{} panicked, first {:?}",
+        failed.len(),
+        &failed[..failed.len().min(10)]
+    );
+}
+
+// memory, registers, the PC and the stack set to anything load_state lets through
+#[test]
+fn random_states() {
+    let mut rng = StdRng::seed_from_u64(9662);
+    let failed = panics(|| {
+        let mut failed = Vec::new();
+        for n in 0..STATES {
+            let mut quirks = Quirks::from_bits(rng.gen());
+            quirks.xo_memory = n % 2 == 1;
+            let permissive = rng.gen();
+            let mut chip8 = Chip8::new();
+            chip8.set_quirks(quirks);
+            chip8.set_permissive(permissive);
+            let mut state = chip8.save_state();
+            // after the header, the memory, V registers, I and PC, then past the screen and the
+            // timers the stack and its pointer
+            let size = chip8.memory().len();
+            let pc = 11 + size + 16 + 2;
+            rng.fill(&mut state[11..pc + 2]);
+            let stack = pc + 2 + 64 * 32 + 2;
+            rng.fill(&mut state[stack..stack + 16 * 2]);
+            // and half the time the PC and return addresses right at the top of memory, returning
+            // from all over it
+            if rng.gen() {
+                for at in (11..11 + size).step_by(2).filter(|_| rng.gen_ratio(1, 4)) {
+                    state[at..at + 2].copy_from_slice(&[0x00, 0xEE]);
+                }
+                for at in (stack..stack + 16 * 2).step_by(2).chain([pc]) {
+                    let top = rng.gen_range(0xFFF0, 0x10000) as u16;
+                    state[at..at + 2].copy_from_slice(&top.to_be_bytes());
+                }
+            }
+            let sp: u16 = rng.gen_range(0, 17);
+            state[stack + 16 * 2..stack + 16 * 2 + 2].copy_from_slice(&sp.to_be_bytes());
+            chip8.load_state(&state).unwrap();
+            if !survives(&mut chip8, IMAGE_STEPS, &mut rng) {
+                failed.push(format!(
+                    "state {} under {} permissive {}",
+                    n, quirks, permissive
+                ));
+            }
+        }
+        failed
+    });
+    assert!(
+        failed.is_empty(),
+        "{} panicked, first {:?}",
+        failed.len(),
+        &failed[..failed.len().min(10)]
+    );
+}
@@ -0,0 +1,35 @@
+// IPS patches applied to ROMs at load
+
+use chip8::patch::apply_ips;
+
+#[test]
+fn records() {
+    let rom = [0x00, 0xE0, 0x12, 0x02];
+    let mut patch = b"PATCH".to_vec();
+    patch.extend([0, 0, 2, 0, 2, 0x12, 0x00]); // 1200 at 2
+    patch.extend([0, 0, 5, 0, 0, 0, 3, 0xAA]); // AA 3 times at 5, past the end
+    patch.extend(b"EOF");
+    assert_eq!(
+        apply_ips(&rom, &patch),
+        Ok(vec![0x00, 0xE0, 0x12, 0x00, 0x00, 0xAA, 0xAA, 0xAA])
+    );
+
+    let mut truncated = patch.clone();
+    truncated.extend([0, 0, 3]);
+    assert_eq!(apply_ips(&rom, &truncated), Ok(vec![0x00, 0xE0, 0x12]));
+}
+
+#[test]
+fn broken() {
+    let rom = [0x12, 0x00];
+    assert!(apply_ips(&rom, b"PATCHEOF").is_ok());
+    assert!(apply_ips(&rom, b"IPS!EOF").is_err());
+    assert_eq!(
+        apply_ips(&rom, b"PATCH\0\0\0\0\x05\x12"),
+        Err("patch ends in the middle of a record at A".to_string())
+    );
+    assert_eq!(
+        apply_ips(&rom, b"PATCH"),
+        Err("patch has no EOF at the end".to_string())
+    );
+}
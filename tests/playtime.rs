@@ -0,0 +1,60 @@
+// playtime is kept a line for each ROM and listed most recently played first, counting time
+// played across state loads
+
+use chip8::chip8::CYCLES_PER_TICK;
+use chip8::playtime::{self, Playtime};
+use chip8::runner::Elapsed;
+use chip8::Chip8;
+
+#[test]
+fn record() {
+    let mut played = Playtime::parse(&format!("{} 2 90 1000 pong.ch8\n", "ab".repeat(20))).unwrap();
+    played.record(&[0xCD; 20], "space invaders.ch8", 30, 2000);
+    played.record(&[0xAB; 20], "pong.ch8", 3600, 3000);
+    let text = played.to_string();
+    assert_eq!(
+        text,
+        format!(
+            "{} 3 3690 3000 pong.ch8\n{} 1 30 2000 space invaders.ch8\n",
+            "ab".repeat(20),
+            "cd".repeat(20)
+        )
+    );
+    let played = Playtime::parse(&text).unwrap();
+    assert_eq!(played.entries()[1].name, "space invaders.ch8");
+    assert_eq!(
+        played.report(),
+        "1970-01-01    1:01:30     3x  pong.ch8\n1970-01-01    0:00:30     1x  space invaders.ch8\n"
+    );
+    assert!(Playtime::parse("pong.ch8 1 2 3\n").is_err());
+}
+
+#[test]
+fn dates() {
+    assert_eq!(playtime::date(0), "1970-01-01");
+    assert_eq!(playtime::date(951_782_400), "2000-02-29");
+    assert_eq!(playtime::date(1_791_936_000), "2026-10-14");
+}
+
+// a run counts all the time played in it, before a state load as well as after
+#[test]
+fn state_loads() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&[0x12, 0x00]); // JP 0x200
+    let state = chip8.save_state();
+    let mut elapsed = Elapsed::default();
+    let mut play = |chip8: &mut Chip8, secs: u32| {
+        for _ in 0..secs * 60 * CYCLES_PER_TICK as u32 {
+            let before = (chip8.cycles(), chip8.ticks());
+            chip8.emulate_cycle().unwrap();
+            elapsed.add(chip8, before);
+        }
+    };
+    play(&mut chip8, 10);
+    chip8.load_state(&state).unwrap();
+    play(&mut chip8, 5);
+
+    let mut played = Playtime::default();
+    played.record(&[0xAB; 20], "pong.ch8", elapsed.ticks / 60, 1000);
+    assert_eq!(played.entries()[0].seconds, 15);
+}
@@ -0,0 +1,130 @@
+// properties of the ALU and drawing that should hold for any values, any registers and any
+// quirks. proptest picks the cases and shrinks a failing one down to the smallest it can find
+
+use chip8::quirks::Quirks;
+use chip8::run_ops;
+use chip8::testing::Ops;
+use proptest::prelude::*;
+
+fn ld(x: u16, value: u8) -> u16 {
+    0x6000 | x << 8 | value as u16
+}
+
+// an 8XYN with x and y in place
+fn alu(n: u16, x: u16, y: u16) -> u16 {
+    0x8000 | x << 8 | y << 4 | n
+}
+
+fn quirks() -> impl Strategy<Value = Quirks> {
+    any::<u16>().prop_map(Quirks::from_bits)
+}
+
+// two different registers, neither of them VF
+fn registers() -> impl Strategy<Value = (u16, u16)> {
+    (0..15u16, 0..15u16).prop_filter("the same register", |(x, y)| x != y)
+}
+
+// Vx and Vy loaded with a and b, then ops
+fn run(quirks: Quirks, (x, y): (u16, u16), (a, b): (u8, u8), ops: &[u16]) -> Ops {
+    run_ops!(quirks; [&[ld(x, a), ld(y, b)], ops].concat())
+}
+
+proptest! {
+    #[test]
+    fn add_sub(a: u8, b: u8, (x, y) in registers(), quirks in quirks()) {
+        let vm = |ops: &[u16]| run(quirks, (x, y), (a, b), ops);
+        let sum = vm(&[alu(4, x, y)]);
+        let (wrapped, carry) = a.overflowing_add(b);
+        let v = sum.chip8.v();
+        prop_assert_eq!((v[x as usize], v[0xF]), (wrapped, carry as u8));
+
+        // subtracting undoes adding, with VF the inverse of the borrow
+        let back = vm(&[alu(4, x, y), alu(5, x, y)]);
+        prop_assert_eq!(back.chip8.v()[x as usize], a);
+        prop_assert_eq!(back.chip8.v()[0xF], (wrapped >= b) as u8);
+
+        // SUBN is SUB the other way round
+        let sub = vm(&[alu(5, y, x)]);
+        let subn = vm(&[alu(7, x, y)]);
+        prop_assert_eq!(sub.chip8.v()[y as usize], subn.chip8.v()[x as usize]);
+        prop_assert_eq!(sub.chip8.v()[0xF], subn.chip8.v()[0xF]);
+    }
+
+    #[test]
+    fn shifts(a: u8, b: u8, (x, y) in registers(), quirks in quirks()) {
+        let vm = |ops: &[u16]| run(quirks, (x, y), (a, b), ops);
+        // whichever register is shifted, the result and VF are the same as doing it by hand
+        let source = if quirks.shifting { a } else { b };
+        let right = vm(&[alu(6, x, y)]);
+        prop_assert_eq!(right.chip8.v()[x as usize], source >> 1);
+        prop_assert_eq!(right.chip8.v()[0xF], source & 1);
+        let left = vm(&[alu(0xE, x, y)]);
+        prop_assert_eq!(left.chip8.v()[x as usize], source << 1);
+        prop_assert_eq!(left.chip8.v()[0xF], source >> 7);
+
+        // shifting left then right only loses the top bit
+        let both = vm(&[alu(0xE, x, x), alu(6, x, x)]);
+        prop_assert_eq!(both.chip8.v()[x as usize], a & 0x7F);
+    }
+
+    #[test]
+    fn xor(a: u8, b: u8, (x, y) in registers(), quirks in quirks()) {
+        let vm = |ops: &[u16]| run(quirks, (x, y), (a, b), ops);
+        let twice = vm(&[alu(3, x, y), alu(3, x, y)]);
+        prop_assert_eq!(twice.chip8.v()[x as usize], a);
+        let or = vm(&[alu(1, x, y)]);
+        let and = vm(&[alu(2, x, y)]);
+        let xor = vm(&[alu(3, x, y)]);
+        prop_assert_eq!(
+            xor.chip8.v()[x as usize],
+            or.chip8.v()[x as usize] & !and.chip8.v()[x as usize]
+        );
+    }
+
+    // an 8XYN only ever changes VX and VF
+    #[test]
+    fn alu_registers(
+        n in prop::sample::select(vec![0, 1, 2, 3, 4, 5, 6, 7, 0xE]),
+        values in prop::array::uniform15(any::<u8>()),
+        (x, y) in registers(),
+        quirks in quirks(),
+    ) {
+        let mut ops: Vec<u16> = (0..15).map(|r| ld(r, values[r as usize])).collect();
+        ops.push(alu(n, x, y));
+        let after = run_ops!(quirks; ops);
+        for r in (0..15).filter(|&r| r != x as usize) {
+            prop_assert_eq!(after.chip8.v()[r], values[r], "V{:X}", r);
+        }
+    }
+
+    #[test]
+    fn draw_twice(
+        sprite in prop::collection::vec(any::<u8>(), 1..16),
+        x: u8,
+        y: u8,
+        quirks in quirks(),
+    ) {
+        // put the sprite at 0x300 with FX55, then draw it from VA,VB
+        let n = sprite.len() as u16;
+        let mut ops: Vec<u16> = (0..n).map(|r| ld(r, sprite[r as usize])).collect();
+        ops.extend([
+            0xA300,
+            0xF055 | (n - 1) << 8,
+            0xA300,
+            ld(0xA, x),
+            ld(0xB, y),
+        ]);
+        let draw = 0xDAB0 | n;
+
+        let once = run_ops!(quirks; [ops.as_slice(), &[draw]].concat());
+        once.ok().vf(0);
+        let lit = once.chip8.gfx().iter().filter(|&&p| p != 0).count();
+        let ones: u32 = sprite.iter().map(|b| b.count_ones()).sum();
+        prop_assert!(lit <= ones as usize);
+
+        // drawing the same sprite again erases it, colliding if there was anything to erase
+        let twice = run_ops!(quirks; [ops.as_slice(), &[draw, draw]].concat());
+        twice.ok().lit(0);
+        prop_assert_eq!(twice.chip8.v()[0xF], (lit > 0) as u8);
+    }
+}
@@ -0,0 +1,22 @@
+// random init fills everything but the font, the same way for the same seed
+
+use chip8::fonts;
+use chip8::Chip8;
+
+fn randomized(seed: u64) -> Chip8 {
+    let mut chip8 = Chip8::new();
+    chip8.randomize(seed);
+    chip8
+}
+
+#[test]
+fn random_init() {
+    let mut a = randomized(7);
+    assert_eq!(&a.memory()[..fonts::FONT_LEN], &fonts::SCHIP);
+    assert!(a.memory()[0x200..].iter().any(|&b| b != 0));
+    assert!(a.v().iter().any(|&v| v != 0));
+
+    assert_ne!(a.memory(), randomized(8).memory());
+    // states hold a seed drawn from the VM's RNG, so this also checks it carries on the same
+    assert_eq!(a.save_state(), randomized(7).save_state());
+}
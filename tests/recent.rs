@@ -0,0 +1,55 @@
+// the recent ROMs are kept newest first, once each
+
+use chip8::recent::{Recent, Run, MAX_RECENT};
+use std::path::PathBuf;
+
+fn run(rom: &str, args: &[&str]) -> Run {
+    Run {
+        rom: PathBuf::from(rom),
+        dir: PathBuf::from("/home/me/roms"),
+        args: args.iter().map(|a| a.to_string()).collect(),
+    }
+}
+
+#[test]
+fn recent() {
+    let mut recent = Recent::default();
+    recent.add(run("/home/me/roms/pong.ch8", &["pong.ch8"]));
+    recent.add(run(
+        "/home/me/roms/tetris.ch8",
+        &["--quirks", "schip", "tetris.ch8"],
+    ));
+    recent.add(run(
+        "/home/me/roms/pong.ch8",
+        &["--frontend", "tui", "pong.ch8"],
+    ));
+    recent.add(run(
+        "/home/me/roms/bad.ch8",
+        &["--patch", "a\tb.ips", "bad.ch8"],
+    ));
+    let text = recent.to_string();
+    assert_eq!(
+        text,
+        "/home/me/roms/pong.ch8\t/home/me/roms\t--frontend\ttui\tpong.ch8\n\
+         /home/me/roms/tetris.ch8\t/home/me/roms\t--quirks\tschip\ttetris.ch8\n"
+    );
+    let recent = Recent::parse(&text).unwrap();
+    assert_eq!(
+        recent.runs()[1],
+        run(
+            "/home/me/roms/tetris.ch8",
+            &["--quirks", "schip", "tetris.ch8"]
+        )
+    );
+    assert!(Recent::parse("pong.ch8\n").is_err());
+
+    let mut recent = Recent::default();
+    for n in 0..MAX_RECENT + 2 {
+        recent.add(run(&format!("/{}.ch8", n), &[]));
+    }
+    assert_eq!(recent.runs().len(), MAX_RECENT);
+    assert_eq!(
+        recent.runs()[0].rom,
+        PathBuf::from(format!("/{}.ch8", MAX_RECENT + 1))
+    );
+}
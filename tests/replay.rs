@@ -0,0 +1,75 @@
+// a recorded movie plays back to exactly where the VM got to, whatever the clock and keys did
+
+use chip8::chip8::state_len;
+use chip8::clock::ManualClock;
+use chip8::movie::{self, Recorder};
+use chip8::Chip8;
+
+// RND V0, 0xFF; LD V1, DT; SE V1, 0; JP 0x200; LD V2, 3; LD DT, V2; SKP V3; ADD V4, 1; JP 0x200
+const ROM: [u8; 18] = [
+    0xC0, 0xFF, 0xF1, 0x07, 0x31, 0x00, 0x12, 0x00, 0x62, 0x03, 0xF2, 0x15, 0xE3, 0x9E, 0x74, 0x01,
+    0x12, 0x00,
+];
+
+fn snapshot(chip8: &Chip8) -> (u16, u16, Vec<u8>, u8, u64) {
+    (
+        chip8.pc(),
+        chip8.i(),
+        chip8.v().to_vec(),
+        chip8.delay_timer(),
+        chip8.cycles(),
+    )
+}
+
+#[test]
+fn replay() {
+    let clock = ManualClock::default();
+    let mut chip8 = Chip8::new();
+    chip8.set_clock(Box::new(clock.clone()));
+    chip8.load_rom(&ROM);
+    for _ in 0..37 {
+        chip8.emulate_cycle().unwrap();
+    }
+
+    let mut recorder = Recorder::new(&mut chip8);
+    let mut halfway = None;
+    for cycle in 0..600 {
+        if cycle % 7 == 0 {
+            clock.advance(cycle % 3);
+        }
+        match cycle % 50 {
+            10 => chip8.press_key(0),
+            11 => chip8.release_key(0),
+            _ => {}
+        }
+        if cycle == 300 {
+            halfway = Some(snapshot(&chip8));
+        }
+        chip8.emulate_cycle_observed(&mut recorder).unwrap();
+    }
+    let movie = recorder.movie(&chip8);
+    assert_eq!(movie.len(), 600);
+    assert!(chip8.v()[4] > 0, "the key was seen");
+
+    let mut replayed = Chip8::new();
+    movie.play(&mut replayed, movie.len()).unwrap();
+    assert_eq!(snapshot(&replayed), snapshot(&chip8));
+    movie.play(&mut replayed, 300).unwrap();
+    assert_eq!(Some(snapshot(&replayed)), halfway);
+
+    let mut rewound = Chip8::new();
+    rewound.load_state(&chip8.save_state()).unwrap();
+    let mut recorder = Recorder::resume(movie.clone(), &mut rewound);
+    assert_eq!(recorder.rewind(&mut rewound, 300).unwrap(), 300);
+    assert_eq!(Some(snapshot(&rewound)), halfway);
+
+    let state = movie::save_state_with_replay(&chip8.save_state(), &movie);
+    assert_eq!(movie::replay_in_state(&state).unwrap(), Some(movie));
+    assert_eq!(
+        movie::replay_in_state(&state[..state_len(&state).unwrap()]).unwrap(),
+        None
+    );
+    Chip8::new()
+        .load_state(&state)
+        .expect("the replay doesn't get in the way of loading the state");
+}
@@ -0,0 +1,34 @@
+// the database of known-good ROM hashes, in sha1sum's format
+
+use chip8::romdb::{RomDb, Verdict};
+use chip8::sha1::{hex, sha1};
+use std::path::Path;
+
+#[test]
+fn check() {
+    let rom = [0x12, 0x00];
+    let good = hex(&sha1(&rom));
+    let other = "0123456789abcdef0123456789abcdef01234567";
+    let text = format!(
+        "# from the pack\n{}  games/loop.ch8\n{} *PONG.ch8\n",
+        good.to_uppercase(),
+        other
+    );
+    let db = RomDb::parse(&text).unwrap();
+    let sha = sha1(&rom);
+    assert_eq!(
+        db.check(Path::new("anything.ch8"), &sha),
+        Verdict::Good("games/loop.ch8".to_string())
+    );
+    assert_eq!(
+        db.check(Path::new("roms/PONG.ch8"), &sha1(&rom[..1])),
+        Verdict::Mismatch(other.to_string())
+    );
+    assert_eq!(db.check(Path::new("TETRIS"), &sha1(&[0])), Verdict::Unknown);
+
+    assert_eq!(
+        RomDb::parse("abc  short.ch8").unwrap_err(),
+        "line 1 isn't a SHA-1 and name"
+    );
+    assert!(RomDb::parse(other).is_err());
+}
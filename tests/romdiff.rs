@@ -0,0 +1,41 @@
+// chip8 diff, byte differences between ROMs shown as disassembly
+
+use chip8::romdiff::{diff, report};
+
+// LD V0, 5; ADD V0, 1; JP 0x200; 2 bytes of data
+const A: [u8; 8] = [0x60, 0x05, 0x70, 0x01, 0x12, 0x00, 0xAB, 0xCD];
+
+#[test]
+fn hunks() {
+    assert!(diff(&A, &A).is_empty());
+    let mut b = A;
+    b[3] = 0x02;
+    b[7] = 0xEF;
+    assert_eq!(diff(&A, &b), [3..4, 7..8]);
+    b[2] = 0x71;
+    assert_eq!(diff(&A, &b), [2..4, 7..8]);
+    assert_eq!(diff(&A, &A[..6]), vec![6..8]);
+}
+
+#[test]
+fn listing() {
+    let mut b = A;
+    b[3] = 0x02;
+    assert_eq!(
+        report(&A, &b, "a.ch8", "b.ch8", 1),
+        "--- a.ch8\n+++ b.ch8\n\
+         @@ 203..204 @@\n\
+         \x20 200: 6005  LD V0, 0x05\n\
+         - 202: 7001  ADD V0, 0x01\n\
+         + 202: 7002  ADD V0, 0x02\n\
+         \x20 204: 1200  JP 0x200\n"
+    );
+
+    let mut longer = A.to_vec();
+    longer.push(0x99);
+    assert_eq!(
+        report(&A, &longer, "a", "b", 1),
+        "--- a\n+++ b\n@@ 208..209 @@\n  207: DB 0xCD\n+ 208: DB 0x99\n"
+    );
+    assert_eq!(report(&A, &A, "a", "b", 3), "");
+}
@@ -0,0 +1,60 @@
+// --rotate turns the picture in the window, and says which keys move which way on it
+
+use chip8::frontend::Rotation;
+
+const SIZE: (u32, u32) = (64, 32);
+
+// where the pixel at x, y goes
+fn pixel(rotation: Rotation, x: i32, y: i32) -> (i32, i32) {
+    let (x, y, w, h) = rotation.rect((x, y, 1, 1), SIZE);
+    assert_eq!((w, h), (1, 1));
+    (x, y)
+}
+
+#[test]
+fn rect() {
+    assert_eq!(Rotation::ThreeQuarter.size(SIZE), (32, 64));
+    assert_eq!(Rotation::Half.size(SIZE), SIZE);
+    // the top left corner goes to the top right turning a quarter clockwise, and so on
+    assert_eq!(pixel(Rotation::None, 0, 0), (0, 0));
+    assert_eq!(pixel(Rotation::Quarter, 0, 0), (31, 0));
+    assert_eq!(pixel(Rotation::Half, 0, 0), (63, 31));
+    assert_eq!(pixel(Rotation::ThreeQuarter, 0, 0), (0, 63));
+    // a row becomes a column
+    assert_eq!(Rotation::Quarter.rect((8, 4, 10, 2), SIZE), (26, 8, 2, 10));
+    assert_eq!(
+        Rotation::ThreeQuarter.rect((8, 4, 10, 2), SIZE),
+        (4, 46, 2, 10)
+    );
+
+    for rotation in ["0", "90", "180", "270"] {
+        let rotation: Rotation = rotation.parse().unwrap();
+        let (w, h) = rotation.size(SIZE);
+        for (x, y) in [(0, 0), (63, 0), (5, 31), (63, 31)] {
+            let (x, y) = pixel(rotation, x, y);
+            assert!((0..w as i32).contains(&x) && (0..h as i32).contains(&y));
+        }
+    }
+    assert!("45".parse::<Rotation>().is_err());
+    assert_eq!(Rotation::Quarter.to_string(), "90");
+}
+
+#[test]
+fn directions() {
+    // the game's own up, down, left and right, as 2, 8, 4 and 6 move it
+    let moves = [(2, (0, -1)), (8, (0, 1)), (4, (-1, 0)), (6, (1, 0))];
+    let on_screen = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+    for rotation in [
+        Rotation::None,
+        Rotation::Quarter,
+        Rotation::Half,
+        Rotation::ThreeQuarter,
+    ] {
+        let (cx, cy) = pixel(rotation, 32, 16);
+        for (key, way) in rotation.directions().iter().zip(on_screen) {
+            let (_, (dx, dy)) = moves.iter().find(|(k, _)| k == key).unwrap();
+            let (x, y) = pixel(rotation, 32 + dx, 16 + dy);
+            assert_eq!((x - cx, y - cy), way, "key {} turned {}", key, rotation);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// after a long host stall the scheduler makes up at most MAX_CATCH_UP worth of instructions,
+// while the timers, which follow the clock and not the instruction count, lose nothing
+
+use chip8::clock::ManualClock;
+use chip8::runner::CYCLE_TIME;
+use chip8::scheduler::{Scheduler, MAX_CATCH_UP};
+use chip8::Chip8;
+use std::time::{Duration, Instant};
+
+// LD V0, 0xFF; LD DT, V0; JP 0x204
+const ROM: [u8; 6] = [0x60, 0xFF, 0xF0, 0x15, 0x12, 0x04];
+
+const TICK: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+#[test]
+fn on_schedule() {
+    let start = Instant::now();
+    let mut scheduler = Scheduler::new(CYCLE_TIME, MAX_CATCH_UP);
+    scheduler.reset(start);
+    assert_eq!(scheduler.due(start), 0);
+    assert_eq!(scheduler.due(start + CYCLE_TIME * 5), 5);
+    assert_eq!(scheduler.due(start + CYCLE_TIME * 5), 0, "already run");
+    assert_eq!(scheduler.next_due(1), start + CYCLE_TIME * 6);
+}
+
+#[test]
+fn stall_is_capped() {
+    let cap = (MAX_CATCH_UP.as_nanos() / CYCLE_TIME.as_nanos()) as u64;
+    let clock = ManualClock::default();
+    let mut chip8 = Chip8::new();
+    chip8.set_clock(Box::new(clock.clone()));
+    chip8.load_rom(&ROM);
+    let start = Instant::now();
+    let mut scheduler = Scheduler::new(CYCLE_TIME, MAX_CATCH_UP);
+    scheduler.reset(start);
+    for _ in 0..2 {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.delay_timer(), 0xFF);
+
+    // the host goes away for a whole second, 60 ticks and about 333 cycles
+    let mut now = start + CYCLE_TIME * 2;
+    assert_eq!(scheduler.due(now), 2);
+    now += TICK * 60;
+    clock.advance(60);
+    let due = scheduler.due(now);
+    assert!(
+        due <= cap,
+        "{} cycles due after the stall, cap is {}",
+        due,
+        cap
+    );
+    assert_eq!(due, cap);
+    let before = chip8.cycles_executed();
+    for _ in 0..due {
+        chip8.emulate_cycle().unwrap();
+    }
+    assert_eq!(chip8.cycles_executed() - before, cap);
+    assert_eq!(
+        chip8.delay_timer(),
+        0xFF - 60,
+        "the timers keep the whole stall"
+    );
+
+    // the rest of the stall is dropped, not owed
+    assert_eq!(scheduler.due(now), 0);
+    assert_eq!(scheduler.due(now + CYCLE_TIME), 1);
+    assert!(scheduler.next_due(1) <= now + CYCLE_TIME * 2);
+}
@@ -0,0 +1,52 @@
+// the high scores keep the best KEEP games, best first, and a game is over when its score drops
+
+use chip8::scores::{Game, Scores, KEEP};
+
+#[test]
+fn record() {
+    let mut scores = Scores::parse("120 1000\n\n300 2000\n").unwrap();
+    assert_eq!(scores.best(), Some(300));
+    assert_eq!(scores.record(200, 3000), Some(2));
+    assert_eq!(scores.record(120, 4000), Some(4), "after the earlier tie");
+    assert_eq!(scores.record(0, 5000), None);
+    let text = scores.to_string();
+    assert_eq!(text, "300 2000\n200 3000\n120 1000\n120 4000\n");
+    assert_eq!(Scores::parse(&text).unwrap(), scores);
+
+    for n in 0..KEEP as u64 {
+        scores.record(1000 + n, 6000);
+    }
+    assert_eq!(scores.entries().len(), KEEP);
+    assert_eq!(scores.record(300, 7000), None, "off the bottom");
+    assert_eq!(scores.record(1005, 7000), Some(6));
+    assert_eq!(scores.entries().last().unwrap().score, 1001);
+
+    assert!(Scores::parse("120\n").is_err());
+    assert!(Scores::parse("120 1000 pong\n").is_err());
+    assert!(Scores::parse("x 1000\n").is_err());
+}
+
+#[test]
+fn board() {
+    assert_eq!(Scores::default().board(), ["No high scores yet"]);
+    let scores = Scores::parse("4200 1791936000\n50 0\n").unwrap();
+    assert_eq!(
+        scores.board(),
+        [
+            "High scores",
+            "1.    4200  2026-10-14",
+            "2.      50  1970-01-01"
+        ]
+    );
+}
+
+#[test]
+fn game() {
+    let mut game = Game::default();
+    assert_eq!(game.frame(0), None);
+    assert_eq!(game.frame(10), None);
+    assert_eq!(game.frame(30), None);
+    assert_eq!(game.frame(0), Some(30), "back to zero for the next game");
+    assert_eq!(game.frame(5), None);
+    assert_eq!(game.best(), 5);
+}
@@ -0,0 +1,91 @@
+// screens compare against references in either format, to within a number of pixels, with a diff
+// of what's different when they don't
+
+use chip8::frontend;
+use chip8::screenshot::{self, Screenshot};
+
+// a pixel at each corner and a line along row 10
+fn gfx() -> Vec<u8> {
+    let mut gfx = vec![0; 64 * 32];
+    for i in [0, 63, 64 * 31, 64 * 32 - 1] {
+        gfx[i] = 1;
+    }
+    gfx[64 * 10..64 * 10 + 20].fill(1);
+    gfx
+}
+
+#[test]
+fn formats() {
+    let shot = Screenshot::of(&gfx());
+    assert!(shot.pixel(0, 0) && shot.pixel(63, 31) && shot.pixel(19, 10));
+    assert!(!shot.pixel(20, 10));
+    assert_eq!(shot.text(), frontend::render_text(&gfx()));
+    assert_eq!(shot.pbm(), frontend::render_pbm(&gfx()));
+    assert_eq!(Screenshot::parse(&shot.text()), Ok(shot.clone()));
+    assert_eq!(Screenshot::parse(&shot.pbm()), Ok(shot.clone()));
+    // PBMs can be laid out any way and have comments
+    let rows = shot.pbm().replace(' ', "").replace("P1\n6432\n", "");
+    let pbm = format!("P1 # made by hand\n64\n32\n{}", rows);
+    assert_eq!(Screenshot::parse(&pbm), Ok(shot));
+
+    assert!(Screenshot::parse("P1\n32 16\n0").is_err());
+    assert!(Screenshot::parse("..#\n").is_err());
+    assert!(Screenshot::parse(&"x".repeat(64).repeat(32)).is_err());
+}
+
+#[test]
+fn compare() {
+    let expected = Screenshot::of(&gfx());
+    let mut gfx = gfx();
+    gfx[64 * 5 + 7] = 1;
+    gfx[0] = 0;
+    let actual = Screenshot::of(&gfx);
+    assert_eq!(expected.compare(&expected, 0), Ok(()));
+    assert_eq!(expected.compare(&actual, 2), Ok(()));
+
+    let mismatch = expected.compare(&actual, 1).unwrap_err();
+    assert_eq!(mismatch.differing, 2);
+    let text = mismatch.to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "2 pixels differ, 1 allowed");
+    assert_eq!(lines.len(), 2 + 32);
+    // the first row differs at the corner, row 5 at x 7
+    assert!(lines[2].contains(" ! "));
+    assert!(lines[2].ends_with(&format!("X{}", ".".repeat(63))));
+    assert!(lines[7].ends_with(&format!("{}X{}", ".".repeat(7), ".".repeat(56))));
+    assert!(!lines[3].contains(" ! "));
+}
+
+#[test]
+fn assert_matches() {
+    let dir = std::env::temp_dir().join(format!("chip8-screenshot-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let text = dir.join("shot.txt");
+    let pbm = dir.join("shot.pbm");
+    std::fs::write(&text, Screenshot::of(&gfx()).text()).unwrap();
+    std::fs::write(&pbm, Screenshot::of(&gfx()).pbm()).unwrap();
+    screenshot::assert_matches(&gfx(), &text, 0);
+    screenshot::assert_matches(&gfx(), &pbm, 0);
+
+    let mut other = gfx();
+    other[100] = 1;
+    screenshot::assert_matches(&other, &pbm, 1);
+    let failed = std::panic::catch_unwind(|| screenshot::assert_matches(&other, &text, 0));
+    let missing = std::panic::catch_unwind(|| {
+        screenshot::assert_matches(&gfx(), &dir.join("missing.txt"), 0)
+    });
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let message = failed.unwrap_err();
+    let message = message.downcast_ref::<String>().unwrap();
+    assert!(
+        message.contains("1 pixels differ, 0 allowed"),
+        "{}",
+        message
+    );
+    let message = missing.unwrap_err();
+    assert!(message
+        .downcast_ref::<String>()
+        .unwrap()
+        .contains("CHIP8_BLESS=1"));
+}
@@ -0,0 +1,50 @@
+// cheat-finder style searches, narrowed down as memory changes
+
+use chip8::debugger::Debugger;
+use chip8::search::{Filter, Search};
+use chip8::Chip8;
+
+#[test]
+fn refine() {
+    let mut memory = vec![3, 0, 3, 7, 3, 7];
+    let mut search = Search::new(&memory, &[3]);
+    assert_eq!(search.matches(), [0, 2, 4]);
+    memory[2] = 2;
+    memory[4] = 4;
+    search.refine(&memory, &Filter::Changed);
+    assert_eq!(search.matches(), [2, 4]);
+    memory[4] = 3;
+    search.refine(&memory, &Filter::Decreased);
+    assert_eq!(search.matches(), [4]);
+    search.refine(&memory, &Filter::Equal(vec![3, 7]));
+    assert_eq!(search.matches(), [4]);
+
+    let search = Search::new(&memory, &[3, 7]);
+    assert_eq!(search.matches(), [4]);
+}
+
+// LD V2, 0x2A
+const ROM: [u8; 2] = [0x62, 0x2A];
+
+#[test]
+fn commands() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    chip8.memory_mut()[0x300] = 0x2A;
+    let mut debugger = Debugger::default();
+    assert!(debugger
+        .command(&mut chip8, "refine up")
+        .1
+        .starts_with("nothing"));
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(
+        debugger.command(&mut chip8, "search v2").1,
+        "2 matches: 201 300\n"
+    );
+    chip8.memory_mut()[0x300] = 0x2B;
+    assert_eq!(
+        debugger.command(&mut chip8, "refine up").1,
+        "1 match: 300\n"
+    );
+    assert_eq!(debugger.command(&mut chip8, "refine 2c").1, "no matches\n");
+}
@@ -0,0 +1,60 @@
+// run --load puts files at addresses of their own, all of them or none when they don't fit or
+// overlap
+
+use chip8::segment::{self, Segment};
+use chip8::Chip8;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn parse() {
+    assert_eq!(
+        "data.bin@0x800".parse(),
+        Ok(Segment {
+            path: PathBuf::from("data.bin"),
+            addr: 0x800
+        })
+    );
+    let segment: Segment = "me@home.bin@A00".parse().unwrap();
+    assert_eq!(
+        (segment.path, segment.addr),
+        (PathBuf::from("me@home.bin"), 0xA00)
+    );
+    assert!("data.bin".parse::<Segment>().is_err());
+    assert!("data.bin@zz".parse::<Segment>().is_err());
+    assert!("@200".parse::<Segment>().is_err());
+}
+
+#[test]
+fn load() {
+    let (code, data) = (Path::new("code.ch8"), Path::new("data.bin"));
+    // LD I, 0x800; LD V1, [I]
+    let mut chip8 = Chip8::new();
+    let segments = [
+        (code, 0x200, &[0xA8, 0x00, 0xF1, 0x65][..]),
+        (data, 0x800, &[0x12, 0x34][..]),
+    ];
+    segment::load(&mut chip8, &segments).unwrap();
+    chip8.emulate_cycle().unwrap();
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(&chip8.v()[..2], [0x12, 0x34]);
+
+    let mut chip8 = Chip8::new();
+    let overlapping = [(data, 0x203, &[1, 2][..]), (code, 0x200, &[3; 4][..])];
+    assert_eq!(
+        segment::load(&mut chip8, &overlapping),
+        Err("code.ch8 and data.bin overlap at 203".to_string())
+    );
+    assert_eq!(chip8.memory()[0x200], 0, "nothing was loaded");
+    let past_end = [(data, 0xFFF, &[1, 2][..])];
+    assert_eq!(
+        segment::load(&mut chip8, &past_end),
+        Err("data.bin at FFF runs 1 bytes past the end of memory".to_string())
+    );
+    // touching is fine
+    segment::load(
+        &mut chip8,
+        &[(code, 0x200, &[1][..]), (data, 0x201, &[2][..])],
+    )
+    .unwrap();
+    assert_eq!(&chip8.memory()[0x200..0x202], [1, 2]);
+}
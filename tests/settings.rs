@@ -0,0 +1,94 @@
+// options left off the command line come from the preset, the environment, then the config file,
+// where some can be set for one ROM
+
+use chip8::config::Config;
+use chip8::settings::{self, Source};
+use std::path::Path;
+
+#[test]
+fn lookup() {
+    assert_eq!(settings::env_name("toast-secs"), "CHIP8_TOAST_SECS");
+    let config = Config::parse("quirks = schip\nfrontend = tui\n").unwrap();
+    let env = |name: &str| (name == "CHIP8_QUIRKS").then(|| "chip8".to_string());
+    assert_eq!(
+        settings::lookup("quirks", &config, None, env),
+        Some(("chip8".to_string(), Source::Env("CHIP8_QUIRKS".to_string())))
+    );
+    assert_eq!(
+        settings::lookup("frontend", &config, None, env),
+        Some(("tui".to_string(), Source::Config))
+    );
+    assert_eq!(settings::lookup("font", &config, None, env), None);
+}
+
+#[test]
+fn flags() {
+    assert_eq!(settings::flag("1"), Some(true));
+    assert_eq!(settings::flag("Off"), Some(false));
+    assert_eq!(settings::flag("maybe"), None);
+}
+
+#[test]
+fn presets() {
+    let config = Config::parse(
+        "quirks = schip\npreset.speedrun.frameskip = 2\npreset.mine.quirks = xochip\n",
+    )
+    .unwrap();
+    assert_eq!(
+        settings::preset_names(&config),
+        ["authentic-vip", "modern", "speedrun", "mine"]
+    );
+    assert_eq!(settings::preset("nope", &config), None);
+
+    // the config file changes a built in preset option by option
+    let speedrun = settings::preset("speedrun", &config).unwrap();
+    assert!(speedrun
+        .settings
+        .contains(&("frameskip".to_string(), "2".to_string())));
+    assert!(speedrun
+        .settings
+        .contains(&("toast-secs".to_string(), "0".to_string())));
+
+    // the preset comes before the environment and the config file
+    let mine = settings::preset("mine", &config).unwrap();
+    let env = |name: &str| (name == "CHIP8_QUIRKS").then(|| "chip8".to_string());
+    assert_eq!(
+        settings::lookup("quirks", &config, Some(&mine), env),
+        Some(("xochip".to_string(), Source::Preset("mine".to_string())))
+    );
+    assert_eq!(
+        settings::lookup("quirks", &config, Some(&speedrun), env),
+        Some(("chip8".to_string(), Source::Env("CHIP8_QUIRKS".to_string())))
+    );
+}
+
+#[test]
+fn preset_arg() {
+    let args = |line: &str| line.split(' ').map(String::from).collect::<Vec<_>>();
+    assert_eq!(
+        settings::preset_arg(&args("chip8 --preset modern rom")),
+        Some("modern")
+    );
+    assert_eq!(
+        settings::preset_arg(&args("chip8 run --preset=mine rom")),
+        Some("mine")
+    );
+    assert_eq!(settings::preset_arg(&args("chip8 rom -- --preset x")), None);
+    assert_eq!(settings::preset_arg(&args("chip8 --preset")), None);
+}
+
+#[test]
+fn rom_setting() {
+    let config = Config::parse("idle = 60\nrom.pong.idle = 10\nrom.pong.idle-action = next\n");
+    let config = config.unwrap();
+    let pong = Path::new("roms/pong.ch8");
+    assert_eq!(settings::rom_setting("idle", &config, pong), Some("10"));
+    assert_eq!(
+        settings::rom_setting("idle-action", &config, pong),
+        Some("next")
+    );
+    assert_eq!(
+        settings::rom_setting("idle", &config, Path::new("tetris.ch8")),
+        None
+    );
+}
@@ -0,0 +1,75 @@
+// writes over code that has already run are reported with what was there and what replaced it,
+// and run only lists them at exit when asked to with --trace-smc
+
+use chip8::observer::{Event, Observer, SelfModifyLog};
+use chip8::Chip8;
+use std::process::Command;
+
+// LD I, 0x208; LD V0, 0x72; JP 0x208; ADD V0, 1; LD [I], V0; LD I, 0x300; LD [I], V0; JP 0x210.
+// the ADD runs, then the LD [I] after it overwrites its first byte with 73, ADD V3, 1
+const ROM: [u8; 18] = [
+    0xA2, 0x08, 0x60, 0x72, 0x12, 0x08, 0x00, 0x00, 0x70, 0x01, 0xF0, 0x55, 0xA3, 0x00, 0xF0, 0x55,
+    0x12, 0x10,
+];
+
+#[derive(Default)]
+struct Events(Vec<Event>);
+
+impl Observer for Events {
+    fn on_event(&mut self, _: &Chip8, event: &Event) {
+        if let Event::SelfModify { .. } = event {
+            self.0.push(*event);
+        }
+    }
+}
+
+#[test]
+fn event() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    let mut events = Events::default();
+    let mut log = SelfModifyLog::default();
+    for _ in 0..10 {
+        chip8
+            .emulate_cycle_observed(&mut (&mut events, &mut log))
+            .unwrap();
+    }
+    // the write to 0x300, which never ran, isn't one
+    assert_eq!(
+        events.0,
+        [Event::SelfModify {
+            pc: 0x20A,
+            addr: 0x208,
+            old: 0x70,
+            new: 0x73
+        }]
+    );
+    assert_eq!(chip8.memory()[0x300], 0x73);
+    assert_eq!(
+        log.report(),
+        "1 byte(s) of executed code were overwritten:\n  0x208 written by 0x20A, 70 -> 73\n"
+    );
+}
+
+#[test]
+fn only_when_asked() {
+    let dir = std::env::temp_dir().join(format!("chip8-smc-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rom = dir.join("smc.ch8");
+    std::fs::write(&rom, ROM).unwrap();
+    let run = |extra: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_chip8"))
+            .args(["run", "--frontend", "null", "--run-for", "20c"])
+            .args(extra)
+            .arg(&rom)
+            .env("XDG_DATA_HOME", &dir)
+            .env("XDG_CONFIG_HOME", &dir)
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{:?}", out);
+        String::from_utf8(out.stderr).unwrap()
+    };
+    assert!(!run(&[]).contains("overwritten"));
+    assert!(run(&["--trace-smc"]).contains("0x208 written by 0x20A, 70 -> 73"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,163 @@
+// --spectate: the websocket handshake, the frame diff encoding the page decodes, and a spectator
+// joining a real server
+
+use chip8::spectate::{self, Server, View, SCREEN_BYTES, SOUND, WHOLE};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+#[test]
+fn accept_key() {
+    // the example from RFC 6455
+    assert_eq!(
+        spectate::accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+        "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+    );
+}
+
+#[test]
+fn pack() {
+    let mut gfx = [0; 64 * 32];
+    gfx[0] = 1;
+    gfx[7] = 1;
+    gfx[64 * 32 - 1] = 1;
+    let screen = spectate::pack(&gfx);
+    assert_eq!(screen[0], 0x81);
+    assert_eq!(screen[SCREEN_BYTES - 1], 0x01);
+    assert_eq!(screen[1..SCREEN_BYTES - 1], [0; SCREEN_BYTES - 2]);
+}
+
+#[test]
+fn encode() {
+    let blank = [0; SCREEN_BYTES];
+    let first = spectate::encode(None, &blank, false).unwrap();
+    assert_eq!((first[0], first.len()), (WHOLE, SCREEN_BYTES + 1));
+    assert_eq!(spectate::encode(Some(&(blank, false)), &blank, false), None);
+    assert_eq!(
+        spectate::encode(Some(&(blank, false)), &blank, true),
+        Some(vec![SOUND])
+    );
+
+    let mut screen = blank;
+    screen[3] = 0xF0;
+    screen[200] = 0x0F;
+    assert_eq!(
+        spectate::encode(Some(&(blank, true)), &screen, true),
+        Some(vec![SOUND, 3, 0xF0, 200, 0x0F])
+    );
+    // the whole screen when that's shorter than the changes
+    let full = [0xFF; SCREEN_BYTES];
+    assert_eq!(
+        spectate::encode(Some(&(blank, false)), &full, false).unwrap()[0],
+        WHOLE
+    );
+}
+
+#[test]
+fn view_follows_the_messages() {
+    let mut view = View::default();
+    let mut prev = None;
+    for n in 0..SCREEN_BYTES {
+        let mut screen = prev.map_or([0; SCREEN_BYTES], |(s, _)| s);
+        screen[n * 37 % SCREEN_BYTES] ^= n as u8 | 1;
+        let sound = n % 3 == 0;
+        let message = spectate::encode(prev.as_ref(), &screen, sound).unwrap();
+        view.apply(&message).unwrap();
+        assert_eq!((view.screen, view.sound), (screen, sound));
+        prev = Some((screen, sound));
+    }
+    assert!(view.apply(&[]).is_err());
+    assert!(view.apply(&[0, 1]).is_err());
+    assert!(view.apply(&[WHOLE, 1]).is_err());
+}
+
+#[test]
+fn frame() {
+    assert_eq!(spectate::frame(&[1, 2]), [0x82, 2, 1, 2]);
+    let long = spectate::frame(&[0; 257]);
+    assert_eq!(long[..4], [0x82, 126, 1, 1]);
+    assert_eq!(long.len(), 4 + 257);
+}
+
+// read one unmasked websocket message
+fn message(stream: &mut impl Read) -> Vec<u8> {
+    let mut head = [0; 2];
+    stream.read_exact(&mut head).unwrap();
+    assert_eq!(head[0], 0x82);
+    let len = match head[1] {
+        126 => {
+            let mut len = [0; 2];
+            stream.read_exact(&mut len).unwrap();
+            u16::from_be_bytes(len) as usize
+        }
+        len => len as usize,
+    };
+    let mut body = vec![0; len];
+    stream.read_exact(&mut body).unwrap();
+    body
+}
+
+#[test]
+fn server() {
+    let mut server = Server::bind("127.0.0.1:0").unwrap();
+    let addr = server.addr();
+
+    let mut page = TcpStream::connect(addr).unwrap();
+    page.write_all(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n")
+        .unwrap();
+    let mut html = String::new();
+    page.read_to_string(&mut html).unwrap();
+    assert!(html.starts_with("HTTP/1.1 200"));
+    assert!(html.contains("new WebSocket"));
+
+    let stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    (&stream)
+        .write_all(
+            b"GET /ws HTTP/1.1\r\nHost: x\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+              Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        )
+        .unwrap();
+    let mut reader = BufReader::new(&stream);
+    let mut response = String::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+            break;
+        }
+        response.push_str(&line);
+    }
+    assert!(response.starts_with("HTTP/1.1 101"));
+    assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+    // the handshake finishes on another thread, so update until the spectator's been let in
+    let mut gfx = [0; 64 * 32];
+    gfx[8] = 1;
+    stream
+        .set_read_timeout(Some(Duration::from_millis(10)))
+        .unwrap();
+    let mut first = None;
+    for _ in 0..500 {
+        server.update(&gfx, false);
+        if reader.fill_buf().is_ok_and(|b| !b.is_empty()) {
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            first = Some(message(&mut reader));
+            break;
+        }
+    }
+    let mut view = View::default();
+    view.apply(&first.expect("no whole screen")).unwrap();
+    assert_eq!(view.screen[1], 0x80);
+
+    gfx[9] = 1;
+    server.update(&gfx, true);
+    let change = message(&mut reader);
+    assert_eq!(change, [SOUND, 1, 0xC0]);
+    view.apply(&change).unwrap();
+    assert!(view.sound);
+}
@@ -0,0 +1,43 @@
+// split screen sends each VM its own keys and puts their screens next to each other
+
+use chip8::fb;
+use chip8::frontend::{self, Hotkey, Machine, Machines};
+use chip8::Chip8;
+
+#[test]
+fn routing() {
+    let mut emus = [Chip8::new(), Chip8::new()];
+    let mut machines = Machines(&mut emus);
+    machines.press_key(3);
+    machines.press_key(16 + 0xA);
+    assert_eq!(emus[0].keys(), 1 << 3);
+    assert_eq!(emus[1].keys(), 1 << 0xA);
+    Machines(&mut emus).clear_keys();
+    assert!(emus.iter().all(|emu| emu.keys() == 0));
+}
+
+#[test]
+fn side_by_side() {
+    let left = [1; 64 * 32];
+    let right = [0; 64 * 32];
+    let gfx = frontend::side_by_side(&[&left, &right]);
+    assert_eq!(gfx.len(), 128 * 32);
+    assert!(gfx.chunks(64).step_by(2).all(|row| row == [1; 64]));
+    assert!(gfx.chunks(64).skip(1).step_by(2).all(|row| row == [0; 64]));
+}
+
+// M mutes in split screen as well, the second player's keypad is clear of the hotkeys
+#[test]
+fn hotkeys() {
+    assert_eq!(fb::keypad(50, 2), None); // M
+    assert_eq!(fb::hotkey(50), Some(Hotkey::Mute));
+    assert_eq!(fb::keypad(49, 2), Some(16 + 0xA)); // N
+
+    #[cfg(feature = "sdl")]
+    {
+        use chip8_sdl::sdl2::keyboard::{Keycode, Scancode};
+        assert_eq!(chip8_sdl::keypad(Scancode::M, 2), None);
+        assert_eq!(chip8_sdl::hotkey(Keycode::M), Some(Hotkey::Mute));
+        assert_eq!(chip8_sdl::keypad(Scancode::N, 2), Some(16 + 0xA));
+    }
+}
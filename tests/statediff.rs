@@ -0,0 +1,50 @@
+// the registers, memory rows and pixels that changed between two save states, and the image of
+// the screens
+
+use chip8::statediff;
+use chip8::Chip8;
+
+// LD V0, 0x80; LD I, 0x300; LD [I], V0; DRW V0, V0, 1; JP 0x208, drawing the one pixel at 0, 0
+const ROM: [u8; 10] = [0x60, 0x80, 0xA3, 0x00, 0xF0, 0x55, 0xD0, 0x01, 0x12, 0x08];
+
+fn states() -> (Chip8, Chip8) {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&ROM);
+    let a = statediff::machine(&chip8.save_state()).unwrap();
+    for _ in 0..4 {
+        chip8.emulate_cycle().unwrap();
+    }
+    let b = statediff::machine(&chip8.save_state()).unwrap();
+    (a, b)
+}
+
+#[test]
+fn report() {
+    let (a, b) = states();
+    let report = statediff::report(&a, &b, "a.state", "b.state");
+    let zeroes = "00 ".repeat(15) + "00";
+    assert_eq!(
+        report,
+        format!(
+            "--- a.state\n+++ b.state\nV0 00 -> 80\nI 000 -> 300\nPC 200 -> 208\ncycles 0 -> 4\n\
+             instructions 0 -> 4\nframes 0 -> 1\n\
+             -300: {}\n+300: 80 {}\nscreen 1 pixels on, 0 off\n",
+            zeroes,
+            &zeroes[3..]
+        )
+    );
+    assert_eq!(statediff::report(&a, &a, "a.state", "a.state"), "");
+    assert_eq!(statediff::pixel_changes(&b, &a), (0, 1));
+}
+
+#[test]
+fn screen_image() {
+    let (a, b) = states();
+    let image = statediff::screen_image(&a, &b);
+    let rows: Vec<&str> = image.lines().collect();
+    assert_eq!(rows[..3], ["P3", "64 32", "255"]);
+    assert_eq!(rows.len(), 3 + 32);
+    let pixels: Vec<&str> = rows[3].split("  ").collect();
+    assert_eq!(pixels.len(), 64);
+    assert_eq!(pixels[..2], ["0 255 0", "0 0 0"]);
+}
@@ -0,0 +1,74 @@
+// run --stats counts opcodes, draws, key waits and timer use from the observer hooks
+
+use chip8::quirks::Quirks;
+use chip8::stats::Stats;
+use chip8::Chip8;
+
+// LD V0, 0x20; LD DT, V0; LD ST, V0; LD V1, DT; LD I, 0x200; DRW V0, V0, 3; LD V2, K
+const ROM: [u8; 14] = [
+    0x60, 0x20, 0xF0, 0x15, 0xF0, 0x18, 0xF1, 0x07, 0xA2, 0x00, 0xD0, 0x03, 0xF2, 0x0A,
+];
+
+fn stats(cycles: usize) -> String {
+    let mut chip8 = Chip8::new();
+    let mut quirks = Quirks::default();
+    quirks.display_wait = false;
+    chip8.set_quirks(quirks);
+    chip8.load_rom(&ROM);
+    let mut stats = Stats::default();
+    for _ in 0..cycles {
+        chip8.emulate_cycle_observed(&mut stats).unwrap();
+    }
+    stats.report()
+}
+
+#[test]
+fn counters() {
+    let report = stats(106);
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines[0], "106 instructions, 1 frames, 0.3s of CHIP-8 time");
+    // most run first, ties by opcode
+    assert_eq!(lines[3], "FX0A    LD              100  94.34");
+    assert_eq!(lines[4], "6XNN    LD                1   0.94");
+    assert_eq!(lines[6], "DXYN    DRW               1   0.94");
+    assert_eq!(lines.len(), 15);
+    assert!(report.contains("\ndraws: 1 (3.1/s), average sprite height 3.0\n"));
+    // the first FX0A isn't waiting yet, the other 99 are
+    assert!(report.contains("\nwaiting for keys: 0.3s (93.4%)\n"));
+    assert!(report.contains("\ndelay timer: set 1 times, read 1 times\n"));
+    assert!(report.contains("\nsound timer: set 1 times, buzzer on for 0.3s\n"));
+}
+
+#[test]
+fn nothing_run() {
+    let report = stats(0);
+    assert!(report.starts_with("0 instructions, 0 frames, 0.0s"));
+    assert!(report.contains("draws: 0 (0.0/s), average sprite height 0.0"));
+    assert!(report.contains("waiting for keys: 0.0s (0.0%)"));
+}
+
+#[test]
+fn earlier_state() {
+    // LD V0, 1; DRW V0, V0, 1; JP 0x200, a frame every three instructions
+    let mut chip8 = Chip8::new();
+    let mut quirks = Quirks::default();
+    quirks.display_wait = false;
+    chip8.set_quirks(quirks);
+    chip8.load_rom(&[0x60, 0x01, 0xD0, 0x01, 0x12, 0x00]);
+    let start = chip8.save_state();
+    for _ in 0..15 {
+        chip8.emulate_cycle().unwrap();
+    }
+    let mut stats = Stats::default();
+    for _ in 0..6 {
+        chip8.emulate_cycle_observed(&mut stats).unwrap();
+    }
+    // a quick load from before the stats started, frames_rendered goes back below where it was
+    chip8.load_state(&start).unwrap();
+    chip8.emulate_cycle_observed(&mut stats).unwrap();
+    assert!(
+        stats.report().starts_with("7 instructions, 0 frames,"),
+        "{}",
+        stats.report()
+    );
+}
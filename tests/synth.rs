@@ -0,0 +1,54 @@
+// the buzzer's models: a clean square wave, and the VIP's tone with its clicks
+
+use chip8::synth::{Buzzer, Model, SquareWave, MODELS, SAMPLE_RATE, VOLUME};
+
+#[test]
+fn square_wave() {
+    // 440Hz at 4400 samples a second is 10 samples a cycle, half high and half low
+    let mut wave = SquareWave::new(440.0, 4400);
+    let mut out = [0.0; 20];
+    wave.fill(&mut out);
+    let high = out.iter().filter(|&&s| s > 0.0).count();
+    assert!((9..=11).contains(&high), "{:?}", out);
+    assert!(out.iter().all(|s| s.abs() == VOLUME));
+}
+
+#[test]
+fn models() {
+    for name in MODELS {
+        assert!(Model::from_name(name).is_some(), "{}", name);
+    }
+    assert_eq!(Model::from_name("sine"), None);
+
+    let mut square = Buzzer::new(Model::Square, SAMPLE_RATE);
+    let mut out = [1.0; 100];
+    square.fill(false, &mut out);
+    assert!(out.iter().all(|&s| s == 0.0), "silent while off");
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0.0, |m, s| s.abs().max(m))
+}
+
+#[test]
+fn vip_clicks() {
+    let tick = (SAMPLE_RATE / 60) as usize;
+    let mut vip = Buzzer::new(Model::Vip, SAMPLE_RATE);
+    let mut beep = vec![0.0; 10 * tick];
+    vip.fill(true, &mut beep);
+    // the tone swings to about VOLUME once it's settled, starting with a bigger jump
+    let settled = peak(&beep[9 * tick..]);
+    assert!(
+        (VOLUME * 0.8..VOLUME * 1.2).contains(&settled),
+        "{}",
+        settled
+    );
+    assert!(peak(&beep[..tick / 10]) > settled * 1.5);
+
+    // and goes on sounding a moment after it's turned off before dying away
+    let mut after = vec![0.0; 10 * tick];
+    vip.fill(false, &mut after);
+    assert!(peak(&after[..tick / 10]) > VOLUME / 2.0);
+    assert!(peak(&after[9 * tick..]) < VOLUME / 100.0);
+    assert!(after.iter().all(|s| s.abs() <= 2.0 * VOLUME));
+}
@@ -0,0 +1,86 @@
+// Runs Timendus' CHIP-8 test suite and compares the screen each test leaves behind against the
+// goldens in tests/golden. The ROM isn't part of the repo, so these are ignored unless asked for:
+// fetch it with scripts/fetch-test-roms.sh, then run cargo test --test timendus -- --ignored.
+// Set CHIP8_BLESS=1 to rewrite the goldens after a deliberate change.
+
+use chip8::headless::frame_hash;
+use chip8::quirks::Quirks;
+use chip8::screenshot;
+use chip8::Chip8;
+use std::path::Path;
+
+const ROM: &str = "tests/roms/chip8-test-suite.ch8";
+const ROM_HASH: u64 = 0xf4f3_b55d_ac63_ef64; // frame_hash of the ROM the goldens were made with
+const CYCLES: usize = 50_000; // every test has finished drawing well before this
+
+fn check(name: &str, test: u8) {
+    check_with(name, test, 0, Quirks::default());
+}
+
+// the suite runs the test whose number is stored at 0x1FF instead of showing its menu,
+// the quirks test also takes the platform to check for from 0x1FE
+fn check_with(name: &str, test: u8, platform: u8, quirks: Quirks) {
+    let rom = std::fs::read(ROM).unwrap_or_else(|e| {
+        panic!("{}: {}, run scripts/fetch-test-roms.sh", ROM, e);
+    });
+    assert_eq!(
+        frame_hash(&rom),
+        ROM_HASH,
+        "{} isn't the version of the suite the goldens were made with",
+        ROM
+    );
+
+    let mut chip8 = Chip8::new();
+    chip8.seed_rng(0);
+    chip8.set_quirks(quirks);
+    chip8.load_rom(&rom);
+    chip8.memory_mut()[0x1FF] = test;
+    chip8.memory_mut()[0x1FE] = platform;
+    for _ in 0..CYCLES {
+        chip8.emulate_cycle().unwrap();
+    }
+    let golden = Path::new("tests/golden").join(format!("{}.txt", name));
+    screenshot::assert_matches(chip8.gfx(), &golden, 0);
+}
+
+#[test]
+#[ignore = "needs the suite from scripts/fetch-test-roms.sh"]
+fn splash() {
+    check("splash", 0);
+}
+
+#[test]
+#[ignore = "needs the suite from scripts/fetch-test-roms.sh"]
+fn ibm_logo() {
+    check("ibm_logo", 1);
+}
+
+#[test]
+#[ignore = "needs the suite from scripts/fetch-test-roms.sh"]
+fn corax_plus() {
+    check("corax_plus", 2);
+}
+
+#[test]
+#[ignore = "needs the suite from scripts/fetch-test-roms.sh"]
+fn flags() {
+    check("flags", 3);
+}
+
+#[test]
+#[ignore = "needs the suite from scripts/fetch-test-roms.sh"]
+fn quirks_chip8() {
+    check_with("quirks_chip8", 4, 1, Quirks::CHIP8);
+}
+
+#[test]
+#[ignore = "needs the suite from scripts/fetch-test-roms.sh"]
+fn quirks_schip() {
+    check_with("quirks_schip", 4, 2, Quirks::SCHIP);
+}
+
+#[test]
+#[ignore = "needs the suite from scripts/fetch-test-roms.sh"]
+fn quirks_xochip() {
+    check_with("quirks_xochip", 4, 3, Quirks::XOCHIP);
+}
@@ -0,0 +1,44 @@
+// the tour's ROM runs to its end with a note on every instruction it gets to
+
+use chip8::tour::{self, Registers};
+use chip8::Chip8;
+use std::collections::BTreeSet;
+
+#[test]
+fn notes() {
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&tour::ROM);
+    let mut ran = BTreeSet::new();
+    while !chip8.halted() {
+        assert!(ran.len() < 1000, "the tour doesn't end");
+        ran.insert(chip8.pc());
+        let before = Registers::of(&chip8);
+        chip8.emulate_cycle().unwrap();
+        let lines = tour::overlay(&chip8, Some(&before));
+        assert!(!lines[1].is_empty(), "no note at {:03X}", before.pc);
+    }
+    ran.insert(chip8.pc());
+    let noted: BTreeSet<u16> = tour::NOTES.iter().map(|(addr, _)| *addr).collect();
+    assert_eq!(ran, noted, "notes on instructions that never run");
+    assert_eq!(chip8.v()[..3], [1, 2, 3]);
+}
+
+#[test]
+fn changes() {
+    let mut chip8 = Chip8::new();
+    // LD VA, 0x2B; CALL 0x200
+    chip8.load_rom(&[0x6A, 0x2B, 0x22, 0x00]);
+    let before = Registers::of(&chip8);
+    chip8.emulate_cycle().unwrap();
+    let lines = tour::overlay(&chip8, Some(&before));
+    assert_eq!(
+        (lines[0].as_str(), lines[2].as_str()),
+        ("200 LD VA, 0x2B", "VA 2B")
+    );
+    let before = Registers::of(&chip8);
+    chip8.emulate_cycle().unwrap();
+    assert_eq!(
+        tour::changes(&before, &Registers::of(&chip8)),
+        "stack 1  PC 200"
+    );
+}
@@ -0,0 +1,36 @@
+// traces that start in different places are lined up before looking for where they disagree
+
+use chip8::tracediff::{diff, parse};
+
+const A: &str = "\
+200: 6001  LD V0, 0x01         00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  I=000
+202: 7001  ADD V0, 0x01        01 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  I=000
+204: A300  LD I, 0x300         02 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  I=000
+206: 1206  JP 0x206            02 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  I=300
+";
+
+// started an instruction later, and with ADD setting VF
+const B: &str = "\
+; from another emulator
+202: 7001  ADD V0, 0x01        01 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  I=000
+204: A300  LD I, 0x300         02 00 00 00 00 00 00 00 00 00 00 00 00 00 00 01  I=000
+206: 1206  JP 0x206            02 00 00 00 00 00 00 00 00 00 00 00 00 00 00 01  I=300
+";
+
+#[test]
+fn first_divergence() {
+    let (a, b) = (parse(A).unwrap(), parse(B).unwrap());
+    let diff = diff(&a, &b);
+    assert_eq!(diff.skipped, (1, 0));
+    assert_eq!(diff.matched, 1);
+    let (step_a, step_b) = diff.diverged().unwrap();
+    assert_eq!((step_a.line, step_b.line), (3, 3));
+    assert!(diff
+        .report("a", "b", 1)
+        .contains("at a line 3 and b line 3: VF 00 vs 01\n"));
+}
+
+#[test]
+fn not_a_trace() {
+    assert_eq!(parse("hello\n").unwrap_err().line, 1);
+}
@@ -0,0 +1,67 @@
+// trimming strips a ROM's padding and, asked to, closes up the gaps of zeroes inside it, keeping
+// the trimmed ROM's frames the same as the original's
+
+use chip8::headless;
+use chip8::quirks::Quirks;
+use chip8::trim;
+
+const CYCLES: usize = 500;
+
+fn frames(rom: &[u8]) -> Vec<u64> {
+    headless::run(rom, CYCLES, Quirks::default()).frames
+}
+
+// 200: LD V0, 0; LD V1, 0; JP 226, over 32 bytes of zeroes
+// 226: LD I, 22E; DRW V0, V1, 5; ADD V0, 8; JP 228
+// 22E: sprite, then 16 bytes of padding
+fn padded() -> Vec<u8> {
+    let mut rom = vec![0x60, 0x00, 0x61, 0x00, 0x12, 0x26];
+    rom.extend([0; 32]);
+    rom.extend([0xA2, 0x2E, 0xD0, 0x15, 0x70, 0x08, 0x12, 0x28]);
+    rom.extend([0xF0, 0x90, 0x90, 0x90, 0xF0]);
+    rom.extend([0; 16]);
+    rom
+}
+
+#[test]
+fn relocates() {
+    let rom = padded();
+    let stripped = trim::trim(&rom, false, CYCLES, Quirks::default()).unwrap();
+    assert_eq!(stripped.rom, &rom[..rom.len() - 16]);
+    assert!(!stripped.relocated);
+
+    let trimmed = trim::trim(&rom, true, CYCLES, Quirks::default()).unwrap();
+    assert!(trimmed.relocated);
+    assert_eq!(trimmed.rom.len(), rom.len() - 16 - 32);
+    // the jumps and I moved down with what they point at
+    assert_eq!(&trimmed.rom[4..6], &[0x12, 0x06]);
+    assert_eq!(&trimmed.rom[6..8], &[0xA2, 0x0E]);
+    assert_eq!(&trimmed.rom[12..14], &[0x12, 0x08]);
+    assert!(trimmed.frames > 1);
+    assert_eq!(frames(&trimmed.rom), frames(&rom));
+}
+
+// 200: LD V0, 2; JP V0, 204, into a table of jumps analysis can't follow
+// 204: JP 228; JP 228, then 32 bytes of zeroes
+// 228: LD I, 22E; DRW V0, V1, 5; JP 22C
+// 22E: sprite, then padding
+fn jump_table() -> Vec<u8> {
+    let mut rom = vec![0x60, 0x02, 0xB2, 0x04, 0x12, 0x28, 0x12, 0x28];
+    rom.extend([0; 32]);
+    rom.extend([0xA2, 0x2E, 0xD0, 0x15, 0x12, 0x2C]);
+    rom.extend([0xF0, 0x90, 0x90, 0x90, 0xF0]);
+    rom.extend([0; 8]);
+    rom
+}
+
+// closing the gap would leave the table jumping to where the code used to be, so the ROM's
+// only stripped
+#[test]
+fn jump_tables() {
+    let rom = jump_table();
+    let trimmed = trim::trim(&rom, true, CYCLES, Quirks::default()).unwrap();
+    assert!(!trimmed.relocated);
+    assert_eq!(trimmed.rom, &rom[..rom.len() - 8]);
+    assert!(!frames(&rom).is_empty());
+    assert_eq!(frames(&trimmed.rom), frames(&rom));
+}
@@ -0,0 +1,35 @@
+// --pipe-video writes a scaled rgb24 frame for every tick, for ffmpeg's rawvideo input
+
+use chip8::video::{VideoPipe, HEIGHT, WIDTH};
+
+#[test]
+fn frames() {
+    let mut screen = vec![0; WIDTH * HEIGHT];
+    screen[1] = 1; // second pixel of the top row
+    let mut out = Vec::new();
+    let mut video = VideoPipe::new(&mut out, 2);
+    assert_eq!(video.size(), (128, 64));
+    video.frames(&screen, 0).unwrap();
+    video.frames(&screen, 3).unwrap();
+    assert_eq!(video.frames, 3);
+    drop(video);
+
+    let frame = 128 * 64 * 3;
+    assert_eq!(out.len(), 3 * frame);
+    assert_eq!(out[..frame], out[frame..2 * frame], "a frame a tick");
+    let pixel = |x: usize, y: usize| &out[(y * 128 + x) * 3..][..3];
+    assert_eq!(pixel(1, 0), [0, 0, 0]);
+    for (x, y) in [(2, 0), (3, 0), (2, 1), (3, 1)] {
+        assert_eq!(pixel(x, y), [255, 255, 255]);
+    }
+    assert_eq!(pixel(2, 2), [0, 0, 0]);
+}
+
+#[test]
+fn command() {
+    let video = VideoPipe::new(Vec::new(), 10);
+    assert_eq!(
+        video.command("-", "out.mp4"),
+        "ffmpeg -f rawvideo -pix_fmt rgb24 -s 640x320 -r 60 -i - out.mp4"
+    );
+}
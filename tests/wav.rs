@@ -0,0 +1,36 @@
+// --record-audio writes the buzzer a frame at a time as a 16 bit mono WAV
+
+use chip8::synth::{Model, VOLUME};
+use chip8::wav::{WavWriter, SAMPLES_PER_TICK};
+use std::io::Cursor;
+
+fn u32_at(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+#[test]
+fn wav() {
+    let mut wav = WavWriter::new(Cursor::new(Vec::new()), Model::Square).unwrap();
+    wav.frames(true, 1).unwrap();
+    wav.frames(false, 2).unwrap();
+    wav.frames(true, 0).unwrap();
+    assert_eq!(wav.samples, 3 * SAMPLES_PER_TICK as u64);
+    let data = wav.finish().unwrap().into_inner();
+
+    let data_len = 3 * SAMPLES_PER_TICK * 2;
+    assert_eq!(data.len(), 44 + data_len);
+    assert_eq!(&data[..4], b"RIFF");
+    assert_eq!(u32_at(&data, 4) as usize, 36 + data_len);
+    assert_eq!(&data[8..16], b"WAVEfmt ");
+    assert_eq!(u32_at(&data, 24), 44_100);
+    assert_eq!(&data[36..40], b"data");
+    assert_eq!(u32_at(&data, 40) as usize, data_len);
+
+    let samples: Vec<i16> = (data[44..].chunks(2))
+        .map(|s| i16::from_le_bytes([s[0], s[1]]))
+        .collect();
+    let loud = (VOLUME * i16::MAX as f32) as i16;
+    assert_eq!(samples[0], loud);
+    assert!(samples[..SAMPLES_PER_TICK].contains(&-loud));
+    assert!(samples[SAMPLES_PER_TICK..].iter().all(|&s| s == 0));
+}